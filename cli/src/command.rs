@@ -0,0 +1,307 @@
+use std::{borrow::Cow, fs, path::Path};
+
+use anyhow::anyhow;
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::account_info::IntoAccountInfo;
+use token2022_wrapper::{
+    client::{WrapperClient, WrapperClientError, WrapperInfo},
+    registry::{Registry, RegistryEntry},
+    utils::{get_mint_fingerprint, get_wrapper_token_mint},
+};
+
+use crate::{
+    output::{
+        print, CheckOutput, InfoOutput, InitOutput, ListEntryOutput, ListOutput, SwapOutput,
+        TransactionOutput, VerifyOutput, WrapperVerificationOutput,
+    },
+    Amount, Config,
+};
+
+/// Sends `instructions`, or simulates them with `--dry-run`
+async fn process(
+    config: &Config,
+    client: &WrapperClient,
+    instructions: &[Instruction],
+) -> anyhow::Result<TransactionOutput> {
+    if config.dry_run {
+        Ok(TransactionOutput::simulated(
+            client.simulate_instructions(instructions).await?,
+        ))
+    } else {
+        Ok(TransactionOutput::sent(
+            client.send_instructions(instructions).await?,
+        ))
+    }
+}
+
+async fn token_balance(client: &WrapperClient, token_account: &Pubkey) -> anyhow::Result<u64> {
+    let balance = client
+        .rpc_client()
+        .get_token_account_balance(token_account)
+        .await
+        .map_err(|e| anyhow!("failed to read the balance of {token_account}: {e}"))?;
+
+    Ok(balance.amount.parse()?)
+}
+
+/// Registry file at `path`, the embedded mainnet registry if `None`
+fn read_registry(path: Option<&Path>) -> anyhow::Result<Cow<'static, Registry>> {
+    let Some(path) = path else {
+        return Ok(Cow::Borrowed(Registry::mainnet()));
+    };
+
+    let json = fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read registry {}: {e}", path.display()))?;
+    let registry = Registry::from_json(&json)
+        .map_err(|e| anyhow!("failed to parse registry {}: {e}", path.display()))?;
+
+    Ok(Cow::Owned(registry))
+}
+
+pub async fn init(config: &Config, mint: &Pubkey) -> anyhow::Result<bool> {
+    let client = config.signing_client()?;
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*mint, token2022_wrapper::id());
+
+    let transaction = process(config, &client, &client.initialize_instructions(mint)).await?;
+    let succeeded = transaction.succeeded();
+
+    print(
+        config.output,
+        &InitOutput {
+            token_2022_mint: mint.to_string(),
+            wrapper_token_mint: wrapper_token_mint.to_string(),
+            transaction,
+        },
+    );
+
+    Ok(succeeded)
+}
+
+pub async fn wrap(config: &Config, mint: &Pubkey, amount: &Amount) -> anyhow::Result<bool> {
+    let client = config.signing_client()?;
+    let wrapper_info = client.get_wrapper_info(mint).await?;
+
+    let balance = match amount {
+        Amount::All => token_balance(&client, &client.token_2022_token_account(mint)).await?,
+        Amount::Tokens(_) => 0,
+    };
+    let amount = amount.to_base_units(wrapper_info.decimals, balance)?;
+
+    let (instructions, quote) = client.wrap_instructions(mint, amount).await?;
+    let transaction = process(config, &client, &instructions).await?;
+    let succeeded = transaction.succeeded();
+
+    print(
+        config.output,
+        &SwapOutput::new(mint.to_string(), &quote, wrapper_info.decimals, transaction),
+    );
+
+    Ok(succeeded)
+}
+
+pub async fn unwrap(config: &Config, mint: &Pubkey, amount: &Amount) -> anyhow::Result<bool> {
+    let client = config.signing_client()?;
+    let wrapper_info = client.get_wrapper_info(mint).await?;
+
+    let balance = match amount {
+        Amount::All => token_balance(&client, &client.wrapper_token_account(mint)).await?,
+        Amount::Tokens(_) => 0,
+    };
+    let amount = amount.to_base_units(wrapper_info.decimals, balance)?;
+
+    let (instructions, quote) = client.unwrap_instructions(mint, amount).await?;
+    let transaction = process(config, &client, &instructions).await?;
+    let succeeded = transaction.succeeded();
+
+    print(
+        config.output,
+        &SwapOutput::new(mint.to_string(), &quote, wrapper_info.decimals, transaction),
+    );
+
+    Ok(succeeded)
+}
+
+pub async fn info(config: &Config, mint: &Pubkey) -> anyhow::Result<bool> {
+    let client = config.read_only_client();
+    let wrapper_info = client.get_wrapper_info(mint).await?;
+
+    print(config.output, &InfoOutput::from(&wrapper_info));
+
+    Ok(true)
+}
+
+pub fn list(config: &Config, registry: Option<&Path>) -> anyhow::Result<bool> {
+    let entries = read_registry(registry)?
+        .entries()
+        .iter()
+        .map(|entry| ListEntryOutput {
+            token_name: entry.token_name.clone(),
+            token_2022_mint: entry.token_2022_mint.to_string(),
+            wrapper_token_mint: entry.wrapper_token_mint.to_string(),
+            decimals: entry.decimals,
+        })
+        .collect();
+
+    print(config.output, &ListOutput(entries));
+
+    Ok(true)
+}
+
+pub async fn verify(
+    config: &Config,
+    mint: Option<Pubkey>,
+    registry: Option<&Path>,
+) -> anyhow::Result<bool> {
+    let client = config.read_only_client();
+    let registry = read_registry(registry)?;
+
+    // A single mint is checked against the registry only if it is listed there, so wrappers
+    // missing from the registry can still be verified
+    let targets = match mint {
+        Some(mint) => vec![(mint, registry.find_by_token_2022_mint(&mint).cloned())],
+        None => registry
+            .entries()
+            .iter()
+            .map(|entry| (entry.token_2022_mint, Some(entry.clone())))
+            .collect(),
+    };
+
+    let mut wrappers = vec![];
+    for (mint, registry_entry) in targets {
+        let checks = verify_wrapper(&client, &mint, registry_entry.as_ref()).await?;
+        wrappers.push(WrapperVerificationOutput {
+            token_2022_mint: mint.to_string(),
+            passed: checks.iter().all(|check| check.passed),
+            checks,
+        });
+    }
+
+    let output = VerifyOutput {
+        passed: wrappers.iter().all(|wrapper| wrapper.passed),
+        wrappers,
+    };
+    print(config.output, &output);
+
+    Ok(output.passed)
+}
+
+pub async fn generate_registry(
+    config: &Config,
+    registry: Option<&Path>,
+    out: Option<&Path>,
+) -> anyhow::Result<bool> {
+    let client = config.read_only_client();
+    let generated = client
+        .generate_registry(&*read_registry(registry)?)
+        .await?
+        .to_json();
+
+    match out {
+        Some(out) => fs::write(out, generated)
+            .map_err(|e| anyhow!("failed to write registry {}: {e}", out.display()))?,
+        None => print!("{generated}"),
+    }
+
+    Ok(true)
+}
+
+fn check(name: &str, passed: bool, detail: Option<String>) -> CheckOutput {
+    CheckOutput {
+        name: name.to_string(),
+        passed,
+        detail: if passed { None } else { detail },
+    }
+}
+
+async fn verify_wrapper(
+    client: &WrapperClient,
+    mint: &Pubkey,
+    registry_entry: Option<&RegistryEntry>,
+) -> anyhow::Result<Vec<CheckOutput>> {
+    // The registry wrapper token mints are checked against their derivation when the registry
+    // is read
+    let mut checks = vec![];
+
+    let wrapper_info = match client.get_wrapper_info(mint).await {
+        Ok(wrapper_info) => wrapper_info,
+        Err(e @ WrapperClientError::WrapperNotInitialized(_))
+        | Err(e @ WrapperClientError::AccountNotFound(_)) => {
+            checks.push(check("wrapper initialized", false, Some(e.to_string())));
+            return Ok(checks);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    checks.push(check("wrapper initialized", true, None));
+
+    if let Some(entry) = registry_entry {
+        let result = entry.validate_mint(&wrapper_info.token_2022_mint_account.data);
+        checks.push(check(
+            "registry decimals",
+            result.is_ok(),
+            result.err().map(|e| e.to_string()),
+        ));
+    }
+
+    checks.push(verify_wrapper_token_mint_authorities(client, &wrapper_info).await?);
+    checks.push(verify_token_2022_mint_unchanged(&wrapper_info)?);
+    checks.push(check(
+        "supply fully backed",
+        wrapper_info.reserve_balance >= wrapper_info.wrapper_supply,
+        Some(format!(
+            "reserve holds {}, wrapper supply is {}",
+            wrapper_info.reserve_balance, wrapper_info.wrapper_supply
+        )),
+    ));
+
+    Ok(checks)
+}
+
+async fn verify_wrapper_token_mint_authorities(
+    client: &WrapperClient,
+    wrapper_info: &WrapperInfo,
+) -> anyhow::Result<CheckOutput> {
+    let wrapper_token_mint_account = client
+        .rpc_client()
+        .get_account(&wrapper_info.wrapper_token_mint)
+        .await?;
+    let wrapper_token_mint = spl_token::state::Mint::unpack(&wrapper_token_mint_account.data)?;
+
+    let expected = Some(wrapper_info.reserve_authority);
+    let mint_authority = Option::from(wrapper_token_mint.mint_authority);
+    let freeze_authority = Option::from(wrapper_token_mint.freeze_authority);
+
+    Ok(check(
+        "wrapper token mint authorities",
+        wrapper_token_mint_account.owner == spl_token::id()
+            && mint_authority == expected
+            && freeze_authority == expected,
+        Some(format!(
+            "mint authority {mint_authority:?}, freeze authority {freeze_authority:?}, expected \
+             the reserve authority {}",
+            wrapper_info.reserve_authority
+        )),
+    ))
+}
+
+fn verify_token_2022_mint_unchanged(wrapper_info: &WrapperInfo) -> anyhow::Result<CheckOutput> {
+    let recorded = wrapper_info
+        .wrapper_config
+        .as_ref()
+        .and_then(|config| config.recorded_fingerprint());
+
+    // Wrappers initialized before the fingerprint was recorded have nothing to compare against
+    let Some(recorded) = recorded else {
+        return Ok(check("token 2022 mint unchanged", true, None));
+    };
+
+    let mut token_2022_mint_account = wrapper_info.token_2022_mint_account.clone();
+    let fingerprint = get_mint_fingerprint(
+        &(&wrapper_info.token_2022_mint, &mut token_2022_mint_account).into_account_info(),
+    )?;
+
+    Ok(check(
+        "token 2022 mint unchanged",
+        fingerprint == recorded,
+        Some("the extensions differ from the ones at initialization".to_string()),
+    ))
+}
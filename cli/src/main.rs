@@ -0,0 +1,204 @@
+//! Command-line tool operating the Token 2022 wrapper program
+
+mod command;
+mod output;
+
+use std::{path::PathBuf, process::ExitCode, str::FromStr};
+
+use anyhow::anyhow;
+use clap::{Parser, Subcommand};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair},
+};
+use token2022_wrapper::client::WrapperClient;
+
+use crate::output::OutputFormat;
+
+#[derive(Parser)]
+#[command(
+    name = "token2022-wrapper",
+    version,
+    about = "Operates the Token 2022 wrapper program"
+)]
+struct Cli {
+    /// Solana CLI config file, for the default RPC URL and keypair
+    #[arg(long, short = 'C', global = true)]
+    config: Option<String>,
+    /// RPC URL, the one of the Solana CLI config by default
+    #[arg(long, short = 'u', global = true)]
+    url: Option<String>,
+    /// Keypair file paying and signing, the one of the Solana CLI config by default
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+    /// Simulates the transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Display, global = true)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates the wrapper of a Token 2022 mint
+    Init { mint: Pubkey },
+    /// Wraps Token 2022 tokens held by the keypair
+    Wrap {
+        mint: Pubkey,
+        /// Amount in tokens, e.g. `1.5`, or `all`
+        amount: Amount,
+    },
+    /// Unwraps wrapper tokens held by the keypair
+    Unwrap {
+        /// Token 2022 mint of the wrapper
+        mint: Pubkey,
+        /// Amount in tokens, e.g. `1.5`, or `all`
+        amount: Amount,
+    },
+    /// Shows the wrapper of a Token 2022 mint and the backing of its supply
+    Info { mint: Pubkey },
+    /// Lists the wrappers of the registry
+    List {
+        /// Registry file, the mainnet registry embedded in the program crate by default
+        #[arg(long)]
+        registry: Option<PathBuf>,
+    },
+    /// Checks the wrappers of the registry, or of a single mint: derivation, authorities,
+    /// decimals, Token 2022 mint unchanged and supply fully backed by the reserve
+    Verify {
+        mint: Option<Pubkey>,
+        /// Registry file, the mainnet registry embedded in the program crate by default
+        #[arg(long)]
+        registry: Option<PathBuf>,
+    },
+    /// Generates the registry from the wrappers configured on chain and the ones of the registry
+    GenerateRegistry {
+        /// Registry file, the mainnet registry embedded in the program crate by default
+        #[arg(long)]
+        registry: Option<PathBuf>,
+        /// File written with the generated registry, printed if omitted
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Amount of a wrap or unwrap, in tokens
+#[derive(Clone, Debug)]
+pub enum Amount {
+    /// Whole balance of the keypair
+    All,
+    Tokens(String),
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Amount::All);
+        }
+
+        s.parse::<f64>()
+            .map_err(|_| format!("invalid amount `{s}`, expected a number of tokens or `all`"))?;
+
+        Ok(Amount::Tokens(s.to_string()))
+    }
+}
+
+impl Amount {
+    /// Amount in base units, `balance` for `all`
+    pub fn to_base_units(&self, decimals: u8, balance: u64) -> anyhow::Result<u64> {
+        match self {
+            Amount::All => Ok(balance),
+            Amount::Tokens(tokens) => {
+                spl_token_2022::try_ui_amount_into_amount(tokens.clone(), decimals)
+                    .map_err(|_| anyhow!("`{tokens}` has more than {decimals} decimals"))
+            }
+        }
+    }
+}
+
+/// Settings shared by the commands
+pub struct Config {
+    pub json_rpc_url: String,
+    pub keypair_path: String,
+    pub dry_run: bool,
+    pub output: OutputFormat,
+}
+
+impl Config {
+    fn new(cli: &Cli) -> anyhow::Result<Self> {
+        let cli_config = match cli
+            .config
+            .as_ref()
+            .or(solana_cli_config::CONFIG_FILE.as_ref())
+        {
+            Some(config_file) => solana_cli_config::Config::load(config_file).unwrap_or_default(),
+            None => solana_cli_config::Config::default(),
+        };
+
+        Ok(Self {
+            json_rpc_url: cli.url.clone().unwrap_or(cli_config.json_rpc_url),
+            keypair_path: cli.keypair.clone().unwrap_or(cli_config.keypair_path),
+            dry_run: cli.dry_run,
+            output: cli.output,
+        })
+    }
+
+    pub fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.json_rpc_url.clone(), CommitmentConfig::confirmed())
+    }
+
+    /// Client signing with the keypair
+    pub fn signing_client(&self) -> anyhow::Result<WrapperClient> {
+        let payer = read_keypair_file(&self.keypair_path)
+            .map_err(|e| anyhow!("failed to read keypair {}: {e}", self.keypair_path))?;
+
+        Ok(WrapperClient::new(self.rpc_client(), payer))
+    }
+
+    /// Client of the read-only commands, which sign nothing
+    pub fn read_only_client(&self) -> WrapperClient {
+        WrapperClient::new(self.rpc_client(), Keypair::new())
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match Config::new(&cli) {
+        Ok(config) => run(&config, cli.command).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs `command`, `false` if it completed but reported a failure
+async fn run(config: &Config, command: Command) -> anyhow::Result<bool> {
+    match command {
+        Command::Init { mint } => command::init(config, &mint).await,
+        Command::Wrap { mint, amount } => command::wrap(config, &mint, &amount).await,
+        Command::Unwrap { mint, amount } => command::unwrap(config, &mint, &amount).await,
+        Command::Info { mint } => command::info(config, &mint).await,
+        Command::List { registry } => command::list(config, registry.as_deref()),
+        Command::Verify { mint, registry } => {
+            command::verify(config, mint, registry.as_deref()).await
+        }
+        Command::GenerateRegistry { registry, out } => {
+            command::generate_registry(config, registry.as_deref(), out.as_deref()).await
+        }
+    }
+}
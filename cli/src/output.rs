@@ -0,0 +1,372 @@
+use std::fmt::{self, Display, Formatter};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::signature::Signature;
+use token2022_wrapper::{client::WrapperInfo, quote::Quote, state::WrapperLimits};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Display,
+    Json,
+}
+
+pub fn print<T: Serialize + Display>(format: OutputFormat, output: &T) {
+    match format {
+        OutputFormat::Display => print!("{output}"),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(output).unwrap()),
+    }
+}
+
+fn ui_amount(amount: u64, decimals: u8) -> String {
+    spl_token::amount_to_ui_amount_string_trimmed(amount, decimals)
+}
+
+/// Sent transaction, or its simulation with `--dry-run`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SimulationOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationOutput {
+    pub error: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+impl TransactionOutput {
+    pub fn sent(signature: Signature) -> Self {
+        Self {
+            signature: Some(signature.to_string()),
+            simulation: None,
+        }
+    }
+
+    pub fn simulated(simulation: RpcSimulateTransactionResult) -> Self {
+        Self {
+            signature: None,
+            simulation: Some(SimulationOutput {
+                error: simulation.err.map(|e| e.to_string()),
+                units_consumed: simulation.units_consumed,
+                logs: simulation.logs.unwrap_or_default(),
+            }),
+        }
+    }
+
+    /// Whether the transaction was sent or simulated without error
+    pub fn succeeded(&self) -> bool {
+        self.simulation
+            .as_ref()
+            .is_none_or(|simulation| simulation.error.is_none())
+    }
+}
+
+impl Display for TransactionOutput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(signature) = &self.signature {
+            writeln!(f, "Signature: {signature}")?;
+        }
+        if let Some(simulation) = &self.simulation {
+            match &simulation.error {
+                Some(error) => writeln!(f, "Simulation failed: {error}")?,
+                None => writeln!(f, "Simulation succeeded")?,
+            }
+            if let Some(units_consumed) = simulation.units_consumed {
+                writeln!(f, "Compute units: {units_consumed}")?;
+            }
+            for log in &simulation.logs {
+                writeln!(f, "  {log}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitOutput {
+    pub token_2022_mint: String,
+    pub wrapper_token_mint: String,
+    pub transaction: TransactionOutput,
+}
+
+impl Display for InitOutput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Token 2022 mint: {}", self.token_2022_mint)?;
+        writeln!(f, "Wrapper token mint: {}", self.wrapper_token_mint)?;
+        write!(f, "{}", self.transaction)
+    }
+}
+
+/// Wrap or unwrap
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapOutput {
+    pub token_2022_mint: String,
+    pub amount_in: String,
+    pub transfer_fee: String,
+    pub wrapper_fee: String,
+    pub amount_out: String,
+    pub transaction: TransactionOutput,
+}
+
+impl SwapOutput {
+    pub fn new(
+        token_2022_mint: String,
+        quote: &Quote,
+        decimals: u8,
+        transaction: TransactionOutput,
+    ) -> Self {
+        Self {
+            token_2022_mint,
+            amount_in: ui_amount(quote.amount_in, decimals),
+            transfer_fee: ui_amount(quote.transfer_fee, decimals),
+            wrapper_fee: ui_amount(quote.wrapper_fee, decimals),
+            amount_out: ui_amount(quote.amount_out, decimals),
+            transaction,
+        }
+    }
+}
+
+impl Display for SwapOutput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Token 2022 mint: {}", self.token_2022_mint)?;
+        writeln!(f, "Amount in: {}", self.amount_in)?;
+        writeln!(f, "Transfer fee: {}", self.transfer_fee)?;
+        writeln!(f, "Wrapper fee: {}", self.wrapper_fee)?;
+        writeln!(f, "Amount out: {}", self.amount_out)?;
+        write!(f, "{}", self.transaction)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoOutput {
+    pub token_2022_mint: String,
+    pub wrapper_token_mint: String,
+    pub reserve_authority: String,
+    pub reserve_token_2022_token_account: String,
+    pub reserve_migrated: bool,
+    pub reserve_shards: u8,
+    pub decimals: u8,
+    pub extensions: Vec<String>,
+    pub reserve_balance: String,
+    /// Circulating wrapper tokens, without the wrapper inventory
+    pub wrapper_supply: String,
+    /// Pre-minted wrapper tokens held by the wrapper inventory, if the wrapper has one
+    pub wrapper_inventory: Option<String>,
+    /// Wrapper limits, `None` when not set
+    pub supply_cap: Option<String>,
+    pub epoch_wrap_limit: Option<String>,
+    pub epoch_unwrap_limit: Option<String>,
+    /// Reserve balance above the circulating wrapper token supply, negative if undercollateralized
+    pub surplus: String,
+    pub fully_backed: bool,
+    pub wrap_fee_basis_points: u16,
+    pub unwrap_fee_basis_points: u16,
+    pub maximum_fee: String,
+    pub paused: bool,
+    pub global_paused: bool,
+    pub retired: bool,
+}
+
+impl From<&WrapperInfo> for InfoOutput {
+    fn from(info: &WrapperInfo) -> Self {
+        let config = info.wrapper_config.clone().unwrap_or_default();
+        let surplus = match info.reserve_balance.checked_sub(info.wrapper_supply) {
+            Some(surplus) => ui_amount(surplus, info.decimals),
+            None => format!(
+                "-{}",
+                ui_amount(info.wrapper_supply - info.reserve_balance, info.decimals)
+            ),
+        };
+        let limit = |limit: fn(&WrapperLimits) -> u64| {
+            info.wrapper_limits
+                .as_ref()
+                .map(limit)
+                .filter(|limit| *limit > 0)
+                .map(|limit| ui_amount(limit, info.decimals))
+        };
+
+        Self {
+            token_2022_mint: info.token_2022_mint.to_string(),
+            wrapper_token_mint: info.wrapper_token_mint.to_string(),
+            reserve_authority: info.reserve_authority.to_string(),
+            reserve_token_2022_token_account: info.reserve_token_2022_token_account.to_string(),
+            reserve_migrated: config.migrated_reserve().is_some(),
+            reserve_shards: config.reserve_shard_count,
+            decimals: info.decimals,
+            extensions: info
+                .token_2022_extensions
+                .iter()
+                .map(|extension| format!("{extension:?}"))
+                .collect(),
+            reserve_balance: ui_amount(info.reserve_balance, info.decimals),
+            wrapper_supply: ui_amount(info.wrapper_supply, info.decimals),
+            wrapper_inventory: config
+                .has_wrapper_inventory
+                .then(|| ui_amount(info.wrapper_inventory_balance, info.decimals)),
+            supply_cap: limit(|limits| limits.supply_cap),
+            epoch_wrap_limit: limit(|limits| limits.epoch_wrap_limit),
+            epoch_unwrap_limit: limit(|limits| limits.epoch_unwrap_limit),
+            surplus,
+            fully_backed: info.reserve_balance >= info.wrapper_supply,
+            wrap_fee_basis_points: config.wrap_fee_basis_points,
+            unwrap_fee_basis_points: config.unwrap_fee_basis_points,
+            maximum_fee: ui_amount(config.maximum_fee, info.decimals),
+            paused: config.paused,
+            global_paused: info.global_paused,
+            retired: config.retired,
+        }
+    }
+}
+
+impl Display for InfoOutput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Token 2022 mint: {}", self.token_2022_mint)?;
+        writeln!(f, "Wrapper token mint: {}", self.wrapper_token_mint)?;
+        writeln!(f, "Reserve authority: {}", self.reserve_authority)?;
+        writeln!(
+            f,
+            "Reserve: {}{}",
+            self.reserve_token_2022_token_account,
+            if self.reserve_migrated {
+                " (migrated)"
+            } else {
+                ""
+            },
+        )?;
+        if self.reserve_shards > 0 {
+            writeln!(f, "Reserve shards: {}", self.reserve_shards)?;
+        }
+        writeln!(f, "Decimals: {}", self.decimals)?;
+        writeln!(f, "Extensions: {}", self.extensions.join(", "))?;
+        writeln!(f, "Reserve balance: {}", self.reserve_balance)?;
+        writeln!(f, "Wrapper supply: {}", self.wrapper_supply)?;
+        if let Some(wrapper_inventory) = &self.wrapper_inventory {
+            writeln!(f, "Wrapper inventory: {}", wrapper_inventory)?;
+        }
+        if self.supply_cap.is_some()
+            || self.epoch_wrap_limit.is_some()
+            || self.epoch_unwrap_limit.is_some()
+        {
+            let none = String::from("none");
+            writeln!(
+                f,
+                "Limits: supply cap {}, epoch wrap {}, epoch unwrap {}",
+                self.supply_cap.as_ref().unwrap_or(&none),
+                self.epoch_wrap_limit.as_ref().unwrap_or(&none),
+                self.epoch_unwrap_limit.as_ref().unwrap_or(&none),
+            )?;
+        }
+        writeln!(
+            f,
+            "Surplus: {}{}",
+            self.surplus,
+            if self.fully_backed {
+                ""
+            } else {
+                " (undercollateralized)"
+            },
+        )?;
+        writeln!(
+            f,
+            "Fees: wrap {} bps, unwrap {} bps, maximum {}",
+            self.wrap_fee_basis_points, self.unwrap_fee_basis_points, self.maximum_fee,
+        )?;
+        writeln!(
+            f,
+            "Deposits: {}",
+            if self.retired {
+                "retired"
+            } else if self.paused || self.global_paused {
+                "paused"
+            } else {
+                "open"
+            },
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEntryOutput {
+    pub token_name: String,
+    pub token_2022_mint: String,
+    pub wrapper_token_mint: String,
+    pub decimals: u8,
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct ListOutput(pub Vec<ListEntryOutput>);
+
+impl Display for ListOutput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for entry in &self.0 {
+            writeln!(
+                f,
+                "{:<12} {:<44} {:<44} {}",
+                entry.token_name, entry.token_2022_mint, entry.wrapper_token_mint, entry.decimals,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckOutput {
+    pub name: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrapperVerificationOutput {
+    pub token_2022_mint: String,
+    pub passed: bool,
+    pub checks: Vec<CheckOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyOutput {
+    pub passed: bool,
+    pub wrappers: Vec<WrapperVerificationOutput>,
+}
+
+impl Display for VerifyOutput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for wrapper in &self.wrappers {
+            writeln!(
+                f,
+                "{} {}",
+                wrapper.token_2022_mint,
+                if wrapper.passed { "OK" } else { "FAILED" },
+            )?;
+            for check in &wrapper.checks {
+                write!(
+                    f,
+                    "  [{}] {}",
+                    if check.passed { "ok" } else { "failed" },
+                    check.name,
+                )?;
+                match &check.detail {
+                    Some(detail) => writeln!(f, ": {detail}")?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
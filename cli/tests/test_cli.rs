@@ -0,0 +1,242 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::{program_pack::Pack, pubkey::Pubkey, system_instruction};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{write_keypair_file, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use token2022_wrapper::utils::get_wrapper_token_mint;
+
+const LOCAL_VALIDATOR_URL: &str = "http://127.0.0.1:8899";
+
+fn registry_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../sdk/mainnet_wrapper_mints.json")
+}
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_token2022-wrapper"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn cli_json(args: &[&str]) -> serde_json::Value {
+    let output = cli(&[args, &["--output", "json"]].concat());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Test 1 - list prints the registry entries
+///
+///
+#[test]
+fn test_1() {
+    let registry = registry_path();
+    let listed = cli_json(&["list", "--registry", registry.to_str().unwrap()]);
+
+    let entries: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&registry).unwrap()).unwrap();
+    assert_eq!(listed, entries);
+
+    // The registry embedded in the program crate is listed by default
+    assert_eq!(cli_json(&["list"]), entries);
+
+    let output = cli(&["list", "--registry", registry.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains("2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo"));
+
+    // The registry wrapper token mints are the PDAs of their Token 2022 mint
+    for entry in entries.as_array().unwrap() {
+        let token_2022_mint: Pubkey = entry["token2022Mint"].as_str().unwrap().parse().unwrap();
+        let (wrapper_token_mint, _, _) =
+            get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id());
+        assert_eq!(entry["wrapperTokenMint"], wrapper_token_mint.to_string());
+    }
+}
+
+/// Test 2 - invalid arguments, keypairs and RPC URLs are reported with a failure exit code
+///
+///
+#[test]
+fn test_2() {
+    let mint = Pubkey::new_unique().to_string();
+
+    let output = cli(&["wrap", &mint, "ten"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid amount `ten`"));
+
+    let output = cli(&["info", "not-a-pubkey"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = cli(&[
+        "wrap",
+        &mint,
+        "1.5",
+        "--keypair",
+        "/nonexistent/keypair.json",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to read keypair"));
+
+    let output = cli(&["info", &mint, "--url", "http://127.0.0.1:1"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Error: "));
+
+    let output = cli(&["list", "--registry", "/nonexistent/registry.json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to read registry"));
+}
+
+/// Test 3 - init, wrap, unwrap, info and verify against a local validator
+///
+/// Needs `solana-test-validator --bpf-program 22WrapbNKwPSy3HcGQTTJpgv43tszbZdTEfBEWmGYX2V
+/// target/deploy/token2022_wrapper.so` running, with the program built by `cargo build-sbf`.
+#[tokio::test]
+#[ignore]
+async fn test_3() {
+    let rpc_client = RpcClient::new_with_commitment(
+        LOCAL_VALIDATOR_URL.to_string(),
+        CommitmentConfig::confirmed(),
+    );
+
+    let payer = Keypair::new();
+    let keypair_path = std::env::temp_dir().join(format!("{}.json", payer.pubkey()));
+    write_keypair_file(&payer, &keypair_path).unwrap();
+    let keypair_path = keypair_path.to_str().unwrap();
+
+    let signature = rpc_client
+        .request_airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    while !rpc_client.confirm_transaction(&signature).await.unwrap() {}
+
+    // Token 2022 mint with 1_000 tokens of 6 decimals held by the payer
+    let token_2022_mint = Keypair::new();
+    let token_2022_token_account = get_associated_token_address_with_program_id(
+        &payer.pubkey(),
+        &token_2022_mint.pubkey(),
+        &spl_token_2022::id(),
+    );
+    let mint_len = spl_token_2022::state::Mint::LEN;
+    let instructions = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &token_2022_mint.pubkey(),
+            rpc_client
+                .get_minimum_balance_for_rent_exemption(mint_len)
+                .await
+                .unwrap(),
+            mint_len as u64,
+            &spl_token_2022::id(),
+        ),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::id(),
+            &token_2022_mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+        create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &token_2022_mint.pubkey(),
+            &spl_token_2022::id(),
+        ),
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::id(),
+            &token_2022_mint.pubkey(),
+            &token_2022_token_account,
+            &payer.pubkey(),
+            &[],
+            1_000_000_000,
+        )
+        .unwrap(),
+    ];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[&payer, &token_2022_mint],
+        rpc_client.get_latest_blockhash().await.unwrap(),
+    );
+    rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .await
+        .unwrap();
+
+    let mint = token_2022_mint.pubkey().to_string();
+    let args = |args: &[&str]| -> Vec<String> {
+        [
+            args,
+            &["--url", LOCAL_VALIDATOR_URL, "--keypair", keypair_path],
+        ]
+        .concat()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+    };
+    let run = |args: Vec<String>| cli_json(&args.iter().map(String::as_str).collect::<Vec<_>>());
+
+    // A dry run sends nothing
+    let simulated = run(args(&["init", &mint, "--dry-run"]));
+    assert!(simulated["transaction"]["simulation"]["error"].is_null());
+    assert!(cli(&args(&["info", &mint])
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>())
+    .stderr
+    .starts_with(b"Error: "));
+
+    let initialized = run(args(&["init", &mint]));
+    let (wrapper_token_mint, _, _) =
+        get_wrapper_token_mint(token_2022_mint.pubkey(), token2022_wrapper::id());
+    assert_eq!(
+        initialized["wrapperTokenMint"],
+        wrapper_token_mint.to_string()
+    );
+
+    // The generated registry lists the wrapper, named after its mint without token metadata
+    let empty_registry = std::env::temp_dir().join(format!("{}-registry.json", payer.pubkey()));
+    std::fs::write(&empty_registry, "[]").unwrap();
+    let generated = run(args(&[
+        "generate-registry",
+        "--registry",
+        empty_registry.to_str().unwrap(),
+    ]));
+    assert!(generated.as_array().unwrap().contains(&serde_json::json!({
+        "token2022Mint": mint,
+        "tokenName": mint,
+        "decimals": 6,
+        "wrapperTokenMint": wrapper_token_mint.to_string(),
+    })));
+
+    let wrapped = run(args(&["wrap", &mint, "250.5"]));
+    assert_eq!(wrapped["amountOut"], "250.5");
+
+    let info = run(args(&["info", &mint]));
+    assert_eq!(info["reserveBalance"], "250.5");
+    assert_eq!(info["wrapperSupply"], "250.5");
+    assert_eq!(info["fullyBacked"], true);
+
+    let unwrapped = run(args(&["unwrap", &mint, "all"]));
+    assert_eq!(unwrapped["amountOut"], "250.5");
+
+    let verified = run(args(&["verify", &mint]));
+    assert_eq!(verified["passed"], true);
+}
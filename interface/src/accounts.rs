@@ -0,0 +1,549 @@
+//! Accounts of each instruction, in the order expected by the program
+//!
+//! Each struct is named after its instruction and converts to the `AccountMeta`s and
+//! `AccountInfo`s of the instruction. Optional trailing accounts are `Option` fields, left out of
+//! the instruction when `None`.
+
+use solana_program::{account_info::AccountInfo, instruction::AccountMeta};
+
+macro_rules! account_meta {
+    (readonly, $account:expr) => {
+        Some(AccountMeta::new_readonly(*$account.key, false))
+    };
+    (writable, $account:expr) => {
+        Some(AccountMeta::new(*$account.key, false))
+    };
+    (signer, $account:expr) => {
+        Some(AccountMeta::new_readonly(*$account.key, true))
+    };
+    (writable_signer, $account:expr) => {
+        Some(AccountMeta::new(*$account.key, true))
+    };
+    (optional_writable, $account:expr) => {
+        $account.map(|account| AccountMeta::new(*account.key, false))
+    };
+}
+
+macro_rules! account_info {
+    (optional_writable, $account:expr) => {
+        $account.cloned()
+    };
+    ($access:ident, $account:expr) => {
+        Some($account.clone())
+    };
+}
+
+macro_rules! account_field {
+    (optional_writable, $a:lifetime, $info:lifetime) => {
+        Option<&$a AccountInfo<$info>>
+    };
+    ($access:ident, $a:lifetime, $info:lifetime) => {
+        &$a AccountInfo<$info>
+    };
+}
+
+macro_rules! accounts {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$field_meta:meta])* $field:ident: $access:ident,)*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Copy, Debug)]
+            pub struct $name<'a, 'info> {
+                $($(#[$field_meta])* pub $field: account_field!($access, 'a, 'info),)*
+            }
+
+            impl<'a, 'info> $name<'a, 'info> {
+                pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+                    [$(account_meta!($access, self.$field)),*].into_iter().flatten().collect()
+                }
+
+                pub fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                    [$(account_info!($access, self.$field)),*].into_iter().flatten().collect()
+                }
+            }
+        )*
+    };
+}
+
+accounts! {
+    /// Accounts of `InitializeWrapperToken`
+    InitializeWrapperToken {
+        /// Pays for the wrapper token mint, the reserve and the wrapper config
+        payer: writable_signer,
+        token_2022_mint: readonly,
+        /// Uninitialized wrapper token mint PDA
+        wrapper_token_mint: writable,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Uninitialized reserve token account PDA
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        system_program: readonly,
+        rent: readonly,
+    }
+
+    /// Accounts of `DepositAndMintWrapperTokens`
+    ///
+    /// Fails for wrappers charging a fee or with limits, which need the fee vault and the wrapper
+    /// limits PDAs of the v3 instructions
+    DepositAndMintWrapperTokens {
+        user_authority: writable_signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Current reserve of the wrapper
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        system_program: readonly,
+        associated_token_program: readonly,
+        rent: readonly,
+        /// Program config PDA
+        program_config: readonly,
+    }
+
+    /// Accounts of `WithdrawAndBurnWrapperTokens`
+    ///
+    /// Fails for wrappers charging a fee or with limits, which need the fee vault and the wrapper
+    /// limits PDAs of the v3 instructions
+    WithdrawAndBurnWrapperTokens {
+        user_authority: writable_signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Current reserve of the wrapper
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        system_program: readonly,
+        rent: readonly,
+    }
+
+    /// Accounts of `InitializeWrapperTokenV2`
+    InitializeWrapperTokenV2 {
+        /// Pays for the wrapper token mint, the reserve and the wrapper config
+        payer: writable_signer,
+        token_2022_mint: readonly,
+        /// Uninitialized wrapper token mint PDA
+        wrapper_token_mint: writable,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Uninitialized reserve token account PDA
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        system_program: readonly,
+    }
+
+    /// Accounts of `DepositAndMintWrapperTokensV2`
+    ///
+    /// Fails for wrappers charging a fee or with limits, which need the fee vault and the wrapper
+    /// limits PDAs of the v3 instructions
+    DepositAndMintWrapperTokensV2 {
+        user_authority: signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Current reserve of the wrapper
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        /// Program config PDA
+        program_config: readonly,
+    }
+
+    /// Accounts of `WithdrawAndBurnWrapperTokensV2`
+    ///
+    /// Fails for wrappers charging a fee or with limits, which need the fee vault and the wrapper
+    /// limits PDAs of the v3 instructions
+    WithdrawAndBurnWrapperTokensV2 {
+        user_authority: signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Current reserve of the wrapper
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+    }
+
+    /// Accounts of `SetFee`
+    SetFee {
+        /// Program admin, pays for the wrapper config and the fee vault
+        admin: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Wrapper token account receiving the collected fees
+        fee_recipient: readonly,
+        system_program: readonly,
+        /// Fee vault PDA, created on first use
+        fee_vault: writable,
+        token_program: readonly,
+    }
+
+    /// Accounts of `CollectFees`
+    CollectFees {
+        /// Program admin
+        admin: signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        /// Fee vault PDA
+        fee_vault: writable,
+        /// Wrapper token account receiving the collected fees
+        fee_recipient: writable,
+        token_program: readonly,
+    }
+
+    /// Accounts of `InitializeProgramConfig`
+    InitializeProgramConfig {
+        /// Program upgrade authority, pays for the program config
+        upgrade_authority: writable_signer,
+        program_data: readonly,
+        /// Uninitialized program config PDA
+        program_config: writable,
+        system_program: readonly,
+    }
+
+    /// Accounts of `SetPause`
+    SetPause {
+        /// Program admin, pays for the wrapper config
+        admin: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        system_program: readonly,
+    }
+
+    /// Accounts of `SetGlobalPause`
+    SetGlobalPause {
+        /// Program admin
+        admin: signer,
+        /// Program config PDA
+        program_config: writable,
+    }
+
+    /// Accounts of `SetPendingAdmin`
+    SetPendingAdmin {
+        /// Program admin
+        admin: signer,
+        /// Program config PDA
+        program_config: writable,
+    }
+
+    /// Accounts of `AcceptAdmin`
+    AcceptAdmin {
+        pending_admin: signer,
+        /// Program config PDA
+        program_config: writable,
+    }
+
+    /// Accounts of `MirrorFreeze`
+    MirrorFreeze {
+        /// Freeze authority of the Token 2022 mint, paying for the issuer freeze, or any signer
+        /// when mirroring the Token 2022 token account state
+        authority: writable_signer,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        wrapper_token_account: writable,
+        /// Token 2022 associated token account of the wrapper token account owner
+        token_2022_token_account: readonly,
+        token_program: readonly,
+        /// Issuer freeze PDA of the wrapper token account
+        issuer_freeze: writable,
+        system_program: readonly,
+    }
+
+    /// Accounts of `MirrorThaw`
+    MirrorThaw {
+        /// Freeze authority of the Token 2022 mint, paying for the issuer freeze, or any signer
+        /// when mirroring the Token 2022 token account state
+        authority: writable_signer,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        wrapper_token_account: writable,
+        /// Token 2022 associated token account of the wrapper token account owner
+        token_2022_token_account: readonly,
+        token_program: readonly,
+        /// Issuer freeze PDA of the wrapper token account
+        issuer_freeze: writable,
+        system_program: readonly,
+    }
+
+    /// Accounts of `MigrateReserve`
+    ///
+    /// The program expects the reserve shards of the wrapper after them, in order, then the
+    /// wrapper inventory if the wrapper has one, which are not part of the struct
+    MigrateReserve {
+        /// Program admin, pays for the wrapper config and the associated token account
+        admin: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Reserve token account PDA
+        reserve_token_2022_token_account: writable,
+        /// Associated token account of the reserve authority
+        new_reserve_token_2022_token_account: writable,
+        token_2022_program: readonly,
+        associated_token_program: readonly,
+        system_program: readonly,
+    }
+
+    /// Accounts of `ReallocReserve`
+    ReallocReserve {
+        /// Pays for the additional rent of the reserve
+        payer: writable_signer,
+        token_2022_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        /// Current reserve of the wrapper
+        reserve_token_2022_token_account: writable,
+        token_2022_program: readonly,
+        system_program: readonly,
+    }
+
+    /// Accounts of `RetireWrapper`
+    RetireWrapper {
+        /// Program admin, or any signer once the Token 2022 mint has changed. Pays for the
+        /// wrapper config
+        authority: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        system_program: readonly,
+    }
+
+    /// Accounts of `RegisterWrapperGroupMember`
+    RegisterWrapperGroupMember {
+        /// Pays for the wrapper group accounts
+        payer: writable_signer,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Wrapper group PDA
+        wrapper_group: writable,
+        /// Wrapper group member PDA
+        wrapper_group_member: writable,
+        system_program: readonly,
+    }
+
+    /// Accounts of `QuoteWrap`
+    QuoteWrap {
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        /// Program config PDA
+        program_config: readonly,
+    }
+
+    /// Accounts of `QuoteUnwrap`
+    QuoteUnwrap {
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+    }
+
+    /// Accounts of `InitializeReserveShard`
+    InitializeReserveShard {
+        /// Program admin, pays for the wrapper config and the reserve shard
+        admin: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Uninitialized reserve shard PDA, the next one of the wrapper
+        reserve_shard: writable,
+        token_2022_program: readonly,
+        system_program: readonly,
+    }
+
+    /// Accounts of `RebalanceReserveShards`
+    ///
+    /// The program expects the reserve shards of the wrapper after them, in order, then the
+    /// wrapper inventory if the wrapper has one, which are not part of the struct
+    RebalanceReserveShards {
+        /// Program admin
+        admin: signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_program: readonly,
+        /// Current reserve of the wrapper
+        reserve_token_2022_token_account: writable,
+    }
+
+    /// Accounts of `DepositAndMintWrapperTokensV3`
+    DepositAndMintWrapperTokensV3 {
+        user_authority: signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Reserve shard of the deposit, the current reserve of the wrapper for shard zero
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        /// Program config PDA
+        program_config: readonly,
+        /// Fee vault PDA, only used if the wrapper has one
+        fee_vault: writable,
+        /// Wrapper limits PDA, only used if the wrapper has them
+        wrapper_limits: writable,
+    }
+
+    /// Accounts of `WithdrawAndBurnWrapperTokensV3`
+    WithdrawAndBurnWrapperTokensV3 {
+        user_authority: signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Reserve shard of the withdrawal, the current reserve of the wrapper for shard zero
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        /// Fee vault PDA, only used if the wrapper has one
+        fee_vault: writable,
+        /// Wrapper limits PDA, only used if the wrapper has them
+        wrapper_limits: writable,
+    }
+
+    /// Accounts of `InitializeWrapperInventory`
+    InitializeWrapperInventory {
+        /// Program admin, pays for the wrapper config and the wrapper inventory
+        admin: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Uninitialized wrapper inventory PDA
+        wrapper_inventory: writable,
+        token_program: readonly,
+        system_program: readonly,
+    }
+
+    /// Accounts of `TopUpWrapperInventory`
+    TopUpWrapperInventory {
+        /// Program admin
+        admin: signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: writable,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        /// Wrapper inventory PDA
+        wrapper_inventory: writable,
+        token_program: readonly,
+        /// Wrapper limits PDA, passed only if the wrapper has them
+        wrapper_limits: optional_writable,
+    }
+
+    /// Accounts of `DepositFromWrapperInventory`
+    DepositFromWrapperInventory {
+        user_authority: signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Reserve shard of the deposit, the current reserve of the wrapper for shard zero
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        /// Program config PDA
+        program_config: readonly,
+        /// Wrapper inventory PDA
+        wrapper_inventory: writable,
+        /// Fee vault PDA, only used if the wrapper has one
+        fee_vault: writable,
+        /// Wrapper limits PDA, only used if the wrapper has them
+        wrapper_limits: writable,
+    }
+
+    /// Accounts of `WithdrawToWrapperInventory`
+    WithdrawToWrapperInventory {
+        user_authority: signer,
+        /// Reserve authority PDA
+        reserve_authority: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        user_wrapper_token_account: writable,
+        user_token_2022_token_account: writable,
+        /// Reserve shard of the withdrawal, the current reserve of the wrapper for shard zero
+        reserve_token_2022_token_account: writable,
+        token_program: readonly,
+        token_2022_program: readonly,
+        /// Wrapper inventory PDA
+        wrapper_inventory: writable,
+        /// Fee vault PDA, only used if the wrapper has one
+        fee_vault: writable,
+        /// Wrapper limits PDA, only used if the wrapper has them
+        wrapper_limits: writable,
+    }
+
+    /// Accounts of `SetWrapperLimits`
+    SetWrapperLimits {
+        /// Program admin, pays for the wrapper config and the wrapper limits
+        admin: writable_signer,
+        /// Program config PDA
+        program_config: readonly,
+        token_2022_mint: readonly,
+        wrapper_token_mint: readonly,
+        /// Reserve authority PDA
+        reserve_authority: writable,
+        /// Wrapper limits PDA, created on first use
+        wrapper_limits: writable,
+        system_program: readonly,
+    }
+}
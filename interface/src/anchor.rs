@@ -0,0 +1,11 @@
+//! Anchor declaration of the program, generated by `declare_program!` from
+//! `idls/token2022_wrapper.json`
+//!
+//! Provides the `Token2022Wrapper` program type, the `cpi` functions taking a `CpiContext` with
+//! their `cpi::accounts` structs, the `client` instruction builders and the program accounts,
+//! which deserialize with their one byte account type as Anchor discriminator. Anchor programs
+//! can instead copy the IDL to their own `idls` directory and declare the program themselves.
+
+anchor_lang::declare_program!(token2022_wrapper);
+
+pub use token2022_wrapper::*;
@@ -0,0 +1,233 @@
+//! `invoke` and `invoke_signed` helpers for each instruction
+//!
+//! For an instruction `X`, `x_instruction` builds the instruction, `x` invokes it and `x_signed`
+//! invokes it with the seeds of the PDAs signing it, e.g. a PDA owning the user token accounts.
+//! `wrap` and `unwrap` are shorthands for the v3 deposit and withdrawal of an amount into and from
+//! the reserve, which take the fee vault and the wrapper limits of wrappers that have them.
+//!
+//! The quote instructions sign nothing and return data, `quote_wrap` and `quote_unwrap` invoke
+//! them and decode their `Quote`.
+
+use solana_program::{
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program::{get_return_data, invoke, invoke_signed},
+    program_error::ProgramError,
+};
+
+use crate::{
+    accounts,
+    instruction::{
+        AmountArgs, DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, Quote,
+        QuoteArgs, RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, TokenWrapperInstruction, WithdrawAndBurnWrapperTokensArgs,
+        WithdrawAndBurnWrapperTokensV3Args,
+    },
+};
+
+macro_rules! cpi {
+    ($(
+        $variant:ident $(($arg:ident: $args:ty))? =>
+            $instruction:ident, $invoke:ident, $invoke_signed:ident;
+    )*) => {
+        $(
+            #[doc = concat!("Builds a `", stringify!($variant), "` instruction")]
+            pub fn $instruction(
+                accounts: &accounts::$variant,
+                $($arg: $args,)?
+            ) -> Instruction {
+                Instruction {
+                    program_id: crate::id(),
+                    accounts: accounts.to_account_metas(),
+                    data: TokenWrapperInstruction::$variant $(($arg))?.pack(),
+                }
+            }
+
+            #[doc = concat!("Invokes `", stringify!($variant), "`")]
+            pub fn $invoke(
+                accounts: &accounts::$variant,
+                $($arg: $args,)?
+            ) -> ProgramResult {
+                invoke(
+                    &$instruction(accounts $(, $arg)?),
+                    &accounts.to_account_infos(),
+                )
+            }
+
+            #[doc = concat!("Invokes `", stringify!($variant), "` signed by `signers_seeds`")]
+            pub fn $invoke_signed(
+                accounts: &accounts::$variant,
+                $($arg: $args,)?
+                signers_seeds: &[&[&[u8]]],
+            ) -> ProgramResult {
+                invoke_signed(
+                    &$instruction(accounts $(, $arg)?),
+                    &accounts.to_account_infos(),
+                    signers_seeds,
+                )
+            }
+        )*
+    };
+}
+
+cpi! {
+    InitializeWrapperToken =>
+        initialize_wrapper_token_instruction, initialize_wrapper_token,
+        initialize_wrapper_token_signed;
+    DepositAndMintWrapperTokens(args: AmountArgs) =>
+        deposit_and_mint_wrapper_tokens_instruction, deposit_and_mint_wrapper_tokens,
+        deposit_and_mint_wrapper_tokens_signed;
+    WithdrawAndBurnWrapperTokens(args: AmountArgs) =>
+        withdraw_and_burn_wrapper_tokens_instruction, withdraw_and_burn_wrapper_tokens,
+        withdraw_and_burn_wrapper_tokens_signed;
+    InitializeWrapperTokenV2 =>
+        initialize_wrapper_token_v2_instruction, initialize_wrapper_token_v2,
+        initialize_wrapper_token_v2_signed;
+    DepositAndMintWrapperTokensV2(args: DepositAndMintWrapperTokensArgs) =>
+        deposit_and_mint_wrapper_tokens_v2_instruction, deposit_and_mint_wrapper_tokens_v2,
+        deposit_and_mint_wrapper_tokens_v2_signed;
+    WithdrawAndBurnWrapperTokensV2(args: WithdrawAndBurnWrapperTokensArgs) =>
+        withdraw_and_burn_wrapper_tokens_v2_instruction, withdraw_and_burn_wrapper_tokens_v2,
+        withdraw_and_burn_wrapper_tokens_v2_signed;
+    SetFee(args: SetFeeArgs) => set_fee_instruction, set_fee, set_fee_signed;
+    CollectFees => collect_fees_instruction, collect_fees, collect_fees_signed;
+    InitializeProgramConfig =>
+        initialize_program_config_instruction, initialize_program_config,
+        initialize_program_config_signed;
+    SetPause(args: SetPauseArgs) => set_pause_instruction, set_pause, set_pause_signed;
+    SetGlobalPause(args: SetPauseArgs) =>
+        set_global_pause_instruction, set_global_pause, set_global_pause_signed;
+    SetPendingAdmin(args: SetPendingAdminArgs) =>
+        set_pending_admin_instruction, set_pending_admin, set_pending_admin_signed;
+    AcceptAdmin => accept_admin_instruction, accept_admin, accept_admin_signed;
+    MirrorFreeze => mirror_freeze_instruction, mirror_freeze, mirror_freeze_signed;
+    MirrorThaw => mirror_thaw_instruction, mirror_thaw, mirror_thaw_signed;
+    MigrateReserve => migrate_reserve_instruction, migrate_reserve, migrate_reserve_signed;
+    ReallocReserve => realloc_reserve_instruction, realloc_reserve, realloc_reserve_signed;
+    RetireWrapper => retire_wrapper_instruction, retire_wrapper, retire_wrapper_signed;
+    RegisterWrapperGroupMember =>
+        register_wrapper_group_member_instruction, register_wrapper_group_member,
+        register_wrapper_group_member_signed;
+    InitializeReserveShard =>
+        initialize_reserve_shard_instruction, initialize_reserve_shard,
+        initialize_reserve_shard_signed;
+    RebalanceReserveShards(args: RebalanceReserveShardsArgs) =>
+        rebalance_reserve_shards_instruction, rebalance_reserve_shards,
+        rebalance_reserve_shards_signed;
+    DepositAndMintWrapperTokensV3(args: DepositAndMintWrapperTokensV3Args) =>
+        deposit_and_mint_wrapper_tokens_v3_instruction, deposit_and_mint_wrapper_tokens_v3,
+        deposit_and_mint_wrapper_tokens_v3_signed;
+    WithdrawAndBurnWrapperTokensV3(args: WithdrawAndBurnWrapperTokensV3Args) =>
+        withdraw_and_burn_wrapper_tokens_v3_instruction, withdraw_and_burn_wrapper_tokens_v3,
+        withdraw_and_burn_wrapper_tokens_v3_signed;
+    InitializeWrapperInventory =>
+        initialize_wrapper_inventory_instruction, initialize_wrapper_inventory,
+        initialize_wrapper_inventory_signed;
+    TopUpWrapperInventory(args: AmountArgs) =>
+        top_up_wrapper_inventory_instruction, top_up_wrapper_inventory,
+        top_up_wrapper_inventory_signed;
+    DepositFromWrapperInventory(args: DepositAndMintWrapperTokensV3Args) =>
+        deposit_from_wrapper_inventory_instruction, deposit_from_wrapper_inventory,
+        deposit_from_wrapper_inventory_signed;
+    WithdrawToWrapperInventory(args: WithdrawAndBurnWrapperTokensV3Args) =>
+        withdraw_to_wrapper_inventory_instruction, withdraw_to_wrapper_inventory,
+        withdraw_to_wrapper_inventory_signed;
+    SetWrapperLimits(args: SetWrapperLimitsArgs) =>
+        set_wrapper_limits_instruction, set_wrapper_limits, set_wrapper_limits_signed;
+}
+
+/// Wraps `amount` Token 2022 tokens, without minimum output or memo
+pub fn wrap(accounts: &accounts::DepositAndMintWrapperTokensV3, amount: u64) -> ProgramResult {
+    deposit_and_mint_wrapper_tokens_v3(accounts, wrap_args(amount))
+}
+
+/// Wraps `amount` Token 2022 tokens signed by `signers_seeds`, without minimum output or memo
+pub fn wrap_signed(
+    accounts: &accounts::DepositAndMintWrapperTokensV3,
+    amount: u64,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    deposit_and_mint_wrapper_tokens_v3_signed(accounts, wrap_args(amount), signers_seeds)
+}
+
+/// Unwraps `amount` wrapper tokens, without minimum output or memo
+pub fn unwrap(accounts: &accounts::WithdrawAndBurnWrapperTokensV3, amount: u64) -> ProgramResult {
+    withdraw_and_burn_wrapper_tokens_v3(accounts, unwrap_args(amount))
+}
+
+/// Unwraps `amount` wrapper tokens signed by `signers_seeds`, without minimum output or memo
+pub fn unwrap_signed(
+    accounts: &accounts::WithdrawAndBurnWrapperTokensV3,
+    amount: u64,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    withdraw_and_burn_wrapper_tokens_v3_signed(accounts, unwrap_args(amount), signers_seeds)
+}
+
+/// Builds a `QuoteWrap` instruction
+pub fn quote_wrap_instruction(accounts: &accounts::QuoteWrap, args: QuoteArgs) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts.to_account_metas(),
+        data: TokenWrapperInstruction::QuoteWrap(args).pack(),
+    }
+}
+
+/// Builds a `QuoteUnwrap` instruction
+pub fn quote_unwrap_instruction(accounts: &accounts::QuoteUnwrap, args: QuoteArgs) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts.to_account_metas(),
+        data: TokenWrapperInstruction::QuoteUnwrap(args).pack(),
+    }
+}
+
+/// Wrapper tokens minted for `amount` Token 2022 tokens at the current epoch, by `QuoteWrap`
+pub fn quote_wrap(accounts: &accounts::QuoteWrap, amount: u64) -> Result<Quote, ProgramError> {
+    invoke(
+        &quote_wrap_instruction(accounts, QuoteArgs { amount }),
+        &accounts.to_account_infos(),
+    )?;
+    get_quote()
+}
+
+/// Token 2022 tokens received for `amount` wrapper tokens at the current epoch, by `QuoteUnwrap`
+pub fn quote_unwrap(accounts: &accounts::QuoteUnwrap, amount: u64) -> Result<Quote, ProgramError> {
+    invoke(
+        &quote_unwrap_instruction(accounts, QuoteArgs { amount }),
+        &accounts.to_account_infos(),
+    )?;
+    get_quote()
+}
+
+/// `Quote` returned by the last quote instruction invoked
+fn get_quote() -> Result<Quote, ProgramError> {
+    match get_return_data() {
+        Some((program_id, data)) if program_id == crate::id() => {
+            borsh1::from_slice(&data).map_err(|_| ProgramError::InvalidAccountData)
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Deposit of `amount` into the reserve, shard zero
+fn wrap_args(amount: u64) -> DepositAndMintWrapperTokensV3Args {
+    DepositAndMintWrapperTokensV3Args {
+        reserve_shard: 0,
+        args: DepositAndMintWrapperTokensArgs {
+            amount,
+            ..Default::default()
+        },
+    }
+}
+
+/// Withdrawal of `amount` from the reserve, shard zero
+fn unwrap_args(amount: u64) -> WithdrawAndBurnWrapperTokensV3Args {
+    WithdrawAndBurnWrapperTokensV3Args {
+        reserve_shard: 0,
+        args: WithdrawAndBurnWrapperTokensArgs {
+            amount,
+            ..Default::default()
+        },
+    }
+}
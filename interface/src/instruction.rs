@@ -0,0 +1,198 @@
+use borsh1::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Arguments of the v1 deposit and withdraw instructions, a plain little-endian `u64` amount
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AmountArgs {
+    /// Amount of tokens to deposit or withdraw, `u64::MAX` uses the full balance
+    pub amount: u64,
+}
+
+/// Arguments of the `DepositAndMintWrapperTokensV2` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepositAndMintWrapperTokensArgs {
+    /// Amount of Token 2022 tokens to deposit, `u64::MAX` uses the full balance
+    pub amount: u64,
+    /// Minimum amount of wrapper tokens to be minted after Token 2022 transfer fees
+    pub min_out: u64,
+    /// Reserved for future use, must be zero
+    pub flags: u8,
+    /// Optional memo logged by the program
+    pub memo: Option<String>,
+}
+
+/// Arguments of the `WithdrawAndBurnWrapperTokensV2` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawAndBurnWrapperTokensArgs {
+    /// Amount of wrapper tokens to burn, `u64::MAX` uses the full balance
+    pub amount: u64,
+    /// Minimum amount of Token 2022 tokens to be received after Token 2022 transfer fees
+    pub min_out: u64,
+    /// Reserved for future use, must be zero
+    pub flags: u8,
+    /// Optional memo logged by the program
+    pub memo: Option<String>,
+}
+
+/// Arguments of the `SetFee` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetFeeArgs {
+    /// Fee charged on wraps, at most `MAX_FEE_BASIS_POINTS`
+    pub wrap_fee_basis_points: u16,
+    /// Fee charged on unwraps, at most `MAX_FEE_BASIS_POINTS`
+    pub unwrap_fee_basis_points: u16,
+    /// Maximum fee charged by a single wrap or unwrap, zero for no maximum
+    pub maximum_fee: u64,
+}
+
+/// Arguments of the `SetPause` and `SetGlobalPause` instructions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetPauseArgs {
+    /// Whether deposits are paused, withdrawals are never paused
+    pub paused: bool,
+}
+
+/// Arguments of the `SetPendingAdmin` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetPendingAdminArgs {
+    /// Admin proposed to replace the current one, takes over once it calls `AcceptAdmin`
+    pub pending_admin: Pubkey,
+}
+
+/// Arguments of the `QuoteWrap` and `QuoteUnwrap` instructions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuoteArgs {
+    /// Amount of Token 2022 tokens to wrap, or of wrapper tokens to unwrap
+    pub amount: u64,
+}
+
+/// Arguments of the `RebalanceReserveShards` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RebalanceReserveShardsArgs {
+    /// Reserve shard the Token 2022 tokens are moved from, zero for the reserve
+    pub source_reserve_shard: u8,
+    /// Reserve shard the Token 2022 tokens are moved to, zero for the reserve
+    pub destination_reserve_shard: u8,
+    /// Amount of Token 2022 tokens to move, `u64::MAX` uses the full balance of the source
+    pub amount: u64,
+}
+
+/// Arguments of the `DepositAndMintWrapperTokensV3` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepositAndMintWrapperTokensV3Args {
+    /// Reserve shard receiving the deposit, zero for the reserve
+    pub reserve_shard: u8,
+    pub args: DepositAndMintWrapperTokensArgs,
+}
+
+/// Arguments of the `WithdrawAndBurnWrapperTokensV3` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawAndBurnWrapperTokensV3Args {
+    /// Reserve shard the withdrawal is paid from, zero for the reserve
+    pub reserve_shard: u8,
+    pub args: WithdrawAndBurnWrapperTokensArgs,
+}
+
+/// Arguments of the `SetWrapperLimits` instruction, zero disables a limit
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetWrapperLimitsArgs {
+    /// Maximum wrapper token supply
+    pub supply_cap: u64,
+    /// Maximum of wrapped minus unwrapped wrapper tokens in an epoch
+    pub epoch_wrap_limit: u64,
+    /// Maximum of unwrapped minus wrapped wrapper tokens in an epoch
+    pub epoch_unwrap_limit: u64,
+}
+
+/// Return data of the `QuoteWrap` and `QuoteUnwrap` instructions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quote {
+    /// Token 2022 tokens deposited when wrapping, wrapper tokens burned when unwrapping
+    pub amount_in: u64,
+    /// Token 2022 transfer fee withheld on the transfer to or from the reserve
+    pub transfer_fee: u64,
+    /// Wrap or unwrap fee collected in the fee vault
+    pub wrapper_fee: u64,
+    /// Wrapper tokens minted when wrapping, Token 2022 tokens received when unwrapping
+    pub amount_out: u64,
+}
+
+/// Instructions of the wrapper program
+///
+/// The accounts of each instruction are described by the struct of the same name in
+/// `crate::accounts`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TokenWrapperInstruction {
+    /// 0
+    InitializeWrapperToken,
+    /// 1
+    DepositAndMintWrapperTokens(AmountArgs),
+    /// 2
+    WithdrawAndBurnWrapperTokens(AmountArgs),
+    /// 3
+    InitializeWrapperTokenV2,
+    /// 4
+    DepositAndMintWrapperTokensV2(DepositAndMintWrapperTokensArgs),
+    /// 5
+    WithdrawAndBurnWrapperTokensV2(WithdrawAndBurnWrapperTokensArgs),
+    /// 6
+    SetFee(SetFeeArgs),
+    /// 7
+    CollectFees,
+    /// 8
+    InitializeProgramConfig,
+    /// 9
+    SetPause(SetPauseArgs),
+    /// 10
+    SetGlobalPause(SetPauseArgs),
+    /// 11
+    SetPendingAdmin(SetPendingAdminArgs),
+    /// 12
+    AcceptAdmin,
+    /// 13
+    MirrorFreeze,
+    /// 14
+    MirrorThaw,
+    /// 15
+    MigrateReserve,
+    /// 16
+    ReallocReserve,
+    /// 17
+    RetireWrapper,
+    /// 18
+    RegisterWrapperGroupMember,
+    /// 19
+    QuoteWrap(QuoteArgs),
+    /// 20
+    QuoteUnwrap(QuoteArgs),
+    /// 21
+    InitializeReserveShard,
+    /// 22
+    RebalanceReserveShards(RebalanceReserveShardsArgs),
+    /// 23
+    DepositAndMintWrapperTokensV3(DepositAndMintWrapperTokensV3Args),
+    /// 24
+    WithdrawAndBurnWrapperTokensV3(WithdrawAndBurnWrapperTokensV3Args),
+    /// 25
+    InitializeWrapperInventory,
+    /// 26
+    TopUpWrapperInventory(AmountArgs),
+    /// 27
+    DepositFromWrapperInventory(DepositAndMintWrapperTokensV3Args),
+    /// 28
+    WithdrawToWrapperInventory(WithdrawAndBurnWrapperTokensV3Args),
+    /// 29
+    SetWrapperLimits(SetWrapperLimitsArgs),
+}
+
+impl TokenWrapperInstruction {
+    /// Unpacks a byte buffer into a valid TokenWrapperInstruction
+    pub fn unpack(instruction_data: &[u8]) -> Result<Self, ProgramError> {
+        borsh1::from_slice(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+
+    /// Packs a TokenWrapperInstruction into a byte buffer, the inverse of `unpack`
+    pub fn pack(&self) -> Vec<u8> {
+        borsh1::to_vec(self).unwrap()
+    }
+}
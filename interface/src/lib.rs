@@ -0,0 +1,24 @@
+//! CPI interface of the Token 2022 wrapper program
+//!
+//! Mirrors the instruction data, account layouts, PDAs and state of the program without its
+//! dependencies, for programs calling it through CPI:
+//!
+//! - `instruction`: the instruction enum and its arguments, Borsh-encoded the same way as the
+//!   program
+//! - `accounts`: the accounts of each instruction as structs with named fields, in the order
+//!   expected by the program
+//! - `cpi`: `invoke` and `invoke_signed` helpers for each instruction
+//! - `pda`: derivation of the program PDAs
+//! - `state`: layouts of the accounts owned by the program
+//! - `anchor`: with the `anchor` feature, the program declared from its Anchor IDL for
+//!   `CpiContext` CPIs
+
+pub mod accounts;
+#[cfg(feature = "anchor")]
+pub mod anchor;
+pub mod cpi;
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+solana_program::declare_id!("22WrapbNKwPSy3HcGQTTJpgv43tszbZdTEfBEWmGYX2V");
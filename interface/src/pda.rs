@@ -0,0 +1,116 @@
+use solana_program::pubkey::Pubkey;
+
+/// Wrapper token mint of `token_2022_mint`
+pub fn find_wrapper_token_mint(token_2022_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"wrapper", token_2022_mint.as_ref()], &crate::id())
+}
+
+/// Reserve authority of `token_2022_mint`, mint and freeze authority of the wrapper token mint
+/// and owner of the reserve
+pub fn find_reserve_authority(token_2022_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"reserve_authority", token_2022_mint.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Reserve token account of `token_2022_mint` created at initialization
+///
+/// A migrated reserve is instead the associated token account of the reserve authority, recorded
+/// in `WrapperConfig::reserve_token_2022_token_account`.
+pub fn find_reserve_authority_token_account(
+    token_2022_mint: &Pubkey,
+    reserve_authority: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"reserve_authority_token_account",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Reserve shard `reserve_shard` of `token_2022_mint`, numbered from one. Shard zero is the
+/// reserve
+pub fn find_reserve_shard(
+    token_2022_mint: &Pubkey,
+    reserve_authority: &Pubkey,
+    reserve_shard: u8,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"reserve_shard",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+            &[reserve_shard],
+        ],
+        &crate::id(),
+    )
+}
+
+/// Wrapper inventory of `token_2022_mint`, the wrapper token account of the reserve authority
+/// holding pre-minted wrapper tokens
+pub fn find_wrapper_inventory(
+    token_2022_mint: &Pubkey,
+    reserve_authority: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"wrapper_inventory",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Fee vault of `token_2022_mint`, the wrapper token account of the reserve authority collecting
+/// the fees, created by `SetFee`
+pub fn find_fee_vault(token_2022_mint: &Pubkey, reserve_authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"fee_vault",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Wrapper limits of `token_2022_mint`, created by `SetWrapperLimits`
+pub fn find_wrapper_limits(token_2022_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"wrapper_limits", token_2022_mint.as_ref()], &crate::id())
+}
+
+/// Issuer freeze of `wrapper_token_account`, created by the first `MirrorFreeze` of the freeze
+/// authority of the Token 2022 mint
+pub fn find_issuer_freeze(wrapper_token_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"issuer_freeze", wrapper_token_account.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Program config
+pub fn find_program_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"program_config"], &crate::id())
+}
+
+/// Wrapper group of the token group `group`
+pub fn find_wrapper_group(group: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"wrapper_group", group.as_ref()], &crate::id())
+}
+
+/// Registration of the wrapper of `token_2022_mint` in the wrapper group of `group`
+pub fn find_wrapper_group_member(group: &Pubkey, token_2022_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"wrapper_group_member",
+            group.as_ref(),
+            token_2022_mint.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
@@ -0,0 +1,271 @@
+use borsh1::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Upper bound for the wrap and unwrap fees, 10%
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000;
+
+/// Upper bound for the number of reserve shards of a wrapper, in addition to the reserve
+pub const MAX_RESERVE_SHARDS: u8 = 15;
+
+const ONE_IN_BASIS_POINTS: u128 = 10_000;
+
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountType {
+    #[default]
+    Uninitialized,
+    WrapperConfig,
+    ProgramConfig,
+    WrapperGroup,
+    WrapperGroupMember,
+    WrapperLimits,
+    IssuerFreeze,
+}
+
+/// Program wide configuration, stored in the program config PDA
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProgramConfig {
+    pub account_type: AccountType,
+    /// Authority configuring the wrappers and pausing deposits
+    pub admin: Pubkey,
+    /// Admin proposed by `SetPendingAdmin`, default pubkey if none
+    pub pending_admin: Pubkey,
+    /// Pauses the deposits of all wrappers
+    pub paused: bool,
+    pub reserved: [u8; 64],
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 1 + 32 + 32 + 1 + 64;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        unpack(data, AccountType::ProgramConfig)
+    }
+}
+
+/// Per wrapper configuration, stored in the reserve authority PDA account
+///
+/// Wrappers which have never been configured have no config, which behaves as the default one:
+/// no fees, not paused and not retired.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperConfig {
+    pub account_type: AccountType,
+    /// Token 2022 mint of the wrapper
+    pub token_2022_mint: Pubkey,
+    /// Wrapper token account receiving the collected fees
+    pub fee_recipient: Pubkey,
+    /// Fee charged on the Token 2022 tokens received by the reserve when wrapping
+    pub wrap_fee_basis_points: u16,
+    /// Fee charged on the wrapper tokens burned when unwrapping
+    pub unwrap_fee_basis_points: u16,
+    /// Maximum fee charged by a single wrap or unwrap, zero for no maximum
+    pub maximum_fee: u64,
+    /// Pauses the deposits of this wrapper
+    pub paused: bool,
+    /// Reserve token account set by `MigrateReserve`. Default pubkey while the reserve is the
+    /// original PDA token account
+    pub reserve_token_2022_token_account: Pubkey,
+    /// Decimals of the Token 2022 mint, recorded at initialization
+    pub token_2022_decimals: u8,
+    /// Hash of the extension types of the Token 2022 mint, except metadata and group extensions,
+    /// recorded at initialization
+    pub token_2022_mint_fingerprint: [u8; 32],
+    /// Set by `RetireWrapper`, rejects deposits
+    pub retired: bool,
+    /// Number of reserve shards created by `InitializeReserveShard`, numbered from one
+    pub reserve_shard_count: u8,
+    /// Set by `InitializeWrapperInventory`, the wrapper inventory is not part of the circulating
+    /// supply
+    pub has_wrapper_inventory: bool,
+    /// Set by `SetWrapperLimits`, deposits and withdrawals then need the wrapper limits PDA of
+    /// the v3 instructions
+    pub has_wrapper_limits: bool,
+    /// Set once the bumps below are recorded, deposits and withdrawals then check the PDAs with
+    /// them
+    pub has_bumps: bool,
+    pub wrapper_token_mint_bump: u8,
+    pub reserve_authority_bump: u8,
+    pub reserve_bump: u8,
+    pub wrapper_limits_bump: u8,
+    /// Set by `SetFee`, deposits and withdrawals charged a fee then need the fee vault PDA of the
+    /// v3 instructions
+    pub has_fee_vault: bool,
+    pub fee_vault_bump: u8,
+    pub reserved: [u8; 51],
+}
+
+impl WrapperConfig {
+    pub const LEN: usize =
+        1 + 32 + 32 + 2 + 2 + 8 + 1 + 32 + 1 + 32 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 51;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        unpack(data, AccountType::WrapperConfig)
+    }
+
+    /// Reserve token account set by `MigrateReserve`, if the reserve has been migrated
+    pub fn migrated_reserve(&self) -> Option<Pubkey> {
+        (self.reserve_token_2022_token_account != Pubkey::default())
+            .then_some(self.reserve_token_2022_token_account)
+    }
+
+    /// Fee minted to the fee vault out of `amount` Token 2022 tokens received by the reserve when
+    /// wrapping
+    pub fn wrap_fee(&self, amount: u64) -> u64 {
+        self.calculate_fee(amount, self.wrap_fee_basis_points)
+    }
+
+    /// Fee transferred to the fee vault out of `amount` wrapper tokens unwrapped
+    pub fn unwrap_fee(&self, amount: u64) -> u64 {
+        self.calculate_fee(amount, self.unwrap_fee_basis_points)
+    }
+
+    /// Zero until `SetFee` created the fee vault, wrappers configured before it charge no fee
+    fn calculate_fee(&self, amount: u64, basis_points: u16) -> u64 {
+        if !self.has_fee_vault || basis_points == 0 || amount == 0 {
+            return 0;
+        }
+
+        // Rounds up, same as the Token 2022 transfer fee
+        let fee = (amount as u128 * basis_points as u128).div_ceil(ONE_IN_BASIS_POINTS);
+
+        let fee = (fee as u64).min(amount);
+
+        if self.maximum_fee == 0 {
+            return fee;
+        }
+
+        fee.min(self.maximum_fee)
+    }
+}
+
+/// Registry of the wrappers of the members of a Token 2022 token group, stored in the wrapper
+/// group PDA
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperGroup {
+    pub account_type: AccountType,
+    /// Token group, the address in the `TokenGroupMember` extension of the member mints
+    pub group: Pubkey,
+    /// Number of registered wrappers
+    pub wrapper_count: u64,
+    pub reserved: [u8; 64],
+}
+
+impl WrapperGroup {
+    pub const LEN: usize = 1 + 32 + 8 + 64;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        unpack(data, AccountType::WrapperGroup)
+    }
+}
+
+/// Wrapper registered in a wrapper group, stored in the wrapper group member PDA
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperGroupMember {
+    pub account_type: AccountType,
+    /// Token group of the Token 2022 mint
+    pub group: Pubkey,
+    /// Token 2022 mint, member of the group
+    pub token_2022_mint: Pubkey,
+    /// Wrapper token mint of the Token 2022 mint
+    pub wrapper_token_mint: Pubkey,
+    /// Member number of the Token 2022 mint in the token group
+    pub member_number: u32,
+    /// Registration order in the wrapper group, starting at zero
+    pub index: u64,
+    pub reserved: [u8; 32],
+}
+
+impl WrapperGroupMember {
+    pub const LEN: usize = 1 + 32 + 32 + 32 + 4 + 8 + 32;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        unpack(data, AccountType::WrapperGroupMember)
+    }
+}
+
+/// Supply cap and per-epoch limits of a wrapper, stored in the wrapper limits PDA. Zero disables
+/// a limit
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperLimits {
+    pub account_type: AccountType,
+    /// Token 2022 mint of the wrapper
+    pub token_2022_mint: Pubkey,
+    /// Maximum wrapper token supply, checked when wrapper tokens are minted
+    pub supply_cap: u64,
+    /// Maximum of wrapped minus unwrapped wrapper tokens in an epoch
+    pub epoch_wrap_limit: u64,
+    /// Maximum of unwrapped minus wrapped wrapper tokens in an epoch
+    pub epoch_unwrap_limit: u64,
+    /// Epoch of the recorded volumes
+    pub epoch: u64,
+    /// Wrapper tokens wrapped during `epoch`, wrap fees included like in the wrapper token supply
+    pub epoch_wrapped: u64,
+    /// Wrapper tokens unwrapped during `epoch`, unwrap fees excluded as they are not burned
+    pub epoch_unwrapped: u64,
+    pub reserved: [u8; 64],
+}
+
+impl WrapperLimits {
+    pub const LEN: usize = 1 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 64;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        unpack(data, AccountType::WrapperLimits)
+    }
+}
+
+/// Freeze of a wrapper token account by the freeze authority of the Token 2022 mint, stored in
+/// the issuer freeze PDA of the wrapper token account
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IssuerFreeze {
+    pub account_type: AccountType,
+    /// Wrapper token account frozen by the freeze authority
+    pub wrapper_token_account: Pubkey,
+    /// Whether the wrapper token account is frozen by the freeze authority, `MirrorThaw` then
+    /// needs its signature
+    pub frozen: bool,
+    pub reserved: [u8; 32],
+}
+
+impl IssuerFreeze {
+    pub const LEN: usize = 1 + 32 + 1 + 32;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        unpack(data, AccountType::IssuerFreeze)
+    }
+}
+
+trait Typed {
+    fn account_type(&self) -> AccountType;
+}
+
+macro_rules! impl_typed {
+    ($($state:ty),*) => {
+        $(impl Typed for $state {
+            fn account_type(&self) -> AccountType {
+                self.account_type
+            }
+        })*
+    };
+}
+
+impl_typed!(
+    ProgramConfig,
+    WrapperConfig,
+    WrapperGroup,
+    WrapperGroupMember,
+    WrapperLimits,
+    IssuerFreeze
+);
+
+fn unpack<T: BorshDeserialize + Typed>(
+    data: &[u8],
+    account_type: AccountType,
+) -> Result<T, ProgramError> {
+    let state = T::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if state.account_type() != account_type {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(state)
+}
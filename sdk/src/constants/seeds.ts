@@ -3,3 +3,11 @@ export const RESERVE_AUTHORITY_SEED = Buffer.from("reserve_authority");
 export const RESERVE_AUTHORITY_TOKEN_ACCOUNT_SEED = Buffer.from(
   "reserve_authority_token_account"
 );
+export const RESERVE_SHARD_SEED = Buffer.from("reserve_shard");
+export const WRAPPER_INVENTORY_SEED = Buffer.from("wrapper_inventory");
+export const WRAPPER_LIMITS_SEED = Buffer.from("wrapper_limits");
+export const FEE_VAULT_SEED = Buffer.from("fee_vault");
+export const ISSUER_FREEZE_SEED = Buffer.from("issuer_freeze");
+export const PROGRAM_CONFIG_SEED = Buffer.from("program_config");
+export const WRAPPER_GROUP_SEED = Buffer.from("wrapper_group");
+export const WRAPPER_GROUP_MEMBER_SEED = Buffer.from("wrapper_group_member");
@@ -267,6 +267,619 @@ export class MissingSignerError extends Error {
 createErrorFromCodeLookup.set(0xa, () => new MissingSignerError());
 createErrorFromNameLookup.set("MissingSigner", () => new MissingSignerError());
 
+/**
+ * UnsupportedInstructionFlags: 'Unsupported instruction flags'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class UnsupportedInstructionFlagsError extends Error {
+  readonly code: number = 0xb;
+  readonly name: string = "UnsupportedInstructionFlags";
+  constructor() {
+    super(
+      "Unsupported instruction flags"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, UnsupportedInstructionFlagsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(
+  0xb,
+  () => new UnsupportedInstructionFlagsError()
+);
+createErrorFromNameLookup.set(
+  "UnsupportedInstructionFlags",
+  () => new UnsupportedInstructionFlagsError()
+);
+
+/**
+ * SlippageExceeded: 'Amount received is below the requested minimum'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class SlippageExceededError extends Error {
+  readonly code: number = 0xc;
+  readonly name: string = "SlippageExceeded";
+  constructor() {
+    super(
+      "Amount received is below the requested minimum"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, SlippageExceededError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0xc, () => new SlippageExceededError());
+createErrorFromNameLookup.set(
+  "SlippageExceeded",
+  () => new SlippageExceededError()
+);
+
+/**
+ * InvalidWrapperConfig: 'Invalid wrapper config'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidWrapperConfigError extends Error {
+  readonly code: number = 0xd;
+  readonly name: string = "InvalidWrapperConfig";
+  constructor() {
+    super(
+      "Invalid wrapper config"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidWrapperConfigError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0xd, () => new InvalidWrapperConfigError());
+createErrorFromNameLookup.set(
+  "InvalidWrapperConfig",
+  () => new InvalidWrapperConfigError()
+);
+
+/**
+ * UnexpectedAdmin: 'Unexpected admin authority'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class UnexpectedAdminError extends Error {
+  readonly code: number = 0xe;
+  readonly name: string = "UnexpectedAdmin";
+  constructor() {
+    super(
+      "Unexpected admin authority"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, UnexpectedAdminError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0xe, () => new UnexpectedAdminError());
+createErrorFromNameLookup.set(
+  "UnexpectedAdmin",
+  () => new UnexpectedAdminError()
+);
+
+/**
+ * FeeTooHigh: 'Fee exceeds the maximum basis points'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class FeeTooHighError extends Error {
+  readonly code: number = 0xf;
+  readonly name: string = "FeeTooHigh";
+  constructor() {
+    super(
+      "Fee exceeds the maximum basis points"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, FeeTooHighError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0xf, () => new FeeTooHighError());
+createErrorFromNameLookup.set("FeeTooHigh", () => new FeeTooHighError());
+
+/**
+ * InvalidProgramConfig: 'Invalid program config'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidProgramConfigError extends Error {
+  readonly code: number = 0x10;
+  readonly name: string = "InvalidProgramConfig";
+  constructor() {
+    super(
+      "Invalid program config"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidProgramConfigError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x10, () => new InvalidProgramConfigError());
+createErrorFromNameLookup.set(
+  "InvalidProgramConfig",
+  () => new InvalidProgramConfigError()
+);
+
+/**
+ * DepositsPaused: 'Deposits are paused'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class DepositsPausedError extends Error {
+  readonly code: number = 0x11;
+  readonly name: string = "DepositsPaused";
+  constructor() {
+    super(
+      "Deposits are paused"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, DepositsPausedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x11, () => new DepositsPausedError());
+createErrorFromNameLookup.set(
+  "DepositsPaused",
+  () => new DepositsPausedError()
+);
+
+/**
+ * FreezeStateMismatch: 'Token 2022 token account state does not allow the freeze or thaw'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class FreezeStateMismatchError extends Error {
+  readonly code: number = 0x12;
+  readonly name: string = "FreezeStateMismatch";
+  constructor() {
+    super(
+      "Token 2022 token account state does not allow the freeze or thaw"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, FreezeStateMismatchError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x12, () => new FreezeStateMismatchError());
+createErrorFromNameLookup.set(
+  "FreezeStateMismatch",
+  () => new FreezeStateMismatchError()
+);
+
+/**
+ * ReserveAlreadyMigrated: 'Reserve has already been migrated'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ReserveAlreadyMigratedError extends Error {
+  readonly code: number = 0x13;
+  readonly name: string = "ReserveAlreadyMigrated";
+  constructor() {
+    super(
+      "Reserve has already been migrated"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, ReserveAlreadyMigratedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x13, () => new ReserveAlreadyMigratedError());
+createErrorFromNameLookup.set(
+  "ReserveAlreadyMigrated",
+  () => new ReserveAlreadyMigratedError()
+);
+
+/**
+ * ReserveUndercollateralized: 'Reserve balance is below the wrapper token supply'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ReserveUndercollateralizedError extends Error {
+  readonly code: number = 0x14;
+  readonly name: string = "ReserveUndercollateralized";
+  constructor() {
+    super(
+      "Reserve balance is below the wrapper token supply"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, ReserveUndercollateralizedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(
+  0x14,
+  () => new ReserveUndercollateralizedError()
+);
+createErrorFromNameLookup.set(
+  "ReserveUndercollateralized",
+  () => new ReserveUndercollateralizedError()
+);
+
+/**
+ * SourceMintChanged: 'Token 2022 mint has changed since the wrapper was initialized'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class SourceMintChangedError extends Error {
+  readonly code: number = 0x15;
+  readonly name: string = "SourceMintChanged";
+  constructor() {
+    super(
+      "Token 2022 mint has changed since the wrapper was initialized"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, SourceMintChangedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x15, () => new SourceMintChangedError());
+createErrorFromNameLookup.set(
+  "SourceMintChanged",
+  () => new SourceMintChangedError()
+);
+
+/**
+ * WrapperRetired: 'Wrapper has been retired'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class WrapperRetiredError extends Error {
+  readonly code: number = 0x16;
+  readonly name: string = "WrapperRetired";
+  constructor() {
+    super(
+      "Wrapper has been retired"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, WrapperRetiredError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x16, () => new WrapperRetiredError());
+createErrorFromNameLookup.set(
+  "WrapperRetired",
+  () => new WrapperRetiredError()
+);
+
+/**
+ * NotAGroupMember: 'Token 2022 mint is not a member of a token group'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class NotAGroupMemberError extends Error {
+  readonly code: number = 0x17;
+  readonly name: string = "NotAGroupMember";
+  constructor() {
+    super(
+      "Token 2022 mint is not a member of a token group"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, NotAGroupMemberError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17, () => new NotAGroupMemberError());
+createErrorFromNameLookup.set(
+  "NotAGroupMember",
+  () => new NotAGroupMemberError()
+);
+
+/**
+ * InvalidWrapperGroup: 'Invalid wrapper group'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidWrapperGroupError extends Error {
+  readonly code: number = 0x18;
+  readonly name: string = "InvalidWrapperGroup";
+  constructor() {
+    super(
+      "Invalid wrapper group"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidWrapperGroupError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x18, () => new InvalidWrapperGroupError());
+createErrorFromNameLookup.set(
+  "InvalidWrapperGroup",
+  () => new InvalidWrapperGroupError()
+);
+
+/**
+ * InvalidReserveShard: 'Invalid reserve shard'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidReserveShardError extends Error {
+  readonly code: number = 0x19;
+  readonly name: string = "InvalidReserveShard";
+  constructor() {
+    super(
+      "Invalid reserve shard"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidReserveShardError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x19, () => new InvalidReserveShardError());
+createErrorFromNameLookup.set(
+  "InvalidReserveShard",
+  () => new InvalidReserveShardError()
+);
+
+/**
+ * TooManyReserveShards: 'Maximum number of reserve shards reached'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TooManyReserveShardsError extends Error {
+  readonly code: number = 0x1a;
+  readonly name: string = "TooManyReserveShards";
+  constructor() {
+    super(
+      "Maximum number of reserve shards reached"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, TooManyReserveShardsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1a, () => new TooManyReserveShardsError());
+createErrorFromNameLookup.set(
+  "TooManyReserveShards",
+  () => new TooManyReserveShardsError()
+);
+
+/**
+ * InvalidWrapperInventory: 'Invalid wrapper inventory'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidWrapperInventoryError extends Error {
+  readonly code: number = 0x1b;
+  readonly name: string = "InvalidWrapperInventory";
+  constructor() {
+    super(
+      "Invalid wrapper inventory"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidWrapperInventoryError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1b, () => new InvalidWrapperInventoryError());
+createErrorFromNameLookup.set(
+  "InvalidWrapperInventory",
+  () => new InvalidWrapperInventoryError()
+);
+
+/**
+ * InsufficientWrapperInventory: 'Wrapper inventory balance is below the wrapper tokens transferred'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InsufficientWrapperInventoryError extends Error {
+  readonly code: number = 0x1c;
+  readonly name: string = "InsufficientWrapperInventory";
+  constructor() {
+    super(
+      "Wrapper inventory balance is below the wrapper tokens transferred"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InsufficientWrapperInventoryError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1c, () => new InsufficientWrapperInventoryError());
+createErrorFromNameLookup.set(
+  "InsufficientWrapperInventory",
+  () => new InsufficientWrapperInventoryError()
+);
+
+/**
+ * InvalidWrapperLimits: 'Invalid wrapper limits'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidWrapperLimitsError extends Error {
+  readonly code: number = 0x1d;
+  readonly name: string = "InvalidWrapperLimits";
+  constructor() {
+    super("Invalid wrapper limits");
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidWrapperLimitsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1d, () => new InvalidWrapperLimitsError());
+createErrorFromNameLookup.set(
+  "InvalidWrapperLimits",
+  () => new InvalidWrapperLimitsError()
+);
+
+/**
+ * SupplyCapExceeded: 'Wrapper token supply would exceed the supply cap'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class SupplyCapExceededError extends Error {
+  readonly code: number = 0x1e;
+  readonly name: string = "SupplyCapExceeded";
+  constructor() {
+    super("Wrapper token supply would exceed the supply cap");
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, SupplyCapExceededError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1e, () => new SupplyCapExceededError());
+createErrorFromNameLookup.set(
+  "SupplyCapExceeded",
+  () => new SupplyCapExceededError()
+);
+
+/**
+ * EpochWrapLimitExceeded: 'Net wrapped amount would exceed the limit of the epoch'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class EpochWrapLimitExceededError extends Error {
+  readonly code: number = 0x1f;
+  readonly name: string = "EpochWrapLimitExceeded";
+  constructor() {
+    super("Net wrapped amount would exceed the limit of the epoch");
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, EpochWrapLimitExceededError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1f, () => new EpochWrapLimitExceededError());
+createErrorFromNameLookup.set(
+  "EpochWrapLimitExceeded",
+  () => new EpochWrapLimitExceededError()
+);
+
+/**
+ * EpochUnwrapLimitExceeded: 'Net unwrapped amount would exceed the limit of the epoch'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class EpochUnwrapLimitExceededError extends Error {
+  readonly code: number = 0x20;
+  readonly name: string = "EpochUnwrapLimitExceeded";
+  constructor() {
+    super("Net unwrapped amount would exceed the limit of the epoch");
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, EpochUnwrapLimitExceededError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x20, () => new EpochUnwrapLimitExceededError());
+createErrorFromNameLookup.set(
+  "EpochUnwrapLimitExceeded",
+  () => new EpochUnwrapLimitExceededError()
+);
+
+/**
+ * InvalidFeeVault: 'Invalid fee vault'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidFeeVaultError extends Error {
+  readonly code: number = 0x21;
+  readonly name: string = "InvalidFeeVault";
+  constructor() {
+    super("Invalid fee vault");
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidFeeVaultError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x21, () => new InvalidFeeVaultError());
+createErrorFromNameLookup.set("InvalidFeeVault", () => new InvalidFeeVaultError());
+
+/**
+ * InvalidIssuerFreeze: 'Invalid issuer freeze'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidIssuerFreezeError extends Error {
+  readonly code: number = 0x22;
+  readonly name: string = "InvalidIssuerFreeze";
+  constructor() {
+    super("Invalid issuer freeze");
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, InvalidIssuerFreezeError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x22, () => new InvalidIssuerFreezeError());
+createErrorFromNameLookup.set(
+  "InvalidIssuerFreeze",
+  () => new InvalidIssuerFreezeError()
+);
+
+/**
+ * UnsupportedInstructionVersion: 'Instruction version does not support the fees or limits of the wrapper'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class UnsupportedInstructionVersionError extends Error {
+  readonly code: number = 0x23;
+  readonly name: string = "UnsupportedInstructionVersion";
+  constructor() {
+    super(
+      "Instruction version does not support the fees or limits of the wrapper"
+    );
+    if (typeof Error.captureStackTrace === "function") {
+      Error.captureStackTrace(this, UnsupportedInstructionVersionError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x23, () => new UnsupportedInstructionVersionError());
+createErrorFromNameLookup.set(
+  "UnsupportedInstructionVersion",
+  () => new UnsupportedInstructionVersionError()
+);
+
 /**
  * Attempts to resolve a custom program error from the provided error code.
  * @category Errors
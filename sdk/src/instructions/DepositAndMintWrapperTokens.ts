@@ -9,6 +9,7 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "../constants";
 import {
+  getProgramConfig,
   getReserveAuthority,
   getReserveAuthorityTokenAccount,
   getWrapperTokenMint,
@@ -110,6 +111,11 @@ export const createDepositAndMintWrapperTokensInstruction = async (
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: getProgramConfig(),
+      isSigner: false,
+      isWritable: false,
+    },
   ];
 
   return new web3.TransactionInstruction({
@@ -2,4 +2,31 @@ export enum TokenWrapperInstruction {
   InitializeWrapperToken = 0,
   DepositAndMintWrapperTokens = 1,
   WithdrawAndBurnWrapperTokens = 2,
+  InitializeWrapperTokenV2 = 3,
+  DepositAndMintWrapperTokensV2 = 4,
+  WithdrawAndBurnWrapperTokensV2 = 5,
+  SetFee = 6,
+  CollectFees = 7,
+  InitializeProgramConfig = 8,
+  SetPause = 9,
+  SetGlobalPause = 10,
+  SetPendingAdmin = 11,
+  AcceptAdmin = 12,
+  MirrorFreeze = 13,
+  MirrorThaw = 14,
+  MigrateReserve = 15,
+  ReallocReserve = 16,
+  RetireWrapper = 17,
+  RegisterWrapperGroupMember = 18,
+  QuoteWrap = 19,
+  QuoteUnwrap = 20,
+  InitializeReserveShard = 21,
+  RebalanceReserveShards = 22,
+  DepositAndMintWrapperTokensV3 = 23,
+  WithdrawAndBurnWrapperTokensV3 = 24,
+  InitializeWrapperInventory = 25,
+  TopUpWrapperInventory = 26,
+  DepositFromWrapperInventory = 27,
+  WithdrawToWrapperInventory = 28,
+  SetWrapperLimits = 29,
 }
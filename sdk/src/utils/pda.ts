@@ -1,8 +1,16 @@
 import * as web3 from "@solana/web3.js";
 import {
+  FEE_VAULT_SEED,
+  ISSUER_FREEZE_SEED,
+  PROGRAM_CONFIG_SEED,
   PROGRAM_ID,
   RESERVE_AUTHORITY_SEED,
   RESERVE_AUTHORITY_TOKEN_ACCOUNT_SEED,
+  RESERVE_SHARD_SEED,
+  WRAPPER_GROUP_MEMBER_SEED,
+  WRAPPER_GROUP_SEED,
+  WRAPPER_INVENTORY_SEED,
+  WRAPPER_LIMITS_SEED,
 } from "../constants";
 import { WRAPPER_TOKEN_MINT_SEED } from "../constants";
 
@@ -45,3 +53,103 @@ export const getReserveAuthorityTokenAccount = (
 
   return reserveAuthorityTokenAccount;
 };
+
+export const getReserveShard = (
+  token2022Mint: web3.PublicKey,
+  reserveShard: number
+): web3.PublicKey => {
+  let reserveAuthority = getReserveAuthority(token2022Mint);
+
+  const [reserveShardTokenAccount, _] = web3.PublicKey.findProgramAddressSync(
+    [
+      RESERVE_SHARD_SEED,
+      token2022Mint.toBuffer(),
+      reserveAuthority.toBuffer(),
+      Buffer.from([reserveShard]),
+    ],
+    PROGRAM_ID
+  );
+
+  return reserveShardTokenAccount;
+};
+
+export const getWrapperInventory = (
+  token2022Mint: web3.PublicKey
+): web3.PublicKey => {
+  let reserveAuthority = getReserveAuthority(token2022Mint);
+
+  const [wrapperInventory, _] = web3.PublicKey.findProgramAddressSync(
+    [
+      WRAPPER_INVENTORY_SEED,
+      token2022Mint.toBuffer(),
+      reserveAuthority.toBuffer(),
+    ],
+    PROGRAM_ID
+  );
+
+  return wrapperInventory;
+};
+
+export const getWrapperLimits = (
+  token2022Mint: web3.PublicKey
+): web3.PublicKey => {
+  const [wrapperLimits, _] = web3.PublicKey.findProgramAddressSync(
+    [WRAPPER_LIMITS_SEED, token2022Mint.toBuffer()],
+    PROGRAM_ID
+  );
+
+  return wrapperLimits;
+};
+
+export const getFeeVault = (
+  token2022Mint: web3.PublicKey,
+  reserveAuthority: web3.PublicKey
+): web3.PublicKey => {
+  const [feeVault, _] = web3.PublicKey.findProgramAddressSync(
+    [FEE_VAULT_SEED, token2022Mint.toBuffer(), reserveAuthority.toBuffer()],
+    PROGRAM_ID
+  );
+
+  return feeVault;
+};
+
+export const getIssuerFreeze = (
+  wrapperTokenAccount: web3.PublicKey
+): web3.PublicKey => {
+  const [issuerFreeze, _] = web3.PublicKey.findProgramAddressSync(
+    [ISSUER_FREEZE_SEED, wrapperTokenAccount.toBuffer()],
+    PROGRAM_ID
+  );
+
+  return issuerFreeze;
+};
+
+export const getProgramConfig = (): web3.PublicKey => {
+  const [programConfig, _] = web3.PublicKey.findProgramAddressSync(
+    [PROGRAM_CONFIG_SEED],
+    PROGRAM_ID
+  );
+
+  return programConfig;
+};
+
+export const getWrapperGroup = (group: web3.PublicKey): web3.PublicKey => {
+  const [wrapperGroup, _] = web3.PublicKey.findProgramAddressSync(
+    [WRAPPER_GROUP_SEED, group.toBuffer()],
+    PROGRAM_ID
+  );
+
+  return wrapperGroup;
+};
+
+export const getWrapperGroupMember = (
+  group: web3.PublicKey,
+  token2022Mint: web3.PublicKey
+): web3.PublicKey => {
+  const [wrapperGroupMember, _] = web3.PublicKey.findProgramAddressSync(
+    [WRAPPER_GROUP_MEMBER_SEED, group.toBuffer(), token2022Mint.toBuffer()],
+    PROGRAM_ID
+  );
+
+  return wrapperGroupMember;
+};
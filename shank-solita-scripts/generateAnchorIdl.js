@@ -0,0 +1,157 @@
+const fs = require('fs');
+const path = require('path');
+
+const programDir = path.join(__dirname, "..");
+const defaultShankIdlPath = path.join(programDir, "sdk", "src", "idl", "token2022_wrapper.json");
+const defaultAnchorIdlPath = path.join(programDir, "interface", "idls", "token2022_wrapper.json");
+
+const PROGRAM_NAME = 'token2022_wrapper';
+const ANCHOR_IDL_SPEC = '0.1.0';
+
+// Accounts with a fixed address, resolved by Anchor clients
+const KNOWN_ADDRESSES = {
+  tokenProgram: 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA',
+  token2022Program: 'TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb',
+  systemProgram: '11111111111111111111111111111111',
+  associatedTokenProgram: 'ATokenGPvbdGVxr1b2hvZbsiqW5xQWQ8ouHUjq4Qo8Y8Y',
+  rent: 'SysvarRent111111111111111111111111111111111',
+};
+
+// The account type is the first byte of every program account, Anchor uses it as discriminator
+const ACCOUNT_DISCRIMINATORS = {
+  WrapperConfig: 1,
+  ProgramConfig: 2,
+  WrapperGroup: 3,
+  WrapperGroupMember: 4,
+  WrapperLimits: 5,
+};
+
+// Types returned as return data, missing from the Shank IDL
+const INSTRUCTION_RETURNS = {
+  QuoteWrap: 'Quote',
+  QuoteUnwrap: 'Quote',
+};
+
+// Same names as the Rust fields, e.g. `token2022Mint` -> `token_2022_mint`
+function snakeCase(name) {
+  return name
+    .replace(/([a-z])(2022)/g, '$1_$2')
+    .replace(/([a-z0-9])([A-Z])/g, '$1_$2')
+    .toLowerCase();
+}
+
+function convertType(type) {
+  if (type === 'publicKey') {
+    return 'pubkey';
+  }
+  if (typeof type === 'string') {
+    return type;
+  }
+  if (type.defined) {
+    return { defined: { name: type.defined } };
+  }
+  if (type.option) {
+    return { option: convertType(type.option) };
+  }
+  if (type.vec) {
+    return { vec: convertType(type.vec) };
+  }
+  if (type.array) {
+    return { array: [convertType(type.array[0]), type.array[1]] };
+  }
+  throw new Error(`Unsupported type ${JSON.stringify(type)}`);
+}
+
+function convertFields(fields) {
+  return fields.map((field) => ({
+    name: snakeCase(field.name),
+    ...(field.docs ? { docs: field.docs } : {}),
+    type: convertType(field.type),
+  }));
+}
+
+function convertTypeDef(typeDef) {
+  const { kind } = typeDef.type;
+  if (kind === 'struct') {
+    return {
+      name: typeDef.name,
+      type: { kind, fields: convertFields(typeDef.type.fields) },
+    };
+  }
+  if (kind === 'enum') {
+    return {
+      name: typeDef.name,
+      type: { kind, variants: typeDef.type.variants.map(({ name }) => ({ name })) },
+    };
+  }
+  throw new Error(`Unsupported type definition ${typeDef.name}`);
+}
+
+function convertInstruction(instruction) {
+  return {
+    name: snakeCase(instruction.name),
+    ...(instruction.docs ? { docs: instruction.docs } : {}),
+    discriminator: [instruction.discriminant.value],
+    accounts: instruction.accounts.map((account) => ({
+      name: snakeCase(account.name),
+      ...(account.docs ? { docs: account.docs } : {}),
+      ...(account.isMut ? { writable: true } : {}),
+      ...(account.isSigner ? { signer: true } : {}),
+      ...(KNOWN_ADDRESSES[account.name] ? { address: KNOWN_ADDRESSES[account.name] } : {}),
+    })),
+    args: instruction.args.map((arg) => ({
+      name: snakeCase(arg.name),
+      type: convertType(arg.type),
+    })),
+    ...(INSTRUCTION_RETURNS[instruction.name]
+      ? { returns: convertType({ defined: INSTRUCTION_RETURNS[instruction.name] }) }
+      : {}),
+  };
+}
+
+// Anchor account types are deserialized after the discriminator, so the account type field is
+// dropped from them
+function convertAccount(account) {
+  const fields = account.type.fields.filter((field) => field.name !== 'accountType');
+  return convertTypeDef({ ...account, type: { kind: 'struct', fields } });
+}
+
+function generateAnchorIdl(shankIdlPath = defaultShankIdlPath, anchorIdlPath = defaultAnchorIdlPath) {
+  const shankIdl = JSON.parse(fs.readFileSync(shankIdlPath, 'utf8'));
+
+  for (const account of shankIdl.accounts) {
+    if (ACCOUNT_DISCRIMINATORS[account.name] === undefined) {
+      throw new Error(`Missing discriminator of account ${account.name}`);
+    }
+  }
+
+  const anchorIdl = {
+    address: shankIdl.metadata.address,
+    metadata: {
+      name: PROGRAM_NAME,
+      version: shankIdl.version,
+      spec: ANCHOR_IDL_SPEC,
+      description: 'Wraps Token 2022 tokens into SPL Token tokens',
+    },
+    instructions: shankIdl.instructions.map(convertInstruction),
+    accounts: shankIdl.accounts.map(({ name }) => ({
+      name,
+      discriminator: [ACCOUNT_DISCRIMINATORS[name]],
+    })),
+    errors: shankIdl.errors.map(({ code, name, msg }) => ({ code, name, msg })),
+    types: [
+      ...shankIdl.accounts.map(convertAccount),
+      ...shankIdl.types.map(convertTypeDef),
+    ],
+  };
+
+  fs.mkdirSync(path.dirname(anchorIdlPath), { recursive: true });
+  fs.writeFileSync(anchorIdlPath, JSON.stringify(anchorIdl, null, 2) + '\n');
+  console.error('Anchor IDL written to %s', anchorIdlPath);
+}
+
+module.exports = { generateAnchorIdl };
+
+if (require.main === module) {
+  generateAnchorIdl(process.argv[2], process.argv[3]);
+}
@@ -5,6 +5,7 @@ const {
   confirmAutoMessageConsole,
 } = require('@metaplex-foundation/rustbin')
 const { spawn } = require('child_process');
+const { generateAnchorIdl } = require('./generateAnchorIdl');
 
 const programDir = path.join(__dirname, "..");
 const cargoToml = path.join(programDir, "Cargo.toml");
@@ -53,6 +54,8 @@ async function generateTypeScriptSDK() {
   const gen = new Solita(idl, { formatCode: true });
   await gen.renderAndWriteTo(generatedSDKDir);
 
+  generateAnchorIdl(generatedIdlPath);
+
   console.error('Success!');
 
   process.exit(0);
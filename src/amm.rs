@@ -0,0 +1,384 @@
+//! Aggregator adapter routing swaps through the wrapper
+//!
+//! `Amm` is a quoting interface in the style of the aggregators: an AMM is built from a keyed
+//! account, refreshed from snapshots of the accounts it lists, then quotes swaps and builds their
+//! accounts offline. `WrapperAmm` treats wrapping and unwrapping as swaps between a Token 2022
+//! mint and its wrapper token mint, 1:1 minus the Token 2022 transfer fee and the wrapper fees.
+//! Its key is the Token 2022 mint, the wrapper accounts are all derived from it.
+
+use std::{collections::HashMap, error::Error, fmt};
+
+use solana_program::{
+    clock::Clock, instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey, sysvar,
+};
+use solana_sdk::account::Account;
+
+use crate::{
+    error::TokenWrapperError,
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args,
+        WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction, with_migrated_reserve,
+    },
+    quote::{quote_unwrap, quote_wrap},
+    state::{ProgramConfig, WrapperConfig},
+    utils::{get_program_config, get_reserve_authority, get_wrapper_token_mint},
+};
+
+// `Display` and `Error` are implemented by hand, shank reads every enum deriving `Error` in the
+// crate as program errors of the IDL
+#[derive(Debug, PartialEq, Eq)]
+pub enum AmmError {
+    /// Error the program would fail the swap with
+    Program(ProgramError),
+    /// Account missing from the snapshots
+    AccountNotFound(Pubkey),
+    /// The mints are not the Token 2022 mint and the wrapper token mint of the AMM
+    UnsupportedMints {
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+    },
+    ExactOutNotSupported,
+}
+
+impl fmt::Display for AmmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AmmError::Program(e) => e.fmt(f),
+            AmmError::AccountNotFound(pubkey) => write!(f, "Account {pubkey} not found"),
+            AmmError::UnsupportedMints {
+                input_mint,
+                output_mint,
+            } => write!(
+                f,
+                "Swaps from {input_mint} to {output_mint} are not supported"
+            ),
+            AmmError::ExactOutNotSupported => write!(f, "Exact out swaps are not supported"),
+        }
+    }
+}
+
+impl Error for AmmError {}
+
+impl From<ProgramError> for AmmError {
+    fn from(e: ProgramError) -> Self {
+        AmmError::Program(e)
+    }
+}
+
+impl From<TokenWrapperError> for AmmError {
+    fn from(e: TokenWrapperError) -> Self {
+        AmmError::Program(e.into())
+    }
+}
+
+pub type AmmResult<T> = Result<T, AmmError>;
+
+/// Snapshots of the accounts listed by `Amm::get_accounts_to_update`
+pub type AccountMap = HashMap<Pubkey, Account>;
+
+pub struct KeyedAccount {
+    pub key: Pubkey,
+    pub account: Account,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SwapMode {
+    /// `amount` is the input amount
+    #[default]
+    ExactIn,
+    /// `amount` is the output amount
+    ExactOut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuoteParams {
+    pub amount: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub swap_mode: SwapMode,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AmmQuote {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    /// Transfer fee and wrapper fee, `in_amount` minus `out_amount`
+    pub fee_amount: u64,
+    /// Input mint of the swap, the wrapper exchanging both mints 1:1
+    pub fee_mint: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapParams {
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub source_token_account: Pubkey,
+    pub destination_token_account: Pubkey,
+    /// Owner of the source token account, signing the swap
+    pub token_transfer_authority: Pubkey,
+    pub in_amount: u64,
+    /// Minimum output, the swap fails if less is received
+    pub out_amount: u64,
+}
+
+/// Program instruction of a swap, data and accounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapAndAccountMetas {
+    pub data: Vec<u8>,
+    pub account_metas: Vec<AccountMeta>,
+}
+
+pub trait Amm {
+    fn from_keyed_account(keyed_account: &KeyedAccount) -> AmmResult<Self>
+    where
+        Self: Sized;
+    fn label(&self) -> String;
+    fn program_id(&self) -> Pubkey;
+    /// Account identifying the AMM
+    fn key(&self) -> Pubkey;
+    /// Mints swapped by the AMM
+    fn get_reserve_mints(&self) -> Vec<Pubkey>;
+    /// Accounts to snapshot for `update`
+    fn get_accounts_to_update(&self) -> Vec<Pubkey>;
+    fn update(&mut self, account_map: &AccountMap) -> AmmResult<()>;
+    fn quote(&self, quote_params: &QuoteParams) -> AmmResult<AmmQuote>;
+    fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> AmmResult<SwapAndAccountMetas>;
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync>;
+}
+
+/// Wrapper of a Token 2022 mint as an AMM, wrapping from the Token 2022 mint and unwrapping from
+/// the wrapper token mint
+#[derive(Clone, Debug)]
+pub struct WrapperAmm {
+    token_2022_mint: Pubkey,
+    wrapper_token_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_config: Pubkey,
+    token_2022_mint_data: Vec<u8>,
+    /// Whether the wrapper token mint exists, the wrapper has been initialized
+    initialized: bool,
+    wrapper_config: Option<WrapperConfig>,
+    global_paused: bool,
+    epoch: u64,
+}
+
+impl WrapperAmm {
+    /// Whether wraps are rejected, by a pause or the retirement of the wrapper
+    pub fn deposits_disabled(&self) -> bool {
+        self.global_paused
+            || self
+                .wrapper_config
+                .as_ref()
+                .is_some_and(|config| config.paused || config.retired)
+    }
+
+    fn is_wrap(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> AmmResult<bool> {
+        if (*input_mint, *output_mint) == (self.token_2022_mint, self.wrapper_token_mint) {
+            Ok(true)
+        } else if (*input_mint, *output_mint) == (self.wrapper_token_mint, self.token_2022_mint) {
+            Ok(false)
+        } else {
+            Err(AmmError::UnsupportedMints {
+                input_mint: *input_mint,
+                output_mint: *output_mint,
+            })
+        }
+    }
+}
+
+impl Amm for WrapperAmm {
+    /// `keyed_account` is the Token 2022 mint
+    fn from_keyed_account(keyed_account: &KeyedAccount) -> AmmResult<Self> {
+        if keyed_account.account.owner != spl_token_2022::id() {
+            return Err(TokenWrapperError::InvalidTokenMint.into());
+        }
+
+        let token_2022_mint = keyed_account.key;
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, crate::id());
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, crate::id());
+        let (program_config, _, _) = get_program_config(crate::id());
+
+        Ok(Self {
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            program_config,
+            token_2022_mint_data: keyed_account.account.data.clone(),
+            initialized: false,
+            wrapper_config: None,
+            global_paused: false,
+            epoch: 0,
+        })
+    }
+
+    fn label(&self) -> String {
+        "Token 2022 Wrapper".to_string()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        crate::id()
+    }
+
+    fn key(&self) -> Pubkey {
+        self.token_2022_mint
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![self.token_2022_mint, self.wrapper_token_mint]
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        vec![
+            self.token_2022_mint,
+            self.wrapper_token_mint,
+            self.reserve_authority,
+            self.program_config,
+            sysvar::clock::id(),
+        ]
+    }
+
+    /// The Token 2022 mint and the clock are required, the other accounts are absent until the
+    /// wrapper is initialized or configured
+    fn update(&mut self, account_map: &AccountMap) -> AmmResult<()> {
+        let get = |pubkey: &Pubkey| {
+            account_map
+                .get(pubkey)
+                .ok_or(AmmError::AccountNotFound(*pubkey))
+        };
+        let get_program_owned = |pubkey: &Pubkey| {
+            account_map
+                .get(pubkey)
+                .filter(|account| account.owner == crate::id())
+        };
+
+        let clock: Clock = bincode::deserialize(&get(&sysvar::clock::id())?.data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let wrapper_config = get_program_owned(&self.reserve_authority)
+            .map(|account| WrapperConfig::unpack(&account.data))
+            .transpose()?;
+        let global_paused = get_program_owned(&self.program_config)
+            .map(|account| ProgramConfig::unpack(&account.data))
+            .transpose()?
+            .is_some_and(|config| config.paused);
+
+        self.token_2022_mint_data = get(&self.token_2022_mint)?.data.clone();
+        self.initialized = account_map
+            .get(&self.wrapper_token_mint)
+            .is_some_and(|account| account.owner == spl_token::id());
+        self.wrapper_config = wrapper_config;
+        self.global_paused = global_paused;
+        self.epoch = clock.epoch;
+
+        Ok(())
+    }
+
+    /// Fails when the wrapper is not initialized and, for wraps, when the wrapper is retired or
+    /// deposits are paused. The Token 2022 mint and the wrapper limits are only checked on chain
+    fn quote(&self, quote_params: &QuoteParams) -> AmmResult<AmmQuote> {
+        let is_wrap = self.is_wrap(&quote_params.input_mint, &quote_params.output_mint)?;
+        if quote_params.swap_mode == SwapMode::ExactOut {
+            return Err(AmmError::ExactOutNotSupported);
+        }
+        if !self.initialized {
+            return Err(TokenWrapperError::UnexpectedWrapperToken.into());
+        }
+
+        let quote = if is_wrap {
+            if self
+                .wrapper_config
+                .as_ref()
+                .is_some_and(|config| config.retired)
+            {
+                return Err(TokenWrapperError::WrapperRetired.into());
+            }
+            if self.deposits_disabled() {
+                return Err(TokenWrapperError::DepositsPaused.into());
+            }
+
+            quote_wrap(
+                &self.token_2022_mint_data,
+                self.wrapper_config.as_ref(),
+                self.epoch,
+                quote_params.amount,
+            )?
+        } else {
+            quote_unwrap(
+                &self.token_2022_mint_data,
+                self.wrapper_config.as_ref(),
+                self.epoch,
+                quote_params.amount,
+            )?
+        };
+
+        Ok(AmmQuote {
+            in_amount: quote.amount_in,
+            out_amount: quote.amount_out,
+            fee_amount: quote.transfer_fee + quote.wrapper_fee,
+            fee_mint: quote_params.input_mint,
+        })
+    }
+
+    /// v3 deposit or withdrawal, which takes the fee vault and the wrapper limits, into and from
+    /// the reserve or the migrated reserve if the reserve has been migrated
+    fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> AmmResult<SwapAndAccountMetas> {
+        let instruction =
+            if self.is_wrap(&swap_params.source_mint, &swap_params.destination_mint)? {
+                create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                    &swap_params.token_transfer_authority,
+                    &self.token_2022_mint,
+                    &swap_params.destination_token_account,
+                    &swap_params.source_token_account,
+                    DepositAndMintWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: DepositAndMintWrapperTokensArgs {
+                            amount: swap_params.in_amount,
+                            min_out: swap_params.out_amount,
+                            ..Default::default()
+                        },
+                    },
+                )
+            } else {
+                create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                    &swap_params.token_transfer_authority,
+                    &self.token_2022_mint,
+                    &swap_params.source_token_account,
+                    &swap_params.destination_token_account,
+                    WithdrawAndBurnWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: WithdrawAndBurnWrapperTokensArgs {
+                            amount: swap_params.in_amount,
+                            min_out: swap_params.out_amount,
+                            ..Default::default()
+                        },
+                    },
+                )
+            };
+        let instruction = match self
+            .wrapper_config
+            .as_ref()
+            .and_then(WrapperConfig::migrated_reserve)
+        {
+            Some(_) => with_migrated_reserve(instruction, &self.token_2022_mint),
+            None => instruction,
+        };
+
+        Ok(SwapAndAccountMetas {
+            data: instruction.data,
+            account_metas: instruction.accounts,
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,600 @@
+//! Async client of the wrapper program over the nonblocking RPC client
+//!
+//! Quotes are computed from the Token 2022 mint `TransferFeeConfig` at the current epoch and the
+//! wrapper config, the same way as the program. `wrap` and `unwrap` use their quote as minimum
+//! output, so they fail instead of receiving less if the fees change before they land.
+//! `generate_registry` lists the wrappers configured on chain in the format of the registry.
+
+use std::{error::Error, fmt};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    client_error::ClientError,
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::RpcSimulateTransactionResult,
+};
+use solana_program::{
+    instruction::Instruction, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+
+use crate::{
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args,
+        WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_initialize_wrapper_token_v2_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction, with_migrated_reserve,
+    },
+    quote::{self, Quote},
+    registry::{Registry, RegistryEntry, RegistryError},
+    state::{AccountType, ProgramConfig, WrapperConfig, WrapperLimits},
+    utils::{
+        get_program_config, get_reserve_authority, get_reserve_authority_token_account,
+        get_reserve_shard, get_wrapper_inventory, get_wrapper_limits, get_wrapper_token_mint,
+    },
+};
+
+// `Display` and `Error` are implemented by hand, shank reads every enum deriving `Error` in the
+// crate as program errors of the IDL
+#[derive(Debug)]
+pub enum WrapperClientError {
+    Rpc(Box<ClientError>),
+    Program(ProgramError),
+    AccountNotFound(Pubkey),
+    WrapperNotInitialized(Pubkey),
+    Registry(RegistryError),
+}
+
+impl fmt::Display for WrapperClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WrapperClientError::Rpc(e) => e.fmt(f),
+            WrapperClientError::Program(e) => e.fmt(f),
+            WrapperClientError::AccountNotFound(pubkey) => write!(f, "Account {pubkey} not found"),
+            WrapperClientError::WrapperNotInitialized(token_2022_mint) => {
+                write!(f, "Token 2022 mint {token_2022_mint} has no wrapper")
+            }
+            WrapperClientError::Registry(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for WrapperClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WrapperClientError::Rpc(e) => e.source(),
+            WrapperClientError::Program(e) => e.source(),
+            WrapperClientError::Registry(e) => e.source(),
+            WrapperClientError::AccountNotFound(_)
+            | WrapperClientError::WrapperNotInitialized(_) => None,
+        }
+    }
+}
+
+impl From<ClientError> for WrapperClientError {
+    fn from(e: ClientError) -> Self {
+        WrapperClientError::Rpc(Box::new(e))
+    }
+}
+
+impl From<ProgramError> for WrapperClientError {
+    fn from(e: ProgramError) -> Self {
+        WrapperClientError::Program(e)
+    }
+}
+
+impl From<RegistryError> for WrapperClientError {
+    fn from(e: RegistryError) -> Self {
+        WrapperClientError::Registry(e)
+    }
+}
+
+pub type WrapperClientResult<T> = Result<T, WrapperClientError>;
+
+/// State of the wrapper of a Token 2022 mint
+#[derive(Clone, Debug, PartialEq)]
+pub struct WrapperInfo {
+    pub token_2022_mint: Pubkey,
+    pub wrapper_token_mint: Pubkey,
+    pub reserve_authority: Pubkey,
+    /// Current reserve, the migrated one if the reserve has been migrated
+    pub reserve_token_2022_token_account: Pubkey,
+    pub decimals: u8,
+    /// Extensions of the Token 2022 mint
+    pub token_2022_extensions: Vec<ExtensionType>,
+    /// Token 2022 tokens held by the reserve and its reserve shards
+    pub reserve_balance: u64,
+    /// Wrapper tokens in circulation, backed by the reserve. The wrapper token supply without the
+    /// wrapper inventory
+    pub wrapper_supply: u64,
+    /// Pre-minted wrapper tokens held by the wrapper inventory, zero without inventory
+    pub wrapper_inventory_balance: u64,
+    /// Wrapper config, `None` if the wrapper has never been configured
+    pub wrapper_config: Option<WrapperConfig>,
+    /// Supply cap and per-epoch limits, `None` if the wrapper has no wrapper limits
+    pub wrapper_limits: Option<WrapperLimits>,
+    /// Deposits paused for all wrappers
+    pub global_paused: bool,
+    /// Raw Token 2022 mint account, quotes are computed from it
+    pub token_2022_mint_account: Account,
+}
+
+impl WrapperInfo {
+    /// Whether deposits are rejected, by a pause or the retirement of the wrapper
+    pub fn deposits_disabled(&self) -> bool {
+        self.global_paused
+            || self
+                .wrapper_config
+                .as_ref()
+                .is_some_and(|config| config.paused || config.retired)
+    }
+
+    /// Wrapper tokens minted for `amount` Token 2022 tokens during `epoch`
+    pub fn quote_wrap(&self, epoch: u64, amount: u64) -> WrapperClientResult<Quote> {
+        Ok(quote::quote_wrap(
+            &self.token_2022_mint_account.data,
+            self.wrapper_config.as_ref(),
+            epoch,
+            amount,
+        )?)
+    }
+
+    /// Token 2022 tokens received for `amount` wrapper tokens during `epoch`
+    pub fn quote_unwrap(&self, epoch: u64, amount: u64) -> WrapperClientResult<Quote> {
+        Ok(quote::quote_unwrap(
+            &self.token_2022_mint_account.data,
+            self.wrapper_config.as_ref(),
+            epoch,
+            amount,
+        )?)
+    }
+}
+
+/// Client wrapping and unwrapping the tokens of `payer`, which also pays the transaction fees and
+/// the rent of the created accounts
+pub struct WrapperClient {
+    rpc_client: RpcClient,
+    payer: Keypair,
+}
+
+impl WrapperClient {
+    pub fn new(rpc_client: RpcClient, payer: Keypair) -> Self {
+        Self { rpc_client, payer }
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Token 2022 associated token account of the payer
+    pub fn token_2022_token_account(&self, token_2022_mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.payer(),
+            token_2022_mint,
+            &spl_token_2022::id(),
+        )
+    }
+
+    /// Wrapper token associated token account of the payer
+    pub fn wrapper_token_account(&self, token_2022_mint: &Pubkey) -> Pubkey {
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+        get_associated_token_address_with_program_id(
+            &self.payer(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+    }
+
+    pub async fn get_wrapper_info(
+        &self,
+        token_2022_mint: &Pubkey,
+    ) -> WrapperClientResult<WrapperInfo> {
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+        let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+        let (program_config, _, _) = get_program_config(crate::id());
+
+        let mut accounts = self
+            .rpc_client
+            .get_multiple_accounts(&[
+                *token_2022_mint,
+                wrapper_token_mint,
+                reserve_authority,
+                program_config,
+            ])
+            .await?
+            .into_iter();
+        let token_2022_mint_account = accounts
+            .next()
+            .flatten()
+            .ok_or(WrapperClientError::AccountNotFound(*token_2022_mint))?;
+        let wrapper_token_mint_account = accounts
+            .next()
+            .flatten()
+            .ok_or(WrapperClientError::WrapperNotInitialized(*token_2022_mint))?;
+        let wrapper_config = accounts
+            .next()
+            .flatten()
+            .filter(|account| account.owner == crate::id())
+            .map(|account| WrapperConfig::unpack(&account.data))
+            .transpose()?;
+        let global_paused = accounts
+            .next()
+            .flatten()
+            .filter(|account| account.owner == crate::id())
+            .map(|account| ProgramConfig::unpack(&account.data))
+            .transpose()?
+            .is_some_and(|config| config.paused);
+
+        let reserve_token_2022_token_account = wrapper_config
+            .as_ref()
+            .and_then(WrapperConfig::migrated_reserve)
+            .unwrap_or_else(|| {
+                get_reserve_authority_token_account(
+                    *token_2022_mint,
+                    reserve_authority,
+                    crate::id(),
+                )
+                .0
+            });
+        let reserve_shard_count = wrapper_config
+            .as_ref()
+            .map_or(0, |config| config.reserve_shard_count);
+        let reserves: Vec<Pubkey> = std::iter::once(reserve_token_2022_token_account)
+            .chain((1..=reserve_shard_count).map(|reserve_shard| {
+                get_reserve_shard(
+                    *token_2022_mint,
+                    reserve_authority,
+                    reserve_shard,
+                    crate::id(),
+                )
+                .0
+            }))
+            .collect();
+        let reserve_accounts = self.rpc_client.get_multiple_accounts(&reserves).await?;
+
+        let token_2022_mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(
+            &token_2022_mint_account.data,
+        )?;
+        let decimals = token_2022_mint_state.base.decimals;
+        let token_2022_extensions = token_2022_mint_state.get_extension_types()?;
+        let mut reserve_balance = 0u64;
+        for (reserve, reserve_account) in reserves.iter().zip(reserve_accounts) {
+            let reserve_account =
+                reserve_account.ok_or(WrapperClientError::AccountNotFound(*reserve))?;
+            let amount = StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+                &reserve_account.data,
+            )?
+            .base
+            .amount;
+            reserve_balance = reserve_balance
+                .checked_add(amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        let wrapper_inventory_balance = if wrapper_config
+            .as_ref()
+            .is_some_and(|config| config.has_wrapper_inventory)
+        {
+            let (wrapper_inventory, _, _) =
+                get_wrapper_inventory(*token_2022_mint, reserve_authority, crate::id());
+            let wrapper_inventory_account = self.rpc_client.get_account(&wrapper_inventory).await?;
+
+            spl_token::state::Account::unpack(&wrapper_inventory_account.data)?.amount
+        } else {
+            0
+        };
+        let wrapper_limits = if wrapper_config
+            .as_ref()
+            .is_some_and(|config| config.has_wrapper_limits)
+        {
+            let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+            let wrapper_limits_account = self.rpc_client.get_account(&wrapper_limits).await?;
+
+            Some(WrapperLimits::unpack(&wrapper_limits_account.data)?)
+        } else {
+            None
+        };
+        let wrapper_supply = spl_token::state::Mint::unpack(&wrapper_token_mint_account.data)?
+            .supply
+            .saturating_sub(wrapper_inventory_balance);
+
+        Ok(WrapperInfo {
+            token_2022_mint: *token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            reserve_token_2022_token_account,
+            decimals,
+            token_2022_extensions,
+            reserve_balance,
+            wrapper_supply,
+            wrapper_inventory_balance,
+            wrapper_config,
+            wrapper_limits,
+            global_paused,
+            token_2022_mint_account,
+        })
+    }
+
+    /// Wrapper tokens minted for `amount` Token 2022 tokens at the current epoch
+    pub async fn quote_wrap(
+        &self,
+        token_2022_mint: &Pubkey,
+        amount: u64,
+    ) -> WrapperClientResult<Quote> {
+        let wrapper_info = self.get_wrapper_info(token_2022_mint).await?;
+        wrapper_info.quote_wrap(self.get_epoch().await?, amount)
+    }
+
+    /// Token 2022 tokens received for `amount` wrapper tokens at the current epoch
+    pub async fn quote_unwrap(
+        &self,
+        token_2022_mint: &Pubkey,
+        amount: u64,
+    ) -> WrapperClientResult<Quote> {
+        let wrapper_info = self.get_wrapper_info(token_2022_mint).await?;
+        wrapper_info.quote_unwrap(self.get_epoch().await?, amount)
+    }
+
+    /// Instructions creating the wrapper of `token_2022_mint`
+    pub fn initialize_instructions(&self, token_2022_mint: &Pubkey) -> Vec<Instruction> {
+        vec![create_initialize_wrapper_token_v2_instruction(
+            &self.payer(),
+            token_2022_mint,
+        )]
+    }
+
+    /// Instructions wrapping `amount` Token 2022 tokens of the payer, creating its wrapper token
+    /// account if needed, and their quote
+    pub async fn wrap_instructions(
+        &self,
+        token_2022_mint: &Pubkey,
+        amount: u64,
+    ) -> WrapperClientResult<(Vec<Instruction>, Quote)> {
+        let wrapper_info = self.get_wrapper_info(token_2022_mint).await?;
+        let quote = wrapper_info.quote_wrap(self.get_epoch().await?, amount)?;
+
+        // v3 takes the fee vault and the wrapper limits, the reserve is shard zero
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+            &self.payer(),
+            token_2022_mint,
+            &self.wrapper_token_account(token_2022_mint),
+            &self.token_2022_token_account(token_2022_mint),
+            DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: DepositAndMintWrapperTokensArgs {
+                    amount,
+                    min_out: quote.amount_out,
+                    ..Default::default()
+                },
+            },
+        );
+
+        let instructions = vec![
+            create_associated_token_account_idempotent(
+                &self.payer(),
+                &self.payer(),
+                &wrapper_info.wrapper_token_mint,
+                &spl_token::id(),
+            ),
+            with_current_accounts(deposit_ix, &wrapper_info),
+        ];
+
+        Ok((instructions, quote))
+    }
+
+    /// Instructions unwrapping `amount` wrapper tokens of the payer, creating its Token 2022
+    /// token account if needed, and their quote
+    pub async fn unwrap_instructions(
+        &self,
+        token_2022_mint: &Pubkey,
+        amount: u64,
+    ) -> WrapperClientResult<(Vec<Instruction>, Quote)> {
+        let wrapper_info = self.get_wrapper_info(token_2022_mint).await?;
+        let quote = wrapper_info.quote_unwrap(self.get_epoch().await?, amount)?;
+
+        let withdraw_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+            &self.payer(),
+            token_2022_mint,
+            &self.wrapper_token_account(token_2022_mint),
+            &self.token_2022_token_account(token_2022_mint),
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount,
+                    min_out: quote.amount_out,
+                    ..Default::default()
+                },
+            },
+        );
+
+        let instructions = vec![
+            create_associated_token_account_idempotent(
+                &self.payer(),
+                &self.payer(),
+                token_2022_mint,
+                &spl_token_2022::id(),
+            ),
+            with_current_accounts(withdraw_ix, &wrapper_info),
+        ];
+
+        Ok((instructions, quote))
+    }
+
+    /// Creates the wrapper of `token_2022_mint`
+    pub async fn initialize(&self, token_2022_mint: &Pubkey) -> WrapperClientResult<Signature> {
+        self.send_instructions(&self.initialize_instructions(token_2022_mint))
+            .await
+    }
+
+    /// Wraps `amount` Token 2022 tokens of the payer, returning the signature and the quote
+    pub async fn wrap(
+        &self,
+        token_2022_mint: &Pubkey,
+        amount: u64,
+    ) -> WrapperClientResult<(Signature, Quote)> {
+        let (instructions, quote) = self.wrap_instructions(token_2022_mint, amount).await?;
+        Ok((self.send_instructions(&instructions).await?, quote))
+    }
+
+    /// Unwraps `amount` wrapper tokens of the payer, returning the signature and the quote
+    pub async fn unwrap(
+        &self,
+        token_2022_mint: &Pubkey,
+        amount: u64,
+    ) -> WrapperClientResult<(Signature, Quote)> {
+        let (instructions, quote) = self.unwrap_instructions(token_2022_mint, amount).await?;
+        Ok((self.send_instructions(&instructions).await?, quote))
+    }
+
+    /// Signs `instructions` with the payer and sends them, waiting for confirmation
+    pub async fn send_instructions(
+        &self,
+        instructions: &[Instruction],
+    ) -> WrapperClientResult<Signature> {
+        let transaction = self.sign_instructions(instructions).await?;
+        Ok(self
+            .rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await?)
+    }
+
+    /// Signs `instructions` with the payer and simulates them
+    pub async fn simulate_instructions(
+        &self,
+        instructions: &[Instruction],
+    ) -> WrapperClientResult<RpcSimulateTransactionResult> {
+        let transaction = self.sign_instructions(instructions).await?;
+        Ok(self
+            .rpc_client
+            .simulate_transaction(&transaction)
+            .await?
+            .value)
+    }
+
+    async fn sign_instructions(
+        &self,
+        instructions: &[Instruction],
+    ) -> WrapperClientResult<Transaction> {
+        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer()),
+            &[&self.payer],
+            recent_blockhash,
+        ))
+    }
+
+    /// Registry of the wrappers of `known` and of the wrappers configured on chain, which are
+    /// appended in the order of their Token 2022 mint
+    ///
+    /// Names are the token metadata symbols of the Token 2022 mints, or the names in `known` for
+    /// mints without one. Decimals are read from the mints. Entries of `known` whose wrapper is
+    /// not initialized on the cluster are dropped.
+    pub async fn generate_registry(&self, known: &Registry) -> WrapperClientResult<Registry> {
+        let wrapper_configs = self
+            .rpc_client
+            .get_program_accounts_with_config(
+                &crate::id(),
+                RpcProgramAccountsConfig {
+                    filters: Some(wrapper_config_filters()),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let mut configured = wrapper_configs
+            .iter()
+            .map(|(_, account)| Ok(WrapperConfig::unpack(&account.data)?.token_2022_mint))
+            .collect::<WrapperClientResult<Vec<_>>>()?;
+        configured.sort();
+
+        let mut token_2022_mints: Vec<Pubkey> = known
+            .entries()
+            .iter()
+            .map(|entry| entry.token_2022_mint)
+            .collect();
+        for token_2022_mint in configured {
+            if !token_2022_mints.contains(&token_2022_mint) {
+                token_2022_mints.push(token_2022_mint);
+            }
+        }
+
+        let mut entries = vec![];
+        // Two accounts per mint, within the 100 accounts of `getMultipleAccounts`
+        for chunk in token_2022_mints.chunks(50) {
+            let keys: Vec<Pubkey> = chunk
+                .iter()
+                .flat_map(|token_2022_mint| {
+                    [
+                        *token_2022_mint,
+                        get_wrapper_token_mint(*token_2022_mint, crate::id()).0,
+                    ]
+                })
+                .collect();
+            let accounts = self.rpc_client.get_multiple_accounts(&keys).await?;
+
+            for (token_2022_mint, accounts) in chunk.iter().zip(accounts.chunks(2)) {
+                let [Some(token_2022_mint_account), Some(_)] = accounts else {
+                    continue;
+                };
+                entries.push(RegistryEntry::from_token_2022_mint(
+                    *token_2022_mint,
+                    &token_2022_mint_account.data,
+                    known
+                        .find_by_token_2022_mint(token_2022_mint)
+                        .map(|entry| entry.token_name.as_str()),
+                )?);
+            }
+        }
+
+        Ok(Registry::new(entries)?)
+    }
+
+    async fn get_epoch(&self) -> WrapperClientResult<u64> {
+        Ok(self.rpc_client.get_epoch_info().await?.epoch)
+    }
+}
+
+/// Filters of the `getProgramAccounts` request listing the wrapper configs
+pub fn wrapper_config_filters() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(WrapperConfig::LEN as u64),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            vec![AccountType::WrapperConfig as u8],
+        )),
+    ]
+}
+
+/// Points a deposit or withdrawal to the migrated reserve, when the wrapper has migrated it
+fn with_current_accounts(instruction: Instruction, wrapper_info: &WrapperInfo) -> Instruction {
+    match wrapper_info
+        .wrapper_config
+        .as_ref()
+        .and_then(WrapperConfig::migrated_reserve)
+    {
+        Some(_) => with_migrated_reserve(instruction, &wrapper_info.token_2022_mint),
+        None => instruction,
+    }
+}
@@ -1,8 +1,8 @@
-use num_enum::IntoPrimitive;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use solana_program::program_error::ProgramError;
 use thiserror::Error;
 
-#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, IntoPrimitive)]
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
 #[repr(u32)]
 
 pub enum TokenWrapperError {
@@ -28,6 +28,56 @@ pub enum TokenWrapperError {
     ExpectedOwnerWrapperProgram = 9,
     #[error("Missing signer")]
     MissingSigner = 10,
+    #[error("Unsupported instruction flags")]
+    UnsupportedInstructionFlags = 11,
+    #[error("Amount received is below the requested minimum")]
+    SlippageExceeded = 12,
+    #[error("Invalid wrapper config")]
+    InvalidWrapperConfig = 13,
+    #[error("Unexpected admin authority")]
+    UnexpectedAdmin = 14,
+    #[error("Fee exceeds the maximum basis points")]
+    FeeTooHigh = 15,
+    #[error("Invalid program config")]
+    InvalidProgramConfig = 16,
+    #[error("Deposits are paused")]
+    DepositsPaused = 17,
+    #[error("Token 2022 token account state does not allow the freeze or thaw")]
+    FreezeStateMismatch = 18,
+    #[error("Reserve has already been migrated")]
+    ReserveAlreadyMigrated = 19,
+    #[error("Reserve balance is below the wrapper token supply")]
+    ReserveUndercollateralized = 20,
+    #[error("Token 2022 mint has changed since the wrapper was initialized")]
+    SourceMintChanged = 21,
+    #[error("Wrapper has been retired")]
+    WrapperRetired = 22,
+    #[error("Token 2022 mint is not a member of a token group")]
+    NotAGroupMember = 23,
+    #[error("Invalid wrapper group")]
+    InvalidWrapperGroup = 24,
+    #[error("Invalid reserve shard")]
+    InvalidReserveShard = 25,
+    #[error("Maximum number of reserve shards reached")]
+    TooManyReserveShards = 26,
+    #[error("Invalid wrapper inventory")]
+    InvalidWrapperInventory = 27,
+    #[error("Wrapper inventory balance is below the wrapper tokens transferred")]
+    InsufficientWrapperInventory = 28,
+    #[error("Invalid wrapper limits")]
+    InvalidWrapperLimits = 29,
+    #[error("Wrapper token supply would exceed the supply cap")]
+    SupplyCapExceeded = 30,
+    #[error("Net wrapped amount would exceed the limit of the epoch")]
+    EpochWrapLimitExceeded = 31,
+    #[error("Net unwrapped amount would exceed the limit of the epoch")]
+    EpochUnwrapLimitExceeded = 32,
+    #[error("Invalid fee vault")]
+    InvalidFeeVault = 33,
+    #[error("Invalid issuer freeze")]
+    InvalidIssuerFreeze = 34,
+    #[error("Instruction version does not support the fees or limits of the wrapper")]
+    UnsupportedInstructionVersion = 35,
 }
 
 impl From<TokenWrapperError> for ProgramError {
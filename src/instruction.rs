@@ -1,99 +1,534 @@
-use num_enum::TryFromPrimitive;
+use borsh::{BorshDeserialize, BorshSerialize};
 use shank::ShankInstruction;
-use solana_program::{msg, program_error::ProgramError};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
-#[repr(u8)]
-#[derive(TryFromPrimitive, Clone, Copy, ShankInstruction, PartialEq, Eq)]
+/// Arguments of the v1 deposit and withdraw instructions, a plain little-endian `u64` amount
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AmountArgs {
+    /// Amount of tokens to deposit or withdraw, `u64::MAX` uses the full balance
+    pub amount: u64,
+}
+
+/// Arguments of the `DepositAndMintWrapperTokensV2` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepositAndMintWrapperTokensArgs {
+    /// Amount of Token 2022 tokens to deposit, `u64::MAX` uses the full balance
+    pub amount: u64,
+    /// Minimum amount of wrapper tokens to be minted after Token 2022 transfer fees
+    pub min_out: u64,
+    /// Reserved for future use, must be zero
+    pub flags: u8,
+    /// Optional memo logged by the program
+    pub memo: Option<String>,
+}
+
+/// Arguments of the `WithdrawAndBurnWrapperTokensV2` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawAndBurnWrapperTokensArgs {
+    /// Amount of wrapper tokens to burn, `u64::MAX` uses the full balance
+    pub amount: u64,
+    /// Minimum amount of Token 2022 tokens to be received after Token 2022 transfer fees
+    pub min_out: u64,
+    /// Reserved for future use, must be zero
+    pub flags: u8,
+    /// Optional memo logged by the program
+    pub memo: Option<String>,
+}
+
+/// Arguments of the `SetFee` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetFeeArgs {
+    /// Fee charged on wraps, at most `MAX_FEE_BASIS_POINTS`
+    pub wrap_fee_basis_points: u16,
+    /// Fee charged on unwraps, at most `MAX_FEE_BASIS_POINTS`
+    pub unwrap_fee_basis_points: u16,
+    /// Maximum fee charged by a single wrap or unwrap, zero for no maximum
+    pub maximum_fee: u64,
+}
+
+/// Arguments of the `SetPause` and `SetGlobalPause` instructions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetPauseArgs {
+    /// Whether deposits are paused, withdrawals are never paused
+    pub paused: bool,
+}
+
+/// Arguments of the `SetPendingAdmin` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetPendingAdminArgs {
+    /// Admin proposed to replace the current one, takes over once it calls `AcceptAdmin`
+    pub pending_admin: Pubkey,
+}
+
+/// Arguments of the `QuoteWrap` and `QuoteUnwrap` instructions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuoteArgs {
+    /// Amount of Token 2022 tokens to wrap, or of wrapper tokens to unwrap
+    pub amount: u64,
+}
+
+/// Arguments of the `RebalanceReserveShards` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RebalanceReserveShardsArgs {
+    /// Reserve shard the Token 2022 tokens are moved from, zero for the reserve
+    pub source_reserve_shard: u8,
+    /// Reserve shard the Token 2022 tokens are moved to, zero for the reserve
+    pub destination_reserve_shard: u8,
+    /// Amount of Token 2022 tokens to move, `u64::MAX` uses the full balance of the source
+    pub amount: u64,
+}
+
+/// Arguments of the `DepositAndMintWrapperTokensV3` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepositAndMintWrapperTokensV3Args {
+    /// Reserve shard receiving the deposit, zero for the reserve
+    pub reserve_shard: u8,
+    pub args: DepositAndMintWrapperTokensArgs,
+}
+
+/// Arguments of the `WithdrawAndBurnWrapperTokensV3` instruction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawAndBurnWrapperTokensV3Args {
+    /// Reserve shard the withdrawal is paid from, zero for the reserve
+    pub reserve_shard: u8,
+    pub args: WithdrawAndBurnWrapperTokensArgs,
+}
+
+/// Arguments of the `SetWrapperLimits` instruction, zero disables a limit
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetWrapperLimitsArgs {
+    /// Maximum wrapper token supply
+    pub supply_cap: u64,
+    /// Maximum of wrapped minus unwrapped wrapper tokens in an epoch
+    pub epoch_wrap_limit: u64,
+    /// Maximum of unwrapped minus wrapped wrapper tokens in an epoch
+    pub epoch_unwrap_limit: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, ShankInstruction, PartialEq, Eq)]
+#[rustfmt::skip]
 pub enum TokenWrapperInstruction {
     /// 0
     /// Initializes a wrapper token mint on the Token Program for a particular Token 2022 token
     ///
-    /// Accounts expected by this instruction:
-    ///
-    /// 0. `[signer, writable]` The payer paying for the initialization of mint account on the Token program
-    /// 1. `[]` Token2022 token mint
-    /// 2. `[writable]` Wrapper token mint, uninitialized
-    ///     Must be a PDA with seeds ["wrapper", Token2022 token mint]
-    /// 3. `[writable]` Reserve authority, uninitialized
-    ///     Must be a PDA with seeds ["reserve_authority", Token2022 token mint]
-    /// 4. `[writable]` Reserve authority token account, uninitialized
-    ///     Must be a PDA with seeds ["reserve_authority_token_account", Token2022 token mint, reserve_authority PDA pubkey]
-    /// 3. `[]` SPL Token program
-    /// 4. `[]` Token 2022 program
-    /// 5. `[]` System program
-    /// 6. `[]` Rent sysvar
-    InitializeWrapperToken = 0,
+    /// Prefer `InitializeWrapperTokenV2`, which does not take the unused Rent sysvar
+    #[account(0, signer, writable, name = "payer", desc = "The payer paying for the initialization of mint account on the Token program")]
+    #[account(1, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(2, writable, name = "wrapper_token_mint", desc = "Wrapper token mint, uninitialized. PDA with seeds [\"wrapper\", Token2022 token mint]")]
+    #[account(3, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(4, writable, name = "reserve_token_2022_token_account", desc = "Reserve authority token account, uninitialized. PDA with seeds [\"reserve_authority_token_account\", Token2022 token mint, reserve authority]")]
+    #[account(5, name = "token_program", desc = "SPL Token program")]
+    #[account(6, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(7, name = "system_program", desc = "System program")]
+    #[account(8, name = "rent", desc = "Rent sysvar")]
+    InitializeWrapperToken,
 
     /// 1
     /// Mints wrapper tokens created using SPL Token Program in exchange of Token 2022 deposits
     ///
-    /// Accounts expected by this instruction:
-    ///
-    /// 0. `[signer, writable]` User authority
-    /// 1. `[]` Reserve authority
-    ///     Must be a PDA with seeds ["reserve_authority", Token2022 token mint]
-    /// 2. `[]` Token2022 token mint
-    /// 3. `[writable]` Wrapper token mint
-    /// 4. `[writable]` User's token account for the wrapper token
-    /// 5. `[writable]` User's token account for the Token2022 token
-    /// 6. `[writable]` Reserve's token account for the Token2022 token
-    /// 7. `[]` SPL Token program
-    /// 8. `[]` Token2022 program
-    /// 9. `[]` System program
-    /// 10. `[]` Associated token program
-    /// 11. `[]` Rent sysvar
-    DepositAndMintWrapperTokens = 1,
+    /// Prefer `DepositAndMintWrapperTokensV2`, which does not take the unused System program,
+    /// Associated token program and Rent sysvar
+    /// Fails with a wrap fee or wrapper limits, which take the fee vault and the wrapper limits
+    /// of `DepositAndMintWrapperTokensV3`
+    #[account(0, signer, writable, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve's token account for the Token2022 token")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, name = "system_program", desc = "System program")]
+    #[account(10, name = "associated_token_program", desc = "Associated token program")]
+    #[account(11, name = "rent", desc = "Rent sysvar")]
+    #[account(12, name = "program_config", desc = "Program config, holds the global pause. PDA with seeds [\"program_config\"]")]
+    DepositAndMintWrapperTokens(AmountArgs),
 
     /// 2
     /// Burns wrapper tokens created using Token Program in exchange of Token 2022 withdrawals
     ///
-    /// Accounts expected by this instruction:
-    ///
-    /// 0. `[signer, writable]` User authority
-    /// 1. `[]` Reserve authority
-    ///     Must be a PDA with seeds ["reserve_authority", Token2022 token mint]
-    /// 2. `[]` Token2022 token mint
-    /// 3. `[]` Wrapper token mint
-    /// 4. `[writable]` User's token account for the wrapper token
-    /// 5. `[writable]` User's token account for the Token2022 token
-    /// 6. `[writable]` Reserve's token account for the Token2022 token
-    /// 7. `[]` SPL Token program
-    /// 8. `[]` Token2022 program
-    /// 9. `[]` System program
-    /// 10. `[]` Rent sysvar
-    WithdrawAndBurnWrapperTokens = 2,
+    /// Prefer `WithdrawAndBurnWrapperTokensV2`, which does not take the unused System program
+    /// and Rent sysvar
+    /// Fails with an unwrap fee or wrapper limits, which take the fee vault and the wrapper limits
+    /// of `WithdrawAndBurnWrapperTokensV3`
+    #[account(0, signer, writable, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve's token account for the Token2022 token")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, name = "system_program", desc = "System program")]
+    #[account(10, name = "rent", desc = "Rent sysvar")]
+    WithdrawAndBurnWrapperTokens(AmountArgs),
+
+    /// 3
+    /// Initializes a wrapper token mint on the Token Program for a particular Token 2022 token
+    /// Same as `InitializeWrapperToken`, without the Rent sysvar
+    #[account(0, signer, writable, name = "payer", desc = "The payer paying for the initialization of mint account on the Token program")]
+    #[account(1, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(2, writable, name = "wrapper_token_mint", desc = "Wrapper token mint, uninitialized. PDA with seeds [\"wrapper\", Token2022 token mint]")]
+    #[account(3, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(4, writable, name = "reserve_token_2022_token_account", desc = "Reserve authority token account, uninitialized. PDA with seeds [\"reserve_authority_token_account\", Token2022 token mint, reserve authority]")]
+    #[account(5, name = "token_program", desc = "SPL Token program")]
+    #[account(6, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(7, name = "system_program", desc = "System program")]
+    InitializeWrapperTokenV2,
+
+    /// 4
+    /// Mints wrapper tokens created using SPL Token Program in exchange of Token 2022 deposits
+    /// Same as `DepositAndMintWrapperTokens`, without the System program, Associated token
+    /// program and Rent sysvar
+    /// Fails with a wrap fee or wrapper limits, which take the fee vault and the wrapper limits
+    /// of `DepositAndMintWrapperTokensV3`
+    #[account(0, signer, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve's token account for the Token2022 token")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, name = "program_config", desc = "Program config, holds the global pause. PDA with seeds [\"program_config\"]")]
+    DepositAndMintWrapperTokensV2(DepositAndMintWrapperTokensArgs),
+
+    /// 5
+    /// Burns wrapper tokens created using Token Program in exchange of Token 2022 withdrawals
+    /// Same as `WithdrawAndBurnWrapperTokens`, without the System program and Rent sysvar
+    /// Fails with an unwrap fee or wrapper limits, which take the fee vault and the wrapper limits
+    /// of `WithdrawAndBurnWrapperTokensV3`
+    #[account(0, signer, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve's token account for the Token2022 token")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    WithdrawAndBurnWrapperTokensV2(WithdrawAndBurnWrapperTokensArgs),
+
+    /// 6
+    /// Sets the wrap and unwrap fees of a wrapper and the wrapper token account receiving them
+    /// Creates the wrapper config in the reserve authority account and the fee vault on first use
+    #[account(0, signer, writable, name = "admin", desc = "Program admin, pays for the wrapper config and the fee vault")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, name = "fee_recipient", desc = "Wrapper token account receiving the collected fees")]
+    #[account(6, name = "system_program", desc = "System program")]
+    #[account(7, writable, name = "fee_vault", desc = "Fee vault, created on first use. PDA with seeds [\"fee_vault\", Token2022 token mint, reserve authority]")]
+    #[account(8, name = "token_program", desc = "SPL Token program")]
+    SetFee(SetFeeArgs),
+
+    /// 7
+    /// Transfers the wrapper tokens collected in the fee vault to the fee recipient
+    #[account(0, signer, name = "admin", desc = "Program admin")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, writable, name = "fee_vault", desc = "Fee vault. PDA with seeds [\"fee_vault\", Token2022 token mint, reserve authority]")]
+    #[account(6, writable, name = "fee_recipient", desc = "Wrapper token account receiving the collected fees")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    CollectFees,
+
+    /// 8
+    /// Initializes the program config, with the program upgrade authority as admin
+    #[account(0, signer, writable, name = "upgrade_authority", desc = "Program upgrade authority, pays for the program config")]
+    #[account(1, name = "program_data", desc = "Program data account of the wrapper program")]
+    #[account(2, writable, name = "program_config", desc = "Program config, uninitialized. PDA with seeds [\"program_config\"]")]
+    #[account(3, name = "system_program", desc = "System program")]
+    InitializeProgramConfig,
+
+    /// 9
+    /// Pauses or resumes the deposits of a wrapper, withdrawals remain available
+    /// Creates the wrapper config in the reserve authority account on first use
+    #[account(0, signer, writable, name = "admin", desc = "Program admin, pays for the wrapper config")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, name = "system_program", desc = "System program")]
+    SetPause(SetPauseArgs),
+
+    /// 10
+    /// Pauses or resumes the deposits of all wrappers, withdrawals remain available
+    #[account(0, signer, name = "admin", desc = "Program admin")]
+    #[account(1, writable, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    SetGlobalPause(SetPauseArgs),
+
+    /// 11
+    /// Proposes a new program admin, which takes over once it calls `AcceptAdmin`
+    #[account(0, signer, name = "admin", desc = "Program admin")]
+    #[account(1, writable, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    SetPendingAdmin(SetPendingAdminArgs),
+
+    /// 12
+    /// Makes the pending admin the program admin
+    #[account(0, signer, name = "pending_admin", desc = "Pending program admin")]
+    #[account(1, writable, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    AcceptAdmin,
+
+    /// 13
+    /// Freezes a wrapper token account whose owner's Token 2022 associated token account is frozen,
+    /// or any wrapper token account when signed by the freeze authority of the Token 2022 mint,
+    /// which is then recorded in the issuer freeze
+    #[account(0, signer, writable, name = "authority", desc = "Freeze authority of the Token2022 token mint, paying for the issuer freeze, or any signer when mirroring the Token 2022 token account state")]
+    #[account(1, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(2, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(3, name = "reserve_authority", desc = "Reserve authority, freeze authority of the wrapper token mint. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(4, writable, name = "wrapper_token_account", desc = "Token account for the wrapper token")]
+    #[account(5, name = "token_2022_token_account", desc = "Associated token account for the Token2022 token of the wrapper token account owner")]
+    #[account(6, name = "token_program", desc = "SPL Token program")]
+    #[account(7, writable, name = "issuer_freeze", desc = "Issuer freeze of the wrapper token account, created by the freeze authority. PDA with seeds [\"issuer_freeze\", wrapper token account]")]
+    #[account(8, name = "system_program", desc = "System program")]
+    MirrorFreeze,
+
+    /// 14
+    /// Thaws a wrapper token account whose owner's Token 2022 associated token account is not
+    /// frozen and which the freeze authority of the Token 2022 mint did not freeze, or any wrapper
+    /// token account when signed by that freeze authority
+    #[account(0, signer, writable, name = "authority", desc = "Freeze authority of the Token2022 token mint, paying for the issuer freeze, or any signer when mirroring the Token 2022 token account state")]
+    #[account(1, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(2, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(3, name = "reserve_authority", desc = "Reserve authority, freeze authority of the wrapper token mint. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(4, writable, name = "wrapper_token_account", desc = "Token account for the wrapper token")]
+    #[account(5, name = "token_2022_token_account", desc = "Associated token account for the Token2022 token of the wrapper token account owner")]
+    #[account(6, name = "token_program", desc = "SPL Token program")]
+    #[account(7, writable, name = "issuer_freeze", desc = "Issuer freeze of the wrapper token account, created by the freeze authority. PDA with seeds [\"issuer_freeze\", wrapper token account]")]
+    #[account(8, name = "system_program", desc = "System program")]
+    MirrorThaw,
+
+    /// 15
+    /// Moves the reserve from the PDA token account to the associated token account of the
+    /// reserve authority, which becomes the reserve of the wrapper
+    /// Token 2022 transfer fees on the moved funds must be covered by a surplus in the reserve
+    /// Followed by the reserve shards in order, if the wrapper has any, then by the wrapper
+    /// inventory if it has one
+    #[account(0, signer, writable, name = "admin", desc = "Program admin, pays for the wrapper config and the associated token account")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, writable, name = "reserve_token_2022_token_account", desc = "Reserve authority token account. PDA with seeds [\"reserve_authority_token_account\", Token2022 token mint, reserve authority]")]
+    #[account(6, writable, name = "new_reserve_token_2022_token_account", desc = "Associated token account of the reserve authority for the Token2022 token")]
+    #[account(7, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(8, name = "associated_token_program", desc = "Associated token program")]
+    #[account(9, name = "system_program", desc = "System program")]
+    MigrateReserve,
+
+    /// 16
+    /// Grows the reserve token account to hold every account extension required by the Token
+    /// 2022 mint extensions
+    #[account(0, signer, writable, name = "payer", desc = "The payer paying for the additional rent of the reserve token account")]
+    #[account(1, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(2, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(3, writable, name = "reserve_token_2022_token_account", desc = "Reserve's token account for the Token2022 token")]
+    #[account(4, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(5, name = "system_program", desc = "System program")]
+    ReallocReserve,
+
+    /// 17
+    /// Retires a wrapper, rejecting further deposits. Permissionless once the Token 2022 mint has
+    /// been closed, handed to another program or re-created with other decimals or extensions,
+    /// otherwise signed by the admin
+    /// Creates the wrapper config in the reserve authority account if missing
+    #[account(0, signer, writable, name = "authority", desc = "Program admin, or any signer once the Token2022 token mint has changed. Pays for the wrapper config")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, name = "system_program", desc = "System program")]
+    RetireWrapper,
+
+    /// 18
+    /// Registers the wrapper of a Token 2022 mint with the `TokenGroupMember` extension in the
+    /// wrapper group of its token group, creating the wrapper group if missing
+    /// Permissionless, no-op if the wrapper is already registered
+    #[account(0, signer, writable, name = "payer", desc = "Pays for the wrapper group accounts")]
+    #[account(1, name = "token_2022_mint", desc = "Token2022 token mint, member of the token group")]
+    #[account(2, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(3, writable, name = "wrapper_group", desc = "Wrapper group. PDA with seeds [\"wrapper_group\", token group]")]
+    #[account(4, writable, name = "wrapper_group_member", desc = "Wrapper group member. PDA with seeds [\"wrapper_group_member\", token group, Token2022 token mint]")]
+    #[account(5, name = "system_program", desc = "System program")]
+    RegisterWrapperGroupMember,
+
+    /// 19
+    /// Returns the `Quote` of a wrap of `amount` Token 2022 tokens at the current epoch as return
+    /// data, without moving funds. Fails like `DepositAndMintWrapperTokensV2` while deposits are
+    /// paused, the wrapper is retired or the Token 2022 mint changed, the wrapper limits are not
+    /// checked
+    #[account(0, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(1, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(2, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(3, name = "program_config", desc = "Program config, holds the global pause. PDA with seeds [\"program_config\"]")]
+    QuoteWrap(QuoteArgs),
+
+    /// 20
+    /// Returns the `Quote` of an unwrap of `amount` wrapper tokens at the current epoch as return
+    /// data, without moving funds
+    #[account(0, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(1, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(2, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    QuoteUnwrap(QuoteArgs),
+
+    /// 21
+    /// Creates the next reserve shard of a wrapper, a Token 2022 token account owned by the
+    /// reserve authority which deposits and withdrawals can use instead of the reserve, so they
+    /// do not all write-lock the same account
+    /// Creates the wrapper config in the reserve authority account on first use
+    #[account(0, signer, writable, name = "admin", desc = "Program admin, pays for the wrapper config and the reserve shard")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, writable, name = "reserve_shard", desc = "Reserve shard, uninitialized. PDA with seeds [\"reserve_shard\", Token2022 token mint, reserve authority, [shard number]], numbered from one")]
+    #[account(6, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(7, name = "system_program", desc = "System program")]
+    InitializeReserveShard,
+
+    /// 22
+    /// Moves Token 2022 tokens between two reserve shards of a wrapper
+    /// Followed by every reserve shard in order, then by the wrapper inventory if the wrapper has
+    /// one. The reserve and the shards must still back the circulating wrapper token supply once
+    /// the Token 2022 transfer fee is withheld
+    #[account(0, signer, name = "admin", desc = "Program admin")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve's token account for the Token2022 token, reserve shard zero")]
+    RebalanceReserveShards(RebalanceReserveShardsArgs),
+
+    /// 23
+    /// Mints wrapper tokens created using SPL Token Program in exchange of Token 2022 deposits
+    /// Same as `DepositAndMintWrapperTokensV2`, into the reserve shard `reserve_shard`
+    #[account(0, signer, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve shard receiving the deposit, the reserve's token account for shard zero")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, name = "program_config", desc = "Program config, holds the global pause. PDA with seeds [\"program_config\"]")]
+    #[account(10, writable, name = "fee_vault", desc = "Fee vault, receives the wrap fee if the wrapper has one. PDA with seeds [\"fee_vault\", Token2022 token mint, reserve authority]")]
+    #[account(11, writable, name = "wrapper_limits", desc = "Wrapper limits, checked if the wrapper has them. PDA with seeds [\"wrapper_limits\", Token2022 token mint]")]
+    DepositAndMintWrapperTokensV3(DepositAndMintWrapperTokensV3Args),
+
+    /// 24
+    /// Burns wrapper tokens created using Token Program in exchange of Token 2022 withdrawals
+    /// Same as `WithdrawAndBurnWrapperTokensV2`, from the reserve shard `reserve_shard`
+    #[account(0, signer, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve shard paying the withdrawal, the reserve's token account for shard zero")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, writable, name = "fee_vault", desc = "Fee vault, receives the unwrap fee if the wrapper has one. PDA with seeds [\"fee_vault\", Token2022 token mint, reserve authority]")]
+    #[account(10, writable, name = "wrapper_limits", desc = "Wrapper limits, checked if the wrapper has them. PDA with seeds [\"wrapper_limits\", Token2022 token mint]")]
+    WithdrawAndBurnWrapperTokensV3(WithdrawAndBurnWrapperTokensV3Args),
+
+    /// 25
+    /// Creates the wrapper inventory, a wrapper token account owned by the reserve authority
+    /// holding pre-minted wrapper tokens, which deposits and withdrawals can transfer instead of
+    /// minting and burning, so they do not write-lock the wrapper token mint
+    /// Creates the wrapper config in the reserve authority account on first use
+    #[account(0, signer, writable, name = "admin", desc = "Program admin, pays for the wrapper config and the wrapper inventory")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, writable, name = "wrapper_inventory", desc = "Wrapper inventory, uninitialized. PDA with seeds [\"wrapper_inventory\", Token2022 token mint, reserve authority]")]
+    #[account(6, name = "token_program", desc = "SPL Token program")]
+    #[account(7, name = "system_program", desc = "System program")]
+    InitializeWrapperInventory,
+
+    /// 26
+    /// Mints `amount` wrapper tokens into the wrapper inventory. They stay out of the
+    /// circulating supply, which the reserve backs, until a deposit transfers them out
+    /// Followed by the wrapper limits if the wrapper has them, the supply cap applies
+    #[account(0, signer, name = "admin", desc = "Program admin")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, writable, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, writable, name = "wrapper_inventory", desc = "Wrapper inventory. PDA with seeds [\"wrapper_inventory\", Token2022 token mint, reserve authority]")]
+    #[account(6, name = "token_program", desc = "SPL Token program")]
+    TopUpWrapperInventory(AmountArgs),
+
+    /// 27
+    /// Transfers wrapper tokens from the wrapper inventory in exchange of Token 2022 deposits
+    /// Same as `DepositAndMintWrapperTokensV3`, without minting, fails if the inventory holds
+    /// fewer wrapper tokens than the deposit is worth
+    #[account(0, signer, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve shard receiving the deposit, the reserve's token account for shard zero")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, name = "program_config", desc = "Program config, holds the global pause. PDA with seeds [\"program_config\"]")]
+    #[account(10, writable, name = "wrapper_inventory", desc = "Wrapper inventory. PDA with seeds [\"wrapper_inventory\", Token2022 token mint, reserve authority]")]
+    #[account(11, writable, name = "fee_vault", desc = "Fee vault, receives the wrap fee if the wrapper has one. PDA with seeds [\"fee_vault\", Token2022 token mint, reserve authority]")]
+    #[account(12, writable, name = "wrapper_limits", desc = "Wrapper limits, checked if the wrapper has them. PDA with seeds [\"wrapper_limits\", Token2022 token mint]")]
+    DepositFromWrapperInventory(DepositAndMintWrapperTokensV3Args),
+
+    /// 28
+    /// Transfers wrapper tokens into the wrapper inventory in exchange of Token 2022 withdrawals
+    /// Same as `WithdrawAndBurnWrapperTokensV3`, without burning
+    #[account(0, signer, name = "user_authority", desc = "User authority")]
+    #[account(1, name = "reserve_authority", desc = "Reserve authority. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "user_wrapper_token_account", desc = "User's token account for the wrapper token")]
+    #[account(5, writable, name = "user_token_2022_token_account", desc = "User's token account for the Token2022 token")]
+    #[account(6, writable, name = "reserve_token_2022_token_account", desc = "Reserve shard paying the withdrawal, the reserve's token account for shard zero")]
+    #[account(7, name = "token_program", desc = "SPL Token program")]
+    #[account(8, name = "token_2022_program", desc = "Token 2022 program")]
+    #[account(9, writable, name = "wrapper_inventory", desc = "Wrapper inventory. PDA with seeds [\"wrapper_inventory\", Token2022 token mint, reserve authority]")]
+    #[account(10, writable, name = "fee_vault", desc = "Fee vault, receives the unwrap fee if the wrapper has one. PDA with seeds [\"fee_vault\", Token2022 token mint, reserve authority]")]
+    #[account(11, writable, name = "wrapper_limits", desc = "Wrapper limits, checked if the wrapper has them. PDA with seeds [\"wrapper_limits\", Token2022 token mint]")]
+    WithdrawToWrapperInventory(WithdrawAndBurnWrapperTokensV3Args),
+
+    /// 29
+    /// Sets the supply cap and the per-epoch net wrap and unwrap limits of a wrapper, zero
+    /// disabling a limit. Deposits and withdrawals then check them, which needs the v3 instructions
+    /// Creates the wrapper config and the wrapper limits on first use
+    #[account(0, signer, writable, name = "admin", desc = "Program admin, pays for the wrapper config and the wrapper limits")]
+    #[account(1, name = "program_config", desc = "Program config. PDA with seeds [\"program_config\"]")]
+    #[account(2, name = "token_2022_mint", desc = "Token2022 token mint")]
+    #[account(3, name = "wrapper_token_mint", desc = "Wrapper token mint")]
+    #[account(4, writable, name = "reserve_authority", desc = "Reserve authority, holds the wrapper config. PDA with seeds [\"reserve_authority\", Token2022 token mint]")]
+    #[account(5, writable, name = "wrapper_limits", desc = "Wrapper limits. PDA with seeds [\"wrapper_limits\", Token2022 token mint]")]
+    #[account(6, name = "system_program", desc = "System program")]
+    SetWrapperLimits(SetWrapperLimitsArgs),
 }
 
 impl TokenWrapperInstruction {
-    // Unpacks a byte buffer into a valid TokenWrapperInstruction
+    /// Unpacks a byte buffer into a valid TokenWrapperInstruction
+    ///
+    /// The first byte is the instruction tag, followed by the Borsh-encoded arguments of the
+    /// variant. Trailing bytes are rejected.
     pub fn unpack(instruction_data: &[u8]) -> Result<Self, ProgramError> {
-        let (tag, _) = instruction_data
-            .split_first()
-            .ok_or(ProgramError::InvalidInstructionData)?;
-
-        Ok(match tag {
-            0 => TokenWrapperInstruction::InitializeWrapperToken,
-            1 => TokenWrapperInstruction::DepositAndMintWrapperTokens,
-            2 => TokenWrapperInstruction::WithdrawAndBurnWrapperTokens,
-            _ => return Err(ProgramError::InvalidInstructionData),
-        })
-    }
-
-    pub fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
-        if input.len() < 8 {
-            msg!("u64 cannot be unpacked");
-            return Err(ProgramError::InvalidInstructionData);
-        }
-        let (bytes, rest) = input.split_at(8);
-        let value = bytes
-            .get(..8)
-            .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
-            .ok_or(ProgramError::InvalidInstructionData)?;
-        Ok((value, rest))
+        borsh::from_slice(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)
     }
 
-    pub fn to_vec(&self) -> Vec<u8> {
-        vec![*self as u8]
+    /// Packs a TokenWrapperInstruction into a byte buffer, the inverse of `unpack`
+    pub fn pack(&self) -> Vec<u8> {
+        borsh::to_vec(self).unwrap()
     }
 }
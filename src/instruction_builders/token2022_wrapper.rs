@@ -1,12 +1,25 @@
 use solana_program::{
+    bpf_loader_upgradeable,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     system_program, sysvar,
 };
 
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
 use crate::{
-    instruction::TokenWrapperInstruction,
-    utils::{get_reserve_authority, get_reserve_authority_token_account, get_wrapper_token_mint},
+    instruction::{
+        AmountArgs, DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, QuoteArgs,
+        RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, TokenWrapperInstruction, WithdrawAndBurnWrapperTokensArgs,
+        WithdrawAndBurnWrapperTokensV3Args,
+    },
+    utils::{
+        get_fee_vault, get_issuer_freeze, get_program_config, get_reserve_authority,
+        get_reserve_authority_token_account, get_reserve_shard, get_wrapper_group,
+        get_wrapper_group_member, get_wrapper_inventory, get_wrapper_limits,
+        get_wrapper_token_mint,
+    },
 };
 
 pub fn create_initialize_wrapper_token_instruction(
@@ -31,7 +44,7 @@ pub fn create_initialize_wrapper_token_instruction(
             AccountMeta::new_readonly(system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
         ],
-        data: TokenWrapperInstruction::InitializeWrapperToken.to_vec(),
+        data: TokenWrapperInstruction::InitializeWrapperToken.pack(),
     }
 }
 
@@ -48,10 +61,10 @@ pub fn create_deposit_and_mint_wrapper_tokens_instruction(
     let (reserve_token_2022_token_account, _, _) =
         get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
 
-    let mut instruction_data = Vec::new();
-    instruction_data
-        .extend_from_slice(&TokenWrapperInstruction::DepositAndMintWrapperTokens.to_vec());
-    instruction_data.extend_from_slice(&amount.to_le_bytes());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    let instruction_data =
+        TokenWrapperInstruction::DepositAndMintWrapperTokens(AmountArgs { amount }).pack();
 
     Instruction {
         program_id: crate::id(),
@@ -68,6 +81,7 @@ pub fn create_deposit_and_mint_wrapper_tokens_instruction(
             AccountMeta::new_readonly(system_program::id(), false),
             AccountMeta::new_readonly(spl_associated_token_account::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(program_config, false),
         ],
         data: instruction_data,
     }
@@ -86,10 +100,8 @@ pub fn create_withdraw_and_burn_wrapper_tokens_instruction(
     let (reserve_token_2022_token_account, _, _) =
         get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
 
-    let mut instruction_data = Vec::new();
-    instruction_data
-        .extend_from_slice(&TokenWrapperInstruction::WithdrawAndBurnWrapperTokens.to_vec());
-    instruction_data.extend_from_slice(&amount.to_le_bytes());
+    let instruction_data =
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokens(AmountArgs { amount }).pack();
 
     Instruction {
         program_id: crate::id(),
@@ -109,3 +121,814 @@ pub fn create_withdraw_and_burn_wrapper_tokens_instruction(
         data: instruction_data,
     }
 }
+
+pub fn create_initialize_wrapper_token_v2_instruction(
+    payer: &Pubkey,
+    token_2022_mint: &Pubkey,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new(reserve_token_2022_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::InitializeWrapperTokenV2.pack(),
+    }
+}
+
+pub fn create_deposit_and_mint_wrapper_tokens_v2_instruction(
+    user_authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    user_wrapper_token_account: &Pubkey,
+    user_token_2022_token_account: &Pubkey,
+    args: DepositAndMintWrapperTokensArgs,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    let instruction_data = TokenWrapperInstruction::DepositAndMintWrapperTokensV2(args).pack();
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*user_authority, true),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new(wrapper_token_mint, false),
+            AccountMeta::new(*user_wrapper_token_account, false),
+            AccountMeta::new(*user_token_2022_token_account, false),
+            AccountMeta::new(reserve_token_2022_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(program_config, false),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub fn create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+    user_authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    user_wrapper_token_account: &Pubkey,
+    user_token_2022_token_account: &Pubkey,
+    args: WithdrawAndBurnWrapperTokensArgs,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+
+    let instruction_data = TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2(args).pack();
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*user_authority, true),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new(wrapper_token_mint, false),
+            AccountMeta::new(*user_wrapper_token_account, false),
+            AccountMeta::new(*user_token_2022_token_account, false),
+            AccountMeta::new(reserve_token_2022_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub fn create_set_fee_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    fee_recipient: &Pubkey,
+    args: SetFeeArgs,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (fee_vault, _, _) = get_fee_vault(*token_2022_mint, reserve_authority, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new_readonly(*fee_recipient, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: TokenWrapperInstruction::SetFee(args).pack(),
+    }
+}
+
+pub fn create_collect_fees_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    fee_recipient: &Pubkey,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (fee_vault, _, _) = get_fee_vault(*token_2022_mint, reserve_authority, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: TokenWrapperInstruction::CollectFees.pack(),
+    }
+}
+
+pub fn create_initialize_program_config_instruction(upgrade_authority: &Pubkey) -> Instruction {
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*upgrade_authority, true),
+            AccountMeta::new_readonly(
+                bpf_loader_upgradeable::get_program_data_address(&crate::id()),
+                false,
+            ),
+            AccountMeta::new(program_config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::InitializeProgramConfig.pack(),
+    }
+}
+
+pub fn create_set_pause_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    args: SetPauseArgs,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::SetPause(args).pack(),
+    }
+}
+
+pub fn create_set_global_pause_instruction(admin: &Pubkey, args: SetPauseArgs) -> Instruction {
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(program_config, false),
+        ],
+        data: TokenWrapperInstruction::SetGlobalPause(args).pack(),
+    }
+}
+
+pub fn create_set_pending_admin_instruction(
+    admin: &Pubkey,
+    args: SetPendingAdminArgs,
+) -> Instruction {
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(program_config, false),
+        ],
+        data: TokenWrapperInstruction::SetPendingAdmin(args).pack(),
+    }
+}
+
+pub fn create_accept_admin_instruction(pending_admin: &Pubkey) -> Instruction {
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*pending_admin, true),
+            AccountMeta::new(program_config, false),
+        ],
+        data: TokenWrapperInstruction::AcceptAdmin.pack(),
+    }
+}
+
+pub fn create_mirror_freeze_instruction(
+    authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    wrapper_token_account: &Pubkey,
+    wrapper_token_account_owner: &Pubkey,
+) -> Instruction {
+    create_mirror_freeze_state_instruction(
+        authority,
+        token_2022_mint,
+        wrapper_token_account,
+        wrapper_token_account_owner,
+        TokenWrapperInstruction::MirrorFreeze,
+    )
+}
+
+pub fn create_mirror_thaw_instruction(
+    authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    wrapper_token_account: &Pubkey,
+    wrapper_token_account_owner: &Pubkey,
+) -> Instruction {
+    create_mirror_freeze_state_instruction(
+        authority,
+        token_2022_mint,
+        wrapper_token_account,
+        wrapper_token_account_owner,
+        TokenWrapperInstruction::MirrorThaw,
+    )
+}
+
+fn create_mirror_freeze_state_instruction(
+    authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    wrapper_token_account: &Pubkey,
+    wrapper_token_account_owner: &Pubkey,
+    instruction: TokenWrapperInstruction,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let token_2022_token_account = get_associated_token_address_with_program_id(
+        wrapper_token_account_owner,
+        token_2022_mint,
+        &spl_token_2022::id(),
+    );
+    let (issuer_freeze, _, _) = get_issuer_freeze(*wrapper_token_account, crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new(*wrapper_token_account, false),
+            AccountMeta::new_readonly(token_2022_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(issuer_freeze, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: instruction.pack(),
+    }
+}
+
+pub fn create_migrate_reserve_instruction(admin: &Pubkey, token_2022_mint: &Pubkey) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+    let new_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+        &reserve_authority,
+        token_2022_mint,
+        &spl_token_2022::id(),
+    );
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new(reserve_token_2022_token_account, false),
+            AccountMeta::new(new_reserve_token_2022_token_account, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::MigrateReserve.pack(),
+    }
+}
+
+pub fn create_realloc_reserve_instruction(payer: &Pubkey, token_2022_mint: &Pubkey) -> Instruction {
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new(reserve_token_2022_token_account, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::ReallocReserve.pack(),
+    }
+}
+
+pub fn create_retire_wrapper_instruction(
+    authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::RetireWrapper.pack(),
+    }
+}
+
+/// `group` is the token group in the `TokenGroupMember` extension of `token_2022_mint`
+pub fn create_register_wrapper_group_member_instruction(
+    payer: &Pubkey,
+    token_2022_mint: &Pubkey,
+    group: &Pubkey,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (wrapper_group, _, _) = get_wrapper_group(*group, crate::id());
+    let (wrapper_group_member, _, _) =
+        get_wrapper_group_member(*group, *token_2022_mint, crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(wrapper_group, false),
+            AccountMeta::new(wrapper_group_member, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::RegisterWrapperGroupMember.pack(),
+    }
+}
+
+pub fn create_quote_wrap_instruction(token_2022_mint: &Pubkey, args: QuoteArgs) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(program_config, false),
+        ],
+        data: TokenWrapperInstruction::QuoteWrap(args).pack(),
+    }
+}
+
+pub fn create_quote_unwrap_instruction(token_2022_mint: &Pubkey, args: QuoteArgs) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+        ],
+        data: TokenWrapperInstruction::QuoteUnwrap(args).pack(),
+    }
+}
+
+pub fn create_initialize_reserve_shard_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    reserve_shard: u8,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (reserve_shard, _, _) = get_reserve_shard(
+        *token_2022_mint,
+        reserve_authority,
+        reserve_shard,
+        crate::id(),
+    );
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new(reserve_shard, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::InitializeReserveShard.pack(),
+    }
+}
+
+/// `reserve_shard_count` is the number of reserve shards of the wrapper, which are all passed
+pub fn create_rebalance_reserve_shards_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    reserve_shard_count: u8,
+    args: RebalanceReserveShardsArgs,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    let instruction = Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new(reserve_token_2022_token_account, false),
+        ],
+        data: TokenWrapperInstruction::RebalanceReserveShards(args).pack(),
+    };
+
+    with_reserve_shards(instruction, token_2022_mint, reserve_shard_count)
+}
+
+pub fn create_deposit_and_mint_wrapper_tokens_v3_instruction(
+    user_authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    user_wrapper_token_account: &Pubkey,
+    user_token_2022_token_account: &Pubkey,
+    args: DepositAndMintWrapperTokensV3Args,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let reserve_shard =
+        get_reserve_shard_token_account(token_2022_mint, &reserve_authority, args.reserve_shard);
+    let (program_config, _, _) = get_program_config(crate::id());
+    let (fee_vault, _, _) = get_fee_vault(*token_2022_mint, reserve_authority, crate::id());
+    let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+
+    let instruction_data = TokenWrapperInstruction::DepositAndMintWrapperTokensV3(args).pack();
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*user_authority, true),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new(wrapper_token_mint, false),
+            AccountMeta::new(*user_wrapper_token_account, false),
+            AccountMeta::new(*user_token_2022_token_account, false),
+            AccountMeta::new(reserve_shard, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(wrapper_limits, false),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub fn create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+    user_authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    user_wrapper_token_account: &Pubkey,
+    user_token_2022_token_account: &Pubkey,
+    args: WithdrawAndBurnWrapperTokensV3Args,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let reserve_shard =
+        get_reserve_shard_token_account(token_2022_mint, &reserve_authority, args.reserve_shard);
+    let (fee_vault, _, _) = get_fee_vault(*token_2022_mint, reserve_authority, crate::id());
+    let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+
+    let instruction_data = TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV3(args).pack();
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*user_authority, true),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new(wrapper_token_mint, false),
+            AccountMeta::new(*user_wrapper_token_account, false),
+            AccountMeta::new(*user_token_2022_token_account, false),
+            AccountMeta::new(reserve_shard, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(wrapper_limits, false),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub fn create_initialize_wrapper_inventory_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (wrapper_inventory, _, _) =
+        get_wrapper_inventory(*token_2022_mint, reserve_authority, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new(wrapper_inventory, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::InitializeWrapperInventory.pack(),
+    }
+}
+
+pub fn create_top_up_wrapper_inventory_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (wrapper_inventory, _, _) =
+        get_wrapper_inventory(*token_2022_mint, reserve_authority, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new(wrapper_token_mint, false),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new(wrapper_inventory, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: TokenWrapperInstruction::TopUpWrapperInventory(AmountArgs { amount }).pack(),
+    }
+}
+
+pub fn create_deposit_from_wrapper_inventory_instruction(
+    user_authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    user_wrapper_token_account: &Pubkey,
+    user_token_2022_token_account: &Pubkey,
+    args: DepositAndMintWrapperTokensV3Args,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let reserve_shard =
+        get_reserve_shard_token_account(token_2022_mint, &reserve_authority, args.reserve_shard);
+    let (wrapper_inventory, _, _) =
+        get_wrapper_inventory(*token_2022_mint, reserve_authority, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+    let (fee_vault, _, _) = get_fee_vault(*token_2022_mint, reserve_authority, crate::id());
+    let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+
+    let instruction_data = TokenWrapperInstruction::DepositFromWrapperInventory(args).pack();
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*user_authority, true),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(*user_wrapper_token_account, false),
+            AccountMeta::new(*user_token_2022_token_account, false),
+            AccountMeta::new(reserve_shard, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new(wrapper_inventory, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(wrapper_limits, false),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub fn create_withdraw_to_wrapper_inventory_instruction(
+    user_authority: &Pubkey,
+    token_2022_mint: &Pubkey,
+    user_wrapper_token_account: &Pubkey,
+    user_token_2022_token_account: &Pubkey,
+    args: WithdrawAndBurnWrapperTokensV3Args,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let reserve_shard =
+        get_reserve_shard_token_account(token_2022_mint, &reserve_authority, args.reserve_shard);
+    let (wrapper_inventory, _, _) =
+        get_wrapper_inventory(*token_2022_mint, reserve_authority, crate::id());
+    let (fee_vault, _, _) = get_fee_vault(*token_2022_mint, reserve_authority, crate::id());
+    let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+
+    let instruction_data = TokenWrapperInstruction::WithdrawToWrapperInventory(args).pack();
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*user_authority, true),
+            AccountMeta::new_readonly(reserve_authority, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(*user_wrapper_token_account, false),
+            AccountMeta::new(*user_token_2022_token_account, false),
+            AccountMeta::new(reserve_shard, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new(wrapper_inventory, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(wrapper_limits, false),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub fn create_set_wrapper_limits_instruction(
+    admin: &Pubkey,
+    token_2022_mint: &Pubkey,
+    args: SetWrapperLimitsArgs,
+) -> Instruction {
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, crate::id());
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+    let (program_config, _, _) = get_program_config(crate::id());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(program_config, false),
+            AccountMeta::new_readonly(*token_2022_mint, false),
+            AccountMeta::new_readonly(wrapper_token_mint, false),
+            AccountMeta::new(reserve_authority, false),
+            AccountMeta::new(wrapper_limits, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenWrapperInstruction::SetWrapperLimits(args).pack(),
+    }
+}
+
+/// Token account of the reserve shard `reserve_shard`, the original PDA token account of the
+/// reserve for shard zero
+fn get_reserve_shard_token_account(
+    token_2022_mint: &Pubkey,
+    reserve_authority: &Pubkey,
+    reserve_shard: u8,
+) -> Pubkey {
+    if reserve_shard == 0 {
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(*token_2022_mint, *reserve_authority, crate::id());
+
+        return reserve_token_2022_token_account;
+    }
+
+    let (reserve_shard, _, _) = get_reserve_shard(
+        *token_2022_mint,
+        *reserve_authority,
+        reserve_shard,
+        crate::id(),
+    );
+
+    reserve_shard
+}
+
+/// Points an instruction built for `token_2022_mint` to the reserve token account set by
+/// `MigrateReserve`, instead of the original PDA token account
+pub fn with_migrated_reserve(
+    mut instruction: Instruction,
+    token_2022_mint: &Pubkey,
+) -> Instruction {
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (reserve_token_2022_token_account, _, _) =
+        get_reserve_authority_token_account(*token_2022_mint, reserve_authority, crate::id());
+    let migrated_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+        &reserve_authority,
+        token_2022_mint,
+        &spl_token_2022::id(),
+    );
+
+    for account in instruction.accounts.iter_mut() {
+        if account.pubkey == reserve_token_2022_token_account {
+            account.pubkey = migrated_reserve_token_2022_token_account;
+        }
+    }
+
+    instruction
+}
+
+/// Appends the `reserve_shard_count` reserve shards of `token_2022_mint` to an instruction, in
+/// order, as expected by `MigrateReserve` and `RebalanceReserveShards`
+pub fn with_reserve_shards(
+    mut instruction: Instruction,
+    token_2022_mint: &Pubkey,
+    reserve_shard_count: u8,
+) -> Instruction {
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+
+    for reserve_shard in 1..=reserve_shard_count {
+        let (reserve_shard, _, _) = get_reserve_shard(
+            *token_2022_mint,
+            reserve_authority,
+            reserve_shard,
+            crate::id(),
+        );
+
+        instruction
+            .accounts
+            .push(AccountMeta::new(reserve_shard, false));
+    }
+
+    instruction
+}
+
+/// Appends the wrapper inventory of `token_2022_mint` to an instruction, after its reserve
+/// shards, as expected by `MigrateReserve` and `RebalanceReserveShards` once the wrapper has an
+/// inventory
+pub fn with_wrapper_inventory(
+    mut instruction: Instruction,
+    token_2022_mint: &Pubkey,
+) -> Instruction {
+    let (reserve_authority, _, _) = get_reserve_authority(*token_2022_mint, crate::id());
+    let (wrapper_inventory, _, _) =
+        get_wrapper_inventory(*token_2022_mint, reserve_authority, crate::id());
+
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(wrapper_inventory, false));
+
+    instruction
+}
+
+/// Appends the wrapper limits of `token_2022_mint` to `TopUpWrapperInventory`, after all its
+/// other accounts, once the wrapper has limits
+pub fn with_wrapper_limits(mut instruction: Instruction, token_2022_mint: &Pubkey) -> Instruction {
+    let (wrapper_limits, _, _) = get_wrapper_limits(*token_2022_mint, crate::id());
+
+    instruction
+        .accounts
+        .push(AccountMeta::new(wrapper_limits, false));
+
+    instruction
+}
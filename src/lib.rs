@@ -1,10 +1,28 @@
+#[cfg(feature = "amm")]
+pub mod amm;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod error;
 pub mod instruction;
 pub mod instruction_builders;
+#[cfg(feature = "lite")]
+pub mod lite;
+#[cfg(feature = "parser")]
+pub mod parser;
 pub mod processor;
+pub mod quote;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod state;
 pub mod utils;
 
+#[cfg(all(not(feature = "no-entrypoint"), not(feature = "lite")))]
 use processor::process_instruction;
+
+#[cfg(all(not(feature = "no-entrypoint"), feature = "lite"))]
+use lite::process_instruction;
+
+#[cfg(not(feature = "no-entrypoint"))]
 use solana_program::entrypoint;
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -0,0 +1,696 @@
+//! Allocation-free deposit and withdraw handlers, built with the `lite` feature
+//!
+//! The handlers parse the instruction arguments in place and build their CPIs on the stack. On
+//! chain the CPIs go through the C ABI of `sol_invoke_signed_c`, so no `Instruction` is
+//! allocated. Natively, as under `solana-program-test`, they fall back to `invoke_signed`.
+//!
+//! `tests/test_lite.rs` runs the program natively, so it only covers the `invoke_signed`
+//! fallback: the `sol_invoke_signed_c` path of `invoke_raw` is only exercised by the SBF build,
+//! which the tests do not load.
+//!
+//! The accounts are checked by the same code as in `processor`, `WrapperAccounts` and the views,
+//! with the PDA bumps recorded in the wrapper config. The reserve shard, wrapper inventory and
+//! other instructions are left to `processor::process_instruction`. `tests/test_lite.rs` runs the
+//! same scenarios against both and compares the resulting accounts.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    log::sol_log,
+    msg,
+    program_error::ProgramError,
+    pubkey::{Pubkey, MAX_SEEDS},
+};
+
+use crate::{
+    error::TokenWrapperError,
+    processor::{
+        self, assert_fee_vault_passed, OptionalAccounts, WrapperAccounts,
+        DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN,
+        WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2_ACCOUNTS_LEN,
+    },
+    state::ProgramConfig,
+    utils::{
+        assert_associated_token_program, assert_rent, assert_system_program, assert_with_msg,
+        is_program_config, TokenAccountView, WrapperMintsView,
+    },
+};
+
+const DEPOSIT_AND_MINT_WRAPPER_TOKENS: u8 = 1;
+const WITHDRAW_AND_BURN_WRAPPER_TOKENS: u8 = 2;
+const DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2: u8 = 4;
+const WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2: u8 = 5;
+
+const TRANSFER_CHECKED: u8 = 12;
+const MINT_TO_CHECKED: u8 = 14;
+const BURN_CHECKED: u8 = 15;
+const SYNC_NATIVE: u8 = 17;
+
+/// Entrypoint of the `lite` feature, the deposit and withdraw instructions are handled here and
+/// the other ones by `processor::process_instruction`
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match instruction_data.split_first() {
+        Some((&DEPOSIT_AND_MINT_WRAPPER_TOKENS, data)) => {
+            process_deposit_and_mint_wrapper_tokens(program_id, accounts, data)
+        }
+        Some((&WITHDRAW_AND_BURN_WRAPPER_TOKENS, data)) => {
+            process_withdraw_and_burn_wrapper_tokens(program_id, accounts, data)
+        }
+        Some((&DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2, data)) => {
+            process_deposit_and_mint_wrapper_tokens_v2(program_id, accounts, data)
+        }
+        Some((&WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2, data)) => {
+            process_withdraw_and_burn_wrapper_tokens_v2(program_id, accounts, data)
+        }
+        _ => processor::process_instruction(program_id, accounts, instruction_data),
+    }
+}
+
+/// Arguments of the deposit and withdraw instructions, borrowed from the instruction data
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Args<'a> {
+    amount: u64,
+    min_out: u64,
+    flags: u8,
+    memo: Option<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    /// Parses the `AmountArgs` of the v1 instructions
+    fn unpack_v1(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let (amount, rest) = read_u64(data)?;
+        check_no_trailing_bytes(rest)?;
+
+        Ok(Self {
+            amount,
+            ..Default::default()
+        })
+    }
+
+    /// Parses the Borsh-encoded `DepositAndMintWrapperTokensArgs` or
+    /// `WithdrawAndBurnWrapperTokensArgs` of the v2 instructions
+    fn unpack_v2(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let (amount, rest) = read_u64(data)?;
+        let (min_out, rest) = read_u64(rest)?;
+        let (&flags, rest) = rest
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        let (memo, rest) = match rest.split_first() {
+            Some((0, rest)) => (None, rest),
+            Some((1, rest)) => {
+                let (memo_len, rest) = rest
+                    .split_first_chunk::<4>()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let memo_len = u32::from_le_bytes(*memo_len) as usize;
+                if rest.len() < memo_len {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                let (memo, rest) = rest.split_at(memo_len);
+                let memo =
+                    core::str::from_utf8(memo).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+                (Some(memo), rest)
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        check_no_trailing_bytes(rest)?;
+
+        Ok(Self {
+            amount,
+            min_out,
+            flags,
+            memo,
+        })
+    }
+}
+
+fn read_u64(data: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+    let (value, rest) = data
+        .split_first_chunk::<8>()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((u64::from_le_bytes(*value), rest))
+}
+
+fn check_no_trailing_bytes(rest: &[u8]) -> ProgramResult {
+    if rest.is_empty() {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Logs the memo of the instruction, without formatting it
+fn log_memo(memo: Option<&str>) {
+    if let Some(memo) = memo {
+        msg!("Memo:");
+        sol_log(memo);
+    }
+}
+
+/// Syncs the reserve with its lamports if it holds Token 2022 native SOL, as
+/// `sync_native_reserve`
+fn sync_native_reserve(wrapper_accounts: &WrapperAccounts) -> ProgramResult {
+    if wrapper_accounts.token_2022_mint.key != &spl_token_2022::native_mint::id() {
+        return Ok(());
+    }
+
+    invoke_raw(
+        wrapper_accounts.token_2022_program,
+        [CpiAccount::writable(
+            wrapper_accounts.reserve_token_2022_token_account,
+        )],
+        &[SYNC_NATIVE],
+        &[],
+    )
+}
+
+pub fn process_deposit_and_mint_wrapper_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokens");
+
+    let args = Args::unpack_v1(data)?;
+
+    // The v1 layout is the v2 layout followed by the legacy System program, Associated token
+    // program and Rent sysvar, then the program config
+    let legacy_accounts_iter = &mut accounts
+        .iter()
+        .skip(DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let system_program = next_account_info(legacy_accounts_iter)?;
+    let associated_token_program = next_account_info(legacy_accounts_iter)?;
+    let rent_sysvar = next_account_info(legacy_accounts_iter)?;
+    let program_config = next_account_info(legacy_accounts_iter)?;
+
+    assert_system_program(*system_program.key)?;
+    assert_associated_token_program(*associated_token_program.key)?;
+    assert_rent(*rent_sysvar.key)?;
+
+    deposit_and_mint_wrapper_tokens(program_id, accounts, program_config, &args)?;
+
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokens --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_deposit_and_mint_wrapper_tokens_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokensV2");
+
+    let args = Args::unpack_v2(data)?;
+
+    let program_config = next_account_info(
+        &mut accounts
+            .iter()
+            .skip(DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN),
+    )?;
+
+    deposit_and_mint_wrapper_tokens(program_id, accounts, program_config, &args)?;
+
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokensV2 --> Everything done, returning");
+
+    Ok(())
+}
+
+/// `processor::deposit_and_mint_wrapper_tokens` into the reserve, minting the wrapper tokens,
+/// for the v1 and v2 layouts, which take neither the fee vault nor the wrapper limits
+fn deposit_and_mint_wrapper_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    program_config: &AccountInfo,
+    args: &Args,
+) -> ProgramResult {
+    let wrapper_accounts = WrapperAccounts::load(
+        program_id,
+        accounts,
+        0,
+        OptionalAccounts::default(),
+        args.flags,
+    )?;
+    let wrapper_config = wrapper_accounts.wrapper_config.as_ref();
+
+    log_memo(args.memo);
+
+    assert_with_msg(
+        is_program_config(*program_id, program_config),
+        TokenWrapperError::InvalidProgramConfig,
+        "Invalid program config passed",
+    )?;
+
+    assert_with_msg(
+        !wrapper_config.is_some_and(|config| config.retired),
+        TokenWrapperError::WrapperRetired,
+        "The wrapper has been retired",
+    )?;
+
+    let mints = WrapperMintsView::load(
+        wrapper_accounts.token_2022_mint,
+        wrapper_accounts.wrapper_token_mint,
+        wrapper_config,
+    )?;
+
+    assert_with_msg(
+        !ProgramConfig::load(program_config, program_id)?.is_some_and(|config| config.paused),
+        TokenWrapperError::DepositsPaused,
+        "Deposits are paused for all wrappers",
+    )?;
+    assert_with_msg(
+        !wrapper_config.is_some_and(|config| config.paused),
+        TokenWrapperError::DepositsPaused,
+        "Deposits are paused for this wrapper",
+    )?;
+
+    let token_2022_decimals = mints.decimals();
+
+    TokenAccountView::load(
+        wrapper_accounts.user_token_2022_token_account,
+        wrapper_accounts.user_authority.key,
+        wrapper_accounts.token_2022_mint.key,
+        true,
+    )?;
+    TokenAccountView::load(
+        wrapper_accounts.user_wrapper_token_account,
+        wrapper_accounts.user_authority.key,
+        wrapper_accounts.wrapper_token_mint.key,
+        false,
+    )?;
+
+    sync_native_reserve(&wrapper_accounts)?;
+
+    let pre_transfer_balance = TokenAccountView::load(
+        wrapper_accounts.reserve_token_2022_token_account,
+        wrapper_accounts.reserve_authority.key,
+        wrapper_accounts.token_2022_mint.key,
+        true,
+    )?
+    .amount;
+
+    let mut amount = args.amount;
+
+    if amount == u64::MAX {
+        amount = pre_transfer_balance;
+    }
+
+    invoke_raw(
+        wrapper_accounts.token_2022_program,
+        [
+            CpiAccount::writable(wrapper_accounts.user_token_2022_token_account),
+            CpiAccount::readonly(wrapper_accounts.token_2022_mint),
+            CpiAccount::writable(wrapper_accounts.reserve_token_2022_token_account),
+            CpiAccount::signer(wrapper_accounts.user_authority),
+        ],
+        &checked_amount_data(TRANSFER_CHECKED, amount, token_2022_decimals),
+        &[],
+    )?;
+
+    let post_transfer_balance =
+        TokenAccountView::reload_amount(wrapper_accounts.reserve_token_2022_token_account)?;
+
+    let received_amount = post_transfer_balance
+        .checked_sub(pre_transfer_balance)
+        .unwrap();
+
+    let wrap_fee = wrapper_config.map_or(0, |config| config.wrap_fee(received_amount));
+    assert_fee_vault_passed(wrap_fee, wrapper_accounts.fee_vault)?;
+
+    assert_with_msg(
+        received_amount >= args.min_out,
+        TokenWrapperError::SlippageExceeded,
+        "Wrapper tokens minted are below the requested minimum",
+    )?;
+
+    invoke_raw(
+        wrapper_accounts.token_program,
+        [
+            CpiAccount::writable(wrapper_accounts.wrapper_token_mint),
+            CpiAccount::writable(wrapper_accounts.user_wrapper_token_account),
+            CpiAccount::signer(wrapper_accounts.reserve_authority),
+        ],
+        &checked_amount_data(MINT_TO_CHECKED, received_amount, token_2022_decimals),
+        &[
+            b"reserve_authority",
+            wrapper_accounts.token_2022_mint.key.as_ref(),
+            &[wrapper_accounts.reserve_authority_bump],
+        ],
+    )
+}
+
+pub fn process_withdraw_and_burn_wrapper_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokens");
+
+    let args = Args::unpack_v1(data)?;
+
+    // The v1 layout is the v2 layout followed by the legacy System program and Rent sysvar
+    let legacy_accounts_iter = &mut accounts
+        .iter()
+        .skip(WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let system_program = next_account_info(legacy_accounts_iter)?;
+    let rent_sysvar = next_account_info(legacy_accounts_iter)?;
+
+    assert_system_program(*system_program.key)?;
+    assert_rent(*rent_sysvar.key)?;
+
+    withdraw_and_burn_wrapper_tokens(program_id, accounts, &args)?;
+
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokens --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_withdraw_and_burn_wrapper_tokens_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2");
+
+    let args = Args::unpack_v2(data)?;
+
+    withdraw_and_burn_wrapper_tokens(program_id, accounts, &args)?;
+
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2 --> Everything done, returning");
+
+    Ok(())
+}
+
+/// `processor::withdraw_and_burn_wrapper_tokens` from the reserve, burning the wrapper tokens,
+/// for the v1 and v2 layouts, which take neither the fee vault nor the wrapper limits
+fn withdraw_and_burn_wrapper_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &Args,
+) -> ProgramResult {
+    let wrapper_accounts = WrapperAccounts::load(
+        program_id,
+        accounts,
+        0,
+        OptionalAccounts::default(),
+        args.flags,
+    )?;
+    let wrapper_config = wrapper_accounts.wrapper_config.as_ref();
+
+    log_memo(args.memo);
+
+    let mints = WrapperMintsView::load(
+        wrapper_accounts.token_2022_mint,
+        wrapper_accounts.wrapper_token_mint,
+        wrapper_config,
+    )?;
+    let token_2022_decimals = mints.decimals();
+
+    let pre_transfer_balance = TokenAccountView::load(
+        wrapper_accounts.user_token_2022_token_account,
+        wrapper_accounts.user_authority.key,
+        wrapper_accounts.token_2022_mint.key,
+        true,
+    )?
+    .amount;
+    let user_wrapper_token_balance = TokenAccountView::load(
+        wrapper_accounts.user_wrapper_token_account,
+        wrapper_accounts.user_authority.key,
+        wrapper_accounts.wrapper_token_mint.key,
+        false,
+    )?
+    .amount;
+    TokenAccountView::load(
+        wrapper_accounts.reserve_token_2022_token_account,
+        wrapper_accounts.reserve_authority.key,
+        wrapper_accounts.token_2022_mint.key,
+        true,
+    )?;
+
+    let mut amount = args.amount;
+
+    if amount == u64::MAX {
+        amount = user_wrapper_token_balance;
+    }
+
+    let unwrap_fee = wrapper_config.map_or(0, |config| config.unwrap_fee(amount));
+    assert_fee_vault_passed(unwrap_fee, wrapper_accounts.fee_vault)?;
+
+    invoke_raw(
+        wrapper_accounts.token_program,
+        [
+            CpiAccount::writable(wrapper_accounts.user_wrapper_token_account),
+            CpiAccount::writable(wrapper_accounts.wrapper_token_mint),
+            CpiAccount::signer(wrapper_accounts.user_authority),
+        ],
+        &checked_amount_data(BURN_CHECKED, amount, token_2022_decimals),
+        &[],
+    )?;
+
+    sync_native_reserve(&wrapper_accounts)?;
+
+    invoke_raw(
+        wrapper_accounts.token_2022_program,
+        [
+            CpiAccount::writable(wrapper_accounts.reserve_token_2022_token_account),
+            CpiAccount::readonly(wrapper_accounts.token_2022_mint),
+            CpiAccount::writable(wrapper_accounts.user_token_2022_token_account),
+            CpiAccount::signer(wrapper_accounts.reserve_authority),
+        ],
+        &checked_amount_data(TRANSFER_CHECKED, amount, token_2022_decimals),
+        &[
+            b"reserve_authority",
+            wrapper_accounts.token_2022_mint.key.as_ref(),
+            &[wrapper_accounts.reserve_authority_bump],
+        ],
+    )?;
+
+    let post_transfer_balance =
+        TokenAccountView::reload_amount(wrapper_accounts.user_token_2022_token_account)?;
+
+    assert_with_msg(
+        post_transfer_balance.saturating_sub(pre_transfer_balance) >= args.min_out,
+        TokenWrapperError::SlippageExceeded,
+        "Token 2022 tokens received are below the requested minimum",
+    )
+}
+
+/// Data of the `TransferChecked`, `MintToChecked` and `BurnChecked` instructions, shared by the
+/// Token and Token 2022 programs
+fn checked_amount_data(instruction: u8, amount: u64, decimals: u8) -> [u8; 10] {
+    let mut data = [0; 10];
+    data[0] = instruction;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9] = decimals;
+    data
+}
+
+/// Account passed to a CPI, with its privileges in the instruction
+struct CpiAccount<'a, 'info> {
+    account_info: &'a AccountInfo<'info>,
+    is_writable: bool,
+    is_signer: bool,
+}
+
+impl<'a, 'info> CpiAccount<'a, 'info> {
+    fn readonly(account_info: &'a AccountInfo<'info>) -> Self {
+        Self {
+            account_info,
+            is_writable: false,
+            is_signer: false,
+        }
+    }
+
+    fn writable(account_info: &'a AccountInfo<'info>) -> Self {
+        Self {
+            account_info,
+            is_writable: true,
+            is_signer: false,
+        }
+    }
+
+    fn signer(account_info: &'a AccountInfo<'info>) -> Self {
+        Self {
+            account_info,
+            is_writable: false,
+            is_signer: true,
+        }
+    }
+}
+
+/// Invokes `program` with `accounts` and `data`, signed by the PDA of `signer_seeds` if any
+///
+/// No account data may be borrowed during the call, the runtime writes the accounts back
+/// through the pointers of their `AccountInfo`.
+#[cfg(target_os = "solana")]
+fn invoke_raw<'info, const N: usize>(
+    program: &AccountInfo<'info>,
+    accounts: [CpiAccount<'_, 'info>; N],
+    data: &[u8],
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    // Layouts of the C SDK, `sol/cpi.h`
+    #[repr(C)]
+    struct SolAccountMeta {
+        pubkey: *const Pubkey,
+        is_writable: bool,
+        is_signer: bool,
+    }
+
+    #[repr(C)]
+    struct SolAccountInfo {
+        key: *const Pubkey,
+        lamports: *const u64,
+        data_len: u64,
+        data: *const u8,
+        owner: *const Pubkey,
+        rent_epoch: u64,
+        is_signer: bool,
+        is_writable: bool,
+        executable: bool,
+    }
+
+    #[repr(C)]
+    struct SolInstruction {
+        program_id: *const Pubkey,
+        accounts: *const SolAccountMeta,
+        account_len: u64,
+        data: *const u8,
+        data_len: u64,
+    }
+
+    #[repr(C)]
+    struct SolSignerSeed {
+        addr: *const u8,
+        len: u64,
+    }
+
+    #[repr(C)]
+    struct SolSignerSeeds {
+        addr: *const SolSignerSeed,
+        len: u64,
+    }
+
+    if signer_seeds.len() > MAX_SEEDS {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    let account_metas: [SolAccountMeta; N] = core::array::from_fn(|index| SolAccountMeta {
+        pubkey: accounts[index].account_info.key,
+        is_writable: accounts[index].is_writable,
+        is_signer: accounts[index].is_signer,
+    });
+
+    let account_infos: [SolAccountInfo; N] = core::array::from_fn(|index| {
+        let account_info = accounts[index].account_info;
+
+        // SAFETY: `RefCell::as_ptr` never fails and the pointer is to the `&mut [u8]` held by
+        // the cell, which is only read here. No `RefMut` of the data is alive, as no account
+        // data may be borrowed during the call, so the read does not alias a mutable borrow.
+        let data = unsafe { &*account_info.data.as_ptr() };
+        // SAFETY: as for the data, the cell holds the `&mut u64` of the lamports, read without
+        // any `RefMut` alive. The lamports and data both point into the input buffer of the
+        // entrypoint, which outlives the call, so the runtime can write the accounts back.
+        let lamports = unsafe { &**account_info.lamports.as_ptr() as *const u64 };
+        let (data_len, data) = (data.len() as u64, data.as_ptr());
+
+        SolAccountInfo {
+            key: account_info.key,
+            lamports,
+            data_len,
+            data,
+            owner: account_info.owner,
+            rent_epoch: account_info.rent_epoch,
+            is_signer: account_info.is_signer,
+            is_writable: account_info.is_writable,
+            executable: account_info.executable,
+        }
+    });
+
+    let instruction = SolInstruction {
+        program_id: program.key,
+        accounts: account_metas.as_ptr(),
+        account_len: N as u64,
+        data: data.as_ptr(),
+        data_len: data.len() as u64,
+    };
+
+    let seeds: [SolSignerSeed; MAX_SEEDS] = core::array::from_fn(|index| {
+        let seed = signer_seeds.get(index).copied().unwrap_or_default();
+
+        SolSignerSeed {
+            addr: seed.as_ptr(),
+            len: seed.len() as u64,
+        }
+    });
+    let signers = SolSignerSeeds {
+        addr: seeds.as_ptr(),
+        len: signer_seeds.len() as u64,
+    };
+    let signers_len = u64::from(!signer_seeds.is_empty());
+
+    // SAFETY: the instruction, account infos and signer seeds are `repr(C)` in the layouts the
+    // syscall reads, and every pointer in them refers to the locals above, to `data`, to the
+    // seeds or to the accounts, all of which outlive the call. `account_infos` holds `N` entries.
+    let result = unsafe {
+        solana_program::syscalls::sol_invoke_signed_c(
+            &instruction as *const SolInstruction as *const u8,
+            account_infos.as_ptr() as *const u8,
+            N as u64,
+            &signers as *const SolSignerSeeds as *const u8,
+            signers_len,
+        )
+    };
+
+    match result {
+        solana_program::entrypoint::SUCCESS => Ok(()),
+        error => Err(error.into()),
+    }
+}
+
+/// Invokes `program` with `accounts` and `data`, signed by the PDA of `signer_seeds` if any
+///
+/// Off chain the syscall is not available, the CPI is made by `invoke_signed` instead.
+#[cfg(not(target_os = "solana"))]
+fn invoke_raw<'info, const N: usize>(
+    program: &AccountInfo<'info>,
+    accounts: [CpiAccount<'_, 'info>; N],
+    data: &[u8],
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    use solana_program::instruction::{AccountMeta, Instruction};
+
+    if signer_seeds.len() > MAX_SEEDS {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    let instruction = Instruction {
+        program_id: *program.key,
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.account_info.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    let account_infos = accounts
+        .iter()
+        .map(|account| account.account_info.clone())
+        .chain([program.clone()])
+        .collect::<Vec<_>>();
+
+    if signer_seeds.is_empty() {
+        solana_program::program::invoke(&instruction, &account_infos)
+    } else {
+        solana_program::program::invoke_signed(&instruction, &account_infos, &[signer_seeds])
+    }
+}
@@ -0,0 +1,788 @@
+//! JSON parsing of the wrapper instructions and accounts, for explorers and indexers
+//!
+//! Follows the parsers of `spl-token` in `solana-transaction-status` and `solana-account-decoder`:
+//! instructions are parsed into their type and an `info` object of named accounts and decoded
+//! arguments, `u64` amounts as strings. Accounts are parsed into their type and fields.
+
+use std::{error::Error, fmt};
+
+use borsh::BorshDeserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use solana_program::{instruction::CompiledInstruction, pubkey::Pubkey};
+
+use crate::{
+    instruction::{
+        AmountArgs, DepositAndMintWrapperTokensArgs, QuoteArgs, RebalanceReserveShardsArgs,
+        SetFeeArgs, SetPauseArgs, SetPendingAdminArgs, SetWrapperLimitsArgs,
+        TokenWrapperInstruction, WithdrawAndBurnWrapperTokensArgs,
+    },
+    state::{
+        AccountType, IssuerFreeze, ProgramConfig, WrapperConfig, WrapperGroup, WrapperGroupMember,
+        WrapperLimits,
+    },
+};
+
+// `Display` and `Error` are implemented by hand, shank reads every enum deriving `Error` in the
+// crate as program errors of the IDL
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedProgramId(Pubkey),
+    /// Index of an account of the instruction outside of the account keys
+    AccountIndexOutOfBounds(u8),
+    InvalidInstructionData,
+    NotEnoughAccounts {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidAccountData,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedProgramId(program_id) => {
+                write!(f, "Program {program_id} is not the wrapper program")
+            }
+            ParseError::AccountIndexOutOfBounds(index) => {
+                write!(f, "Account index {index} is out of bounds")
+            }
+            ParseError::InvalidInstructionData => write!(f, "Invalid instruction data"),
+            ParseError::NotEnoughAccounts { expected, actual } => {
+                write!(f, "Expected {expected} accounts, got {actual}")
+            }
+            ParseError::InvalidAccountData => write!(f, "Invalid account data"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Instruction parsed as by `solana-transaction-status`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedWrapperInstruction {
+    /// camelCase name of the instruction
+    #[serde(rename = "type")]
+    pub instruction_type: String,
+    /// Named accounts and decoded arguments
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub info: Value,
+}
+
+/// Parses a compiled instruction of a transaction, whose indices point into `account_keys`
+pub fn parse_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<ParsedWrapperInstruction, ParseError> {
+    let get_key = |index: u8| {
+        account_keys
+            .get(index as usize)
+            .copied()
+            .ok_or(ParseError::AccountIndexOutOfBounds(index))
+    };
+
+    let program_id = get_key(instruction.program_id_index)?;
+    if program_id != crate::id() {
+        return Err(ParseError::UnexpectedProgramId(program_id));
+    }
+
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|index| get_key(*index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    parse_wrapper_instruction(&accounts, &instruction.data)
+}
+
+/// Parses the instruction `data` with the `accounts` it is called with, trailing optional accounts
+/// are named when passed and any other extra accounts are listed as `remainingAccounts`
+pub fn parse_wrapper_instruction(
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> Result<ParsedWrapperInstruction, ParseError> {
+    let instruction =
+        TokenWrapperInstruction::unpack(data).map_err(|_| ParseError::InvalidInstructionData)?;
+
+    let (instruction_type, account_names, args) = match &instruction {
+        TokenWrapperInstruction::InitializeWrapperToken => (
+            "initializeWrapperToken",
+            &INITIALIZE_WRAPPER_TOKEN_ACCOUNTS[..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::DepositAndMintWrapperTokens(args) => (
+            "depositAndMintWrapperTokens",
+            &DEPOSIT_AND_MINT_WRAPPER_TOKENS_ACCOUNTS[..],
+            amount_info(args),
+        ),
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokens(args) => (
+            "withdrawAndBurnWrapperTokens",
+            &WITHDRAW_AND_BURN_WRAPPER_TOKENS_ACCOUNTS[..],
+            amount_info(args),
+        ),
+        TokenWrapperInstruction::InitializeWrapperTokenV2 => (
+            "initializeWrapperTokenV2",
+            &INITIALIZE_WRAPPER_TOKEN_ACCOUNTS[..8],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::DepositAndMintWrapperTokensV2(args) => (
+            "depositAndMintWrapperTokensV2",
+            &DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS[..],
+            deposit_info(args),
+        ),
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2(args) => (
+            "withdrawAndBurnWrapperTokensV2",
+            &WITHDRAW_AND_BURN_WRAPPER_TOKENS_ACCOUNTS[..9],
+            withdraw_info(args),
+        ),
+        TokenWrapperInstruction::SetFee(args) => (
+            "setFee",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "feeRecipient",
+                "systemProgram",
+                "feeVault",
+                "tokenProgram",
+            ][..],
+            set_fee_info(args),
+        ),
+        TokenWrapperInstruction::CollectFees => (
+            "collectFees",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "feeVault",
+                "feeRecipient",
+                "tokenProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::InitializeProgramConfig => (
+            "initializeProgramConfig",
+            &[
+                "upgradeAuthority",
+                "programData",
+                "programConfig",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::SetPause(args) => (
+            "setPause",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "systemProgram",
+            ][..],
+            pause_info(args),
+        ),
+        TokenWrapperInstruction::SetGlobalPause(args) => (
+            "setGlobalPause",
+            &["admin", "programConfig"][..],
+            pause_info(args),
+        ),
+        TokenWrapperInstruction::SetPendingAdmin(args) => (
+            "setPendingAdmin",
+            &["admin", "programConfig"][..],
+            pending_admin_info(args),
+        ),
+        TokenWrapperInstruction::AcceptAdmin => (
+            "acceptAdmin",
+            &["pendingAdmin", "programConfig"][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::MirrorFreeze => ("mirrorFreeze", &MIRROR_ACCOUNTS[..], Map::new()),
+        TokenWrapperInstruction::MirrorThaw => ("mirrorThaw", &MIRROR_ACCOUNTS[..], Map::new()),
+        TokenWrapperInstruction::MigrateReserve => (
+            "migrateReserve",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "reserveToken2022TokenAccount",
+                "newReserveToken2022TokenAccount",
+                "token2022Program",
+                "associatedTokenProgram",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::ReallocReserve => (
+            "reallocReserve",
+            &[
+                "payer",
+                "token2022Mint",
+                "reserveAuthority",
+                "reserveToken2022TokenAccount",
+                "token2022Program",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::RetireWrapper => (
+            "retireWrapper",
+            &[
+                "authority",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::RegisterWrapperGroupMember => (
+            "registerWrapperGroupMember",
+            &[
+                "payer",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "wrapperGroup",
+                "wrapperGroupMember",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::QuoteWrap(args) => {
+            ("quoteWrap", &QUOTE_ACCOUNTS[..], quote_info(args))
+        }
+        TokenWrapperInstruction::QuoteUnwrap(args) => {
+            ("quoteUnwrap", &QUOTE_ACCOUNTS[..3], quote_info(args))
+        }
+        TokenWrapperInstruction::InitializeReserveShard => (
+            "initializeReserveShard",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "reserveShard",
+                "token2022Program",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::RebalanceReserveShards(args) => (
+            "rebalanceReserveShards",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "token2022Program",
+                "reserveToken2022TokenAccount",
+            ][..],
+            rebalance_info(args),
+        ),
+        TokenWrapperInstruction::DepositAndMintWrapperTokensV3(args) => {
+            let mut info = deposit_info(&args.args);
+            info.insert("reserveShard".to_string(), json!(args.reserve_shard));
+
+            (
+                "depositAndMintWrapperTokensV3",
+                &DEPOSIT_AND_MINT_WRAPPER_TOKENS_V3_ACCOUNTS[..],
+                info,
+            )
+        }
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV3(args) => {
+            let mut info = withdraw_info(&args.args);
+            info.insert("reserveShard".to_string(), json!(args.reserve_shard));
+
+            (
+                "withdrawAndBurnWrapperTokensV3",
+                &WITHDRAW_AND_BURN_WRAPPER_TOKENS_V3_ACCOUNTS[..],
+                info,
+            )
+        }
+        TokenWrapperInstruction::InitializeWrapperInventory => (
+            "initializeWrapperInventory",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "wrapperInventory",
+                "tokenProgram",
+                "systemProgram",
+            ][..],
+            Map::new(),
+        ),
+        TokenWrapperInstruction::TopUpWrapperInventory(args) => (
+            "topUpWrapperInventory",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "wrapperInventory",
+                "tokenProgram",
+            ][..],
+            amount_info(args),
+        ),
+        TokenWrapperInstruction::DepositFromWrapperInventory(args) => {
+            let mut info = deposit_info(&args.args);
+            info.insert("reserveShard".to_string(), json!(args.reserve_shard));
+
+            (
+                "depositFromWrapperInventory",
+                &DEPOSIT_FROM_WRAPPER_INVENTORY_ACCOUNTS[..],
+                info,
+            )
+        }
+        TokenWrapperInstruction::WithdrawToWrapperInventory(args) => {
+            let mut info = withdraw_info(&args.args);
+            info.insert("reserveShard".to_string(), json!(args.reserve_shard));
+
+            (
+                "withdrawToWrapperInventory",
+                &WITHDRAW_TO_WRAPPER_INVENTORY_ACCOUNTS[..],
+                info,
+            )
+        }
+        TokenWrapperInstruction::SetWrapperLimits(args) => (
+            "setWrapperLimits",
+            &[
+                "admin",
+                "programConfig",
+                "token2022Mint",
+                "wrapperTokenMint",
+                "reserveAuthority",
+                "wrapperLimits",
+                "systemProgram",
+            ][..],
+            set_wrapper_limits_info(args),
+        ),
+    };
+
+    // Trailing accounts the instruction is also called without
+    let optional_account_names: &[&str] = match &instruction {
+        // Deposits sent before the program config was required took 12 accounts
+        TokenWrapperInstruction::DepositAndMintWrapperTokens(_) => &["programConfig"],
+        TokenWrapperInstruction::TopUpWrapperInventory(_) => &["wrapperLimits"],
+        _ => &[],
+    };
+
+    info(
+        instruction_type,
+        account_names,
+        optional_account_names,
+        accounts,
+        args,
+    )
+}
+
+const INITIALIZE_WRAPPER_TOKEN_ACCOUNTS: [&str; 9] = [
+    "payer",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "reserveAuthority",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "systemProgram",
+    "rent",
+];
+
+const DEPOSIT_AND_MINT_WRAPPER_TOKENS_ACCOUNTS: [&str; 12] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "systemProgram",
+    "associatedTokenProgram",
+    "rent",
+];
+
+const DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS: [&str; 10] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "programConfig",
+];
+
+const DEPOSIT_AND_MINT_WRAPPER_TOKENS_V3_ACCOUNTS: [&str; 12] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "programConfig",
+    "feeVault",
+    "wrapperLimits",
+];
+
+const WITHDRAW_AND_BURN_WRAPPER_TOKENS_ACCOUNTS: [&str; 11] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "systemProgram",
+    "rent",
+];
+
+const WITHDRAW_AND_BURN_WRAPPER_TOKENS_V3_ACCOUNTS: [&str; 11] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "feeVault",
+    "wrapperLimits",
+];
+
+const DEPOSIT_FROM_WRAPPER_INVENTORY_ACCOUNTS: [&str; 13] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "programConfig",
+    "wrapperInventory",
+    "feeVault",
+    "wrapperLimits",
+];
+
+const WITHDRAW_TO_WRAPPER_INVENTORY_ACCOUNTS: [&str; 12] = [
+    "userAuthority",
+    "reserveAuthority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "userWrapperTokenAccount",
+    "userToken2022TokenAccount",
+    "reserveToken2022TokenAccount",
+    "tokenProgram",
+    "token2022Program",
+    "wrapperInventory",
+    "feeVault",
+    "wrapperLimits",
+];
+
+const MIRROR_ACCOUNTS: [&str; 9] = [
+    "authority",
+    "token2022Mint",
+    "wrapperTokenMint",
+    "reserveAuthority",
+    "wrapperTokenAccount",
+    "token2022TokenAccount",
+    "tokenProgram",
+    "issuerFreeze",
+    "systemProgram",
+];
+
+const QUOTE_ACCOUNTS: [&str; 4] = [
+    "token2022Mint",
+    "wrapperTokenMint",
+    "reserveAuthority",
+    "programConfig",
+];
+
+fn info(
+    instruction_type: &str,
+    account_names: &[&str],
+    optional_account_names: &[&str],
+    accounts: &[Pubkey],
+    args: Map<String, Value>,
+) -> Result<ParsedWrapperInstruction, ParseError> {
+    if accounts.len() < account_names.len() {
+        return Err(ParseError::NotEnoughAccounts {
+            expected: account_names.len(),
+            actual: accounts.len(),
+        });
+    }
+
+    let named_accounts_len = accounts
+        .len()
+        .min(account_names.len() + optional_account_names.len());
+    let mut info: Map<String, Value> = account_names
+        .iter()
+        .chain(optional_account_names)
+        .zip(&accounts[..named_accounts_len])
+        .map(|(name, pubkey)| (name.to_string(), json!(pubkey.to_string())))
+        .collect();
+    let remaining_accounts = &accounts[named_accounts_len..];
+    if !remaining_accounts.is_empty() {
+        info.insert(
+            "remainingAccounts".to_string(),
+            json!(remaining_accounts
+                .iter()
+                .map(Pubkey::to_string)
+                .collect::<Vec<_>>()),
+        );
+    }
+    info.extend(args);
+
+    Ok(ParsedWrapperInstruction {
+        instruction_type: instruction_type.to_string(),
+        info: Value::Object(info),
+    })
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+fn amount_info(args: &AmountArgs) -> Map<String, Value> {
+    object(json!({ "amount": args.amount.to_string() }))
+}
+
+fn deposit_info(args: &DepositAndMintWrapperTokensArgs) -> Map<String, Value> {
+    object(json!({
+        "amount": args.amount.to_string(),
+        "minOut": args.min_out.to_string(),
+        "flags": args.flags,
+        "memo": args.memo,
+    }))
+}
+
+fn withdraw_info(args: &WithdrawAndBurnWrapperTokensArgs) -> Map<String, Value> {
+    object(json!({
+        "amount": args.amount.to_string(),
+        "minOut": args.min_out.to_string(),
+        "flags": args.flags,
+        "memo": args.memo,
+    }))
+}
+
+fn set_fee_info(args: &SetFeeArgs) -> Map<String, Value> {
+    object(json!({
+        "wrapFeeBasisPoints": args.wrap_fee_basis_points,
+        "unwrapFeeBasisPoints": args.unwrap_fee_basis_points,
+        "maximumFee": args.maximum_fee.to_string(),
+    }))
+}
+
+fn pause_info(args: &SetPauseArgs) -> Map<String, Value> {
+    object(json!({ "paused": args.paused }))
+}
+
+fn pending_admin_info(args: &SetPendingAdminArgs) -> Map<String, Value> {
+    object(json!({ "pendingAdmin": args.pending_admin.to_string() }))
+}
+
+fn quote_info(args: &QuoteArgs) -> Map<String, Value> {
+    object(json!({ "amount": args.amount.to_string() }))
+}
+
+fn rebalance_info(args: &RebalanceReserveShardsArgs) -> Map<String, Value> {
+    object(json!({
+        "sourceReserveShard": args.source_reserve_shard,
+        "destinationReserveShard": args.destination_reserve_shard,
+        "amount": args.amount.to_string(),
+    }))
+}
+
+fn set_wrapper_limits_info(args: &SetWrapperLimitsArgs) -> Map<String, Value> {
+    object(json!({
+        "supplyCap": args.supply_cap.to_string(),
+        "epochWrapLimit": args.epoch_wrap_limit.to_string(),
+        "epochUnwrapLimit": args.epoch_unwrap_limit.to_string(),
+    }))
+}
+
+/// Account parsed as by `solana-account-decoder`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum WrapperAccountType {
+    WrapperConfig(UiWrapperConfig),
+    ProgramConfig(UiProgramConfig),
+    WrapperGroup(UiWrapperGroup),
+    WrapperGroupMember(UiWrapperGroupMember),
+    WrapperLimits(UiWrapperLimits),
+    IssuerFreeze(UiIssuerFreeze),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiWrapperConfig {
+    pub token_2022_mint: String,
+    pub fee_recipient: String,
+    pub wrap_fee_basis_points: u16,
+    pub unwrap_fee_basis_points: u16,
+    pub maximum_fee: String,
+    pub paused: bool,
+    /// Migrated reserve, if the reserve has been migrated
+    pub reserve_token_2022_token_account: Option<String>,
+    pub token_2022_decimals: u8,
+    /// Hex fingerprint of the Token 2022 mint extensions, if recorded
+    pub token_2022_mint_fingerprint: Option<String>,
+    pub retired: bool,
+    pub reserve_shard_count: u8,
+    pub has_wrapper_inventory: bool,
+    pub has_wrapper_limits: bool,
+    pub has_fee_vault: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiProgramConfig {
+    pub admin: String,
+    /// Proposed admin, if any
+    pub pending_admin: Option<String>,
+    pub paused: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiWrapperGroup {
+    pub group: String,
+    pub wrapper_count: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiWrapperGroupMember {
+    pub group: String,
+    pub token_2022_mint: String,
+    pub wrapper_token_mint: String,
+    pub member_number: u32,
+    pub index: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiWrapperLimits {
+    pub token_2022_mint: String,
+    pub supply_cap: String,
+    pub epoch_wrap_limit: String,
+    pub epoch_unwrap_limit: String,
+    pub epoch: String,
+    pub epoch_wrapped: String,
+    pub epoch_unwrapped: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiIssuerFreeze {
+    pub wrapper_token_account: String,
+    pub frozen: bool,
+}
+
+/// Parses the data of an account owned by the wrapper program, by its account type
+pub fn parse_account_data(data: &[u8]) -> Result<WrapperAccountType, ParseError> {
+    let account_type =
+        AccountType::deserialize(&mut &data[..]).map_err(|_| ParseError::InvalidAccountData)?;
+
+    let parsed = match account_type {
+        AccountType::Uninitialized => return Err(ParseError::InvalidAccountData),
+        AccountType::WrapperConfig => {
+            let config = WrapperConfig::unpack(data).map_err(|_| ParseError::InvalidAccountData)?;
+            WrapperAccountType::WrapperConfig(UiWrapperConfig {
+                token_2022_mint: config.token_2022_mint.to_string(),
+                fee_recipient: config.fee_recipient.to_string(),
+                wrap_fee_basis_points: config.wrap_fee_basis_points,
+                unwrap_fee_basis_points: config.unwrap_fee_basis_points,
+                maximum_fee: config.maximum_fee.to_string(),
+                paused: config.paused,
+                reserve_token_2022_token_account: config
+                    .migrated_reserve()
+                    .map(|reserve| reserve.to_string()),
+                token_2022_decimals: config.token_2022_decimals,
+                token_2022_mint_fingerprint: config
+                    .recorded_fingerprint()
+                    .map(|fingerprint| fingerprint.iter().map(|b| format!("{b:02x}")).collect()),
+                retired: config.retired,
+                reserve_shard_count: config.reserve_shard_count,
+                has_wrapper_inventory: config.has_wrapper_inventory,
+                has_wrapper_limits: config.has_wrapper_limits,
+                has_fee_vault: config.has_fee_vault,
+            })
+        }
+        AccountType::ProgramConfig => {
+            let config = ProgramConfig::unpack(data).map_err(|_| ParseError::InvalidAccountData)?;
+            WrapperAccountType::ProgramConfig(UiProgramConfig {
+                admin: config.admin.to_string(),
+                pending_admin: (config.pending_admin != Pubkey::default())
+                    .then(|| config.pending_admin.to_string()),
+                paused: config.paused,
+            })
+        }
+        AccountType::WrapperGroup => {
+            let group = WrapperGroup::unpack(data).map_err(|_| ParseError::InvalidAccountData)?;
+            WrapperAccountType::WrapperGroup(UiWrapperGroup {
+                group: group.group.to_string(),
+                wrapper_count: group.wrapper_count.to_string(),
+            })
+        }
+        AccountType::WrapperGroupMember => {
+            let member =
+                WrapperGroupMember::unpack(data).map_err(|_| ParseError::InvalidAccountData)?;
+            WrapperAccountType::WrapperGroupMember(UiWrapperGroupMember {
+                group: member.group.to_string(),
+                token_2022_mint: member.token_2022_mint.to_string(),
+                wrapper_token_mint: member.wrapper_token_mint.to_string(),
+                member_number: member.member_number,
+                index: member.index.to_string(),
+            })
+        }
+        AccountType::WrapperLimits => {
+            let limits = WrapperLimits::unpack(data).map_err(|_| ParseError::InvalidAccountData)?;
+            WrapperAccountType::WrapperLimits(UiWrapperLimits {
+                token_2022_mint: limits.token_2022_mint.to_string(),
+                supply_cap: limits.supply_cap.to_string(),
+                epoch_wrap_limit: limits.epoch_wrap_limit.to_string(),
+                epoch_unwrap_limit: limits.epoch_unwrap_limit.to_string(),
+                epoch: limits.epoch.to_string(),
+                epoch_wrapped: limits.epoch_wrapped.to_string(),
+                epoch_unwrapped: limits.epoch_unwrapped.to_string(),
+            })
+        }
+        AccountType::IssuerFreeze => {
+            let issuer_freeze =
+                IssuerFreeze::unpack(data).map_err(|_| ParseError::InvalidAccountData)?;
+            WrapperAccountType::IssuerFreeze(UiIssuerFreeze {
+                wrapper_token_account: issuer_freeze.wrapper_token_account.to_string(),
+                frozen: issuer_freeze.frozen,
+            })
+        }
+    };
+
+    Ok(parsed)
+}
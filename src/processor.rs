@@ -1,33 +1,62 @@
+use solana_program::clock::Clock;
 use solana_program::rent::Rent;
 use solana_program::sysvar::Sysvar;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::{invoke, invoke_signed},
+    program::{invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
 };
-use spl_associated_token_account::tools::account::get_account_len;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token::state::Mint;
-use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::extension::BaseStateWithExtensions;
+use spl_token_group_interface::state::TokenGroupMember;
+
+use std::ops::RangeInclusive;
+use std::slice::Iter;
 
 use crate::error::TokenWrapperError;
 use crate::utils::{
-    assert_associated_token_program, assert_with_msg, create_account, validate_mint,
-    validate_token_account,
+    assert_admin, assert_associated_token_program, assert_current_reserve_token_account,
+    assert_fee_vault, assert_program_config, assert_reserve_shard, assert_upgrade_authority,
+    assert_with_msg, assert_wrapper_inventory, assert_wrapper_limits, assert_wrapper_pdas,
+    create_account, get_fee_vault, get_mint_fingerprint, get_program_config,
+    get_reserve_account_extensions, get_reserve_account_len, get_reserve_shard,
+    get_wrapper_inventory, get_wrapper_limits, is_program_config, sync_native_reserve,
+    validate_mint, validate_token_account_mint, MintView, TokenAccountView, WrapperMintsView,
 };
 use crate::{
-    instruction::TokenWrapperInstruction,
+    instruction::{
+        AmountArgs, DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, QuoteArgs,
+        RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, TokenWrapperInstruction, WithdrawAndBurnWrapperTokensArgs,
+        WithdrawAndBurnWrapperTokensV3Args,
+    },
+    quote::{quote_unwrap, quote_wrap, Quote},
+    state::{
+        AccountType, IssuerFreeze, ProgramConfig, WrapperConfig, WrapperGroup, WrapperGroupMember,
+        WrapperLimits, MAX_FEE_BASIS_POINTS, MAX_RESERVE_SHARDS,
+    },
     utils::{
         assert_rent, assert_reserve_authority, assert_reserve_authority_token_account,
         assert_system_program, assert_token_2022_program, assert_token_program,
-        assert_wrapper_token_mint, get_reserve_authority, get_reserve_authority_token_account,
+        assert_wrapper_token_mint, get_issuer_freeze, get_reserve_authority,
+        get_reserve_authority_token_account, get_wrapper_group, get_wrapper_group_member,
         get_wrapper_token_mint,
     },
 };
 
+/// Number of accounts in the `InitializeWrapperTokenV2` layout
+pub const INITIALIZE_WRAPPER_TOKEN_V2_ACCOUNTS_LEN: usize = 8;
+/// Number of accounts in the `DepositAndMintWrapperTokensV2` layout, without the trailing
+/// program config
+pub const DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN: usize = 9;
+/// Number of accounts in the `WithdrawAndBurnWrapperTokensV2` layout
+pub const WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2_ACCOUNTS_LEN: usize = 9;
+
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -35,24 +64,77 @@ pub fn process_instruction(
 ) -> ProgramResult {
     let instruction = TokenWrapperInstruction::unpack(instruction_data)?;
 
-    let (_, data) = instruction_data
-        .split_first()
-        .ok_or(ProgramError::InvalidInstructionData)?;
-
     match instruction {
         TokenWrapperInstruction::InitializeWrapperToken => {
             process_initialize_wrapper_token(program_id, accounts)
         }
-        TokenWrapperInstruction::DepositAndMintWrapperTokens => {
-            let (amount, _) = TokenWrapperInstruction::unpack_u64(data)?;
-
+        TokenWrapperInstruction::DepositAndMintWrapperTokens(AmountArgs { amount }) => {
             process_deposit_and_mint_wrapper_tokens(program_id, accounts, amount)
         }
-        TokenWrapperInstruction::WithdrawAndBurnWrapperTokens => {
-            let (amount, _) = TokenWrapperInstruction::unpack_u64(data)?;
-
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokens(AmountArgs { amount }) => {
             process_withdraw_and_burn_wrapper_tokens(program_id, accounts, amount)
         }
+        TokenWrapperInstruction::InitializeWrapperTokenV2 => {
+            process_initialize_wrapper_token_v2(program_id, accounts)
+        }
+        TokenWrapperInstruction::DepositAndMintWrapperTokensV2(args) => {
+            process_deposit_and_mint_wrapper_tokens_v2(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2(args) => {
+            process_withdraw_and_burn_wrapper_tokens_v2(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::SetFee(args) => process_set_fee(program_id, accounts, &args),
+        TokenWrapperInstruction::CollectFees => process_collect_fees(program_id, accounts),
+        TokenWrapperInstruction::InitializeProgramConfig => {
+            process_initialize_program_config(program_id, accounts)
+        }
+        TokenWrapperInstruction::SetPause(args) => process_set_pause(program_id, accounts, &args),
+        TokenWrapperInstruction::SetGlobalPause(args) => {
+            process_set_global_pause(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::SetPendingAdmin(args) => {
+            process_set_pending_admin(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::AcceptAdmin => process_accept_admin(program_id, accounts),
+        TokenWrapperInstruction::MirrorFreeze => process_mirror_freeze(program_id, accounts),
+        TokenWrapperInstruction::MirrorThaw => process_mirror_thaw(program_id, accounts),
+        TokenWrapperInstruction::MigrateReserve => process_migrate_reserve(program_id, accounts),
+        TokenWrapperInstruction::ReallocReserve => process_realloc_reserve(program_id, accounts),
+        TokenWrapperInstruction::RetireWrapper => process_retire_wrapper(program_id, accounts),
+        TokenWrapperInstruction::RegisterWrapperGroupMember => {
+            process_register_wrapper_group_member(program_id, accounts)
+        }
+        TokenWrapperInstruction::QuoteWrap(args) => process_quote_wrap(program_id, accounts, &args),
+        TokenWrapperInstruction::QuoteUnwrap(args) => {
+            process_quote_unwrap(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::InitializeReserveShard => {
+            process_initialize_reserve_shard(program_id, accounts)
+        }
+        TokenWrapperInstruction::RebalanceReserveShards(args) => {
+            process_rebalance_reserve_shards(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::DepositAndMintWrapperTokensV3(args) => {
+            process_deposit_and_mint_wrapper_tokens_v3(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV3(args) => {
+            process_withdraw_and_burn_wrapper_tokens_v3(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::InitializeWrapperInventory => {
+            process_initialize_wrapper_inventory(program_id, accounts)
+        }
+        TokenWrapperInstruction::TopUpWrapperInventory(AmountArgs { amount }) => {
+            process_top_up_wrapper_inventory(program_id, accounts, amount)
+        }
+        TokenWrapperInstruction::DepositFromWrapperInventory(args) => {
+            process_deposit_from_wrapper_inventory(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::WithdrawToWrapperInventory(args) => {
+            process_withdraw_to_wrapper_inventory(program_id, accounts, &args)
+        }
+        TokenWrapperInstruction::SetWrapperLimits(args) => {
+            process_set_wrapper_limits(program_id, accounts, &args)
+        }
     }
 }
 
@@ -62,6 +144,35 @@ pub fn process_initialize_wrapper_token(
 ) -> ProgramResult {
     msg!("TokenWrapperInstruction::InitializeWrapperToken");
 
+    // The v1 layout is the v2 layout followed by the legacy Rent sysvar
+    let legacy_accounts_iter = &mut accounts
+        .iter()
+        .skip(INITIALIZE_WRAPPER_TOKEN_V2_ACCOUNTS_LEN);
+    let rent_sysvar = next_account_info(legacy_accounts_iter)?;
+
+    assert_rent(*rent_sysvar.key)?;
+
+    initialize_wrapper_token(program_id, accounts)?;
+
+    msg!("TokenWrapperInstruction::InitializeWrapperToken --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_initialize_wrapper_token_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::InitializeWrapperTokenV2");
+
+    initialize_wrapper_token(program_id, accounts)?;
+
+    msg!("TokenWrapperInstruction::InitializeWrapperTokenV2 --> Everything done, returning");
+
+    Ok(())
+}
+
+fn initialize_wrapper_token(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let accounts_info_iter = &mut accounts.iter();
     let payer = next_account_info(accounts_info_iter)?;
     let token_2022_mint = next_account_info(accounts_info_iter)?;
@@ -71,7 +182,6 @@ pub fn process_initialize_wrapper_token(
     let token_program = next_account_info(accounts_info_iter)?;
     let token_2022_program = next_account_info(accounts_info_iter)?;
     let system_program = next_account_info(accounts_info_iter)?;
-    let rent_sysvar = next_account_info(accounts_info_iter)?;
 
     assert_with_msg(
         payer.is_signer,
@@ -92,7 +202,6 @@ pub fn process_initialize_wrapper_token(
     assert_token_program(*token_program.key)?;
     assert_token_2022_program(*token_2022_program.key)?;
     assert_system_program(*system_program.key)?;
-    assert_rent(*rent_sysvar.key)?;
 
     validate_mint(token_2022_mint, true)?;
 
@@ -127,7 +236,7 @@ pub fn process_initialize_wrapper_token(
     let token_2022_decimals = token_2022_mint_data_parsed.base.decimals;
     drop(token_2022_mint_data);
 
-    let init_mint_ix = spl_token::instruction::initialize_mint(
+    let init_mint_ix = spl_token::instruction::initialize_mint2(
         token_program.key,
         wrapper_token_mint.key,
         &reserve_authority.key,
@@ -137,12 +246,7 @@ pub fn process_initialize_wrapper_token(
 
     invoke_signed(
         &init_mint_ix,
-        &[
-            wrapper_token_mint.clone(),
-            rent_sysvar.clone(),
-            token_program.clone(),
-            payer.clone(),
-        ],
+        &[wrapper_token_mint.clone(), token_program.clone()],
         &[wrapper_token_mint_seeds
             .iter()
             .map(|seed| seed.as_slice())
@@ -150,11 +254,8 @@ pub fn process_initialize_wrapper_token(
             .as_slice()],
     )?;
 
-    let token_account_data_length = get_account_len(
-        &token_2022_mint.clone(),
-        &token_2022_program.clone(),
-        &[ExtensionType::ImmutableOwner],
-    )? as u64;
+    let reserve_account_extensions = get_reserve_account_extensions(token_2022_mint)?;
+    let token_account_data_length = get_reserve_account_len(&reserve_account_extensions)? as u64;
     let rent = Rent::get().unwrap();
 
     create_account(
@@ -164,7 +265,7 @@ pub fn process_initialize_wrapper_token(
         &spl_token_2022::id(),
         &rent,
         token_account_data_length,
-        reserve_token_account_seeds.clone(),
+        reserve_token_account_seeds,
     )?;
 
     invoke(
@@ -198,9 +299,27 @@ pub fn process_initialize_wrapper_token(
             .as_slice()],
     )?;
 
-    msg!("TokenWrapperInstruction::InitializeWrapperToken --> Everything done, returning");
+    // Records the Token 2022 mint as it is now, to detect it being closed and re-created
+    create_account(
+        payer,
+        reserve_authority,
+        system_program,
+        program_id,
+        &rent,
+        WrapperConfig::LEN as u64,
+        reserve_authority_seeds,
+    )?;
 
-    Ok(())
+    let mut wrapper_config = WrapperConfig {
+        account_type: AccountType::WrapperConfig,
+        token_2022_mint: *token_2022_mint.key,
+        token_2022_decimals,
+        token_2022_mint_fingerprint: get_mint_fingerprint(token_2022_mint)?,
+        ..Default::default()
+    };
+    wrapper_config.record_bumps(program_id);
+
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)
 }
 
 pub fn process_deposit_and_mint_wrapper_tokens(
@@ -210,80 +329,361 @@ pub fn process_deposit_and_mint_wrapper_tokens(
 ) -> ProgramResult {
     msg!("TokenWrapperInstruction::DepositAndMintWrapperTokens");
 
-    let accounts_info_iter = &mut accounts.iter();
-    let user_authority = next_account_info(accounts_info_iter)?;
-    let reserve_authority = next_account_info(accounts_info_iter)?;
-    let token_2022_mint = next_account_info(accounts_info_iter)?;
-    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
-    let user_wrapper_token_account = next_account_info(accounts_info_iter)?;
-    let user_token_2022_token_account = next_account_info(accounts_info_iter)?;
-    let reserve_token_2022_token_account = next_account_info(accounts_info_iter)?;
-    let token_program = next_account_info(accounts_info_iter)?;
-    let token_2022_program = next_account_info(accounts_info_iter)?;
-    let system_program = next_account_info(accounts_info_iter)?;
-    let associated_token_program = next_account_info(accounts_info_iter)?;
-    let rent_sysvar = next_account_info(accounts_info_iter)?;
+    // The v1 layout is the v2 layout followed by the legacy System program, Associated token
+    // program and Rent sysvar, then the program config
+    let legacy_accounts_iter = &mut accounts
+        .iter()
+        .skip(DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let system_program = next_account_info(legacy_accounts_iter)?;
+    let associated_token_program = next_account_info(legacy_accounts_iter)?;
+    let rent_sysvar = next_account_info(legacy_accounts_iter)?;
+    let program_config = next_account_info(legacy_accounts_iter)?;
 
-    assert_with_msg(
-        user_authority.is_signer,
-        TokenWrapperError::MissingSigner,
-        "The user authority needs to be a signer",
+    assert_system_program(*system_program.key)?;
+    assert_associated_token_program(*associated_token_program.key)?;
+    assert_rent(*rent_sysvar.key)?;
+
+    deposit_and_mint_wrapper_tokens(
+        program_id,
+        accounts,
+        program_config,
+        0,
+        OptionalAccounts::default(),
+        &DepositAndMintWrapperTokensArgs {
+            amount,
+            ..Default::default()
+        },
     )?;
 
-    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
-    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
-    assert_reserve_authority_token_account(
-        *token_2022_mint.key,
-        *reserve_authority.key,
-        *program_id,
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokens --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_deposit_and_mint_wrapper_tokens_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &DepositAndMintWrapperTokensArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokensV2");
+
+    let program_config = next_account_info(
+        &mut accounts
+            .iter()
+            .skip(DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN),
+    )?;
+
+    deposit_and_mint_wrapper_tokens(
+        program_id,
+        accounts,
+        program_config,
+        0,
+        OptionalAccounts::default(),
+        args,
+    )?;
+
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokensV2 --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_deposit_and_mint_wrapper_tokens_v3(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &DepositAndMintWrapperTokensV3Args,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokensV3");
+
+    // Same layout as v2 followed by the fee vault and the wrapper limits, the reserve shard takes
+    // the place of the reserve
+    let v3_accounts_iter = &mut accounts
+        .iter()
+        .skip(DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let program_config = next_account_info(v3_accounts_iter)?;
+    let fee_vault = next_account_info(v3_accounts_iter)?;
+    let wrapper_limits = next_account_info(v3_accounts_iter)?;
+
+    deposit_and_mint_wrapper_tokens(
+        program_id,
+        accounts,
+        program_config,
+        args.reserve_shard,
+        OptionalAccounts {
+            wrapper_inventory: None,
+            fee_vault: Some(fee_vault),
+            wrapper_limits: Some(wrapper_limits),
+        },
+        &args.args,
+    )?;
+
+    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokensV3 --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_deposit_from_wrapper_inventory(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &DepositAndMintWrapperTokensV3Args,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::DepositFromWrapperInventory");
+
+    // Same layout as v3 with the wrapper inventory before the fee vault, the wrapper token mint
+    // is read-only
+    let v3_accounts_iter = &mut accounts
+        .iter()
+        .skip(DEPOSIT_AND_MINT_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let program_config = next_account_info(v3_accounts_iter)?;
+    let wrapper_inventory = next_account_info(v3_accounts_iter)?;
+    let fee_vault = next_account_info(v3_accounts_iter)?;
+    let wrapper_limits = next_account_info(v3_accounts_iter)?;
+
+    deposit_and_mint_wrapper_tokens(
+        program_id,
+        accounts,
+        program_config,
+        args.reserve_shard,
+        OptionalAccounts {
+            wrapper_inventory: Some(wrapper_inventory),
+            fee_vault: Some(fee_vault),
+            wrapper_limits: Some(wrapper_limits),
+        },
+        &args.args,
+    )?;
+
+    msg!("TokenWrapperInstruction::DepositFromWrapperInventory --> Everything done, returning");
+
+    Ok(())
+}
+
+/// Accounts of the deposit and withdraw instructions, in the order of the v2 layout, checked
+/// the same way by `processor` and `lite`
+pub struct WrapperAccounts<'a, 'info> {
+    pub user_authority: &'a AccountInfo<'info>,
+    pub reserve_authority: &'a AccountInfo<'info>,
+    pub token_2022_mint: &'a AccountInfo<'info>,
+    pub wrapper_token_mint: &'a AccountInfo<'info>,
+    pub user_wrapper_token_account: &'a AccountInfo<'info>,
+    pub user_token_2022_token_account: &'a AccountInfo<'info>,
+    /// Reserve shard of the instruction, the reserve for shard zero
+    pub reserve_token_2022_token_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub token_2022_program: &'a AccountInfo<'info>,
+    pub wrapper_config: Option<WrapperConfig>,
+    pub reserve_authority_bump: u8,
+    /// Fee vault, if the layout takes it and the wrapper has one
+    pub fee_vault: Option<&'a AccountInfo<'info>>,
+    /// Wrapper limits, if the layout takes them and the wrapper has limits
+    pub wrapper_limits: Option<&'a AccountInfo<'info>>,
+}
+
+/// Accounts of the deposit and withdraw layouts following the v2 accounts, `None` for the
+/// layouts not taking them. The v1 and v2 layouts take none of them
+#[derive(Clone, Copy, Default)]
+pub struct OptionalAccounts<'a, 'info> {
+    pub wrapper_inventory: Option<&'a AccountInfo<'info>>,
+    pub fee_vault: Option<&'a AccountInfo<'info>>,
+    pub wrapper_limits: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'a, 'info> WrapperAccounts<'a, 'info> {
+    /// Takes the accounts from `accounts` and checks them against the PDAs of the wrapper and
+    /// the wrapper config, with the `optional_accounts` of the layout. The wrapper inventory is
+    /// only checked, and the fee vault and the wrapper limits are only checked and kept if the
+    /// wrapper has them. Wrappers with limits fail with the layouts not taking them
+    pub fn load(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        reserve_shard: u8,
+        optional_accounts: OptionalAccounts<'a, 'info>,
+        flags: u8,
+    ) -> Result<Self, ProgramError> {
+        let accounts_info_iter = &mut accounts.iter();
+        let user_authority = next_account_info(accounts_info_iter)?;
+        let reserve_authority = next_account_info(accounts_info_iter)?;
+        let token_2022_mint = next_account_info(accounts_info_iter)?;
+        let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+        let user_wrapper_token_account = next_account_info(accounts_info_iter)?;
+        let user_token_2022_token_account = next_account_info(accounts_info_iter)?;
+        let reserve_token_2022_token_account = next_account_info(accounts_info_iter)?;
+        let token_program = next_account_info(accounts_info_iter)?;
+        let token_2022_program = next_account_info(accounts_info_iter)?;
+
+        assert_with_msg(
+            user_authority.is_signer,
+            TokenWrapperError::MissingSigner,
+            "The user authority needs to be a signer",
+        )?;
+        assert_with_msg(
+            flags == 0,
+            TokenWrapperError::UnsupportedInstructionFlags,
+            "Instruction flags are reserved and must be zero",
+        )?;
+
+        let (wrapper_config, reserve_authority_bump) = assert_wrapper_pdas(
+            *token_2022_mint.key,
+            *program_id,
+            wrapper_token_mint,
+            reserve_authority,
+        )?;
+
+        assert_reserve_shard(
+            *token_2022_mint.key,
+            *reserve_authority.key,
+            *program_id,
+            wrapper_config.as_ref(),
+            reserve_shard,
+            reserve_token_2022_token_account,
+        )?;
+
+        if let Some(wrapper_inventory) = optional_accounts.wrapper_inventory {
+            assert_wrapper_inventory(
+                *token_2022_mint.key,
+                *reserve_authority.key,
+                *program_id,
+                wrapper_config.as_ref(),
+                wrapper_inventory,
+            )?;
+        }
+
+        let fee_vault = next_fee_vault(
+            program_id,
+            token_2022_mint,
+            reserve_authority,
+            wrapper_config.as_ref(),
+            optional_accounts.fee_vault,
+        )?;
+
+        assert_with_msg(
+            optional_accounts.wrapper_limits.is_some()
+                || !wrapper_config
+                    .as_ref()
+                    .is_some_and(|config| config.has_wrapper_limits),
+            TokenWrapperError::UnsupportedInstructionVersion,
+            "The wrapper has limits, deposits and withdrawals must use the v3 instructions",
+        )?;
+        let wrapper_limits = next_wrapper_limits(
+            program_id,
+            token_2022_mint,
+            wrapper_config.as_ref(),
+            optional_accounts.wrapper_limits,
+        )?;
+
+        assert_token_program(*token_program.key)?;
+        assert_token_2022_program(*token_2022_program.key)?;
+
+        Ok(Self {
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            wrapper_config,
+            reserve_authority_bump,
+            fee_vault,
+            wrapper_limits,
+        })
+    }
+}
+
+/// Deposits into the reserve shard `reserve_shard`, and mints the wrapper tokens or transfers
+/// them from the wrapper inventory if the layout takes it
+fn deposit_and_mint_wrapper_tokens<'a, 'info>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'info>],
+    program_config: &AccountInfo,
+    reserve_shard: u8,
+    optional_accounts: OptionalAccounts<'a, 'info>,
+    args: &DepositAndMintWrapperTokensArgs,
+) -> ProgramResult {
+    let wrapper_inventory = optional_accounts.wrapper_inventory;
+
+    let WrapperAccounts {
+        user_authority,
+        reserve_authority,
+        token_2022_mint,
+        wrapper_token_mint,
+        user_wrapper_token_account,
+        user_token_2022_token_account,
         reserve_token_2022_token_account,
-        true,
+        token_program,
+        token_2022_program,
+        wrapper_config,
+        reserve_authority_bump,
+        fee_vault,
+        wrapper_limits,
+    } = WrapperAccounts::load(
+        program_id,
+        accounts,
+        reserve_shard,
+        optional_accounts,
+        args.flags,
     )?;
 
-    assert_token_program(*token_program.key)?;
-    assert_token_2022_program(*token_2022_program.key)?;
-    assert_system_program(*system_program.key)?;
-    assert_associated_token_program(*associated_token_program.key)?;
-    assert_rent(*rent_sysvar.key)?;
+    if let Some(memo) = &args.memo {
+        msg!("Memo: {}", memo);
+    }
 
-    let token_2022_mint_data = token_2022_mint.try_borrow_data()?;
-    let token_2022_mint_data_parsed = spl_token_2022::extension::StateWithExtensions::<
-        spl_token_2022::state::Mint,
-    >::unpack(&token_2022_mint_data)?;
-    let token_2022_decimals = token_2022_mint_data_parsed.base.decimals;
-    drop(token_2022_mint_data);
+    assert_with_msg(
+        is_program_config(*program_id, program_config),
+        TokenWrapperError::InvalidProgramConfig,
+        "Invalid program config passed",
+    )?;
 
-    validate_mint(token_2022_mint, true)?;
-    validate_mint(wrapper_token_mint, false)?;
+    assert_with_msg(
+        !wrapper_config.as_ref().is_some_and(|config| config.retired),
+        TokenWrapperError::WrapperRetired,
+        "The wrapper has been retired",
+    )?;
+
+    let mints =
+        WrapperMintsView::load(token_2022_mint, wrapper_token_mint, wrapper_config.as_ref())?;
+
+    assert_with_msg(
+        !ProgramConfig::load(program_config, program_id)?.is_some_and(|config| config.paused),
+        TokenWrapperError::DepositsPaused,
+        "Deposits are paused for all wrappers",
+    )?;
+    assert_with_msg(
+        !wrapper_config.as_ref().is_some_and(|config| config.paused),
+        TokenWrapperError::DepositsPaused,
+        "Deposits are paused for this wrapper",
+    )?;
+
+    let token_2022_decimals = mints.decimals();
 
-    validate_token_account(
+    TokenAccountView::load(
         user_token_2022_token_account,
         user_authority.key,
         token_2022_mint.key,
         true,
     )?;
-    validate_token_account(
+    TokenAccountView::load(
         user_wrapper_token_account,
         user_authority.key,
         wrapper_token_mint.key,
         false,
     )?;
-    validate_token_account(
+
+    sync_native_reserve(
+        token_2022_mint,
+        reserve_token_2022_token_account,
+        token_2022_program,
+    )?;
+
+    let pre_transfer_balance = TokenAccountView::load(
         reserve_token_2022_token_account,
         reserve_authority.key,
         token_2022_mint.key,
         true,
-    )?;
-
-    let reserve_token_2022_token_account_data =
-        reserve_token_2022_token_account.try_borrow_data()?;
-    let reserve_token_2022_data_parsed = spl_token_2022::extension::StateWithExtensions::<
-        spl_token_2022::state::Account,
-    >::unpack(&reserve_token_2022_token_account_data)?;
-    let pre_transfer_balance = reserve_token_2022_data_parsed.base.amount;
-    drop(reserve_token_2022_token_account_data);
+    )?
+    .amount;
 
-    let mut amount = amount;
+    let mut amount = args.amount;
 
     if amount == u64::MAX {
         amount = pre_transfer_balance;
@@ -311,44 +711,116 @@ pub fn process_deposit_and_mint_wrapper_tokens(
         ],
     )?;
 
-    let reserve_token_2022_token_account_data =
-        reserve_token_2022_token_account.try_borrow_data()?;
-    let reserve_token_2022_data_parsed = spl_token_2022::extension::StateWithExtensions::<
-        spl_token_2022::state::Account,
-    >::unpack(&reserve_token_2022_token_account_data)?;
-    let post_transfer_balance = reserve_token_2022_data_parsed.base.amount;
-    drop(reserve_token_2022_token_account_data);
+    let post_transfer_balance = TokenAccountView::reload_amount(reserve_token_2022_token_account)?;
 
-    let (_, _, reserve_authority_seeds) = get_reserve_authority(*token_2022_mint.key, *program_id);
+    let received_amount = post_transfer_balance
+        .checked_sub(pre_transfer_balance)
+        .unwrap();
 
-    let user_mint_ix = spl_token::instruction::mint_to_checked(
-        token_program.key,
-        wrapper_token_mint.key,
-        user_wrapper_token_account.key,
-        reserve_authority.key,
-        &[reserve_authority.key],
-        post_transfer_balance
-            .checked_sub(pre_transfer_balance)
-            .unwrap(),
-        token_2022_decimals,
-    )?;
+    // Fees are only charged by wrappers with a fee vault, created by `SetFee`
+    let wrap_fee = wrapper_config
+        .as_ref()
+        .map_or(0, |config| config.wrap_fee(received_amount));
+    assert_fee_vault_passed(wrap_fee, fee_vault)?;
+    let mint_amount = received_amount.checked_sub(wrap_fee).unwrap();
 
-    invoke_signed(
-        &user_mint_ix,
-        &[
-            token_program.clone(),
-            wrapper_token_mint.clone(),
-            user_wrapper_token_account.clone(),
-            reserve_authority.clone(),
-        ],
-        &[reserve_authority_seeds
-            .iter()
-            .map(|seed| seed.as_slice())
-            .collect::<Vec<&[u8]>>()
-            .as_slice()],
+    assert_with_msg(
+        mint_amount >= args.min_out,
+        TokenWrapperError::SlippageExceeded,
+        "Wrapper tokens minted are below the requested minimum",
     )?;
 
-    msg!("TokenWrapperInstruction::DepositAndMintWrapperTokens --> Everything done, returning");
+    // Transfers out of the inventory leave the wrapper token supply unchanged
+    if let Some(wrapper_limits) = wrapper_limits {
+        record_wrap(
+            wrapper_limits,
+            received_amount,
+            wrapper_inventory.is_none().then(|| {
+                mints
+                    .wrapper_token_mint
+                    .supply
+                    .saturating_add(received_amount)
+            }),
+        )?;
+    }
+
+    let reserve_authority_seeds: &[&[u8]] = &[
+        b"reserve_authority",
+        token_2022_mint.key.as_ref(),
+        &[reserve_authority_bump],
+    ];
+
+    // The user receives the deposit net of the fee, the fee vault the fee
+    let recipients = [
+        Some((user_wrapper_token_account, mint_amount)),
+        fee_vault
+            .filter(|_| wrap_fee > 0)
+            .map(|fee_vault| (fee_vault, wrap_fee)),
+    ];
+
+    if let Some(wrapper_inventory) = wrapper_inventory {
+        let wrapper_inventory_balance = TokenAccountView::load(
+            wrapper_inventory,
+            reserve_authority.key,
+            wrapper_token_mint.key,
+            false,
+        )?
+        .amount;
+
+        assert_with_msg(
+            wrapper_inventory_balance >= received_amount,
+            TokenWrapperError::InsufficientWrapperInventory,
+            "The wrapper inventory holds fewer wrapper tokens than the deposit is worth",
+        )?;
+
+        for (recipient, amount) in recipients.into_iter().flatten() {
+            invoke_signed(
+                &spl_token::instruction::transfer_checked(
+                    token_program.key,
+                    wrapper_inventory.key,
+                    wrapper_token_mint.key,
+                    recipient.key,
+                    reserve_authority.key,
+                    &[reserve_authority.key],
+                    amount,
+                    token_2022_decimals,
+                )?,
+                &[
+                    token_program.clone(),
+                    wrapper_inventory.clone(),
+                    wrapper_token_mint.clone(),
+                    recipient.clone(),
+                    reserve_authority.clone(),
+                ],
+                &[reserve_authority_seeds],
+            )?;
+        }
+
+        return Ok(());
+    }
+
+    for (recipient, amount) in recipients.into_iter().flatten() {
+        let mint_ix = spl_token::instruction::mint_to_checked(
+            token_program.key,
+            wrapper_token_mint.key,
+            recipient.key,
+            reserve_authority.key,
+            &[reserve_authority.key],
+            amount,
+            token_2022_decimals,
+        )?;
+
+        invoke_signed(
+            &mint_ix,
+            &[
+                token_program.clone(),
+                wrapper_token_mint.clone(),
+                recipient.clone(),
+                reserve_authority.clone(),
+            ],
+            &[reserve_authority_seeds],
+        )?;
+    }
 
     Ok(())
 }
@@ -360,99 +832,256 @@ pub fn process_withdraw_and_burn_wrapper_tokens(
 ) -> ProgramResult {
     msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokens");
 
-    let accounts_info_iter = &mut accounts.iter();
-    let user_authority = next_account_info(accounts_info_iter)?;
-    let reserve_authority = next_account_info(accounts_info_iter)?;
-    let token_2022_mint = next_account_info(accounts_info_iter)?;
-    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
-    let user_wrapper_token_account = next_account_info(accounts_info_iter)?;
-    let user_token_2022_token_account = next_account_info(accounts_info_iter)?;
-    let reserve_token_2022_token_account = next_account_info(accounts_info_iter)?;
-    let token_program = next_account_info(accounts_info_iter)?;
-    let token_2022_program = next_account_info(accounts_info_iter)?;
-    let system_program = next_account_info(accounts_info_iter)?;
-    let rent_sysvar = next_account_info(accounts_info_iter)?;
-
-    assert_with_msg(
-        user_authority.is_signer,
-        TokenWrapperError::MissingSigner,
-        "The user authority needs to be a signer",
-    )?;
-
-    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
-    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
-    assert_reserve_authority_token_account(
-        *token_2022_mint.key,
-        *reserve_authority.key,
-        *program_id,
-        reserve_token_2022_token_account,
-        true,
-    )?;
+    // The v1 layout is the v2 layout followed by the legacy System program and Rent sysvar
+    let legacy_accounts_iter = &mut accounts
+        .iter()
+        .skip(WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let system_program = next_account_info(legacy_accounts_iter)?;
+    let rent_sysvar = next_account_info(legacy_accounts_iter)?;
 
-    assert_token_program(*token_program.key)?;
-    assert_token_2022_program(*token_2022_program.key)?;
     assert_system_program(*system_program.key)?;
     assert_rent(*rent_sysvar.key)?;
 
-    validate_mint(token_2022_mint, true)?;
-    validate_mint(wrapper_token_mint, false)?;
-
-    validate_token_account(
-        user_token_2022_token_account,
-        user_authority.key,
-        token_2022_mint.key,
-        true,
-    )?;
-    validate_token_account(
-        user_wrapper_token_account,
-        user_authority.key,
-        wrapper_token_mint.key,
-        false,
-    )?;
-    validate_token_account(
-        reserve_token_2022_token_account,
-        reserve_authority.key,
-        token_2022_mint.key,
-        true,
+    withdraw_and_burn_wrapper_tokens(
+        program_id,
+        accounts,
+        0,
+        OptionalAccounts::default(),
+        &WithdrawAndBurnWrapperTokensArgs {
+            amount,
+            ..Default::default()
+        },
     )?;
 
-    let user_wrapper_token_account_data = user_wrapper_token_account.try_borrow_data()?;
-    let user_wrapper_token_account_data_parsed =
-        <spl_token::state::Account>::unpack(&user_wrapper_token_account_data)?;
-    let user_wrapper_token_balance = user_wrapper_token_account_data_parsed.amount;
-    drop(user_wrapper_token_account_data);
-
-    let mut amount = amount;
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokens --> Everything done, returning");
 
-    if amount == u64::MAX {
-        amount = user_wrapper_token_balance;
-    }
+    Ok(())
+}
 
-    let token_2022_mint_data = token_2022_mint.try_borrow_data()?;
-    let token_2022_mint_data_parsed = spl_token_2022::extension::StateWithExtensions::<
-        spl_token_2022::state::Mint,
-    >::unpack(&token_2022_mint_data)?;
-    let token_2022_decimals = token_2022_mint_data_parsed.base.decimals;
-    drop(token_2022_mint_data);
+pub fn process_withdraw_and_burn_wrapper_tokens_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &WithdrawAndBurnWrapperTokensArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2");
 
-    let user_burn_ix = spl_token::instruction::burn_checked(
-        token_program.key,
-        user_wrapper_token_account.key,
-        wrapper_token_mint.key,
+    withdraw_and_burn_wrapper_tokens(program_id, accounts, 0, OptionalAccounts::default(), args)?;
+
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2 --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_withdraw_and_burn_wrapper_tokens_v3(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &WithdrawAndBurnWrapperTokensV3Args,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV3");
+
+    // Same layout as v2 followed by the fee vault and the wrapper limits, the reserve shard takes
+    // the place of the reserve
+    let v3_accounts_iter = &mut accounts
+        .iter()
+        .skip(WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let fee_vault = next_account_info(v3_accounts_iter)?;
+    let wrapper_limits = next_account_info(v3_accounts_iter)?;
+
+    withdraw_and_burn_wrapper_tokens(
+        program_id,
+        accounts,
+        args.reserve_shard,
+        OptionalAccounts {
+            wrapper_inventory: None,
+            fee_vault: Some(fee_vault),
+            wrapper_limits: Some(wrapper_limits),
+        },
+        &args.args,
+    )?;
+
+    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV3 --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_withdraw_to_wrapper_inventory(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &WithdrawAndBurnWrapperTokensV3Args,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::WithdrawToWrapperInventory");
+
+    // Same layout as v3 with the wrapper inventory before the fee vault, the wrapper token mint
+    // is read-only
+    let v3_accounts_iter = &mut accounts
+        .iter()
+        .skip(WITHDRAW_AND_BURN_WRAPPER_TOKENS_V2_ACCOUNTS_LEN);
+    let wrapper_inventory = next_account_info(v3_accounts_iter)?;
+    let fee_vault = next_account_info(v3_accounts_iter)?;
+    let wrapper_limits = next_account_info(v3_accounts_iter)?;
+
+    withdraw_and_burn_wrapper_tokens(
+        program_id,
+        accounts,
+        args.reserve_shard,
+        OptionalAccounts {
+            wrapper_inventory: Some(wrapper_inventory),
+            fee_vault: Some(fee_vault),
+            wrapper_limits: Some(wrapper_limits),
+        },
+        &args.args,
+    )?;
+
+    msg!("TokenWrapperInstruction::WithdrawToWrapperInventory --> Everything done, returning");
+
+    Ok(())
+}
+
+/// Withdraws from the reserve shard `reserve_shard`, and burns the wrapper tokens or transfers
+/// them to the wrapper inventory if the layout takes it
+fn withdraw_and_burn_wrapper_tokens<'a, 'info>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'info>],
+    reserve_shard: u8,
+    optional_accounts: OptionalAccounts<'a, 'info>,
+    args: &WithdrawAndBurnWrapperTokensArgs,
+) -> ProgramResult {
+    let wrapper_inventory = optional_accounts.wrapper_inventory;
+
+    let WrapperAccounts {
+        user_authority,
+        reserve_authority,
+        token_2022_mint,
+        wrapper_token_mint,
+        user_wrapper_token_account,
+        user_token_2022_token_account,
+        reserve_token_2022_token_account,
+        token_program,
+        token_2022_program,
+        wrapper_config,
+        reserve_authority_bump,
+        fee_vault,
+        wrapper_limits,
+    } = WrapperAccounts::load(
+        program_id,
+        accounts,
+        reserve_shard,
+        optional_accounts,
+        args.flags,
+    )?;
+
+    if let Some(memo) = &args.memo {
+        msg!("Memo: {}", memo);
+    }
+
+    let mints =
+        WrapperMintsView::load(token_2022_mint, wrapper_token_mint, wrapper_config.as_ref())?;
+    let token_2022_decimals = mints.decimals();
+
+    let user_token_2022_token_account_view = TokenAccountView::load(
+        user_token_2022_token_account,
         user_authority.key,
-        &[user_authority.key],
-        amount,
-        token_2022_decimals,
+        token_2022_mint.key,
+        true,
+    )?;
+    let user_wrapper_token_account_view = TokenAccountView::load(
+        user_wrapper_token_account,
+        user_authority.key,
+        wrapper_token_mint.key,
+        false,
+    )?;
+    TokenAccountView::load(
+        reserve_token_2022_token_account,
+        reserve_authority.key,
+        token_2022_mint.key,
+        true,
     )?;
 
-    invoke(
-        &user_burn_ix,
-        &[
-            token_program.clone(),
-            user_wrapper_token_account.clone(),
-            wrapper_token_mint.clone(),
-            user_authority.clone(),
-        ],
+    let mut amount = args.amount;
+
+    if amount == u64::MAX {
+        amount = user_wrapper_token_account_view.amount;
+    }
+
+    // Fees are only charged by wrappers with a fee vault, created by `SetFee`
+    let unwrap_fee = wrapper_config
+        .as_ref()
+        .map_or(0, |config| config.unwrap_fee(amount));
+    assert_fee_vault_passed(unwrap_fee, fee_vault)?;
+    let withdraw_amount = amount.checked_sub(unwrap_fee).unwrap();
+
+    if let Some(wrapper_limits) = wrapper_limits {
+        record_unwrap(wrapper_limits, withdraw_amount)?;
+    }
+
+    if let Some(fee_vault) = fee_vault.filter(|_| unwrap_fee > 0) {
+        invoke(
+            &spl_token::instruction::transfer_checked(
+                token_program.key,
+                user_wrapper_token_account.key,
+                wrapper_token_mint.key,
+                fee_vault.key,
+                user_authority.key,
+                &[user_authority.key],
+                unwrap_fee,
+                token_2022_decimals,
+            )?,
+            &[
+                token_program.clone(),
+                user_wrapper_token_account.clone(),
+                wrapper_token_mint.clone(),
+                fee_vault.clone(),
+                user_authority.clone(),
+            ],
+        )?;
+    }
+
+    if let Some(wrapper_inventory) = wrapper_inventory {
+        invoke(
+            &spl_token::instruction::transfer_checked(
+                token_program.key,
+                user_wrapper_token_account.key,
+                wrapper_token_mint.key,
+                wrapper_inventory.key,
+                user_authority.key,
+                &[user_authority.key],
+                withdraw_amount,
+                token_2022_decimals,
+            )?,
+            &[
+                token_program.clone(),
+                user_wrapper_token_account.clone(),
+                wrapper_token_mint.clone(),
+                wrapper_inventory.clone(),
+                user_authority.clone(),
+            ],
+        )?;
+    } else {
+        let user_burn_ix = spl_token::instruction::burn_checked(
+            token_program.key,
+            user_wrapper_token_account.key,
+            wrapper_token_mint.key,
+            user_authority.key,
+            &[user_authority.key],
+            withdraw_amount,
+            token_2022_decimals,
+        )?;
+
+        invoke(
+            &user_burn_ix,
+            &[
+                token_program.clone(),
+                user_wrapper_token_account.clone(),
+                wrapper_token_mint.clone(),
+                user_authority.clone(),
+            ],
+        )?;
+    }
+
+    sync_native_reserve(
+        token_2022_mint,
+        reserve_token_2022_token_account,
+        token_2022_program,
     )?;
 
     let user_withdraw_ix = spl_token_2022::instruction::transfer_checked(
@@ -462,11 +1091,11 @@ pub fn process_withdraw_and_burn_wrapper_tokens(
         user_token_2022_token_account.key,
         reserve_authority.key,
         &[reserve_authority.key],
-        amount,
+        withdraw_amount,
         token_2022_decimals,
     )?;
 
-    let (_, _, reserve_authority_seeds) = get_reserve_authority(*token_2022_mint.key, *program_id);
+    let pre_transfer_balance = user_token_2022_token_account_view.amount;
 
     invoke_signed(
         &user_withdraw_ix,
@@ -477,14 +1106,1799 @@ pub fn process_withdraw_and_burn_wrapper_tokens(
             user_token_2022_token_account.clone(),
             reserve_authority.clone(),
         ],
-        &[reserve_authority_seeds
-            .iter()
-            .map(|seed| seed.as_slice())
-            .collect::<Vec<&[u8]>>()
-            .as_slice()],
+        &[&[
+            b"reserve_authority",
+            token_2022_mint.key.as_ref(),
+            &[reserve_authority_bump],
+        ]],
     )?;
 
-    msg!("TokenWrapperInstruction::WithdrawAndBurnWrapperTokens --> Everything done, returning");
+    let post_transfer_balance = TokenAccountView::reload_amount(user_token_2022_token_account)?;
+
+    assert_with_msg(
+        post_transfer_balance.saturating_sub(pre_transfer_balance) >= args.min_out,
+        TokenWrapperError::SlippageExceeded,
+        "Token 2022 tokens received are below the requested minimum",
+    )
+}
+
+pub fn process_set_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetFeeArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::SetFee");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let fee_recipient = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+    let fee_vault = next_account_info(accounts_info_iter)?;
+    let token_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_system_program(*system_program.key)?;
+    assert_token_program(*token_program.key)?;
+
+    validate_token_account_mint(fee_recipient, wrapper_token_mint.key, false)?;
+
+    assert_with_msg(
+        args.wrap_fee_basis_points <= MAX_FEE_BASIS_POINTS
+            && args.unwrap_fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        TokenWrapperError::FeeTooHigh,
+        "Fee basis points exceed the maximum",
+    )?;
+
+    let mut wrapper_config = load_or_create_wrapper_config(
+        program_id,
+        admin,
+        token_2022_mint,
+        reserve_authority,
+        system_program,
+    )?;
+
+    if wrapper_config.has_fee_vault {
+        assert_fee_vault(
+            *token_2022_mint.key,
+            *reserve_authority.key,
+            *program_id,
+            &wrapper_config,
+            fee_vault,
+        )?;
+    } else {
+        let (expected_fee_vault, fee_vault_bump, fee_vault_seeds) =
+            get_fee_vault(*token_2022_mint.key, *reserve_authority.key, *program_id);
+
+        assert_with_msg(
+            fee_vault.key == &expected_fee_vault
+                && fee_vault.owner == &solana_program::system_program::id(),
+            TokenWrapperError::InvalidFeeVault,
+            "Invalid fee vault passed, expected the uninitialized fee vault",
+        )?;
+
+        create_account(
+            admin,
+            fee_vault,
+            system_program,
+            &spl_token::id(),
+            &Rent::get()?,
+            spl_token::state::Account::LEN as u64,
+            fee_vault_seeds,
+        )?;
+
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                fee_vault.key,
+                wrapper_token_mint.key,
+                reserve_authority.key,
+            )?,
+            &[
+                fee_vault.clone(),
+                wrapper_token_mint.clone(),
+                token_program.clone(),
+            ],
+        )?;
+
+        wrapper_config.has_fee_vault = true;
+        wrapper_config.fee_vault_bump = fee_vault_bump;
+    }
+
+    wrapper_config.fee_recipient = *fee_recipient.key;
+    wrapper_config.wrap_fee_basis_points = args.wrap_fee_basis_points;
+    wrapper_config.unwrap_fee_basis_points = args.unwrap_fee_basis_points;
+    wrapper_config.maximum_fee = args.maximum_fee;
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::SetFee --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_collect_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::CollectFees");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let fee_vault = next_account_info(accounts_info_iter)?;
+    let fee_recipient = next_account_info(accounts_info_iter)?;
+    let token_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_token_program(*token_program.key)?;
+
+    let wrapper_config = WrapperConfig::load(reserve_authority, program_id)?
+        .ok_or(TokenWrapperError::InvalidWrapperConfig)?;
+
+    assert_fee_vault(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        *program_id,
+        &wrapper_config,
+        fee_vault,
+    )?;
+
+    assert_with_msg(
+        fee_recipient.key == &wrapper_config.fee_recipient,
+        TokenWrapperError::InvalidTokenAccount,
+        "Invalid fee recipient passed",
+    )?;
+
+    let decimals = MintView::load(wrapper_token_mint, false)?.decimals;
+    let fees = TokenAccountView::load(
+        fee_vault,
+        reserve_authority.key,
+        wrapper_token_mint.key,
+        false,
+    )?
+    .amount;
+
+    if fees > 0 {
+        let (_, _, reserve_authority_seeds) =
+            get_reserve_authority(*token_2022_mint.key, *program_id);
+
+        invoke_signed(
+            &spl_token::instruction::transfer_checked(
+                token_program.key,
+                fee_vault.key,
+                wrapper_token_mint.key,
+                fee_recipient.key,
+                reserve_authority.key,
+                &[reserve_authority.key],
+                fees,
+                decimals,
+            )?,
+            &[
+                token_program.clone(),
+                fee_vault.clone(),
+                wrapper_token_mint.clone(),
+                fee_recipient.clone(),
+                reserve_authority.clone(),
+            ],
+            &[reserve_authority_seeds
+                .iter()
+                .map(|seed| seed.as_slice())
+                .collect::<Vec<&[u8]>>()
+                .as_slice()],
+        )?;
+    }
+
+    msg!("TokenWrapperInstruction::CollectFees --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_initialize_program_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::InitializeProgramConfig");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let upgrade_authority = next_account_info(accounts_info_iter)?;
+    let program_data = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_upgrade_authority(*program_id, program_data, upgrade_authority)?;
+    assert_program_config(*program_id, program_config, false)?;
+    assert_system_program(*system_program.key)?;
+
+    let (_, _, program_config_seeds) = get_program_config(*program_id);
+    let rent = Rent::get()?;
+
+    create_account(
+        upgrade_authority,
+        program_config,
+        system_program,
+        program_id,
+        &rent,
+        ProgramConfig::LEN as u64,
+        program_config_seeds,
+    )?;
+
+    ProgramConfig {
+        account_type: AccountType::ProgramConfig,
+        admin: *upgrade_authority.key,
+        ..Default::default()
+    }
+    .pack(&mut program_config.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::InitializeProgramConfig --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_set_pause(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetPauseArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::SetPause");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_system_program(*system_program.key)?;
+
+    let mut wrapper_config = load_or_create_wrapper_config(
+        program_id,
+        admin,
+        token_2022_mint,
+        reserve_authority,
+        system_program,
+    )?;
+
+    wrapper_config.paused = args.paused;
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::SetPause --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_set_global_pause(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetPauseArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::SetGlobalPause");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let mut program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    program_config_data.paused = args.paused;
+    program_config_data.pack(&mut program_config.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::SetGlobalPause --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_set_pending_admin(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetPendingAdminArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::SetPendingAdmin");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let mut program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    program_config_data.pending_admin = args.pending_admin;
+    program_config_data.pack(&mut program_config.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::SetPendingAdmin --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_accept_admin(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::AcceptAdmin");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let pending_admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let mut program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+
+    assert_with_msg(
+        pending_admin.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The pending admin needs to be a signer",
+    )?;
+    assert_with_msg(
+        pending_admin.key == &program_config_data.pending_admin
+            && program_config_data.pending_admin != Pubkey::default(),
+        TokenWrapperError::UnexpectedAdmin,
+        "The signer needs to be the pending admin",
+    )?;
+
+    program_config_data.admin = program_config_data.pending_admin;
+    program_config_data.pending_admin = Pubkey::default();
+    program_config_data.pack(&mut program_config.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::AcceptAdmin --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_mirror_freeze(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::MirrorFreeze");
+
+    mirror_freeze_state(program_id, accounts, true)?;
+
+    msg!("TokenWrapperInstruction::MirrorFreeze --> Everything done, returning");
 
     Ok(())
 }
+
+pub fn process_mirror_thaw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::MirrorThaw");
+
+    mirror_freeze_state(program_id, accounts, false)?;
+
+    msg!("TokenWrapperInstruction::MirrorThaw --> Everything done, returning");
+
+    Ok(())
+}
+
+/// Freezes or thaws a wrapper token account with the reserve authority, the freeze authority
+/// of the wrapper token mint
+///
+/// Signed by the freeze authority of the Token 2022 mint, any wrapper token account can be
+/// frozen or thawed, and the freeze is recorded in the issuer freeze of the wrapper token account.
+/// Otherwise the wrapper token account mirrors the state of its owner's Token 2022 associated
+/// token account, and is not thawed while the freeze authority froze it.
+fn mirror_freeze_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    freeze: bool,
+) -> ProgramResult {
+    let accounts_info_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let wrapper_token_account = next_account_info(accounts_info_iter)?;
+    let token_2022_token_account = next_account_info(accounts_info_iter)?;
+    let token_program = next_account_info(accounts_info_iter)?;
+    let issuer_freeze = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_with_msg(
+        authority.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The authority needs to be a signer",
+    )?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_token_program(*token_program.key)?;
+    assert_system_program(*system_program.key)?;
+
+    let token_2022_freeze_authority = MintView::load(token_2022_mint, true)?.freeze_authority;
+    let wrapper_token_account_owner =
+        TokenAccountView::load_of_mint(wrapper_token_account, wrapper_token_mint.key, false)?.owner;
+
+    let (expected_issuer_freeze, _, issuer_freeze_seeds) =
+        get_issuer_freeze(*wrapper_token_account.key, *program_id);
+
+    assert_with_msg(
+        issuer_freeze.key == &expected_issuer_freeze,
+        TokenWrapperError::InvalidIssuerFreeze,
+        "Invalid issuer freeze passed",
+    )?;
+
+    let issuer_freeze_data = if issuer_freeze.owner == program_id {
+        Some(IssuerFreeze::unpack(&issuer_freeze.try_borrow_data()?)?)
+    } else {
+        None
+    };
+
+    if token_2022_freeze_authority == Some(*authority.key) {
+        if issuer_freeze_data.is_none() && freeze {
+            create_account(
+                authority,
+                issuer_freeze,
+                system_program,
+                program_id,
+                &Rent::get()?,
+                IssuerFreeze::LEN as u64,
+                issuer_freeze_seeds,
+            )?;
+        }
+
+        if freeze || issuer_freeze_data.is_some() {
+            IssuerFreeze {
+                account_type: AccountType::IssuerFreeze,
+                wrapper_token_account: *wrapper_token_account.key,
+                frozen: freeze,
+                ..Default::default()
+            }
+            .pack(&mut issuer_freeze.try_borrow_mut_data()?)?;
+        }
+    } else {
+        assert_with_msg(
+            freeze || !issuer_freeze_data.is_some_and(|data| data.frozen),
+            TokenWrapperError::FreezeStateMismatch,
+            "The wrapper token account was frozen by the freeze authority of the Token 2022 mint",
+        )?;
+
+        assert_with_msg(
+            token_2022_token_account.key
+                == &get_associated_token_address_with_program_id(
+                    &wrapper_token_account_owner,
+                    token_2022_mint.key,
+                    &spl_token_2022::id(),
+                ),
+            TokenWrapperError::InvalidTokenAccount,
+            "Invalid Token 2022 associated token account passed",
+        )?;
+
+        let token_2022_token_account_frozen = TokenAccountView::load(
+            token_2022_token_account,
+            &wrapper_token_account_owner,
+            token_2022_mint.key,
+            true,
+        )?
+        .is_frozen;
+
+        assert_with_msg(
+            token_2022_token_account_frozen == freeze,
+            TokenWrapperError::FreezeStateMismatch,
+            "The Token 2022 token account state does not match",
+        )?;
+    }
+
+    let (_, _, reserve_authority_seeds) = get_reserve_authority(*token_2022_mint.key, *program_id);
+
+    let freeze_state_ix = if freeze {
+        spl_token::instruction::freeze_account(
+            token_program.key,
+            wrapper_token_account.key,
+            wrapper_token_mint.key,
+            reserve_authority.key,
+            &[reserve_authority.key],
+        )?
+    } else {
+        spl_token::instruction::thaw_account(
+            token_program.key,
+            wrapper_token_account.key,
+            wrapper_token_mint.key,
+            reserve_authority.key,
+            &[reserve_authority.key],
+        )?
+    };
+
+    invoke_signed(
+        &freeze_state_ix,
+        &[
+            token_program.clone(),
+            wrapper_token_account.clone(),
+            wrapper_token_mint.clone(),
+            reserve_authority.clone(),
+        ],
+        &[reserve_authority_seeds
+            .iter()
+            .map(|seed| seed.as_slice())
+            .collect::<Vec<&[u8]>>()
+            .as_slice()],
+    )
+}
+
+pub fn process_migrate_reserve(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::MigrateReserve");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let reserve_token_2022_token_account = next_account_info(accounts_info_iter)?;
+    let new_reserve_token_2022_token_account = next_account_info(accounts_info_iter)?;
+    let token_2022_program = next_account_info(accounts_info_iter)?;
+    let associated_token_program = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_reserve_authority_token_account(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        *program_id,
+        reserve_token_2022_token_account,
+        true,
+    )?;
+    assert_with_msg(
+        new_reserve_token_2022_token_account.key
+            == &get_associated_token_address_with_program_id(
+                reserve_authority.key,
+                token_2022_mint.key,
+                &spl_token_2022::id(),
+            ),
+        TokenWrapperError::UnexpectedReserveTokenAccount,
+        "Invalid new reserve token account passed",
+    )?;
+
+    assert_token_2022_program(*token_2022_program.key)?;
+    assert_associated_token_program(*associated_token_program.key)?;
+    assert_system_program(*system_program.key)?;
+
+    let mut wrapper_config = load_or_create_wrapper_config(
+        program_id,
+        admin,
+        token_2022_mint,
+        reserve_authority,
+        system_program,
+    )?;
+
+    assert_with_msg(
+        wrapper_config.migrated_reserve().is_none(),
+        TokenWrapperError::ReserveAlreadyMigrated,
+        "The reserve has already been migrated",
+    )?;
+
+    let reserve_shards = next_reserve_shards(
+        program_id,
+        token_2022_mint,
+        reserve_authority,
+        &wrapper_config,
+        wrapper_config.reserve_shards(),
+        accounts_info_iter,
+    )?;
+    let wrapper_inventory_balance = next_wrapper_inventory_balance(
+        program_id,
+        token_2022_mint,
+        wrapper_token_mint,
+        reserve_authority,
+        &wrapper_config,
+        accounts_info_iter,
+    )?;
+
+    invoke(
+        &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            admin.key,
+            reserve_authority.key,
+            token_2022_mint.key,
+            token_2022_program.key,
+        ),
+        &[
+            admin.clone(),
+            new_reserve_token_2022_token_account.clone(),
+            reserve_authority.clone(),
+            token_2022_mint.clone(),
+            system_program.clone(),
+            token_2022_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+
+    let token_2022_mint_data = token_2022_mint.try_borrow_data()?;
+    let token_2022_mint_data_parsed = spl_token_2022::extension::StateWithExtensions::<
+        spl_token_2022::state::Mint,
+    >::unpack(&token_2022_mint_data)?;
+    let token_2022_decimals = token_2022_mint_data_parsed.base.decimals;
+    drop(token_2022_mint_data);
+
+    let reserve_token_2022_token_account_data =
+        reserve_token_2022_token_account.try_borrow_data()?;
+    let reserve_balance = spl_token_2022::extension::StateWithExtensions::<
+        spl_token_2022::state::Account,
+    >::unpack(&reserve_token_2022_token_account_data)?
+    .base
+    .amount;
+    drop(reserve_token_2022_token_account_data);
+
+    if reserve_balance > 0 {
+        let (_, _, reserve_authority_seeds) =
+            get_reserve_authority(*token_2022_mint.key, *program_id);
+
+        invoke_signed(
+            &spl_token_2022::instruction::transfer_checked(
+                token_2022_program.key,
+                reserve_token_2022_token_account.key,
+                token_2022_mint.key,
+                new_reserve_token_2022_token_account.key,
+                reserve_authority.key,
+                &[reserve_authority.key],
+                reserve_balance,
+                token_2022_decimals,
+            )?,
+            &[
+                token_2022_program.clone(),
+                reserve_token_2022_token_account.clone(),
+                token_2022_mint.clone(),
+                new_reserve_token_2022_token_account.clone(),
+                reserve_authority.clone(),
+            ],
+            &[reserve_authority_seeds
+                .iter()
+                .map(|seed| seed.as_slice())
+                .collect::<Vec<&[u8]>>()
+                .as_slice()],
+        )?;
+    }
+
+    // Token 2022 transfer fees are withheld from the migrated funds, the reserve and its shards
+    // must still back the circulating wrapper supply
+    let wrapper_token_mint_data = wrapper_token_mint.try_borrow_data()?;
+    let circulating_supply = Mint::unpack(&wrapper_token_mint_data)?
+        .supply
+        .saturating_sub(wrapper_inventory_balance);
+    drop(wrapper_token_mint_data);
+
+    let new_reserve_token_2022_token_account_data =
+        new_reserve_token_2022_token_account.try_borrow_data()?;
+    let new_reserve_balance = spl_token_2022::extension::StateWithExtensions::<
+        spl_token_2022::state::Account,
+    >::unpack(&new_reserve_token_2022_token_account_data)?
+    .base
+    .amount;
+    drop(new_reserve_token_2022_token_account_data);
+
+    let reserve_shards_balance = sum_reserve_balances(&load_reserve_balances(
+        token_2022_mint,
+        &reserve_shards,
+        token_2022_program,
+    )?)?;
+
+    assert_with_msg(
+        new_reserve_balance.saturating_add(reserve_shards_balance) >= circulating_supply,
+        TokenWrapperError::ReserveUndercollateralized,
+        "The migrated reserve and the reserve shards do not back the wrapper token supply",
+    )?;
+
+    wrapper_config.reserve_token_2022_token_account = *new_reserve_token_2022_token_account.key;
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::MigrateReserve --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_realloc_reserve(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::ReallocReserve");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let reserve_token_2022_token_account = next_account_info(accounts_info_iter)?;
+    let token_2022_program = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_with_msg(
+        payer.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The payer account needs to be a signer",
+    )?;
+
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+
+    let wrapper_config = WrapperConfig::load(reserve_authority, program_id)?;
+
+    assert_current_reserve_token_account(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        *program_id,
+        wrapper_config.as_ref(),
+        reserve_token_2022_token_account,
+    )?;
+
+    assert_token_2022_program(*token_2022_program.key)?;
+    assert_system_program(*system_program.key)?;
+
+    validate_mint(token_2022_mint, true)?;
+
+    let reserve_account_extensions = get_reserve_account_extensions(token_2022_mint)?;
+
+    let reserve_token_2022_token_account_data =
+        reserve_token_2022_token_account.try_borrow_data()?;
+    let current_reserve_account_extensions = spl_token_2022::extension::StateWithExtensions::<
+        spl_token_2022::state::Account,
+    >::unpack(&reserve_token_2022_token_account_data)?
+    .get_extension_types()?;
+    drop(reserve_token_2022_token_account_data);
+
+    let missing_reserve_account_extensions = reserve_account_extensions
+        .into_iter()
+        .filter(|extension| !current_reserve_account_extensions.contains(extension))
+        .collect::<Vec<_>>();
+
+    if missing_reserve_account_extensions.is_empty() {
+        msg!("The reserve already holds every required account extension");
+    } else {
+        let (_, _, reserve_authority_seeds) =
+            get_reserve_authority(*token_2022_mint.key, *program_id);
+
+        invoke_signed(
+            &spl_token_2022::instruction::reallocate(
+                token_2022_program.key,
+                reserve_token_2022_token_account.key,
+                payer.key,
+                reserve_authority.key,
+                &[],
+                &missing_reserve_account_extensions,
+            )?,
+            &[
+                token_2022_program.clone(),
+                reserve_token_2022_token_account.clone(),
+                payer.clone(),
+                system_program.clone(),
+                reserve_authority.clone(),
+            ],
+            &[reserve_authority_seeds
+                .iter()
+                .map(|seed| seed.as_slice())
+                .collect::<Vec<&[u8]>>()
+                .as_slice()],
+        )?;
+    }
+
+    msg!("TokenWrapperInstruction::ReallocReserve --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_retire_wrapper(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("TokenWrapperInstruction::RetireWrapper");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_with_msg(
+        authority.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The authority needs to be a signer",
+    )?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_system_program(*system_program.key)?;
+
+    let (expected_program_config, _, _) = get_program_config(*program_id);
+    assert_with_msg(
+        program_config.key == &expected_program_config,
+        TokenWrapperError::InvalidProgramConfig,
+        "Invalid program config passed",
+    )?;
+
+    let wrapper_config = WrapperConfig::load(reserve_authority, program_id)?;
+
+    // The source mint is replaced if it was closed, is owned by another program, or was
+    // re-created with other decimals or extensions. Any other failure to load the mints is
+    // returned, so that it cannot be used to retire the wrapper without the admin
+    let source_mint_changed = if token_2022_mint.owner != &spl_token_2022::id()
+        || token_2022_mint.data_is_empty()
+    {
+        true
+    } else {
+        match WrapperMintsView::load(token_2022_mint, wrapper_token_mint, wrapper_config.as_ref()) {
+            Ok(_) => false,
+            Err(error) if error == TokenWrapperError::SourceMintChanged.into() => true,
+            Err(error) => return Err(error),
+        }
+    };
+
+    if !source_mint_changed {
+        let program_config_data = ProgramConfig::load(program_config, program_id)?
+            .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+        assert_admin(authority, &program_config_data)?;
+    }
+
+    let mut wrapper_config = match wrapper_config {
+        Some(wrapper_config) => wrapper_config,
+        None => load_or_create_wrapper_config(
+            program_id,
+            authority,
+            token_2022_mint,
+            reserve_authority,
+            system_program,
+        )?,
+    };
+
+    wrapper_config.retired = true;
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::RetireWrapper --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_register_wrapper_group_member(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::RegisterWrapperGroupMember");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_group = next_account_info(accounts_info_iter)?;
+    let wrapper_group_member = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_with_msg(
+        payer.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The payer account needs to be a signer",
+    )?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_system_program(*system_program.key)?;
+    validate_mint(token_2022_mint, true)?;
+
+    // The member extension is only written by Token 2022, with the signature of the group update
+    // authority, so the group it names can be trusted
+    let token_2022_mint_data = token_2022_mint.try_borrow_data()?;
+    let token_group_member = *spl_token_2022::extension::StateWithExtensions::<
+        spl_token_2022::state::Mint,
+    >::unpack(&token_2022_mint_data)?
+    .get_extension::<TokenGroupMember>()
+    .map_err(|_| TokenWrapperError::NotAGroupMember)?;
+    drop(token_2022_mint_data);
+
+    assert_with_msg(
+        &token_group_member.mint == token_2022_mint.key,
+        TokenWrapperError::NotAGroupMember,
+        "The token group member extension belongs to another mint",
+    )?;
+
+    let group = token_group_member.group;
+
+    let (expected_wrapper_group, _, wrapper_group_seeds) = get_wrapper_group(group, *program_id);
+    let (expected_wrapper_group_member, _, wrapper_group_member_seeds) =
+        get_wrapper_group_member(group, *token_2022_mint.key, *program_id);
+
+    assert_with_msg(
+        wrapper_group.key == &expected_wrapper_group
+            && wrapper_group_member.key == &expected_wrapper_group_member,
+        TokenWrapperError::InvalidWrapperGroup,
+        "Invalid wrapper group accounts passed",
+    )?;
+
+    if wrapper_group_member.owner == program_id {
+        msg!("The wrapper is already registered in its group");
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+
+    let mut wrapper_group_data = match WrapperGroup::load(wrapper_group, program_id)? {
+        Some(wrapper_group_data) => wrapper_group_data,
+        None => {
+            create_account(
+                payer,
+                wrapper_group,
+                system_program,
+                program_id,
+                &rent,
+                WrapperGroup::LEN as u64,
+                wrapper_group_seeds,
+            )?;
+
+            WrapperGroup {
+                account_type: AccountType::WrapperGroup,
+                group,
+                ..Default::default()
+            }
+        }
+    };
+
+    create_account(
+        payer,
+        wrapper_group_member,
+        system_program,
+        program_id,
+        &rent,
+        WrapperGroupMember::LEN as u64,
+        wrapper_group_member_seeds,
+    )?;
+
+    WrapperGroupMember {
+        account_type: AccountType::WrapperGroupMember,
+        group,
+        token_2022_mint: *token_2022_mint.key,
+        wrapper_token_mint: *wrapper_token_mint.key,
+        member_number: token_group_member.member_number.into(),
+        index: wrapper_group_data.wrapper_count,
+        ..Default::default()
+    }
+    .pack(&mut wrapper_group_member.try_borrow_mut_data()?)?;
+
+    wrapper_group_data.wrapper_count = wrapper_group_data
+        .wrapper_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    wrapper_group_data.pack(&mut wrapper_group.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::RegisterWrapperGroupMember --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_quote_wrap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &QuoteArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::QuoteWrap");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+
+    let wrapper_config = load_quoted_wrapper_config(
+        program_id,
+        token_2022_mint,
+        wrapper_token_mint,
+        reserve_authority,
+    )?;
+
+    let (expected_program_config, _, _) = get_program_config(*program_id);
+    assert_with_msg(
+        program_config.key == &expected_program_config,
+        TokenWrapperError::InvalidProgramConfig,
+        "Invalid program config passed",
+    )?;
+
+    // Quotes fail as the deposit would on a retired wrapper or paused deposits, the wrapper
+    // limits are only checked by the deposit
+    assert_with_msg(
+        !wrapper_config.as_ref().is_some_and(|config| config.retired),
+        TokenWrapperError::WrapperRetired,
+        "The wrapper has been retired",
+    )?;
+    assert_with_msg(
+        !ProgramConfig::load(program_config, program_id)?.is_some_and(|config| config.paused),
+        TokenWrapperError::DepositsPaused,
+        "Deposits are paused for all wrappers",
+    )?;
+    assert_with_msg(
+        !wrapper_config.as_ref().is_some_and(|config| config.paused),
+        TokenWrapperError::DepositsPaused,
+        "Deposits are paused for this wrapper",
+    )?;
+
+    let quote = quote_wrap(
+        &token_2022_mint.try_borrow_data()?,
+        wrapper_config.as_ref(),
+        Clock::get()?.epoch,
+        args.amount,
+    )?;
+    return_quote(&quote);
+
+    msg!("TokenWrapperInstruction::QuoteWrap --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_quote_unwrap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &QuoteArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::QuoteUnwrap");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+
+    let wrapper_config = load_quoted_wrapper_config(
+        program_id,
+        token_2022_mint,
+        wrapper_token_mint,
+        reserve_authority,
+    )?;
+
+    let quote = quote_unwrap(
+        &token_2022_mint.try_borrow_data()?,
+        wrapper_config.as_ref(),
+        Clock::get()?.epoch,
+        args.amount,
+    )?;
+    return_quote(&quote);
+
+    msg!("TokenWrapperInstruction::QuoteUnwrap --> Everything done, returning");
+
+    Ok(())
+}
+
+/// Validates the accounts of a quote the same way as the deposits and withdrawals, and loads
+/// the wrapper config
+fn load_quoted_wrapper_config(
+    program_id: &Pubkey,
+    token_2022_mint: &AccountInfo,
+    wrapper_token_mint: &AccountInfo,
+    reserve_authority: &AccountInfo,
+) -> Result<Option<WrapperConfig>, ProgramError> {
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+
+    let wrapper_config = WrapperConfig::load(reserve_authority, program_id)?;
+
+    WrapperMintsView::load(token_2022_mint, wrapper_token_mint, wrapper_config.as_ref())?;
+
+    Ok(wrapper_config)
+}
+
+fn return_quote(quote: &Quote) {
+    msg!(
+        "Quote: {} in, {} transfer fee, {} wrapper fee, {} out",
+        quote.amount_in,
+        quote.transfer_fee,
+        quote.wrapper_fee,
+        quote.amount_out
+    );
+
+    set_return_data(&borsh::to_vec(quote).unwrap());
+}
+
+pub fn process_initialize_reserve_shard(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::InitializeReserveShard");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let reserve_shard = next_account_info(accounts_info_iter)?;
+    let token_2022_program = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_token_2022_program(*token_2022_program.key)?;
+    assert_system_program(*system_program.key)?;
+
+    validate_mint(token_2022_mint, true)?;
+
+    let mut wrapper_config = load_or_create_wrapper_config(
+        program_id,
+        admin,
+        token_2022_mint,
+        reserve_authority,
+        system_program,
+    )?;
+
+    assert_with_msg(
+        wrapper_config.reserve_shard_count < MAX_RESERVE_SHARDS,
+        TokenWrapperError::TooManyReserveShards,
+        "The wrapper already has the maximum number of reserve shards",
+    )?;
+
+    let reserve_shard_number = wrapper_config.reserve_shard_count + 1;
+    let (expected_reserve_shard, _, reserve_shard_seeds) = get_reserve_shard(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        reserve_shard_number,
+        *program_id,
+    );
+
+    assert_with_msg(
+        reserve_shard.key == &expected_reserve_shard
+            && reserve_shard.owner == &solana_program::system_program::id(),
+        TokenWrapperError::InvalidReserveShard,
+        "Invalid reserve shard passed, expected the next uninitialized shard",
+    )?;
+
+    // Sized and initialized the same way as the reserve
+    let reserve_account_extensions = get_reserve_account_extensions(token_2022_mint)?;
+    let token_account_data_length = get_reserve_account_len(&reserve_account_extensions)? as u64;
+    let rent = Rent::get()?;
+
+    create_account(
+        admin,
+        reserve_shard,
+        system_program,
+        &spl_token_2022::id(),
+        &rent,
+        token_account_data_length,
+        reserve_shard_seeds,
+    )?;
+
+    invoke(
+        &spl_token_2022::instruction::initialize_immutable_owner(
+            &spl_token_2022::id(),
+            reserve_shard.key,
+        )?,
+        &[reserve_shard.clone(), token_2022_program.clone()],
+    )?;
+
+    invoke(
+        &spl_token_2022::instruction::initialize_account3(
+            &spl_token_2022::id(),
+            reserve_shard.key,
+            token_2022_mint.key,
+            reserve_authority.key,
+        )?,
+        &[
+            reserve_shard.clone(),
+            token_2022_mint.clone(),
+            token_2022_program.clone(),
+        ],
+    )?;
+
+    wrapper_config.reserve_shard_count = reserve_shard_number;
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::InitializeReserveShard --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_rebalance_reserve_shards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &RebalanceReserveShardsArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::RebalanceReserveShards");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let token_2022_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_token_2022_program(*token_2022_program.key)?;
+
+    let wrapper_config = WrapperConfig::load(reserve_authority, program_id)?
+        .ok_or(TokenWrapperError::InvalidWrapperConfig)?;
+
+    let reserves = next_reserve_shards(
+        program_id,
+        token_2022_mint,
+        reserve_authority,
+        &wrapper_config,
+        0..=wrapper_config.reserve_shard_count,
+        accounts_info_iter,
+    )?;
+    let wrapper_inventory_balance = next_wrapper_inventory_balance(
+        program_id,
+        token_2022_mint,
+        wrapper_token_mint,
+        reserve_authority,
+        &wrapper_config,
+        accounts_info_iter,
+    )?;
+
+    let (Some(source), Some(destination)) = (
+        reserves.get(args.source_reserve_shard as usize),
+        reserves.get(args.destination_reserve_shard as usize),
+    ) else {
+        return assert_with_msg(
+            false,
+            TokenWrapperError::InvalidReserveShard,
+            "The reserve shard has not been created",
+        );
+    };
+
+    assert_with_msg(
+        args.source_reserve_shard != args.destination_reserve_shard,
+        TokenWrapperError::InvalidReserveShard,
+        "The source and destination reserve shards must differ",
+    )?;
+
+    let mints = WrapperMintsView::load(token_2022_mint, wrapper_token_mint, Some(&wrapper_config))?;
+
+    sync_native_reserve(token_2022_mint, source, token_2022_program)?;
+
+    let mut amount = args.amount;
+
+    if amount == u64::MAX {
+        amount = TokenAccountView::reload_amount(source)?;
+    }
+
+    let (_, _, reserve_authority_seeds) = get_reserve_authority(*token_2022_mint.key, *program_id);
+
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_2022_program.key,
+            source.key,
+            token_2022_mint.key,
+            destination.key,
+            reserve_authority.key,
+            &[reserve_authority.key],
+            amount,
+            mints.decimals(),
+        )?,
+        &[
+            token_2022_program.clone(),
+            (*source).clone(),
+            token_2022_mint.clone(),
+            (*destination).clone(),
+            reserve_authority.clone(),
+        ],
+        &[reserve_authority_seeds
+            .iter()
+            .map(|seed| seed.as_slice())
+            .collect::<Vec<&[u8]>>()
+            .as_slice()],
+    )?;
+
+    // Token 2022 transfer fees are withheld from the moved funds, the reserve and its shards must
+    // still back the circulating wrapper supply
+    let reserve_balance = sum_reserve_balances(&load_reserve_balances(
+        token_2022_mint,
+        &reserves,
+        token_2022_program,
+    )?)?;
+
+    assert_with_msg(
+        reserve_balance
+            >= mints
+                .wrapper_token_mint
+                .supply
+                .saturating_sub(wrapper_inventory_balance),
+        TokenWrapperError::ReserveUndercollateralized,
+        "The reserve and the reserve shards do not back the wrapper token supply",
+    )?;
+
+    msg!("TokenWrapperInstruction::RebalanceReserveShards --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_initialize_wrapper_inventory(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::InitializeWrapperInventory");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let wrapper_inventory = next_account_info(accounts_info_iter)?;
+    let token_program = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_token_program(*token_program.key)?;
+    assert_system_program(*system_program.key)?;
+
+    let mut wrapper_config = load_or_create_wrapper_config(
+        program_id,
+        admin,
+        token_2022_mint,
+        reserve_authority,
+        system_program,
+    )?;
+
+    let (expected_wrapper_inventory, _, wrapper_inventory_seeds) =
+        get_wrapper_inventory(*token_2022_mint.key, *reserve_authority.key, *program_id);
+
+    assert_with_msg(
+        !wrapper_config.has_wrapper_inventory
+            && wrapper_inventory.key == &expected_wrapper_inventory
+            && wrapper_inventory.owner == &solana_program::system_program::id(),
+        TokenWrapperError::InvalidWrapperInventory,
+        "Invalid wrapper inventory passed, expected the uninitialized wrapper inventory",
+    )?;
+
+    let rent = Rent::get()?;
+
+    create_account(
+        admin,
+        wrapper_inventory,
+        system_program,
+        &spl_token::id(),
+        &rent,
+        spl_token::state::Account::LEN as u64,
+        wrapper_inventory_seeds,
+    )?;
+
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            wrapper_inventory.key,
+            wrapper_token_mint.key,
+            reserve_authority.key,
+        )?,
+        &[
+            wrapper_inventory.clone(),
+            wrapper_token_mint.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    wrapper_config.has_wrapper_inventory = true;
+    wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::InitializeWrapperInventory --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_top_up_wrapper_inventory(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::TopUpWrapperInventory");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let wrapper_inventory = next_account_info(accounts_info_iter)?;
+    let token_program = next_account_info(accounts_info_iter)?;
+    let wrapper_limits = accounts_info_iter.next();
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_token_program(*token_program.key)?;
+
+    let wrapper_config = WrapperConfig::load(reserve_authority, program_id)?;
+
+    assert_wrapper_inventory(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        *program_id,
+        wrapper_config.as_ref(),
+        wrapper_inventory,
+    )?;
+
+    let wrapper_limits = next_wrapper_limits(
+        program_id,
+        token_2022_mint,
+        wrapper_config.as_ref(),
+        wrapper_limits,
+    )?;
+
+    let mints =
+        WrapperMintsView::load(token_2022_mint, wrapper_token_mint, wrapper_config.as_ref())?;
+
+    if let Some(wrapper_limits) = wrapper_limits {
+        assert_with_msg(
+            WrapperLimits::unpack(&wrapper_limits.try_borrow_data()?)?
+                .allows_supply(mints.wrapper_token_mint.supply.saturating_add(amount)),
+            TokenWrapperError::SupplyCapExceeded,
+            "The wrapper token supply would exceed the supply cap",
+        )?;
+    }
+
+    let (_, _, reserve_authority_seeds) = get_reserve_authority(*token_2022_mint.key, *program_id);
+
+    // The minted wrapper tokens stay in the inventory, the circulating supply is unchanged
+    invoke_signed(
+        &spl_token::instruction::mint_to_checked(
+            token_program.key,
+            wrapper_token_mint.key,
+            wrapper_inventory.key,
+            reserve_authority.key,
+            &[reserve_authority.key],
+            amount,
+            mints.decimals(),
+        )?,
+        &[
+            token_program.clone(),
+            wrapper_token_mint.clone(),
+            wrapper_inventory.clone(),
+            reserve_authority.clone(),
+        ],
+        &[reserve_authority_seeds
+            .iter()
+            .map(|seed| seed.as_slice())
+            .collect::<Vec<&[u8]>>()
+            .as_slice()],
+    )?;
+
+    msg!("TokenWrapperInstruction::TopUpWrapperInventory --> Everything done, returning");
+
+    Ok(())
+}
+
+pub fn process_set_wrapper_limits(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetWrapperLimitsArgs,
+) -> ProgramResult {
+    msg!("TokenWrapperInstruction::SetWrapperLimits");
+
+    let accounts_info_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_info_iter)?;
+    let program_config = next_account_info(accounts_info_iter)?;
+    let token_2022_mint = next_account_info(accounts_info_iter)?;
+    let wrapper_token_mint = next_account_info(accounts_info_iter)?;
+    let reserve_authority = next_account_info(accounts_info_iter)?;
+    let wrapper_limits = next_account_info(accounts_info_iter)?;
+    let system_program = next_account_info(accounts_info_iter)?;
+
+    assert_program_config(*program_id, program_config, true)?;
+    let program_config_data = ProgramConfig::load(program_config, program_id)?
+        .ok_or(TokenWrapperError::InvalidProgramConfig)?;
+    assert_admin(admin, &program_config_data)?;
+
+    assert_wrapper_token_mint(*token_2022_mint.key, *program_id, wrapper_token_mint, true)?;
+    assert_reserve_authority(*token_2022_mint.key, *program_id, reserve_authority)?;
+    assert_system_program(*system_program.key)?;
+
+    let mut wrapper_config = load_or_create_wrapper_config(
+        program_id,
+        admin,
+        token_2022_mint,
+        reserve_authority,
+        system_program,
+    )?;
+
+    let mut limits = if wrapper_config.has_wrapper_limits {
+        assert_wrapper_limits(
+            *token_2022_mint.key,
+            *program_id,
+            Some(&wrapper_config),
+            wrapper_limits,
+        )?;
+
+        WrapperLimits::unpack(&wrapper_limits.try_borrow_data()?)?
+    } else {
+        let (expected_wrapper_limits, _, wrapper_limits_seeds) =
+            get_wrapper_limits(*token_2022_mint.key, *program_id);
+
+        assert_with_msg(
+            wrapper_limits.key == &expected_wrapper_limits
+                && wrapper_limits.owner == &solana_program::system_program::id(),
+            TokenWrapperError::InvalidWrapperLimits,
+            "Invalid wrapper limits passed, expected the uninitialized wrapper limits",
+        )?;
+
+        let rent = Rent::get()?;
+
+        create_account(
+            admin,
+            wrapper_limits,
+            system_program,
+            program_id,
+            &rent,
+            WrapperLimits::LEN as u64,
+            wrapper_limits_seeds,
+        )?;
+
+        wrapper_config.has_wrapper_limits = true;
+        wrapper_config.pack(&mut reserve_authority.try_borrow_mut_data()?)?;
+
+        WrapperLimits {
+            account_type: AccountType::WrapperLimits,
+            token_2022_mint: *token_2022_mint.key,
+            epoch: Clock::get()?.epoch,
+            ..Default::default()
+        }
+    };
+
+    // The volumes recorded in the current epoch are kept
+    limits.supply_cap = args.supply_cap;
+    limits.epoch_wrap_limit = args.epoch_wrap_limit;
+    limits.epoch_unwrap_limit = args.epoch_unwrap_limit;
+    limits.pack(&mut wrapper_limits.try_borrow_mut_data()?)?;
+
+    msg!("TokenWrapperInstruction::SetWrapperLimits --> Everything done, returning");
+
+    Ok(())
+}
+
+/// Returns the fee vault `fee_vault` if the wrapper has one, `None` otherwise or if the layout
+/// does not take it, see `assert_fee_vault_passed`
+pub fn next_fee_vault<'a, 'info>(
+    program_id: &Pubkey,
+    token_2022_mint: &AccountInfo,
+    reserve_authority: &AccountInfo,
+    wrapper_config: Option<&WrapperConfig>,
+    fee_vault: Option<&'a AccountInfo<'info>>,
+) -> Result<Option<&'a AccountInfo<'info>>, ProgramError> {
+    let (Some(wrapper_config), Some(fee_vault)) = (
+        wrapper_config.filter(|config| config.has_fee_vault),
+        fee_vault,
+    ) else {
+        return Ok(None);
+    };
+
+    assert_fee_vault(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        *program_id,
+        wrapper_config,
+        fee_vault,
+    )?;
+
+    Ok(Some(fee_vault))
+}
+
+/// Asserts that a deposit or withdrawal charged a `fee` has the fee vault, which the v1 and v2
+/// layouts do not take
+pub fn assert_fee_vault_passed(fee: u64, fee_vault: Option<&AccountInfo>) -> ProgramResult {
+    assert_with_msg(
+        fee == 0 || fee_vault.is_some(),
+        TokenWrapperError::UnsupportedInstructionVersion,
+        "The wrapper charges a fee, deposits and withdrawals must use the v3 instructions",
+    )
+}
+
+/// Returns the wrapper limits `wrapper_limits` if the wrapper has limits, in which case they must
+/// be passed, `None` otherwise
+pub fn next_wrapper_limits<'a, 'info>(
+    program_id: &Pubkey,
+    token_2022_mint: &AccountInfo,
+    wrapper_config: Option<&WrapperConfig>,
+    wrapper_limits: Option<&'a AccountInfo<'info>>,
+) -> Result<Option<&'a AccountInfo<'info>>, ProgramError> {
+    if !wrapper_config.is_some_and(|config| config.has_wrapper_limits) {
+        return Ok(None);
+    }
+
+    let wrapper_limits = wrapper_limits.ok_or_else(|| {
+        msg!("The wrapper has limits, the wrapper limits must be passed");
+        ProgramError::from(TokenWrapperError::InvalidWrapperLimits)
+    })?;
+
+    assert_wrapper_limits(
+        *token_2022_mint.key,
+        *program_id,
+        wrapper_config,
+        wrapper_limits,
+    )?;
+
+    Ok(Some(wrapper_limits))
+}
+
+/// Records a wrap of `amount` wrapper tokens in the wrapper limits, the Token 2022 tokens received
+/// by the reserve with the wrap fee, checking the epoch wrap limit and, for wraps minting the
+/// wrapper tokens, the supply cap against `wrapper_token_supply`, the supply once minted
+pub fn record_wrap(
+    wrapper_limits: &AccountInfo,
+    amount: u64,
+    wrapper_token_supply: Option<u64>,
+) -> ProgramResult {
+    let mut limits = WrapperLimits::unpack(&wrapper_limits.try_borrow_data()?)?;
+    limits.roll_epoch(Clock::get()?.epoch);
+
+    if let Some(wrapper_token_supply) = wrapper_token_supply {
+        assert_with_msg(
+            limits.allows_supply(wrapper_token_supply),
+            TokenWrapperError::SupplyCapExceeded,
+            "The wrapper token supply would exceed the supply cap",
+        )?;
+    }
+
+    assert_with_msg(
+        limits.allows_wrap(amount),
+        TokenWrapperError::EpochWrapLimitExceeded,
+        "The net wrapped amount would exceed the limit of the epoch",
+    )?;
+
+    limits.epoch_wrapped = limits.epoch_wrapped.saturating_add(amount);
+    limits.pack(&mut wrapper_limits.try_borrow_mut_data()?)
+}
+
+/// Records an unwrap of `amount` wrapper tokens in the wrapper limits, the Token 2022 tokens
+/// withdrawn from the reserve without the unwrap fee, checking the epoch unwrap limit
+pub fn record_unwrap(wrapper_limits: &AccountInfo, amount: u64) -> ProgramResult {
+    let mut limits = WrapperLimits::unpack(&wrapper_limits.try_borrow_data()?)?;
+    limits.roll_epoch(Clock::get()?.epoch);
+
+    assert_with_msg(
+        limits.allows_unwrap(amount),
+        TokenWrapperError::EpochUnwrapLimitExceeded,
+        "The net unwrapped amount would exceed the limit of the epoch",
+    )?;
+
+    limits.epoch_unwrapped = limits.epoch_unwrapped.saturating_add(amount);
+    limits.pack(&mut wrapper_limits.try_borrow_mut_data()?)
+}
+
+/// Loads the wrapper config stored in the reserve authority account, creating the account
+/// funded by `payer` if the wrapper has no config yet
+fn load_or_create_wrapper_config<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    token_2022_mint: &AccountInfo<'a>,
+    reserve_authority: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<WrapperConfig, ProgramError> {
+    if let Some(mut wrapper_config) = WrapperConfig::load(reserve_authority, program_id)? {
+        if !wrapper_config.has_bumps {
+            wrapper_config.record_bumps(program_id);
+        }
+
+        return Ok(wrapper_config);
+    }
+
+    let (_, _, reserve_authority_seeds) = get_reserve_authority(*token_2022_mint.key, *program_id);
+    let rent = Rent::get()?;
+
+    create_account(
+        payer,
+        reserve_authority,
+        system_program,
+        program_id,
+        &rent,
+        WrapperConfig::LEN as u64,
+        reserve_authority_seeds,
+    )?;
+
+    let mut wrapper_config = WrapperConfig {
+        account_type: AccountType::WrapperConfig,
+        token_2022_mint: *token_2022_mint.key,
+        ..Default::default()
+    };
+    wrapper_config.record_bumps(program_id);
+
+    Ok(wrapper_config)
+}
+
+/// Takes the reserve shards `reserve_shards` of the wrapper from the accounts following the fixed
+/// accounts of an instruction, in order, and validates them
+fn next_reserve_shards<'a, 'info>(
+    program_id: &Pubkey,
+    token_2022_mint: &AccountInfo,
+    reserve_authority: &AccountInfo,
+    wrapper_config: &WrapperConfig,
+    reserve_shards: RangeInclusive<u8>,
+    accounts_info_iter: &mut Iter<'a, AccountInfo<'info>>,
+) -> Result<Vec<&'a AccountInfo<'info>>, ProgramError> {
+    reserve_shards
+        .map(|reserve_shard| {
+            let reserve_shard_info = next_account_info(accounts_info_iter)?;
+
+            assert_reserve_shard(
+                *token_2022_mint.key,
+                *reserve_authority.key,
+                *program_id,
+                Some(wrapper_config),
+                reserve_shard,
+                reserve_shard_info,
+            )?;
+
+            Ok(reserve_shard_info)
+        })
+        .collect()
+}
+
+/// Takes the wrapper inventory from the accounts following the reserve shards if the wrapper has
+/// one, and returns its balance, zero otherwise
+fn next_wrapper_inventory_balance(
+    program_id: &Pubkey,
+    token_2022_mint: &AccountInfo,
+    wrapper_token_mint: &AccountInfo,
+    reserve_authority: &AccountInfo,
+    wrapper_config: &WrapperConfig,
+    accounts_info_iter: &mut Iter<AccountInfo>,
+) -> Result<u64, ProgramError> {
+    if !wrapper_config.has_wrapper_inventory {
+        return Ok(0);
+    }
+
+    let wrapper_inventory = next_account_info(accounts_info_iter)?;
+
+    assert_wrapper_inventory(
+        *token_2022_mint.key,
+        *reserve_authority.key,
+        *program_id,
+        Some(wrapper_config),
+        wrapper_inventory,
+    )?;
+
+    Ok(TokenAccountView::load(
+        wrapper_inventory,
+        reserve_authority.key,
+        wrapper_token_mint.key,
+        false,
+    )?
+    .amount)
+}
+
+/// Balances of `reserves`, synced first if they hold Token 2022 native SOL
+fn load_reserve_balances<'a>(
+    token_2022_mint: &AccountInfo<'a>,
+    reserves: &[&AccountInfo<'a>],
+    token_2022_program: &AccountInfo<'a>,
+) -> Result<Vec<u64>, ProgramError> {
+    reserves
+        .iter()
+        .map(|reserve| {
+            sync_native_reserve(token_2022_mint, reserve, token_2022_program)?;
+            TokenAccountView::reload_amount(reserve)
+        })
+        .collect()
+}
+
+fn sum_reserve_balances(reserve_balances: &[u64]) -> Result<u64, ProgramError> {
+    reserve_balances
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
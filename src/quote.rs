@@ -0,0 +1,93 @@
+//! Amounts received by a wrap or an unwrap, after the Token 2022 transfer fee and the wrapper fees
+//!
+//! The transfer fee is the only Token 2022 mint extension changing the amounts transferred, the
+//! other extensions either leave them unchanged or only change how they are displayed.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+
+use crate::state::WrapperConfig;
+
+/// Outcome of a wrap or an unwrap of `amount_in` tokens
+///
+/// Borsh-encoded in the return data of the `QuoteWrap` and `QuoteUnwrap` instructions.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quote {
+    /// Token 2022 tokens deposited when wrapping, wrapper tokens burned when unwrapping
+    pub amount_in: u64,
+    /// Token 2022 transfer fee withheld on the transfer to or from the reserve
+    pub transfer_fee: u64,
+    /// Wrap or unwrap fee collected in the fee vault
+    pub wrapper_fee: u64,
+    /// Wrapper tokens minted when wrapping, Token 2022 tokens received when unwrapping
+    pub amount_out: u64,
+}
+
+/// Transfer fee withheld by the Token 2022 mint on a transfer of `amount` during `epoch`, zero if
+/// the mint has no `TransferFeeConfig`
+pub fn get_transfer_fee(
+    token_2022_mint_data: &[u8],
+    epoch: u64,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    let token_2022_mint = StateWithExtensions::<Mint>::unpack(token_2022_mint_data)?;
+
+    match token_2022_mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(ProgramError::ArithmeticOverflow),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Wrapper tokens minted for a deposit of `amount` Token 2022 tokens during `epoch`
+///
+/// The wrap fee is charged on the Token 2022 tokens received by the reserve, after the transfer
+/// fee.
+pub fn quote_wrap(
+    token_2022_mint_data: &[u8],
+    wrapper_config: Option<&WrapperConfig>,
+    epoch: u64,
+    amount: u64,
+) -> Result<Quote, ProgramError> {
+    let transfer_fee = get_transfer_fee(token_2022_mint_data, epoch, amount)?;
+    let received_amount = amount
+        .checked_sub(transfer_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let wrapper_fee = wrapper_config.map_or(0, |config| config.wrap_fee(received_amount));
+
+    Ok(Quote {
+        amount_in: amount,
+        transfer_fee,
+        wrapper_fee,
+        amount_out: received_amount - wrapper_fee,
+    })
+}
+
+/// Token 2022 tokens received for `amount` wrapper tokens burned during `epoch`
+///
+/// The unwrap fee is transferred to the fee vault and the rest is burned, the transfer fee is then
+/// withheld on the withdrawal from the reserve.
+pub fn quote_unwrap(
+    token_2022_mint_data: &[u8],
+    wrapper_config: Option<&WrapperConfig>,
+    epoch: u64,
+    amount: u64,
+) -> Result<Quote, ProgramError> {
+    let wrapper_fee = wrapper_config.map_or(0, |config| config.unwrap_fee(amount));
+    let withdraw_amount = amount - wrapper_fee;
+    let transfer_fee = get_transfer_fee(token_2022_mint_data, epoch, withdraw_amount)?;
+
+    Ok(Quote {
+        amount_in: amount,
+        transfer_fee,
+        wrapper_fee,
+        amount_out: withdraw_amount
+            .checked_sub(transfer_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    })
+}
@@ -0,0 +1,235 @@
+//! Canonical list of the known wrappers
+//!
+//! The list is `sdk/mainnet_wrapper_mints.json`, shared with the TypeScript SDK and embedded in
+//! the crate. Each entry is validated against the PDA derivation of its wrapper token mint, and
+//! against its Token 2022 mint with `RegistryEntry::validate_mint`. `WrapperClient::
+//! generate_registry` produces the list from the wrapper config accounts on chain.
+
+use std::{collections::HashSet, error::Error, fmt, str::FromStr, sync::OnceLock};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::utils::get_wrapper_token_mint;
+
+/// `sdk/mainnet_wrapper_mints.json`
+pub const MAINNET_REGISTRY_JSON: &str = include_str!("../sdk/mainnet_wrapper_mints.json");
+
+// `Display` and `Error` are implemented by hand, shank reads every enum deriving `Error` in the
+// crate as program errors of the IDL
+#[derive(Debug)]
+pub enum RegistryError {
+    Json(serde_json::Error),
+    DuplicateToken2022Mint(Pubkey),
+    UnexpectedWrapperTokenMint {
+        token_2022_mint: Pubkey,
+        listed: Pubkey,
+        expected: Pubkey,
+    },
+    UnexpectedDecimals {
+        token_2022_mint: Pubkey,
+        listed: u8,
+        actual: u8,
+    },
+    Program(ProgramError),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::Json(e) => write!(f, "Invalid registry: {e}"),
+            RegistryError::DuplicateToken2022Mint(token_2022_mint) => {
+                write!(
+                    f,
+                    "Token 2022 mint {token_2022_mint} is listed more than once"
+                )
+            }
+            RegistryError::UnexpectedWrapperTokenMint {
+                token_2022_mint,
+                listed,
+                expected,
+            } => write!(
+                f,
+                "Wrapper token mint of {token_2022_mint} is listed as {listed}, expected {expected}"
+            ),
+            RegistryError::UnexpectedDecimals {
+                token_2022_mint,
+                listed,
+                actual,
+            } => write!(
+                f,
+                "Decimals of {token_2022_mint} are listed as {listed}, the mint has {actual}"
+            ),
+            RegistryError::Program(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for RegistryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RegistryError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for RegistryError {
+    fn from(e: serde_json::Error) -> Self {
+        RegistryError::Json(e)
+    }
+}
+
+impl From<ProgramError> for RegistryError {
+    fn from(e: ProgramError) -> Self {
+        RegistryError::Program(e)
+    }
+}
+
+/// Wrapper of the registry
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    #[serde(with = "pubkey_string")]
+    pub token_2022_mint: Pubkey,
+    pub token_name: String,
+    /// Decimals of the Token 2022 mint, and of the wrapper token mint
+    pub decimals: u8,
+    #[serde(with = "pubkey_string")]
+    pub wrapper_token_mint: Pubkey,
+}
+
+impl RegistryEntry {
+    /// Entry of the wrapper of `token_2022_mint`, named after the symbol of its token metadata,
+    /// or `token_name` if the mint has none
+    pub fn from_token_2022_mint(
+        token_2022_mint: Pubkey,
+        token_2022_mint_data: &[u8],
+        token_name: Option<&str>,
+    ) -> Result<Self, ProgramError> {
+        let mint = StateWithExtensions::<Mint>::unpack(token_2022_mint_data)?;
+        let symbol = mint
+            .get_variable_len_extension::<TokenMetadata>()
+            .ok()
+            .map(|metadata| metadata.symbol)
+            .filter(|symbol| !symbol.is_empty());
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, crate::id());
+
+        Ok(Self {
+            token_2022_mint,
+            token_name: symbol
+                .or(token_name.map(str::to_string))
+                .unwrap_or_else(|| token_2022_mint.to_string()),
+            decimals: mint.base.decimals,
+            wrapper_token_mint,
+        })
+    }
+
+    /// Checks the wrapper token mint against its PDA derivation
+    pub fn validate(&self) -> Result<(), RegistryError> {
+        let (expected, _, _) = get_wrapper_token_mint(self.token_2022_mint, crate::id());
+        if self.wrapper_token_mint != expected {
+            return Err(RegistryError::UnexpectedWrapperTokenMint {
+                token_2022_mint: self.token_2022_mint,
+                listed: self.wrapper_token_mint,
+                expected,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks the decimals against the data of the Token 2022 mint account
+    pub fn validate_mint(&self, token_2022_mint_data: &[u8]) -> Result<(), RegistryError> {
+        let actual = StateWithExtensions::<Mint>::unpack(token_2022_mint_data)?
+            .base
+            .decimals;
+        if self.decimals != actual {
+            return Err(RegistryError::UnexpectedDecimals {
+                token_2022_mint: self.token_2022_mint,
+                listed: self.decimals,
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Validated list of wrappers
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Registry {
+    entries: Vec<RegistryEntry>,
+}
+
+impl Registry {
+    /// Validates `entries`, each Token 2022 mint listed once with its derived wrapper token mint
+    pub fn new(entries: Vec<RegistryEntry>) -> Result<Self, RegistryError> {
+        let mut token_2022_mints = HashSet::new();
+        for entry in &entries {
+            if !token_2022_mints.insert(entry.token_2022_mint) {
+                return Err(RegistryError::DuplicateToken2022Mint(entry.token_2022_mint));
+            }
+            entry.validate()?;
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Registry embedded in the crate, `MAINNET_REGISTRY_JSON`
+    pub fn mainnet() -> &'static Self {
+        static MAINNET: OnceLock<Registry> = OnceLock::new();
+        MAINNET.get_or_init(|| {
+            Self::from_json(MAINNET_REGISTRY_JSON).expect("embedded registry is valid")
+        })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RegistryError> {
+        Self::new(serde_json::from_str(json)?)
+    }
+
+    /// JSON of the registry, in the format of `sdk/mainnet_wrapper_mints.json`
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(&self.entries).unwrap();
+        json.push('\n');
+        json
+    }
+
+    pub fn entries(&self) -> &[RegistryEntry] {
+        &self.entries
+    }
+
+    pub fn find_by_token_2022_mint(&self, token_2022_mint: &Pubkey) -> Option<&RegistryEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.token_2022_mint == *token_2022_mint)
+    }
+
+    pub fn find_by_wrapper_token_mint(
+        &self,
+        wrapper_token_mint: &Pubkey,
+    ) -> Option<&RegistryEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.wrapper_token_mint == *wrapper_token_mint)
+    }
+}
+
+/// Pubkeys as base58 strings, as in the JSON of the TypeScript SDK
+mod pubkey_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let pubkey = String::deserialize(deserializer)?;
+        Pubkey::from_str(&pubkey).map_err(serde::de::Error::custom)
+    }
+}
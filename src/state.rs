@@ -0,0 +1,542 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use shank::ShankAccount;
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    error::TokenWrapperError,
+    utils::{
+        get_reserve_authority, get_reserve_authority_token_account, get_wrapper_limits,
+        get_wrapper_token_mint,
+    },
+};
+
+/// Upper bound for the wrap and unwrap fees, 10%
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000;
+
+/// Upper bound for the number of reserve shards of a wrapper, in addition to the reserve
+pub const MAX_RESERVE_SHARDS: u8 = 15;
+
+const ONE_IN_BASIS_POINTS: u128 = 10_000;
+
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountType {
+    #[default]
+    Uninitialized,
+    WrapperConfig,
+    ProgramConfig,
+    WrapperGroup,
+    WrapperGroupMember,
+    WrapperLimits,
+    IssuerFreeze,
+}
+
+/// Program wide configuration
+///
+/// Stored in the program config PDA, created by the program upgrade authority. The admin
+/// configures the wrappers and can pause deposits, and is rotated in two steps by
+/// `SetPendingAdmin` and `AcceptAdmin`.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Clone, Debug, PartialEq, Eq)]
+pub struct ProgramConfig {
+    pub account_type: AccountType,
+    /// Authority configuring the wrappers and pausing deposits
+    pub admin: Pubkey,
+    /// Admin proposed by `SetPendingAdmin`, default pubkey if none
+    pub pending_admin: Pubkey,
+    /// Pauses the deposits of all wrappers
+    pub paused: bool,
+    pub reserved: [u8; 64],
+}
+
+impl Default for ProgramConfig {
+    fn default() -> Self {
+        Self {
+            account_type: AccountType::Uninitialized,
+            admin: Pubkey::default(),
+            pending_admin: Pubkey::default(),
+            paused: false,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 1 + 32 + 32 + 1 + 64;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let config = Self::deserialize(&mut &data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidProgramConfig))?;
+
+        if config.account_type != AccountType::ProgramConfig {
+            return Err(TokenWrapperError::InvalidProgramConfig.into());
+        }
+
+        Ok(config)
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidProgramConfig))
+    }
+
+    /// Loads the program config, if it has been initialized
+    pub fn load(
+        program_config: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Option<Self>, ProgramError> {
+        if program_config.owner != program_id {
+            return Ok(None);
+        }
+
+        let data = program_config.try_borrow_data()?;
+        Self::unpack(&data).map(Some)
+    }
+}
+
+/// Per wrapper configuration
+///
+/// Stored in the reserve authority PDA account, which is passed to every instruction of a
+/// wrapper, so the configuration applies to the v1 and v2 instruction layouts alike.
+///
+/// Wrap and unwrap fees are collected in wrapper tokens into the fee vault, created by `SetFee`,
+/// and moved to the fee recipient by `CollectFees`.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperConfig {
+    pub account_type: AccountType,
+    /// Token 2022 mint of the wrapper
+    pub token_2022_mint: Pubkey,
+    /// Wrapper token account receiving the collected fees
+    pub fee_recipient: Pubkey,
+    /// Fee charged on the Token 2022 tokens received by the reserve when wrapping
+    pub wrap_fee_basis_points: u16,
+    /// Fee charged on the wrapper tokens burned when unwrapping
+    pub unwrap_fee_basis_points: u16,
+    /// Maximum fee charged by a single wrap or unwrap, zero for no maximum
+    pub maximum_fee: u64,
+    /// Pauses the deposits of this wrapper
+    pub paused: bool,
+    /// Reserve token account set by `MigrateReserve`, the associated token account of the
+    /// reserve authority. Default pubkey while the reserve is the original PDA token account
+    pub reserve_token_2022_token_account: Pubkey,
+    /// Decimals of the Token 2022 mint, recorded at initialization
+    pub token_2022_decimals: u8,
+    /// Hash of the extension types of the Token 2022 mint, except metadata and group extensions,
+    /// recorded at initialization. Zero for wrappers initialized before it was recorded
+    pub token_2022_mint_fingerprint: [u8; 32],
+    /// Set by `RetireWrapper`, rejects deposits
+    pub retired: bool,
+    /// Number of reserve shards created by `InitializeReserveShard`. The reserve is shard zero,
+    /// the shards are numbered from one
+    pub reserve_shard_count: u8,
+    /// Set by `InitializeWrapperInventory`. The wrapper inventory holds pre-minted wrapper tokens,
+    /// which are not backed by the reserve until they leave it
+    pub has_wrapper_inventory: bool,
+    /// Set by `SetWrapperLimits`. Deposits and withdrawals then need the wrapper limits account of
+    /// the v3 instructions, which caps the wrapper supply and the net volume of each epoch
+    pub has_wrapper_limits: bool,
+    /// Set once the bumps below are recorded, at initialization or else by the first instruction
+    /// writing the wrapper config. Deposits and withdrawals then check the PDAs with them instead
+    /// of looking the bumps up
+    pub has_bumps: bool,
+    /// Bump of the wrapper token mint PDA
+    pub wrapper_token_mint_bump: u8,
+    /// Bump of the reserve authority PDA
+    pub reserve_authority_bump: u8,
+    /// Bump of the original reserve PDA token account
+    pub reserve_bump: u8,
+    /// Bump of the wrapper limits PDA
+    pub wrapper_limits_bump: u8,
+    /// Set by `SetFee`. Deposits and withdrawals charged a fee then need the fee vault of the v3
+    /// instructions, the wrapper token account of the reserve authority collecting the fees
+    pub has_fee_vault: bool,
+    /// Bump of the fee vault PDA, recorded with `has_fee_vault`
+    pub fee_vault_bump: u8,
+    pub reserved: [u8; 51],
+}
+
+impl Default for WrapperConfig {
+    fn default() -> Self {
+        Self {
+            account_type: AccountType::Uninitialized,
+            token_2022_mint: Pubkey::default(),
+            fee_recipient: Pubkey::default(),
+            wrap_fee_basis_points: 0,
+            unwrap_fee_basis_points: 0,
+            maximum_fee: 0,
+            paused: false,
+            reserve_token_2022_token_account: Pubkey::default(),
+            token_2022_decimals: 0,
+            token_2022_mint_fingerprint: [0; 32],
+            retired: false,
+            reserve_shard_count: 0,
+            has_wrapper_inventory: false,
+            has_wrapper_limits: false,
+            has_bumps: false,
+            wrapper_token_mint_bump: 0,
+            reserve_authority_bump: 0,
+            reserve_bump: 0,
+            wrapper_limits_bump: 0,
+            has_fee_vault: false,
+            fee_vault_bump: 0,
+            reserved: [0; 51],
+        }
+    }
+}
+
+impl WrapperConfig {
+    pub const LEN: usize =
+        1 + 32 + 32 + 2 + 2 + 8 + 1 + 32 + 1 + 32 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 51;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let config = Self::deserialize(&mut &data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperConfig))?;
+
+        if config.account_type != AccountType::WrapperConfig {
+            return Err(TokenWrapperError::InvalidWrapperConfig.into());
+        }
+
+        Ok(config)
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperConfig))
+    }
+
+    /// Loads the configuration stored in the reserve authority account, if any
+    pub fn load(
+        reserve_authority: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Option<Self>, ProgramError> {
+        if reserve_authority.owner != program_id {
+            return Ok(None);
+        }
+
+        let data = reserve_authority.try_borrow_data()?;
+        Self::unpack(&data).map(Some)
+    }
+
+    /// Reserve token account set by `MigrateReserve`, if the reserve has been migrated
+    pub fn migrated_reserve(&self) -> Option<Pubkey> {
+        (self.reserve_token_2022_token_account != Pubkey::default())
+            .then_some(self.reserve_token_2022_token_account)
+    }
+
+    /// Reserve shards of the wrapper, from one to `reserve_shard_count`
+    pub fn reserve_shards(&self) -> std::ops::RangeInclusive<u8> {
+        1..=self.reserve_shard_count
+    }
+
+    /// Records the bumps of the PDAs of the wrapper, looked up once here so that deposits and
+    /// withdrawals only check them
+    pub fn record_bumps(&mut self, program_id: &Pubkey) {
+        let (_, wrapper_token_mint_bump, _) =
+            get_wrapper_token_mint(self.token_2022_mint, *program_id);
+        let (reserve_authority, reserve_authority_bump, _) =
+            get_reserve_authority(self.token_2022_mint, *program_id);
+        let (_, reserve_bump, _) = get_reserve_authority_token_account(
+            self.token_2022_mint,
+            reserve_authority,
+            *program_id,
+        );
+        let (_, wrapper_limits_bump, _) = get_wrapper_limits(self.token_2022_mint, *program_id);
+
+        self.has_bumps = true;
+        self.wrapper_token_mint_bump = wrapper_token_mint_bump;
+        self.reserve_authority_bump = reserve_authority_bump;
+        self.reserve_bump = reserve_bump;
+        self.wrapper_limits_bump = wrapper_limits_bump;
+    }
+
+    /// Fingerprint of the Token 2022 mint recorded at initialization, if any
+    pub fn recorded_fingerprint(&self) -> Option<[u8; 32]> {
+        (self.token_2022_mint_fingerprint != [0; 32]).then_some(self.token_2022_mint_fingerprint)
+    }
+
+    /// Fee minted to the fee vault out of `amount` Token 2022 tokens received by the reserve when
+    /// wrapping
+    pub fn wrap_fee(&self, amount: u64) -> u64 {
+        self.calculate_fee(amount, self.wrap_fee_basis_points)
+    }
+
+    /// Fee transferred to the fee vault out of `amount` wrapper tokens unwrapped
+    pub fn unwrap_fee(&self, amount: u64) -> u64 {
+        self.calculate_fee(amount, self.unwrap_fee_basis_points)
+    }
+
+    /// Zero until `SetFee` created the fee vault, wrappers configured before it charge no fee
+    fn calculate_fee(&self, amount: u64, basis_points: u16) -> u64 {
+        if !self.has_fee_vault || basis_points == 0 || amount == 0 {
+            return 0;
+        }
+
+        // Rounds up, same as the Token 2022 transfer fee
+        let fee = (amount as u128 * basis_points as u128).div_ceil(ONE_IN_BASIS_POINTS);
+
+        let fee = (fee as u64).min(amount);
+
+        if self.maximum_fee == 0 {
+            return fee;
+        }
+
+        fee.min(self.maximum_fee)
+    }
+}
+
+/// Supply cap and per-epoch volume limits of a wrapper
+///
+/// Stored in the wrapper limits PDA, created by `SetWrapperLimits`. Kept out of the wrapper config
+/// so that only the deposits and withdrawals of limited wrappers write-lock it. A limit of zero
+/// is disabled.
+///
+/// Volumes are in wrapper tokens, minted or transferred out of the wrapper inventory for wraps,
+/// burned or transferred into it for unwraps, and are counted from the start of `epoch`. The
+/// first wrap or unwrap of a later epoch resets them.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperLimits {
+    pub account_type: AccountType,
+    /// Token 2022 mint of the wrapper
+    pub token_2022_mint: Pubkey,
+    /// Maximum wrapper token supply, checked when wrapper tokens are minted
+    pub supply_cap: u64,
+    /// Maximum of wrapped minus unwrapped wrapper tokens in an epoch
+    pub epoch_wrap_limit: u64,
+    /// Maximum of unwrapped minus wrapped wrapper tokens in an epoch
+    pub epoch_unwrap_limit: u64,
+    /// Epoch of the recorded volumes
+    pub epoch: u64,
+    /// Wrapper tokens wrapped during `epoch`, wrap fees included like in the wrapper token supply
+    pub epoch_wrapped: u64,
+    /// Wrapper tokens unwrapped during `epoch`, unwrap fees excluded as they are not burned
+    pub epoch_unwrapped: u64,
+    pub reserved: [u8; 64],
+}
+
+impl Default for WrapperLimits {
+    fn default() -> Self {
+        Self {
+            account_type: AccountType::Uninitialized,
+            token_2022_mint: Pubkey::default(),
+            supply_cap: 0,
+            epoch_wrap_limit: 0,
+            epoch_unwrap_limit: 0,
+            epoch: 0,
+            epoch_wrapped: 0,
+            epoch_unwrapped: 0,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl WrapperLimits {
+    pub const LEN: usize = 1 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 64;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let limits = Self::deserialize(&mut &data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperLimits))?;
+
+        if limits.account_type != AccountType::WrapperLimits {
+            return Err(TokenWrapperError::InvalidWrapperLimits.into());
+        }
+
+        Ok(limits)
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperLimits))
+    }
+
+    /// Resets the recorded volumes if `epoch` is past the epoch they were recorded in
+    pub fn roll_epoch(&mut self, epoch: u64) {
+        if epoch > self.epoch {
+            self.epoch = epoch;
+            self.epoch_wrapped = 0;
+            self.epoch_unwrapped = 0;
+        }
+    }
+
+    /// Whether a wrapper token supply of `supply` is within the supply cap
+    pub fn allows_supply(&self, supply: u64) -> bool {
+        self.supply_cap == 0 || supply <= self.supply_cap
+    }
+
+    /// Whether wrapping `amount` more wrapper tokens this epoch is within the epoch wrap limit
+    pub fn allows_wrap(&self, amount: u64) -> bool {
+        self.epoch_wrap_limit == 0
+            || self
+                .epoch_wrapped
+                .saturating_add(amount)
+                .saturating_sub(self.epoch_unwrapped)
+                <= self.epoch_wrap_limit
+    }
+
+    /// Whether unwrapping `amount` more wrapper tokens this epoch is within the epoch unwrap
+    /// limit
+    pub fn allows_unwrap(&self, amount: u64) -> bool {
+        self.epoch_unwrap_limit == 0
+            || self
+                .epoch_unwrapped
+                .saturating_add(amount)
+                .saturating_sub(self.epoch_wrapped)
+                <= self.epoch_unwrap_limit
+    }
+}
+
+/// Registry of the wrappers of the members of a Token 2022 token group
+///
+/// Stored in the wrapper group PDA of the group, created when the first member wrapper is
+/// registered by `RegisterWrapperGroupMember`. Each registered wrapper has a
+/// `WrapperGroupMember` account, which can be enumerated by filtering on the group.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperGroup {
+    pub account_type: AccountType,
+    /// Token group, the address in the `TokenGroupMember` extension of the member mints
+    pub group: Pubkey,
+    /// Number of registered wrappers
+    pub wrapper_count: u64,
+    pub reserved: [u8; 64],
+}
+
+impl Default for WrapperGroup {
+    fn default() -> Self {
+        Self {
+            account_type: AccountType::Uninitialized,
+            group: Pubkey::default(),
+            wrapper_count: 0,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl WrapperGroup {
+    pub const LEN: usize = 1 + 32 + 8 + 64;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let group = Self::deserialize(&mut &data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperGroup))?;
+
+        if group.account_type != AccountType::WrapperGroup {
+            return Err(TokenWrapperError::InvalidWrapperGroup.into());
+        }
+
+        Ok(group)
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperGroup))
+    }
+
+    /// Loads the wrapper group, if a member wrapper has been registered
+    pub fn load(
+        wrapper_group: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Option<Self>, ProgramError> {
+        if wrapper_group.owner != program_id {
+            return Ok(None);
+        }
+
+        let data = wrapper_group.try_borrow_data()?;
+        Self::unpack(&data).map(Some)
+    }
+}
+
+/// Wrapper registered in a wrapper group
+///
+/// Stored in the wrapper group member PDA of the group and the Token 2022 mint.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Clone, Debug, PartialEq, Eq)]
+pub struct WrapperGroupMember {
+    pub account_type: AccountType,
+    /// Token group of the Token 2022 mint
+    pub group: Pubkey,
+    /// Token 2022 mint, member of the group
+    pub token_2022_mint: Pubkey,
+    /// Wrapper token mint of the Token 2022 mint
+    pub wrapper_token_mint: Pubkey,
+    /// Member number of the Token 2022 mint in the token group
+    pub member_number: u32,
+    /// Registration order in the wrapper group, starting at zero
+    pub index: u64,
+    pub reserved: [u8; 32],
+}
+
+impl Default for WrapperGroupMember {
+    fn default() -> Self {
+        Self {
+            account_type: AccountType::Uninitialized,
+            group: Pubkey::default(),
+            token_2022_mint: Pubkey::default(),
+            wrapper_token_mint: Pubkey::default(),
+            member_number: 0,
+            index: 0,
+            reserved: [0; 32],
+        }
+    }
+}
+
+impl WrapperGroupMember {
+    pub const LEN: usize = 1 + 32 + 32 + 32 + 4 + 8 + 32;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let member = Self::deserialize(&mut &data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperGroup))?;
+
+        if member.account_type != AccountType::WrapperGroupMember {
+            return Err(TokenWrapperError::InvalidWrapperGroup.into());
+        }
+
+        Ok(member)
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidWrapperGroup))
+    }
+}
+
+/// Freeze of a wrapper token account by the freeze authority of the Token 2022 mint
+///
+/// Stored in the issuer freeze PDA of the wrapper token account, created by the first
+/// `MirrorFreeze` signed by the freeze authority. While `frozen` is set, `MirrorThaw` must be
+/// signed by the freeze authority, whatever the state of the Token 2022 token account.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Clone, Debug, PartialEq, Eq)]
+pub struct IssuerFreeze {
+    pub account_type: AccountType,
+    /// Wrapper token account frozen by the freeze authority
+    pub wrapper_token_account: Pubkey,
+    /// Whether the wrapper token account is frozen by the freeze authority
+    pub frozen: bool,
+    pub reserved: [u8; 32],
+}
+
+impl Default for IssuerFreeze {
+    fn default() -> Self {
+        Self {
+            account_type: AccountType::Uninitialized,
+            wrapper_token_account: Pubkey::default(),
+            frozen: false,
+            reserved: [0; 32],
+        }
+    }
+}
+
+impl IssuerFreeze {
+    pub const LEN: usize = 1 + 32 + 1 + 32;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let issuer_freeze = Self::deserialize(&mut &data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidIssuerFreeze))?;
+
+        if issuer_freeze.account_type != AccountType::IssuerFreeze {
+            return Err(TokenWrapperError::InvalidIssuerFreeze.into());
+        }
+
+        Ok(issuer_freeze)
+    }
+
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        self.serialize(&mut &mut data[..])
+            .map_err(|_| ProgramError::from(TokenWrapperError::InvalidIssuerFreeze))
+    }
+}
@@ -1,11 +1,19 @@
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
-    pubkey::Pubkey, system_program, sysvar,
+    account_info::AccountInfo, bpf_loader_upgradeable,
+    bpf_loader_upgradeable::UpgradeableLoaderState, entrypoint::ProgramResult, msg,
+    program_error::ProgramError, pubkey::Pubkey, system_program, sysvar,
 };
 
-use crate::error::TokenWrapperError;
+use crate::{
+    error::TokenWrapperError,
+    state::{AccountType, ProgramConfig, WrapperConfig, WrapperGroupMember},
+};
 
-use super::{get_reserve_authority, get_reserve_authority_token_account, get_wrapper_token_mint};
+use super::{
+    get_program_config, get_reserve_authority, get_reserve_authority_token_account,
+    get_reserve_shard, get_wrapper_group_member, get_wrapper_inventory, get_wrapper_limits,
+    get_wrapper_token_mint, MintView, TokenAccountView,
+};
 
 #[track_caller]
 #[inline(always)]
@@ -59,6 +67,91 @@ pub fn assert_rent(p: Pubkey) -> ProgramResult {
     )
 }
 
+/// Asserts that `upgrade_authority` is a signer and the upgrade authority of the program
+pub fn assert_upgrade_authority(
+    program_id: Pubkey,
+    program_data: &AccountInfo,
+    upgrade_authority: &AccountInfo,
+) -> ProgramResult {
+    assert_with_msg(
+        upgrade_authority.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The upgrade authority needs to be a signer",
+    )?;
+
+    assert_with_msg(
+        program_data.key == &bpf_loader_upgradeable::get_program_data_address(&program_id)
+            && program_data.owner == &bpf_loader_upgradeable::id(),
+        TokenWrapperError::UnexpectedAdmin,
+        "Invalid program data account passed",
+    )?;
+
+    let upgrade_authority_address = match bincode::deserialize(&program_data.try_borrow_data()?) {
+        Ok(UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address,
+            ..
+        }) => upgrade_authority_address,
+        _ => None,
+    };
+
+    assert_with_msg(
+        upgrade_authority_address == Some(*upgrade_authority.key),
+        TokenWrapperError::UnexpectedAdmin,
+        "The signer needs to be the program upgrade authority",
+    )
+}
+
+pub fn assert_program_config(
+    program_id: Pubkey,
+    actual_program_config: &AccountInfo,
+    is_initialized: bool,
+) -> ProgramResult {
+    let (expected_program_config, _, _) = get_program_config(program_id);
+    let expected_program_owner = if is_initialized {
+        program_id
+    } else {
+        system_program::id()
+    };
+
+    assert_with_msg(
+        &expected_program_config == actual_program_config.key
+            && actual_program_config.owner == &expected_program_owner,
+        TokenWrapperError::InvalidProgramConfig,
+        "Invalid program config passed",
+    )
+}
+
+/// Whether `program_config` is the program config PDA, initialized or not
+///
+/// An initialized program config is recognized by its owner and account type, the PDA is only
+/// looked up while the program config is not initialized.
+pub fn is_program_config(program_id: Pubkey, program_config: &AccountInfo) -> bool {
+    if program_config.owner == &program_id {
+        return program_config
+            .try_borrow_data()
+            .is_ok_and(|data| data.first() == Some(&(AccountType::ProgramConfig as u8)));
+    }
+
+    let (expected_program_config, _, _) = get_program_config(program_id);
+
+    program_config.key == &expected_program_config
+}
+
+/// Asserts that `admin` is a signer and the admin of the program config
+pub fn assert_admin(admin: &AccountInfo, program_config: &ProgramConfig) -> ProgramResult {
+    assert_with_msg(
+        admin.is_signer,
+        TokenWrapperError::MissingSigner,
+        "The admin needs to be a signer",
+    )?;
+
+    assert_with_msg(
+        admin.key == &program_config.admin,
+        TokenWrapperError::UnexpectedAdmin,
+        "The signer needs to be the program admin",
+    )
+}
+
 pub fn assert_wrapper_token_mint(
     token_2022_mint: Pubkey,
     program_id: Pubkey,
@@ -80,6 +173,66 @@ pub fn assert_wrapper_token_mint(
     )
 }
 
+/// Asserts that `actual_wrapper_token_mint` and `actual_reserve_authority` are the wrapper token
+/// mint and the reserve authority of the wrapper of `token_2022_mint`, and returns the wrapper
+/// config stored in the reserve authority with the reserve authority bump
+///
+/// The PDAs are checked with `create_program_address` and the bumps recorded in the wrapper
+/// config, and only looked up for wrappers without recorded bumps.
+pub fn assert_wrapper_pdas(
+    token_2022_mint: Pubkey,
+    program_id: Pubkey,
+    actual_wrapper_token_mint: &AccountInfo,
+    actual_reserve_authority: &AccountInfo,
+) -> Result<(Option<WrapperConfig>, u8), ProgramError> {
+    let wrapper_config = WrapperConfig::load(actual_reserve_authority, &program_id)?;
+
+    let Some(bumps) = wrapper_config.as_ref().filter(|config| config.has_bumps) else {
+        assert_wrapper_token_mint(token_2022_mint, program_id, actual_wrapper_token_mint, true)?;
+        assert_reserve_authority(token_2022_mint, program_id, actual_reserve_authority)?;
+
+        let (_, reserve_authority_bump, _) = get_reserve_authority(token_2022_mint, program_id);
+
+        return Ok((wrapper_config, reserve_authority_bump));
+    };
+
+    assert_with_msg(
+        is_pda(
+            &[
+                b"wrapper",
+                token_2022_mint.as_ref(),
+                &[bumps.wrapper_token_mint_bump],
+            ],
+            &program_id,
+            actual_wrapper_token_mint.key,
+        ) && actual_wrapper_token_mint.owner == &spl_token::id(),
+        TokenWrapperError::UnexpectedWrapperToken,
+        "Invalid wrapper token mint passed",
+    )?;
+    assert_with_msg(
+        is_pda(
+            &[
+                b"reserve_authority",
+                token_2022_mint.as_ref(),
+                &[bumps.reserve_authority_bump],
+            ],
+            &program_id,
+            actual_reserve_authority.key,
+        ),
+        TokenWrapperError::UnexpectedReserveAuthority,
+        "Invalid reserve authority passed",
+    )?;
+
+    let reserve_authority_bump = bumps.reserve_authority_bump;
+
+    Ok((wrapper_config, reserve_authority_bump))
+}
+
+/// Whether `actual` is the PDA of `seeds`, ending with a bump recorded in the wrapper config
+fn is_pda(seeds: &[&[u8]], program_id: &Pubkey, actual: &Pubkey) -> bool {
+    Pubkey::create_program_address(seeds, program_id).is_ok_and(|address| &address == actual)
+}
+
 pub fn assert_reserve_authority(
     token_2022_mint: Pubkey,
     program_id: Pubkey,
@@ -117,46 +270,255 @@ pub fn assert_reserve_authority_token_account(
     )
 }
 
+/// Asserts that `actual_reserve_token_2022_token_account` is the current reserve of the wrapper,
+/// the token account set by `MigrateReserve` or else the original PDA token account
+pub fn assert_current_reserve_token_account(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_id: Pubkey,
+    wrapper_config: Option<&WrapperConfig>,
+    actual_reserve_token_2022_token_account: &AccountInfo,
+) -> ProgramResult {
+    if let Some(reserve_token_2022_token_account) =
+        wrapper_config.and_then(WrapperConfig::migrated_reserve)
+    {
+        return assert_with_msg(
+            &reserve_token_2022_token_account == actual_reserve_token_2022_token_account.key
+                && actual_reserve_token_2022_token_account.owner == &spl_token_2022::id(),
+            TokenWrapperError::UnexpectedReserveTokenAccount,
+            "Invalid reserve token account passed, the reserve has been migrated",
+        );
+    }
+
+    let Some(bumps) = wrapper_config.filter(|config| config.has_bumps) else {
+        return assert_reserve_authority_token_account(
+            token_2022_mint,
+            reserve_authority,
+            program_id,
+            actual_reserve_token_2022_token_account,
+            true,
+        );
+    };
+
+    assert_with_msg(
+        is_pda(
+            &[
+                b"reserve_authority_token_account",
+                token_2022_mint.as_ref(),
+                reserve_authority.as_ref(),
+                &[bumps.reserve_bump],
+            ],
+            &program_id,
+            actual_reserve_token_2022_token_account.key,
+        ) && actual_reserve_token_2022_token_account.owner == &spl_token_2022::id(),
+        TokenWrapperError::UnexpectedReserveTokenAccount,
+        "Invalid reserve authority token account passed",
+    )
+}
+
+/// Asserts that `actual_reserve_shard` is the reserve shard `reserve_shard` of the wrapper, the
+/// current reserve for shard zero or else a shard created by `InitializeReserveShard`
+pub fn assert_reserve_shard(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_id: Pubkey,
+    wrapper_config: Option<&WrapperConfig>,
+    reserve_shard: u8,
+    actual_reserve_shard: &AccountInfo,
+) -> ProgramResult {
+    if reserve_shard == 0 {
+        return assert_current_reserve_token_account(
+            token_2022_mint,
+            reserve_authority,
+            program_id,
+            wrapper_config,
+            actual_reserve_shard,
+        );
+    }
+
+    assert_with_msg(
+        wrapper_config.is_some_and(|config| reserve_shard <= config.reserve_shard_count),
+        TokenWrapperError::InvalidReserveShard,
+        "The reserve shard has not been created",
+    )?;
+
+    let (expected_reserve_shard, _, _) = get_reserve_shard(
+        token_2022_mint,
+        reserve_authority,
+        reserve_shard,
+        program_id,
+    );
+
+    assert_with_msg(
+        &expected_reserve_shard == actual_reserve_shard.key
+            && actual_reserve_shard.owner == &spl_token_2022::id(),
+        TokenWrapperError::InvalidReserveShard,
+        "Invalid reserve shard passed",
+    )
+}
+
+/// Asserts that `actual_wrapper_inventory` is the wrapper inventory of the wrapper, created by
+/// `InitializeWrapperInventory`
+pub fn assert_wrapper_inventory(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_id: Pubkey,
+    wrapper_config: Option<&WrapperConfig>,
+    actual_wrapper_inventory: &AccountInfo,
+) -> ProgramResult {
+    assert_with_msg(
+        wrapper_config.is_some_and(|config| config.has_wrapper_inventory),
+        TokenWrapperError::InvalidWrapperInventory,
+        "The wrapper inventory has not been created",
+    )?;
+
+    let (expected_wrapper_inventory, _, _) =
+        get_wrapper_inventory(token_2022_mint, reserve_authority, program_id);
+
+    assert_with_msg(
+        &expected_wrapper_inventory == actual_wrapper_inventory.key
+            && actual_wrapper_inventory.owner == &spl_token::id(),
+        TokenWrapperError::InvalidWrapperInventory,
+        "Invalid wrapper inventory passed",
+    )
+}
+
+/// Asserts that `actual_wrapper_limits` is the wrapper limits of the wrapper, created by
+/// `SetWrapperLimits`, with the bump recorded in `wrapper_config` if any
+pub fn assert_wrapper_limits(
+    token_2022_mint: Pubkey,
+    program_id: Pubkey,
+    wrapper_config: Option<&WrapperConfig>,
+    actual_wrapper_limits: &AccountInfo,
+) -> ProgramResult {
+    let is_wrapper_limits = match wrapper_config.filter(|config| config.has_bumps) {
+        Some(bumps) => is_pda(
+            &[
+                b"wrapper_limits",
+                token_2022_mint.as_ref(),
+                &[bumps.wrapper_limits_bump],
+            ],
+            &program_id,
+            actual_wrapper_limits.key,
+        ),
+        None => get_wrapper_limits(token_2022_mint, program_id).0 == *actual_wrapper_limits.key,
+    };
+
+    assert_with_msg(
+        is_wrapper_limits && actual_wrapper_limits.owner == &program_id,
+        TokenWrapperError::InvalidWrapperLimits,
+        "Invalid wrapper limits passed",
+    )
+}
+
+/// Asserts that `actual_fee_vault` is the fee vault of the wrapper, created by `SetFee`, with the
+/// bump recorded in `wrapper_config`
+pub fn assert_fee_vault(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_id: Pubkey,
+    wrapper_config: &WrapperConfig,
+    actual_fee_vault: &AccountInfo,
+) -> ProgramResult {
+    assert_with_msg(
+        wrapper_config.has_fee_vault
+            && is_pda(
+                &[
+                    b"fee_vault",
+                    token_2022_mint.as_ref(),
+                    reserve_authority.as_ref(),
+                    &[wrapper_config.fee_vault_bump],
+                ],
+                &program_id,
+                actual_fee_vault.key,
+            )
+            && actual_fee_vault.owner == &spl_token::id(),
+        TokenWrapperError::InvalidFeeVault,
+        "Invalid fee vault passed",
+    )
+}
+
+/// Asserts that the Token 2022 mint has not been closed and re-created with other decimals or
+/// extensions since the wrapper was initialized
+///
+/// The fingerprint is compared when `wrapper_config` recorded one, `token_2022_mint` must then
+/// be loaded with `MintView::load_with_fingerprint`.
+pub fn assert_source_mint_unchanged(
+    token_2022_mint: &MintView,
+    wrapper_token_mint: &MintView,
+    wrapper_config: Option<&WrapperConfig>,
+) -> ProgramResult {
+    assert_with_msg(
+        token_2022_mint.decimals == wrapper_token_mint.decimals,
+        TokenWrapperError::SourceMintChanged,
+        "Token 2022 mint decimals differ from the wrapper token mint decimals",
+    )?;
+
+    if let Some(wrapper_config) = wrapper_config {
+        if let Some(recorded_fingerprint) = wrapper_config.recorded_fingerprint() {
+            assert_with_msg(
+                token_2022_mint.decimals == wrapper_config.token_2022_decimals
+                    && token_2022_mint.fingerprint == Some(recorded_fingerprint),
+                TokenWrapperError::SourceMintChanged,
+                "Token 2022 mint extensions differ from the recorded ones",
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn validate_token_account(
     token_account_info: &AccountInfo,
     expected_owner: &Pubkey,
     expected_mint: &Pubkey,
     is_token_2022: bool,
 ) -> ProgramResult {
-    let token_account_data = token_account_info.try_borrow_data()?;
-    let token_account = spl_token_2022::extension::StateWithExtensions::<
-        spl_token_2022::state::Account,
-    >::unpack(&token_account_data)?;
-    let expected_program_owner = if is_token_2022 {
-        spl_token_2022::id()
-    } else {
-        spl_token::id()
-    };
-
-    assert_with_msg(
-        &token_account.base.owner == expected_owner
-            && token_account_info.owner == &expected_program_owner
-            && &token_account.base.mint == expected_mint,
-        TokenWrapperError::InvalidTokenAccount,
-        "Incorrect token account",
+    TokenAccountView::load(
+        token_account_info,
+        expected_owner,
+        expected_mint,
+        is_token_2022,
     )
+    .map(|_| ())
 }
 
 pub fn validate_mint(token_mint_info: &AccountInfo, is_token_2022: bool) -> ProgramResult {
-    let token_mint_data = token_mint_info.try_borrow_data()?;
-    let token_mint =
-        spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(
-            &token_mint_data,
-        )?;
-    let expected_program_owner = if is_token_2022 {
-        spl_token_2022::id()
-    } else {
-        spl_token::id()
+    MintView::load(token_mint_info, is_token_2022).map(|_| ())
+}
+
+pub fn validate_token_account_mint(
+    token_account_info: &AccountInfo,
+    expected_mint: &Pubkey,
+    is_token_2022: bool,
+) -> ProgramResult {
+    TokenAccountView::load_of_mint(token_account_info, expected_mint, is_token_2022).map(|_| ())
+}
+
+/// Whether `wrapper_group_member` is the registration of the wrapper of `token_2022_mint` in
+/// the wrapper group of `group`
+///
+/// Meant for programs checking that a wrapper belongs to a token group, given the wrapper group
+/// member account of its Token 2022 mint.
+pub fn is_wrapper_group_member(
+    program_id: &Pubkey,
+    group: &Pubkey,
+    token_2022_mint: &Pubkey,
+    wrapper_group_member: &AccountInfo,
+) -> bool {
+    let (expected_wrapper_group_member, _, _) =
+        get_wrapper_group_member(*group, *token_2022_mint, *program_id);
+
+    if wrapper_group_member.key != &expected_wrapper_group_member
+        || wrapper_group_member.owner != program_id
+    {
+        return false;
+    }
+
+    let Ok(data) = wrapper_group_member.try_borrow_data() else {
+        return false;
     };
 
-    assert_with_msg(
-        token_mint.base.is_initialized && token_mint_info.owner == &expected_program_owner,
-        TokenWrapperError::InvalidTokenMint,
-        "Incorrect token mint",
-    )
+    WrapperGroupMember::unpack(&data)
+        .is_ok_and(|member| &member.group == group && &member.token_2022_mint == token_2022_mint)
 }
@@ -1,7 +1,11 @@
 pub mod assertion;
 pub mod pda;
 pub mod system_utils;
+pub mod token_utils;
+pub mod views;
 
 pub use assertion::*;
 pub use pda::*;
 pub use system_utils::*;
+pub use token_utils::*;
+pub use views::*;
@@ -57,3 +57,166 @@ pub fn get_reserve_authority_token_account(
 
     (addr, bump, seeds)
 }
+
+/// Reserve shard `reserve_shard` of the wrapper, a Token 2022 token account owned by the reserve
+/// authority. Shards are numbered from one, shard zero is the reserve
+pub fn get_reserve_shard(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    reserve_shard: u8,
+    program_id: Pubkey,
+) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) = Pubkey::find_program_address(
+        &[
+            b"reserve_shard",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+            &[reserve_shard],
+        ],
+        &program_id,
+    );
+
+    let seeds = vec![
+        b"reserve_shard".to_vec(),
+        token_2022_mint.as_ref().to_vec(),
+        reserve_authority.as_ref().to_vec(),
+        vec![reserve_shard],
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+/// Wrapper inventory of the wrapper, a wrapper token account owned by the reserve authority
+/// holding pre-minted wrapper tokens
+pub fn get_wrapper_inventory(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_id: Pubkey,
+) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) = Pubkey::find_program_address(
+        &[
+            b"wrapper_inventory",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+        ],
+        &program_id,
+    );
+
+    let seeds = vec![
+        b"wrapper_inventory".to_vec(),
+        token_2022_mint.as_ref().to_vec(),
+        reserve_authority.as_ref().to_vec(),
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+/// Fee vault of the wrapper, a wrapper token account owned by the reserve authority collecting
+/// the wrap and unwrap fees
+pub fn get_fee_vault(
+    token_2022_mint: Pubkey,
+    reserve_authority: Pubkey,
+    program_id: Pubkey,
+) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) = Pubkey::find_program_address(
+        &[
+            b"fee_vault",
+            token_2022_mint.as_ref(),
+            reserve_authority.as_ref(),
+        ],
+        &program_id,
+    );
+
+    let seeds = vec![
+        b"fee_vault".to_vec(),
+        token_2022_mint.as_ref().to_vec(),
+        reserve_authority.as_ref().to_vec(),
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+/// Wrapper limits of the wrapper, holding its supply cap and per-epoch volume limits
+pub fn get_wrapper_limits(
+    token_2022_mint: Pubkey,
+    program_id: Pubkey,
+) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) =
+        Pubkey::find_program_address(&[b"wrapper_limits", token_2022_mint.as_ref()], &program_id);
+
+    let seeds = vec![
+        b"wrapper_limits".to_vec(),
+        token_2022_mint.as_ref().to_vec(),
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+/// Issuer freeze of a wrapper token account, recording its freeze by the freeze authority of the
+/// Token 2022 mint
+pub fn get_issuer_freeze(
+    wrapper_token_account: Pubkey,
+    program_id: Pubkey,
+) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) = Pubkey::find_program_address(
+        &[b"issuer_freeze", wrapper_token_account.as_ref()],
+        &program_id,
+    );
+
+    let seeds = vec![
+        b"issuer_freeze".to_vec(),
+        wrapper_token_account.as_ref().to_vec(),
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+pub fn get_wrapper_group(group: Pubkey, program_id: Pubkey) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) =
+        Pubkey::find_program_address(&[b"wrapper_group", group.as_ref()], &program_id);
+
+    let seeds = vec![
+        b"wrapper_group".to_vec(),
+        group.as_ref().to_vec(),
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+pub fn get_wrapper_group_member(
+    group: Pubkey,
+    token_2022_mint: Pubkey,
+    program_id: Pubkey,
+) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) = Pubkey::find_program_address(
+        &[
+            b"wrapper_group_member",
+            group.as_ref(),
+            token_2022_mint.as_ref(),
+        ],
+        &program_id,
+    );
+
+    let seeds = vec![
+        b"wrapper_group_member".to_vec(),
+        group.as_ref().to_vec(),
+        token_2022_mint.as_ref().to_vec(),
+        vec![bump],
+    ];
+
+    (addr, bump, seeds)
+}
+
+pub fn get_program_config(program_id: Pubkey) -> (Pubkey, u8, Vec<Vec<u8>>) {
+    let (addr, bump) = Pubkey::find_program_address(&[b"program_config"], &program_id);
+
+    let seeds = vec![b"program_config".to_vec(), vec![bump]];
+
+    (addr, bump, seeds)
+}
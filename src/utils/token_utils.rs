@@ -0,0 +1,131 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, hash::hashv, program::invoke,
+    program_error::ProgramError,
+};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::{Account, Mint},
+};
+
+/// Upper bound of the extensions of a mint, each extension type appearing at most once
+const MAX_EXTENSION_TYPES: usize = 64;
+
+/// Extensions the mint authorities can initialize after the mint, left out of the fingerprint
+const POST_INITIALIZATION_EXTENSION_TYPES: [ExtensionType; 3] = [
+    ExtensionType::TokenMetadata,
+    ExtensionType::TokenGroup,
+    ExtensionType::TokenGroupMember,
+];
+
+/// Extensions of a reserve token account for `token_2022_mint`, the account extensions required
+/// by the mint extensions, such as `TransferFeeAmount` or `TransferHookAccount`, and
+/// `ImmutableOwner`
+pub fn get_reserve_account_extensions(
+    token_2022_mint: &AccountInfo,
+) -> Result<Vec<ExtensionType>, ProgramError> {
+    let token_2022_mint_data = token_2022_mint.try_borrow_data()?;
+    let mint_extensions =
+        StateWithExtensions::<Mint>::unpack(&token_2022_mint_data)?.get_extension_types()?;
+
+    let mut reserve_account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint_extensions);
+
+    if !reserve_account_extensions.contains(&ExtensionType::ImmutableOwner) {
+        reserve_account_extensions.push(ExtensionType::ImmutableOwner);
+    }
+
+    Ok(reserve_account_extensions)
+}
+
+/// Size of a reserve token account holding `reserve_account_extensions`
+pub fn get_reserve_account_len(
+    reserve_account_extensions: &[ExtensionType],
+) -> Result<usize, ProgramError> {
+    ExtensionType::try_calculate_account_len::<Account>(reserve_account_extensions)
+}
+
+/// Hash of the extension types of `token_2022_mint`, which changes if the mint is closed and
+/// re-created at the same address with a different set of extensions
+pub fn get_mint_fingerprint(token_2022_mint: &AccountInfo) -> Result<[u8; 32], ProgramError> {
+    let token_2022_mint_data = token_2022_mint.try_borrow_data()?;
+
+    get_extension_types_fingerprint(
+        StateWithExtensions::<Mint>::unpack(&token_2022_mint_data)?.get_tlv_data(),
+    )
+}
+
+/// Hash of the extension types written in `tlv_data`, the TLV data of a mint, independent of
+/// their order in the mint
+///
+/// Walks the TLV entries as `get_extension_types` does, collecting the types on the stack.
+/// Metadata and group extensions are skipped, since they are initialized after the mint.
+pub fn get_extension_types_fingerprint(tlv_data: &[u8]) -> Result<[u8; 32], ProgramError> {
+    let mut mint_extensions = [0u16; MAX_EXTENSION_TYPES];
+    let mut extension_count = 0;
+    let mut start = 0;
+
+    // The last bytes may be too short for a type, they are left for a realloc
+    while start + 2 <= tlv_data.len() {
+        let extension_type = ExtensionType::try_from(&tlv_data[start..start + 2])?;
+        if extension_type == ExtensionType::Uninitialized {
+            break;
+        }
+
+        let length = tlv_data
+            .get(start + 2..start + 4)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let value_end = start + 4 + u16::from_le_bytes([length[0], length[1]]) as usize;
+        if value_end > tlv_data.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !POST_INITIALIZATION_EXTENSION_TYPES.contains(&extension_type) {
+            *mint_extensions
+                .get_mut(extension_count)
+                .ok_or(ProgramError::InvalidAccountData)? = u16::from(extension_type);
+            extension_count += 1;
+        }
+        start = value_end;
+    }
+
+    let mint_extensions = &mut mint_extensions[..extension_count];
+    mint_extensions.sort_unstable();
+
+    let mut mint_extensions_bytes = [0u8; MAX_EXTENSION_TYPES * 2];
+    for (bytes, extension) in mint_extensions_bytes
+        .chunks_exact_mut(2)
+        .zip(mint_extensions.iter())
+    {
+        bytes.copy_from_slice(&extension.to_le_bytes());
+    }
+
+    Ok(hashv(&[
+        b"token_2022_mint_fingerprint",
+        &mint_extensions_bytes[..extension_count * 2],
+    ])
+    .to_bytes())
+}
+
+/// Syncs the amount of a reserve holding Token 2022 native SOL with its lamports, so SOL sent
+/// directly to the reserve is counted as surplus instead of being left out of the balance. No-op
+/// for any other mint
+pub fn sync_native_reserve<'a>(
+    token_2022_mint: &AccountInfo<'a>,
+    reserve_token_2022_token_account: &AccountInfo<'a>,
+    token_2022_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    if token_2022_mint.key != &spl_token_2022::native_mint::id() {
+        return Ok(());
+    }
+
+    invoke(
+        &spl_token_2022::instruction::sync_native(
+            &spl_token_2022::id(),
+            reserve_token_2022_token_account.key,
+        )?,
+        &[
+            reserve_token_2022_token_account.clone(),
+            token_2022_program.clone(),
+        ],
+    )
+}
@@ -0,0 +1,187 @@
+//! Token accounts and mints parsed once into validated views, passed through the instruction
+//! handlers instead of borrowing and unpacking the account data again for each check
+//!
+//! The views read the base state in place with the Pod layouts of Token 2022, which also match
+//! the Token program accounts, and copy out only the fields the handlers use.
+
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, PodStateWithExtensions},
+    pod::{PodAccount, PodMint},
+};
+
+use crate::{error::TokenWrapperError, state::WrapperConfig};
+
+use super::{assert_source_mint_unchanged, assert_with_msg, get_extension_types_fingerprint};
+
+fn token_program_id(is_token_2022: bool) -> Pubkey {
+    if is_token_2022 {
+        spl_token_2022::id()
+    } else {
+        spl_token::id()
+    }
+}
+
+/// Initialized mint owned by the Token or Token 2022 program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintView {
+    pub decimals: u8,
+    pub supply: u64,
+    pub freeze_authority: Option<Pubkey>,
+    /// Hash of the extension types of the mint, only computed by `load_with_fingerprint`
+    pub fingerprint: Option<[u8; 32]>,
+}
+
+impl MintView {
+    /// Parses `mint` as an initialized mint owned by Token 2022 if `is_token_2022`, or else by
+    /// the Token program
+    pub fn load(mint: &AccountInfo, is_token_2022: bool) -> Result<Self, ProgramError> {
+        Self::parse(mint, is_token_2022, false)
+    }
+
+    /// Parses `mint` as `load` does, and computes the fingerprint of its extension types from
+    /// the same borrow of its data
+    pub fn load_with_fingerprint(
+        mint: &AccountInfo,
+        is_token_2022: bool,
+    ) -> Result<Self, ProgramError> {
+        Self::parse(mint, is_token_2022, true)
+    }
+
+    fn parse(
+        mint: &AccountInfo,
+        is_token_2022: bool,
+        with_fingerprint: bool,
+    ) -> Result<Self, ProgramError> {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+
+        assert_with_msg(
+            bool::from(mint_state.base.is_initialized)
+                && mint.owner == &token_program_id(is_token_2022),
+            TokenWrapperError::InvalidTokenMint,
+            "Incorrect token mint",
+        )?;
+
+        let fingerprint = if with_fingerprint {
+            Some(get_extension_types_fingerprint(mint_state.get_tlv_data())?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            decimals: mint_state.base.decimals,
+            supply: mint_state.base.supply.into(),
+            freeze_authority: mint_state.base.freeze_authority.ok_or(()).ok(),
+            fingerprint,
+        })
+    }
+}
+
+/// Token account of an expected mint, held by the Token or Token 2022 program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenAccountView {
+    pub owner: Pubkey,
+    /// Balance when the account was parsed
+    pub amount: u64,
+    pub is_frozen: bool,
+}
+
+impl TokenAccountView {
+    /// Parses `token_account` as a token account of `expected_mint` owned by `expected_owner`,
+    /// held by Token 2022 if `is_token_2022`, or else by the Token program
+    pub fn load(
+        token_account: &AccountInfo,
+        expected_owner: &Pubkey,
+        expected_mint: &Pubkey,
+        is_token_2022: bool,
+    ) -> Result<Self, ProgramError> {
+        let token_account_view = Self::load_of_mint(token_account, expected_mint, is_token_2022)?;
+
+        assert_with_msg(
+            &token_account_view.owner == expected_owner,
+            TokenWrapperError::InvalidTokenAccount,
+            "Incorrect token account",
+        )?;
+
+        Ok(token_account_view)
+    }
+
+    /// Parses `token_account` as a token account of `expected_mint` with any owner, held by
+    /// Token 2022 if `is_token_2022`, or else by the Token program
+    pub fn load_of_mint(
+        token_account: &AccountInfo,
+        expected_mint: &Pubkey,
+        is_token_2022: bool,
+    ) -> Result<Self, ProgramError> {
+        let token_account_data = token_account.try_borrow_data()?;
+        let token_account_state =
+            PodStateWithExtensions::<PodAccount>::unpack(&token_account_data)?;
+
+        assert_with_msg(
+            token_account.owner == &token_program_id(is_token_2022)
+                && &token_account_state.base.mint == expected_mint,
+            TokenWrapperError::InvalidTokenAccount,
+            "Incorrect token account",
+        )?;
+
+        Ok(Self {
+            owner: token_account_state.base.owner,
+            amount: token_account_state.base.amount.into(),
+            is_frozen: token_account_state.base.is_frozen(),
+        })
+    }
+
+    /// Balance of a token account already validated by `load`, read again after a CPI changed
+    /// it
+    pub fn reload_amount(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+        let token_account_data = token_account.try_borrow_data()?;
+
+        Ok(
+            PodStateWithExtensions::<PodAccount>::unpack(&token_account_data)?
+                .base
+                .amount
+                .into(),
+        )
+    }
+}
+
+/// Token 2022 mint and wrapper token mint of a wrapper, checked to be unchanged since the
+/// wrapper was initialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrapperMintsView {
+    pub token_2022_mint: MintView,
+    pub wrapper_token_mint: MintView,
+}
+
+impl WrapperMintsView {
+    /// Parses both mints once, the fingerprint of the Token 2022 mint only being computed when
+    /// `wrapper_config` recorded one
+    pub fn load(
+        token_2022_mint: &AccountInfo,
+        wrapper_token_mint: &AccountInfo,
+        wrapper_config: Option<&WrapperConfig>,
+    ) -> Result<Self, ProgramError> {
+        let token_2022_mint = if wrapper_config
+            .and_then(WrapperConfig::recorded_fingerprint)
+            .is_some()
+        {
+            MintView::load_with_fingerprint(token_2022_mint, true)?
+        } else {
+            MintView::load(token_2022_mint, true)?
+        };
+        let wrapper_token_mint = MintView::load(wrapper_token_mint, false)?;
+
+        assert_source_mint_unchanged(&token_2022_mint, &wrapper_token_mint, wrapper_config)?;
+
+        Ok(Self {
+            token_2022_mint,
+            wrapper_token_mint,
+        })
+    }
+
+    /// Decimals shared by both mints
+    pub fn decimals(&self) -> u8 {
+        self.token_2022_mint.decimals
+    }
+}
@@ -0,0 +1,514 @@
+use solana_program::{
+    clock::Clock, program_option::COption, program_pack::Pack, pubkey::Pubkey, sysvar,
+};
+use solana_sdk::account::Account;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeConfig},
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    },
+    state::Mint,
+};
+use token2022_wrapper::{
+    amm::{
+        AccountMap, Amm, AmmError, AmmQuote, KeyedAccount, QuoteParams, SwapMode, SwapParams,
+        WrapperAmm,
+    },
+    error::TokenWrapperError,
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args,
+        WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction,
+    },
+    quote::{quote_unwrap, quote_wrap},
+    state::{AccountType, ProgramConfig, WrapperConfig},
+    utils::{
+        get_program_config, get_reserve_authority, get_reserve_authority_token_account,
+        get_wrapper_token_mint,
+    },
+};
+
+const EPOCH: u64 = 10;
+
+fn account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner,
+        ..Default::default()
+    }
+}
+
+/// Token 2022 mint with a transfer fee of 1% up to 5_000 before `EPOCH`, and of 2% up to 50
+/// from `EPOCH`
+fn token_2022_mint_account() -> Account {
+    let mut data =
+        vec![
+            0;
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap()
+        ];
+
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    *state.init_extension::<TransferFeeConfig>(true).unwrap() = TransferFeeConfig {
+        older_transfer_fee: TransferFee {
+            epoch: 0.into(),
+            maximum_fee: 5_000.into(),
+            transfer_fee_basis_points: 100.into(),
+        },
+        newer_transfer_fee: TransferFee {
+            epoch: EPOCH.into(),
+            maximum_fee: 50.into(),
+            transfer_fee_basis_points: 200.into(),
+        },
+        ..Default::default()
+    };
+    state.base = Mint {
+        mint_authority: COption::Some(Pubkey::new_unique()),
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    account(spl_token_2022::id(), data)
+}
+
+fn wrapper_token_mint_account(reserve_authority: Pubkey) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::Some(reserve_authority),
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    account(spl_token::id(), data)
+}
+
+fn wrapper_config_account(wrapper_config: &WrapperConfig) -> Account {
+    let mut data = vec![0; WrapperConfig::LEN];
+    wrapper_config.pack(&mut data).unwrap();
+
+    account(token2022_wrapper::id(), data)
+}
+
+fn program_config_account(paused: bool) -> Account {
+    let mut data = vec![0; ProgramConfig::LEN];
+    ProgramConfig {
+        account_type: AccountType::ProgramConfig,
+        paused,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+
+    account(token2022_wrapper::id(), data)
+}
+
+fn clock_account(epoch: u64) -> Account {
+    account(
+        sysvar::id(),
+        bincode::serialize(&Clock {
+            epoch,
+            ..Default::default()
+        })
+        .unwrap(),
+    )
+}
+
+/// Snapshots of an initialized wrapper charging 0.3% on wraps and 0.45% on unwraps, up to 40
+struct Snapshots {
+    token_2022_mint: Pubkey,
+    wrapper_token_mint: Pubkey,
+    reserve_authority: Pubkey,
+    wrapper_config: WrapperConfig,
+    account_map: AccountMap,
+}
+
+impl Snapshots {
+    fn new() -> Self {
+        let token_2022_mint = Pubkey::new_unique();
+        let (wrapper_token_mint, _, _) =
+            get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id());
+        let (reserve_authority, _, _) =
+            get_reserve_authority(token_2022_mint, token2022_wrapper::id());
+        let (program_config, _, _) = get_program_config(token2022_wrapper::id());
+
+        let wrapper_config = WrapperConfig {
+            account_type: AccountType::WrapperConfig,
+            token_2022_mint,
+            fee_recipient: Pubkey::new_unique(),
+            wrap_fee_basis_points: 30,
+            unwrap_fee_basis_points: 45,
+            maximum_fee: 40,
+            has_fee_vault: true,
+            ..Default::default()
+        };
+
+        let account_map = AccountMap::from([
+            (token_2022_mint, token_2022_mint_account()),
+            (
+                wrapper_token_mint,
+                wrapper_token_mint_account(reserve_authority),
+            ),
+            (reserve_authority, wrapper_config_account(&wrapper_config)),
+            (program_config, program_config_account(false)),
+            (sysvar::clock::id(), clock_account(EPOCH)),
+        ]);
+
+        Self {
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            wrapper_config,
+            account_map,
+        }
+    }
+
+    fn amm(&self) -> WrapperAmm {
+        let mut amm = WrapperAmm::from_keyed_account(&KeyedAccount {
+            key: self.token_2022_mint,
+            account: self.account_map[&self.token_2022_mint].clone(),
+        })
+        .unwrap();
+        amm.update(&self.account_map).unwrap();
+        amm
+    }
+
+    fn set_wrapper_config(&mut self, wrapper_config: WrapperConfig) {
+        self.account_map.insert(
+            self.reserve_authority,
+            wrapper_config_account(&wrapper_config),
+        );
+        self.wrapper_config = wrapper_config;
+    }
+
+    fn wrap_params(&self, amount: u64) -> QuoteParams {
+        QuoteParams {
+            amount,
+            input_mint: self.token_2022_mint,
+            output_mint: self.wrapper_token_mint,
+            swap_mode: SwapMode::ExactIn,
+        }
+    }
+
+    fn unwrap_params(&self, amount: u64) -> QuoteParams {
+        QuoteParams {
+            amount,
+            input_mint: self.wrapper_token_mint,
+            output_mint: self.token_2022_mint,
+            swap_mode: SwapMode::ExactIn,
+        }
+    }
+}
+
+/// Test 1 - the AMM of a wrapper swaps between its mints and quotes wraps and unwraps, with their
+/// fees in the input mint, from the account snapshots, at the epoch of the clock snapshot
+///
+///
+#[test]
+fn test_1() {
+    let mut snapshots = Snapshots::new();
+    let amm = snapshots.amm();
+
+    assert_eq!(amm.key(), snapshots.token_2022_mint);
+    assert_eq!(amm.program_id(), token2022_wrapper::id());
+    assert_eq!(
+        amm.get_reserve_mints(),
+        vec![snapshots.token_2022_mint, snapshots.wrapper_token_mint]
+    );
+    for pubkey in amm.get_accounts_to_update() {
+        assert!(snapshots.account_map.contains_key(&pubkey));
+    }
+
+    let token_2022_mint_data = &snapshots.account_map[&snapshots.token_2022_mint].data;
+    for amount in [1, 99, 1_000, 12_345, 1_000_000] {
+        let quote = quote_wrap(
+            token_2022_mint_data,
+            Some(&snapshots.wrapper_config),
+            EPOCH,
+            amount,
+        )
+        .unwrap();
+        assert_eq!(
+            amm.quote(&snapshots.wrap_params(amount)).unwrap(),
+            AmmQuote {
+                in_amount: amount,
+                out_amount: quote.amount_out,
+                fee_amount: quote.transfer_fee + quote.wrapper_fee,
+                fee_mint: snapshots.token_2022_mint,
+            }
+        );
+        assert_eq!(
+            quote.transfer_fee + quote.wrapper_fee,
+            amount - quote.amount_out
+        );
+
+        let quote = quote_unwrap(
+            token_2022_mint_data,
+            Some(&snapshots.wrapper_config),
+            EPOCH,
+            amount,
+        )
+        .unwrap();
+        assert_eq!(
+            amm.quote(&snapshots.unwrap_params(amount)).unwrap(),
+            AmmQuote {
+                in_amount: amount,
+                out_amount: quote.amount_out,
+                fee_amount: quote.transfer_fee + quote.wrapper_fee,
+                fee_mint: snapshots.wrapper_token_mint,
+            }
+        );
+        assert_eq!(
+            quote.transfer_fee + quote.wrapper_fee,
+            amount - quote.amount_out
+        );
+    }
+
+    // 1_000_000 in, 50 transfer fee from EPOCH, then 40 wrap fee
+    assert_eq!(
+        amm.quote(&snapshots.wrap_params(1_000_000))
+            .unwrap()
+            .out_amount,
+        999_910
+    );
+
+    // Before EPOCH, the older transfer fee applies
+    snapshots
+        .account_map
+        .insert(sysvar::clock::id(), clock_account(EPOCH - 1));
+    let amm = snapshots.amm();
+    assert_eq!(
+        amm.quote(&snapshots.wrap_params(1_000_000))
+            .unwrap()
+            .out_amount,
+        1_000_000 - 5_000 - 40
+    );
+
+    // Without a wrapper config there are no wrapper fees
+    snapshots.account_map.remove(&snapshots.reserve_authority);
+    assert_eq!(
+        snapshots
+            .amm()
+            .quote(&snapshots.unwrap_params(1_000_000))
+            .unwrap()
+            .fee_amount,
+        5_000
+    );
+
+    // The Token 2022 mint and the clock are required
+    let mut amm = snapshots.amm();
+    snapshots.account_map.remove(&sysvar::clock::id());
+    assert_eq!(
+        amm.update(&snapshots.account_map),
+        Err(AmmError::AccountNotFound(sysvar::clock::id()))
+    );
+    assert!(WrapperAmm::from_keyed_account(&KeyedAccount {
+        key: snapshots.token_2022_mint,
+        account: wrapper_token_mint_account(snapshots.reserve_authority),
+    })
+    .is_err());
+}
+
+/// Test 2 - quotes fail as the wrap or unwrap would, and for other mints or exact out swaps
+///
+///
+#[test]
+fn test_2() {
+    let mut snapshots = Snapshots::new();
+    let amm = snapshots.amm();
+
+    let other_mint = Pubkey::new_unique();
+    assert_eq!(
+        amm.quote(&QuoteParams {
+            output_mint: other_mint,
+            ..snapshots.wrap_params(1_000)
+        }),
+        Err(AmmError::UnsupportedMints {
+            input_mint: snapshots.token_2022_mint,
+            output_mint: other_mint,
+        })
+    );
+    assert!(matches!(
+        amm.quote(&QuoteParams {
+            output_mint: snapshots.token_2022_mint,
+            ..snapshots.wrap_params(1_000)
+        }),
+        Err(AmmError::UnsupportedMints { .. })
+    ));
+    assert_eq!(
+        amm.quote(&QuoteParams {
+            swap_mode: SwapMode::ExactOut,
+            ..snapshots.wrap_params(1_000)
+        }),
+        Err(AmmError::ExactOutNotSupported)
+    );
+
+    // Paused wrapper, wraps fail and unwraps are still quoted
+    snapshots.set_wrapper_config(WrapperConfig {
+        paused: true,
+        ..snapshots.wrapper_config.clone()
+    });
+    let amm = snapshots.amm();
+    assert!(amm.deposits_disabled());
+    assert_eq!(
+        amm.quote(&snapshots.wrap_params(1_000)),
+        Err(TokenWrapperError::DepositsPaused.into())
+    );
+    assert!(amm.quote(&snapshots.unwrap_params(1_000)).is_ok());
+
+    snapshots.set_wrapper_config(WrapperConfig {
+        paused: false,
+        retired: true,
+        ..snapshots.wrapper_config.clone()
+    });
+    assert_eq!(
+        snapshots.amm().quote(&snapshots.wrap_params(1_000)),
+        Err(TokenWrapperError::WrapperRetired.into())
+    );
+
+    // Global pause
+    snapshots.set_wrapper_config(WrapperConfig {
+        retired: false,
+        ..snapshots.wrapper_config.clone()
+    });
+    let (program_config, _, _) = get_program_config(token2022_wrapper::id());
+    snapshots
+        .account_map
+        .insert(program_config, program_config_account(true));
+    assert_eq!(
+        snapshots.amm().quote(&snapshots.wrap_params(1_000)),
+        Err(TokenWrapperError::DepositsPaused.into())
+    );
+
+    // Uninitialized wrapper
+    snapshots.account_map.remove(&snapshots.wrapper_token_mint);
+    assert_eq!(
+        snapshots.amm().quote(&snapshots.unwrap_params(1_000)),
+        Err(TokenWrapperError::UnexpectedWrapperToken.into())
+    );
+}
+
+/// Test 3 - swaps are the v3 deposit and withdrawal of the program builders, to the migrated
+/// reserve once the reserve is migrated
+///
+///
+#[test]
+fn test_3() {
+    let mut snapshots = Snapshots::new();
+    let amm = snapshots.amm();
+
+    let user = Pubkey::new_unique();
+    let user_token_2022_token_account = Pubkey::new_unique();
+    let user_wrapper_token_account = Pubkey::new_unique();
+
+    let wrap_params = SwapParams {
+        source_mint: snapshots.token_2022_mint,
+        destination_mint: snapshots.wrapper_token_mint,
+        source_token_account: user_token_2022_token_account,
+        destination_token_account: user_wrapper_token_account,
+        token_transfer_authority: user,
+        in_amount: 1_000,
+        out_amount: 950,
+    };
+    let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+        &user,
+        &snapshots.token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensV3Args {
+            reserve_shard: 0,
+            args: DepositAndMintWrapperTokensArgs {
+                amount: 1_000,
+                min_out: 950,
+                ..Default::default()
+            },
+        },
+    );
+    let swap = amm.get_swap_and_account_metas(&wrap_params).unwrap();
+    assert_eq!(swap.data, deposit_ix.data);
+    assert_eq!(swap.account_metas, deposit_ix.accounts);
+
+    let unwrap_params = SwapParams {
+        source_mint: snapshots.wrapper_token_mint,
+        destination_mint: snapshots.token_2022_mint,
+        source_token_account: user_wrapper_token_account,
+        destination_token_account: user_token_2022_token_account,
+        ..wrap_params
+    };
+    let withdraw_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+        &user,
+        &snapshots.token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        WithdrawAndBurnWrapperTokensV3Args {
+            reserve_shard: 0,
+            args: WithdrawAndBurnWrapperTokensArgs {
+                amount: 1_000,
+                min_out: 950,
+                ..Default::default()
+            },
+        },
+    );
+    let swap = amm.get_swap_and_account_metas(&unwrap_params).unwrap();
+    assert_eq!(swap.data, withdraw_ix.data);
+    assert_eq!(swap.account_metas, withdraw_ix.accounts);
+
+    assert!(matches!(
+        amm.get_swap_and_account_metas(&SwapParams {
+            destination_mint: Pubkey::new_unique(),
+            ..wrap_params
+        }),
+        Err(AmmError::UnsupportedMints { .. })
+    ));
+
+    // Migrated reserve
+    let (reserve_token_2022_token_account, _, _) = get_reserve_authority_token_account(
+        snapshots.token_2022_mint,
+        snapshots.reserve_authority,
+        token2022_wrapper::id(),
+    );
+    let migrated_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+        &snapshots.reserve_authority,
+        &snapshots.token_2022_mint,
+        &spl_token_2022::id(),
+    );
+    snapshots.set_wrapper_config(WrapperConfig {
+        reserve_token_2022_token_account: migrated_reserve_token_2022_token_account,
+        ..snapshots.wrapper_config.clone()
+    });
+    let amm = snapshots.amm();
+
+    for params in [wrap_params, unwrap_params] {
+        let reserves = amm
+            .get_swap_and_account_metas(&params)
+            .unwrap()
+            .account_metas
+            .iter()
+            .map(|account_meta| account_meta.pubkey)
+            .filter(|pubkey| {
+                [
+                    reserve_token_2022_token_account,
+                    migrated_reserve_token_2022_token_account,
+                ]
+                .contains(pubkey)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(reserves, vec![migrated_reserve_token_2022_token_account]);
+    }
+
+    // Boxed clones quote the same
+    let boxed = amm.clone_amm();
+    assert_eq!(
+        boxed.quote(&snapshots.unwrap_params(5_000)),
+        amm.quote(&snapshots.unwrap_params(5_000))
+    );
+}
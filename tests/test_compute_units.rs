@@ -0,0 +1,661 @@
+pub mod utils;
+
+use solana_program::instruction::Instruction;
+use solana_program_test::find_file;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{keypair_from_seed, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+use token2022_wrapper::{
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, QuoteArgs,
+        RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_accept_admin_instruction, create_collect_fees_instruction,
+        create_deposit_and_mint_wrapper_tokens_instruction,
+        create_deposit_and_mint_wrapper_tokens_v2_instruction,
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_deposit_from_wrapper_inventory_instruction,
+        create_initialize_program_config_instruction, create_initialize_reserve_shard_instruction,
+        create_initialize_wrapper_inventory_instruction,
+        create_initialize_wrapper_token_instruction,
+        create_initialize_wrapper_token_v2_instruction, create_migrate_reserve_instruction,
+        create_mirror_freeze_instruction, create_mirror_thaw_instruction,
+        create_quote_unwrap_instruction, create_quote_wrap_instruction,
+        create_realloc_reserve_instruction, create_rebalance_reserve_shards_instruction,
+        create_retire_wrapper_instruction, create_set_fee_instruction,
+        create_set_global_pause_instruction, create_set_pause_instruction,
+        create_set_pending_admin_instruction, create_set_wrapper_limits_instruction,
+        create_top_up_wrapper_inventory_instruction,
+        create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction,
+        create_withdraw_to_wrapper_inventory_instruction,
+    },
+    utils::{get_reserve_authority, get_reserve_shard, get_wrapper_token_mint},
+};
+use utils::{
+    airdrop, create_associated_token_account, create_token_2022_mint, mint_token_2022_tokens,
+    sign_send_instructions, test_transfer_fee_config_with_keypairs, TestClient,
+};
+
+/// Compute units each instruction of the SBF build of the program may consume in the scenario of
+/// test 1
+///
+/// An instruction going over its budget fails the test, the budget is only raised once the
+/// increase is understood.
+const COMPUTE_UNIT_BUDGETS: &[(&str, u64)] = &[
+    ("InitializeProgramConfig", 268),
+    ("InitializeWrapperTokenV2", 10907),
+    ("InitializeWrapperToken", 9859),
+    ("SetFee", 4509),
+    ("DepositAndMintWrapperTokens", 18705),
+    ("DepositAndMintWrapperTokensV2", 18705),
+    ("WithdrawAndBurnWrapperTokens", 20569),
+    ("WithdrawAndBurnWrapperTokensV2", 20569),
+    ("CollectFees", 6285),
+    ("InitializeReserveShard", 7662),
+    ("DepositAndMintWrapperTokensV3", 18705),
+    ("WithdrawAndBurnWrapperTokensV3", 20569),
+    ("RebalanceReserveShards", 9447),
+    ("InitializeWrapperInventory", 4509),
+    ("TopUpWrapperInventory", 4630),
+    ("DepositFromWrapperInventory", 22015),
+    ("WithdrawToWrapperInventory", 22015),
+    ("SetWrapperLimits", 408),
+    ("QuoteWrap", 141),
+    ("QuoteUnwrap", 141),
+    ("SetPause", 1),
+    ("SetGlobalPause", 1),
+    ("MirrorFreeze", 4349),
+    ("MirrorThaw", 4351),
+    ("ReallocReserve", 1),
+    ("MigrateReserve", 26299),
+    ("RetireWrapper", 1),
+    ("SetPendingAdmin", 1),
+    ("AcceptAdmin", 1),
+];
+
+/// Sends `instruction`, named `name`, alone in a transaction and returns the compute units it consumed
+async fn measure(
+    name: &str,
+    test_client: &mut TestClient,
+    instruction: Instruction,
+    signers: Vec<&Keypair>,
+) -> u64 {
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction], Some(&test_client.payer.pubkey()));
+    transaction.sign(
+        &signers,
+        test_client
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap(),
+    );
+
+    let result = test_client
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result
+        .result
+        .unwrap_or_else(|error| panic!("{name} failed: {error}"));
+
+    result.metadata.unwrap().compute_units_consumed
+}
+
+/// Compute units consumed by every instruction of the program
+struct ComputeUnits(Vec<(&'static str, u64)>);
+
+impl ComputeUnits {
+    async fn record(
+        &mut self,
+        name: &'static str,
+        test_client: &mut TestClient,
+        instruction: Instruction,
+        signers: Vec<&Keypair>,
+    ) {
+        let compute_units = measure(name, test_client, instruction, signers).await;
+        self.0.push((name, compute_units));
+    }
+}
+
+/// Test 1 - compute units consumed by each instruction stay within their recorded budget
+///
+///
+#[tokio::test]
+async fn test_1() {
+    // Natively, only the CPIs and syscalls of the program would be metered
+    if find_file("token2022_wrapper.so").is_none() {
+        println!(
+            "token2022_wrapper.so not found, run with `cargo test-sbf` to check compute units"
+        );
+        return;
+    }
+
+    let mut test_client = TestClient::new_with_program_from_so().await;
+    let payer = test_client.get_payer_clone();
+    let admin = test_client.admin.insecure_clone();
+    let new_admin = Keypair::new();
+    let user = Keypair::new();
+    let watcher = Keypair::new();
+    let mut compute_units = ComputeUnits(vec![]);
+
+    for keypair in [&admin, &new_admin, &user, &watcher] {
+        airdrop(&mut test_client, &keypair.pubkey(), 5 * LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+
+    compute_units
+        .record(
+            "InitializeProgramConfig",
+            &mut test_client,
+            create_initialize_program_config_instruction(&admin.pubkey()),
+            vec![&payer, &admin],
+        )
+        .await;
+
+    // A Token 2022 mint with a transfer fee wrapped by the v2 layout, and one without wrapped
+    // by the v1 layout. Their addresses are fixed, as the bump searches of the PDAs and
+    // associated token accounts derived from them by the Token programs cost compute units
+    let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+    let token_2022_mint = create_token_2022_mint(
+        &mut test_client,
+        &payer.pubkey(),
+        Some(&payer.pubkey()),
+        6,
+        Some(keypair_from_seed(&[1; 32]).unwrap()),
+        Some(&transfer_fee_config),
+    )
+    .await
+    .unwrap();
+    let v1_token_2022_mint = create_token_2022_mint(
+        &mut test_client,
+        &payer.pubkey(),
+        None,
+        6,
+        Some(keypair_from_seed(&[2; 32]).unwrap()),
+        None,
+    )
+    .await
+    .unwrap();
+
+    compute_units
+        .record(
+            "InitializeWrapperTokenV2",
+            &mut test_client,
+            create_initialize_wrapper_token_v2_instruction(&payer.pubkey(), &token_2022_mint),
+            vec![&payer],
+        )
+        .await;
+    compute_units
+        .record(
+            "InitializeWrapperToken",
+            &mut test_client,
+            create_initialize_wrapper_token_instruction(&payer.pubkey(), &v1_token_2022_mint),
+            vec![&payer],
+        )
+        .await;
+
+    let (wrapper_token_mint, _, _) =
+        get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id());
+    let user_token_2022_token_account = create_associated_token_account(
+        &mut test_client,
+        &user.pubkey(),
+        &token_2022_mint,
+        &spl_token_2022::id(),
+    )
+    .await
+    .unwrap();
+    let user_wrapper_token_account = create_associated_token_account(
+        &mut test_client,
+        &user.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+    let fee_recipient = create_associated_token_account(
+        &mut test_client,
+        &admin.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+    mint_token_2022_tokens(
+        &mut test_client,
+        &payer,
+        &token_2022_mint,
+        &user_token_2022_token_account,
+        1_000_000_000,
+        None,
+    )
+    .await
+    .unwrap();
+
+    compute_units
+        .record(
+            "DepositAndMintWrapperTokens",
+            &mut test_client,
+            create_deposit_and_mint_wrapper_tokens_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                100_000_000,
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "DepositAndMintWrapperTokensV2",
+            &mut test_client,
+            create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount: 100_000_000,
+                    ..Default::default()
+                },
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "WithdrawAndBurnWrapperTokens",
+            &mut test_client,
+            create_withdraw_and_burn_wrapper_tokens_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                10_000_000,
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "WithdrawAndBurnWrapperTokensV2",
+            &mut test_client,
+            create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensArgs {
+                    amount: 10_000_000,
+                    ..Default::default()
+                },
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+
+    compute_units
+        .record(
+            "InitializeReserveShard",
+            &mut test_client,
+            create_initialize_reserve_shard_instruction(&admin.pubkey(), &token_2022_mint, 1),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "SetFee",
+            &mut test_client,
+            create_set_fee_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                &fee_recipient,
+                SetFeeArgs {
+                    wrap_fee_basis_points: 10,
+                    unwrap_fee_basis_points: 10,
+                    maximum_fee: u64::MAX,
+                },
+            ),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "DepositAndMintWrapperTokensV3",
+            &mut test_client,
+            create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 1,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount: 100_000_000,
+                        ..Default::default()
+                    },
+                },
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "WithdrawAndBurnWrapperTokensV3",
+            &mut test_client,
+            create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 1,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount: 10_000_000,
+                        ..Default::default()
+                    },
+                },
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "CollectFees",
+            &mut test_client,
+            create_collect_fees_instruction(&admin.pubkey(), &token_2022_mint, &fee_recipient),
+            vec![&payer, &admin],
+        )
+        .await;
+    // The fees collected in wrapper tokens leave no surplus in the reserve, the Token 2022
+    // transfer fee withheld from the rebalanced funds is covered by tokens minted to the shard
+    let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, token2022_wrapper::id());
+    let (reserve_shard, _, _) = get_reserve_shard(
+        token_2022_mint,
+        reserve_authority,
+        1,
+        token2022_wrapper::id(),
+    );
+    mint_token_2022_tokens(
+        &mut test_client,
+        &payer,
+        &token_2022_mint,
+        &reserve_shard,
+        1_000_000,
+        None,
+    )
+    .await
+    .unwrap();
+    compute_units
+        .record(
+            "RebalanceReserveShards",
+            &mut test_client,
+            create_rebalance_reserve_shards_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                1,
+                RebalanceReserveShardsArgs {
+                    source_reserve_shard: 1,
+                    destination_reserve_shard: 0,
+                    amount: 1_000_000,
+                },
+            ),
+            vec![&payer, &admin],
+        )
+        .await;
+
+    compute_units
+        .record(
+            "InitializeWrapperInventory",
+            &mut test_client,
+            create_initialize_wrapper_inventory_instruction(&admin.pubkey(), &token_2022_mint),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "TopUpWrapperInventory",
+            &mut test_client,
+            create_top_up_wrapper_inventory_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                100_000_000,
+            ),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "DepositFromWrapperInventory",
+            &mut test_client,
+            create_deposit_from_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 1,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount: 10_000_000,
+                        ..Default::default()
+                    },
+                },
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "WithdrawToWrapperInventory",
+            &mut test_client,
+            create_withdraw_to_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 1,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount: 1_000_000,
+                        ..Default::default()
+                    },
+                },
+            ),
+            vec![&payer, &user],
+        )
+        .await;
+    compute_units
+        .record(
+            "SetWrapperLimits",
+            &mut test_client,
+            create_set_wrapper_limits_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                SetWrapperLimitsArgs {
+                    supply_cap: u64::MAX,
+                    epoch_wrap_limit: 1_000_000_000,
+                    epoch_unwrap_limit: 1_000_000_000,
+                },
+            ),
+            vec![&payer, &admin],
+        )
+        .await;
+
+    compute_units
+        .record(
+            "QuoteWrap",
+            &mut test_client,
+            create_quote_wrap_instruction(&token_2022_mint, QuoteArgs { amount: 1_000_000 }),
+            vec![&payer],
+        )
+        .await;
+    compute_units
+        .record(
+            "QuoteUnwrap",
+            &mut test_client,
+            create_quote_unwrap_instruction(&token_2022_mint, QuoteArgs { amount: 1_000_000 }),
+            vec![&payer],
+        )
+        .await;
+
+    compute_units
+        .record(
+            "SetPause",
+            &mut test_client,
+            create_set_pause_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                SetPauseArgs { paused: true },
+            ),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "SetGlobalPause",
+            &mut test_client,
+            create_set_global_pause_instruction(&admin.pubkey(), SetPauseArgs { paused: true }),
+            vec![&payer, &admin],
+        )
+        .await;
+
+    // The Token 2022 token account is frozen by the freeze authority, then mirrored by anyone
+    sign_send_instructions(
+        &mut test_client,
+        &[spl_token_2022::instruction::freeze_account(
+            &spl_token_2022::id(),
+            &user_token_2022_token_account,
+            &token_2022_mint,
+            &payer.pubkey(),
+            &[],
+        )
+        .unwrap()],
+        vec![&payer],
+        None,
+    )
+    .await
+    .unwrap();
+    compute_units
+        .record(
+            "MirrorFreeze",
+            &mut test_client,
+            create_mirror_freeze_instruction(
+                &watcher.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            ),
+            vec![&payer, &watcher],
+        )
+        .await;
+    sign_send_instructions(
+        &mut test_client,
+        &[spl_token_2022::instruction::thaw_account(
+            &spl_token_2022::id(),
+            &user_token_2022_token_account,
+            &token_2022_mint,
+            &payer.pubkey(),
+            &[],
+        )
+        .unwrap()],
+        vec![&payer],
+        None,
+    )
+    .await
+    .unwrap();
+    compute_units
+        .record(
+            "MirrorThaw",
+            &mut test_client,
+            create_mirror_thaw_instruction(
+                &watcher.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            ),
+            vec![&payer, &watcher],
+        )
+        .await;
+
+    compute_units
+        .record(
+            "ReallocReserve",
+            &mut test_client,
+            create_realloc_reserve_instruction(&payer.pubkey(), &token_2022_mint),
+            vec![&payer],
+        )
+        .await;
+    compute_units
+        .record(
+            "MigrateReserve",
+            &mut test_client,
+            create_migrate_reserve_instruction(&admin.pubkey(), &v1_token_2022_mint),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "RetireWrapper",
+            &mut test_client,
+            create_retire_wrapper_instruction(&admin.pubkey(), &v1_token_2022_mint),
+            vec![&payer, &admin],
+        )
+        .await;
+
+    compute_units
+        .record(
+            "SetPendingAdmin",
+            &mut test_client,
+            create_set_pending_admin_instruction(
+                &admin.pubkey(),
+                SetPendingAdminArgs {
+                    pending_admin: new_admin.pubkey(),
+                },
+            ),
+            vec![&payer, &admin],
+        )
+        .await;
+    compute_units
+        .record(
+            "AcceptAdmin",
+            &mut test_client,
+            create_accept_admin_instruction(&new_admin.pubkey()),
+            vec![&payer, &new_admin],
+        )
+        .await;
+
+    // `RegisterWrapperGroupMember` is left out, token groups need the Token 2022 program from the
+    // crate, which runs natively
+
+    for (name, consumed) in &compute_units.0 {
+        println!("(\"{name}\", {consumed}),");
+    }
+
+    let mut over_budget = vec![];
+    for (name, consumed) in &compute_units.0 {
+        let budget = COMPUTE_UNIT_BUDGETS
+            .iter()
+            .find(|(budget_name, _)| budget_name == name)
+            .map(|(_, budget)| *budget)
+            .unwrap_or_else(|| panic!("No compute unit budget recorded for {name}"));
+
+        if *consumed > budget {
+            over_budget.push(format!("{name} consumed {consumed}, budget {budget}"));
+        }
+    }
+    assert!(
+        over_budget.is_empty(),
+        "Compute unit budgets exceeded:\n{}",
+        over_budget.join("\n")
+    );
+}
@@ -0,0 +1,942 @@
+pub mod utils;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use solana_sdk::{
+    account::Account, native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as Token2022Account, AccountState},
+};
+use token2022_wrapper::{
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, QuoteArgs,
+        RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_accept_admin_instruction, create_collect_fees_instruction,
+        create_deposit_and_mint_wrapper_tokens_instruction,
+        create_deposit_and_mint_wrapper_tokens_v2_instruction,
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_deposit_from_wrapper_inventory_instruction,
+        create_initialize_program_config_instruction, create_initialize_reserve_shard_instruction,
+        create_initialize_wrapper_inventory_instruction,
+        create_initialize_wrapper_token_instruction,
+        create_initialize_wrapper_token_v2_instruction, create_migrate_reserve_instruction,
+        create_mirror_freeze_instruction, create_mirror_thaw_instruction,
+        create_quote_unwrap_instruction, create_quote_wrap_instruction,
+        create_realloc_reserve_instruction, create_rebalance_reserve_shards_instruction,
+        create_retire_wrapper_instruction, create_set_fee_instruction,
+        create_set_global_pause_instruction, create_set_pause_instruction,
+        create_set_pending_admin_instruction, create_set_wrapper_limits_instruction,
+        create_top_up_wrapper_inventory_instruction,
+        create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction,
+        create_withdraw_to_wrapper_inventory_instruction, with_migrated_reserve,
+        with_reserve_shards, with_wrapper_inventory, with_wrapper_limits,
+    },
+    state::{AccountType, ProgramConfig, WrapperConfig},
+    utils::{
+        get_fee_vault, get_program_config, get_reserve_authority,
+        get_reserve_authority_token_account, get_reserve_shard, get_wrapper_inventory,
+        get_wrapper_limits, get_wrapper_token_mint,
+    },
+};
+use utils::{
+    airdrop, create_associated_token_account, create_token_2022_mint,
+    create_token_account_token_2022, get_account, mint_token_2022_tokens, sign_send_instructions,
+    test_transfer_fee_config_with_keypairs, TestClient,
+};
+
+/// Transactions sent by a default run, `FUZZ_ITERATIONS` overrides it for longer runs
+const DEFAULT_ITERATIONS: u64 = 300;
+
+/// Token 2022 tokens minted to the user for each wrapper
+const USER_AMOUNT: u64 = 1_000_000;
+
+/// Reserve shards of each wrapper whose lamports are checked and swapped in by the mutations
+const FUZZED_RESERVE_SHARDS: u8 = 3;
+
+/// Wrapper fuzzed by the harness, with the token accounts of the user and the attacker
+struct FuzzedWrapper {
+    token_2022_mint: Pubkey,
+    wrapper_token_mint: Pubkey,
+    reserve_authority: Pubkey,
+    reserve_token_2022_token_account: Pubkey,
+    user_token_2022_token_account: Pubkey,
+    user_wrapper_token_account: Pubkey,
+    attacker_token_2022_token_account: Pubkey,
+    attacker_wrapper_token_account: Pubkey,
+    fee_recipient: Pubkey,
+}
+
+/// Program accounts forged by the attacker at genesis
+struct ForgedAccounts {
+    /// Wrapper config without fees, owned by the System program
+    wrapper_config: Pubkey,
+    /// Program config with the attacker as admin, owned by another program
+    program_config: Pubkey,
+    /// Token 2022 token account of the attacker claiming a large balance, owned by the System
+    /// program
+    token_2022_token_account: Pubkey,
+    /// Zeroed account owned by the wrapper program
+    uninitialized: Pubkey,
+}
+
+impl ForgedAccounts {
+    fn genesis_accounts(
+        &self,
+        attacker: &Pubkey,
+        token_2022_mint: &Pubkey,
+    ) -> Vec<(Pubkey, Account)> {
+        let mut wrapper_config = vec![0; WrapperConfig::LEN];
+        WrapperConfig {
+            account_type: AccountType::WrapperConfig,
+            token_2022_mint: *token_2022_mint,
+            fee_recipient: *attacker,
+            ..Default::default()
+        }
+        .pack(&mut wrapper_config)
+        .unwrap();
+
+        let mut program_config = vec![0; ProgramConfig::LEN];
+        ProgramConfig {
+            account_type: AccountType::ProgramConfig,
+            admin: *attacker,
+            ..Default::default()
+        }
+        .pack(&mut program_config)
+        .unwrap();
+
+        let mut token_2022_token_account = vec![0; Token2022Account::LEN];
+        Token2022Account {
+            mint: *token_2022_mint,
+            owner: *attacker,
+            amount: u64::MAX / 2,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut token_2022_token_account);
+
+        let account = |data: Vec<u8>, owner: Pubkey| Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        vec![
+            (
+                self.wrapper_config,
+                account(wrapper_config, system_program::id()),
+            ),
+            (
+                self.program_config,
+                account(program_config, Pubkey::new_unique()),
+            ),
+            (
+                self.token_2022_token_account,
+                account(token_2022_token_account, system_program::id()),
+            ),
+            (
+                self.uninitialized,
+                account(vec![0; WrapperConfig::LEN], token2022_wrapper::id()),
+            ),
+        ]
+    }
+}
+
+/// Drives the processor with valid, forged and mutated instructions from a seeded RNG, and
+/// checks the invariants of the program after each transaction
+struct Fuzzer {
+    rng: StdRng,
+    test_client: TestClient,
+    payer: Keypair,
+    admin: Keypair,
+    user: Keypair,
+    attacker: Keypair,
+    wrappers: Vec<FuzzedWrapper>,
+    forged: ForgedAccounts,
+    /// Lamports of the PDAs after the previous transaction
+    pda_lamports: Vec<(Pubkey, u64)>,
+}
+
+impl Fuzzer {
+    async fn new(seed: u64) -> Self {
+        let user = Keypair::new();
+        let attacker = Keypair::new();
+        let forged = ForgedAccounts {
+            wrapper_config: Pubkey::new_unique(),
+            program_config: Pubkey::new_unique(),
+            token_2022_token_account: Pubkey::new_unique(),
+            uninitialized: Pubkey::new_unique(),
+        };
+
+        // The forged accounts point to a mint keypair created before genesis
+        let fee_mint_keypair = Keypair::new();
+        let mut test_client = TestClient::new_with_accounts(
+            forged.genesis_accounts(&attacker.pubkey(), &fee_mint_keypair.pubkey()),
+        )
+        .await;
+        let payer = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        for keypair in [&user, &attacker, &admin] {
+            airdrop(&mut test_client, &keypair.pubkey(), 10 * LAMPORTS_PER_SOL)
+                .await
+                .unwrap();
+        }
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_program_config_instruction(
+                &admin.pubkey(),
+            )],
+            vec![&admin, &payer],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // A Token 2022 mint with a transfer fee and one without
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+        let mut wrappers = vec![];
+        for (mint_keypair, transfer_fee_config) in [
+            (fee_mint_keypair, Some(&transfer_fee_config)),
+            (Keypair::new(), None),
+        ] {
+            let token_2022_mint = create_token_2022_mint(
+                &mut test_client,
+                &payer.pubkey(),
+                Some(&payer.pubkey()),
+                6,
+                Some(mint_keypair),
+                transfer_fee_config,
+            )
+            .await
+            .unwrap();
+
+            sign_send_instructions(
+                &mut test_client,
+                &[create_initialize_wrapper_token_v2_instruction(
+                    &payer.pubkey(),
+                    &token_2022_mint,
+                )],
+                vec![&payer],
+                None,
+            )
+            .await
+            .unwrap();
+
+            let (wrapper_token_mint, _, _) =
+                get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id());
+            let (reserve_authority, _, _) =
+                get_reserve_authority(token_2022_mint, token2022_wrapper::id());
+            let (reserve_token_2022_token_account, _, _) = get_reserve_authority_token_account(
+                token_2022_mint,
+                reserve_authority,
+                token2022_wrapper::id(),
+            );
+
+            let user_token_2022_token_account =
+                create_token_account_token_2022(&mut test_client, &user.pubkey(), &token_2022_mint)
+                    .await
+                    .unwrap();
+            mint_token_2022_tokens(
+                &mut test_client,
+                &payer,
+                &token_2022_mint,
+                &user_token_2022_token_account,
+                USER_AMOUNT,
+                None,
+            )
+            .await
+            .unwrap();
+
+            wrappers.push(FuzzedWrapper {
+                token_2022_mint,
+                wrapper_token_mint,
+                reserve_authority,
+                reserve_token_2022_token_account,
+                user_token_2022_token_account,
+                user_wrapper_token_account: create_associated_token_account(
+                    &mut test_client,
+                    &user.pubkey(),
+                    &wrapper_token_mint,
+                    &spl_token::id(),
+                )
+                .await
+                .unwrap(),
+                attacker_token_2022_token_account: create_token_account_token_2022(
+                    &mut test_client,
+                    &attacker.pubkey(),
+                    &token_2022_mint,
+                )
+                .await
+                .unwrap(),
+                attacker_wrapper_token_account: create_associated_token_account(
+                    &mut test_client,
+                    &attacker.pubkey(),
+                    &wrapper_token_mint,
+                    &spl_token::id(),
+                )
+                .await
+                .unwrap(),
+                fee_recipient: create_associated_token_account(
+                    &mut test_client,
+                    &admin.pubkey(),
+                    &wrapper_token_mint,
+                    &spl_token::id(),
+                )
+                .await
+                .unwrap(),
+            });
+        }
+
+        let mut fuzzer = Self {
+            rng: StdRng::seed_from_u64(seed),
+            test_client,
+            payer,
+            admin,
+            user,
+            attacker,
+            wrappers,
+            forged,
+            pda_lamports: vec![],
+        };
+        fuzzer.pda_lamports = fuzzer.get_pda_lamports().await;
+        fuzzer
+    }
+
+    fn pdas(&self) -> Vec<Pubkey> {
+        let (program_config, _, _) = get_program_config(token2022_wrapper::id());
+        let mut pdas = vec![program_config];
+        for wrapper in &self.wrappers {
+            pdas.extend([
+                wrapper.wrapper_token_mint,
+                wrapper.reserve_authority,
+                wrapper.reserve_token_2022_token_account,
+                get_migrated_reserve(wrapper),
+                get_fuzzed_wrapper_inventory(wrapper),
+                get_wrapper_limits(wrapper.token_2022_mint, token2022_wrapper::id()).0,
+                get_fee_vault(
+                    wrapper.token_2022_mint,
+                    wrapper.reserve_authority,
+                    token2022_wrapper::id(),
+                )
+                .0,
+            ]);
+            pdas.extend((1..=FUZZED_RESERVE_SHARDS).map(|reserve_shard| {
+                get_reserve_shard(
+                    wrapper.token_2022_mint,
+                    wrapper.reserve_authority,
+                    reserve_shard,
+                    token2022_wrapper::id(),
+                )
+                .0
+            }));
+        }
+        pdas
+    }
+
+    async fn get_pda_lamports(&mut self) -> Vec<(Pubkey, u64)> {
+        let mut pda_lamports = vec![];
+        for pda in self.pdas() {
+            let lamports = get_account(&mut self.test_client, &pda).await.lamports;
+            pda_lamports.push((pda, lamports));
+        }
+        pda_lamports
+    }
+
+    async fn get_wrapper_config(&mut self, wrapper: usize) -> Option<WrapperConfig> {
+        let account = get_account(
+            &mut self.test_client,
+            &self.wrappers[wrapper].reserve_authority,
+        )
+        .await;
+        (account.owner == token2022_wrapper::id())
+            .then(|| WrapperConfig::unpack(&account.data).unwrap())
+    }
+
+    async fn get_token_2022_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = get_account(&mut self.test_client, token_account).await;
+        StateWithExtensions::<Token2022Account>::unpack(&account.data)
+            .map_or(0, |account| account.base.amount)
+    }
+
+    async fn get_token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = get_account(&mut self.test_client, token_account).await;
+        spl_token::state::Account::unpack(&account.data).map_or(0, |account| account.amount)
+    }
+
+    /// Amount picked around the interesting values, zero, small, large and the full balance
+    fn amount(&mut self) -> u64 {
+        match self.rng.gen_range(0..6) {
+            0 => 0,
+            1 => self.rng.gen_range(1..100),
+            2 => self.rng.gen_range(100..USER_AMOUNT),
+            3 => USER_AMOUNT + self.rng.gen_range(0..10),
+            4 => u64::MAX,
+            _ => self.rng.gen(),
+        }
+    }
+
+    /// Instruction valid for the program, signed by the user, the admin or the attacker
+    async fn valid_instruction(&mut self) -> Instruction {
+        let index = self.rng.gen_range(0..self.wrappers.len());
+        let wrapper_config = self.get_wrapper_config(index).await;
+        let migrated = wrapper_config
+            .as_ref()
+            .and_then(WrapperConfig::migrated_reserve)
+            .is_some();
+        let has_wrapper_inventory = wrapper_config
+            .as_ref()
+            .is_some_and(|config| config.has_wrapper_inventory);
+        let has_wrapper_limits = wrapper_config
+            .as_ref()
+            .is_some_and(|config| config.has_wrapper_limits);
+        let reserve_shard_count = wrapper_config.map_or(0, |config| config.reserve_shard_count);
+        // Created reserve shards, and sometimes the next one which is not created yet
+        let reserve_shard = self.rng.gen_range(0..=reserve_shard_count + 1);
+        let other_reserve_shard = self.rng.gen_range(0..=reserve_shard_count);
+        let amount = self.amount();
+        let min_out = if self.rng.gen_bool(0.8) {
+            0
+        } else {
+            self.amount()
+        };
+        let maximum_fee = self.amount();
+        let wrap_fee_basis_points = self.rng.gen_range(0..1_200);
+        let unwrap_fee_basis_points = self.rng.gen_range(0..1_200);
+        let paused = self.rng.gen_bool(0.5);
+        let global_paused = self.rng.gen_bool(0.3);
+        let admin_instruction = self.rng.gen_range(0..5);
+        let mut limit = || {
+            if self.rng.gen_bool(0.3) {
+                0
+            } else {
+                self.amount()
+            }
+        };
+        let wrapper_limits = SetWrapperLimitsArgs {
+            supply_cap: limit(),
+            epoch_wrap_limit: limit(),
+            epoch_unwrap_limit: limit(),
+        };
+
+        let wrapper = &self.wrappers[index];
+        let user = self.user.pubkey();
+        let admin = self.admin.pubkey();
+        let attacker = self.attacker.pubkey();
+        // Admin instructions over the reserve shards also take the wrapper inventory once created
+        let with_inventory = |instruction| {
+            if has_wrapper_inventory {
+                with_wrapper_inventory(instruction, &wrapper.token_2022_mint)
+            } else {
+                instruction
+            }
+        };
+        // Top ups take the wrapper limits once set
+        let with_limits = |instruction| {
+            if has_wrapper_limits {
+                with_wrapper_limits(instruction, &wrapper.token_2022_mint)
+            } else {
+                instruction
+            }
+        };
+        let instruction = match self.rng.gen_range(0..29) {
+            0..=2 => create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount,
+                    min_out,
+                    ..Default::default()
+                },
+            ),
+            3..=5 => create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensArgs {
+                    amount,
+                    min_out,
+                    ..Default::default()
+                },
+            ),
+            6 => create_deposit_and_mint_wrapper_tokens_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                amount,
+            ),
+            7 => create_withdraw_and_burn_wrapper_tokens_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                amount,
+            ),
+            8 => create_set_fee_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                &wrapper.fee_recipient,
+                SetFeeArgs {
+                    wrap_fee_basis_points,
+                    unwrap_fee_basis_points,
+                    maximum_fee,
+                },
+            ),
+            9 => create_collect_fees_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                &wrapper.fee_recipient,
+            ),
+            10 => create_set_pause_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                SetPauseArgs { paused },
+            ),
+            11 => create_set_global_pause_instruction(
+                &admin,
+                SetPauseArgs {
+                    paused: global_paused,
+                },
+            ),
+            12 => with_inventory(with_reserve_shards(
+                create_migrate_reserve_instruction(&admin, &wrapper.token_2022_mint),
+                &wrapper.token_2022_mint,
+                reserve_shard_count,
+            )),
+            13 => create_realloc_reserve_instruction(&attacker, &wrapper.token_2022_mint),
+            14 => create_mirror_freeze_instruction(
+                &attacker,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &user,
+            ),
+            15 => create_mirror_thaw_instruction(
+                &attacker,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &user,
+            ),
+            16 => create_quote_wrap_instruction(&wrapper.token_2022_mint, QuoteArgs { amount }),
+            17 => create_quote_unwrap_instruction(&wrapper.token_2022_mint, QuoteArgs { amount }),
+            18 => match admin_instruction {
+                0 => create_retire_wrapper_instruction(&attacker, &wrapper.token_2022_mint),
+                1 => create_set_pending_admin_instruction(
+                    &attacker,
+                    SetPendingAdminArgs {
+                        pending_admin: attacker,
+                    },
+                ),
+                2 => create_accept_admin_instruction(&attacker),
+                3 => create_initialize_program_config_instruction(&attacker),
+                _ => {
+                    create_initialize_wrapper_token_instruction(&attacker, &wrapper.token_2022_mint)
+                }
+            },
+            19 => create_initialize_reserve_shard_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                reserve_shard_count + 1,
+            ),
+            20 => create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        min_out,
+                        ..Default::default()
+                    },
+                },
+            ),
+            21 => create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount,
+                        min_out,
+                        ..Default::default()
+                    },
+                },
+            ),
+            22 => with_inventory(create_rebalance_reserve_shards_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                reserve_shard_count,
+                RebalanceReserveShardsArgs {
+                    source_reserve_shard: reserve_shard,
+                    destination_reserve_shard: other_reserve_shard,
+                    amount,
+                },
+            )),
+            23 => create_initialize_wrapper_inventory_instruction(&admin, &wrapper.token_2022_mint),
+            24 => with_limits(create_top_up_wrapper_inventory_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                amount,
+            )),
+            25 => create_deposit_from_wrapper_inventory_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        min_out,
+                        ..Default::default()
+                    },
+                },
+            ),
+            26 => create_withdraw_to_wrapper_inventory_instruction(
+                &user,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount,
+                        min_out,
+                        ..Default::default()
+                    },
+                },
+            ),
+            27 => create_set_wrapper_limits_instruction(
+                &admin,
+                &wrapper.token_2022_mint,
+                wrapper_limits,
+            ),
+            // The attacker wraps and unwraps with the accounts of the user
+            _ => create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+                &attacker,
+                &wrapper.token_2022_mint,
+                &wrapper.user_wrapper_token_account,
+                &wrapper.attacker_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensArgs {
+                    amount,
+                    ..Default::default()
+                },
+            ),
+        };
+
+        if migrated {
+            with_migrated_reserve(instruction, &wrapper.token_2022_mint)
+        } else {
+            instruction
+        }
+    }
+
+    /// Accounts swapped in by the mutations, the accounts of the other wrapper, forged accounts,
+    /// programs and sysvars included
+    fn candidate_accounts(&self) -> Vec<Pubkey> {
+        let mut candidates = vec![
+            self.payer.pubkey(),
+            self.user.pubkey(),
+            self.admin.pubkey(),
+            self.attacker.pubkey(),
+            self.forged.wrapper_config,
+            self.forged.program_config,
+            self.forged.token_2022_token_account,
+            self.forged.uninitialized,
+            system_program::id(),
+            spl_token::id(),
+            spl_token_2022::id(),
+            spl_associated_token_account::id(),
+            token2022_wrapper::id(),
+            sysvar::rent::id(),
+            Pubkey::new_unique(),
+        ];
+        candidates.extend(self.pdas());
+        for wrapper in &self.wrappers {
+            candidates.extend([
+                wrapper.token_2022_mint,
+                wrapper.user_token_2022_token_account,
+                wrapper.user_wrapper_token_account,
+                wrapper.attacker_token_2022_token_account,
+                wrapper.attacker_wrapper_token_account,
+                wrapper.fee_recipient,
+            ]);
+        }
+        candidates
+    }
+
+    /// Mutates the accounts or the data of `instruction`, the signatures of the user and the
+    /// admin are then dropped
+    fn mutate(&mut self, instruction: &mut Instruction) {
+        let candidates = self.candidate_accounts();
+        let accounts = &mut instruction.accounts;
+
+        match self.rng.gen_range(0..7) {
+            // Forged or wrong accounts
+            0 | 1 if !accounts.is_empty() => {
+                for _ in 0..self.rng.gen_range(1..=3) {
+                    let index = self.rng.gen_range(0..accounts.len());
+                    accounts[index].pubkey = *candidates.choose(&mut self.rng).unwrap();
+                    accounts[index].is_writable |= self.rng.gen_bool(0.5);
+                }
+            }
+            // Duplicated accounts
+            2 if accounts.len() > 1 => {
+                let from = self.rng.gen_range(0..accounts.len());
+                let to = self.rng.gen_range(0..accounts.len());
+                accounts[to].pubkey = accounts[from].pubkey;
+            }
+            // Shuffled accounts
+            3 => accounts.shuffle(&mut self.rng),
+            // Missing or extra accounts
+            4 => {
+                if self.rng.gen_bool(0.5) {
+                    let len = self.rng.gen_range(0..=accounts.len());
+                    accounts.truncate(len);
+                } else {
+                    let pubkey = *candidates.choose(&mut self.rng).unwrap();
+                    accounts.push(AccountMeta::new(pubkey, false));
+                }
+            }
+            // Arbitrary data, with a valid tag or not
+            5 => {
+                let len = self.rng.gen_range(0..64);
+                let mut data: Vec<u8> = (0..len).map(|_| self.rng.gen()).collect();
+                if !data.is_empty() && self.rng.gen_bool(0.7) {
+                    data[0] = self.rng.gen_range(0..=29);
+                }
+                instruction.data = data;
+            }
+            // Flipped or truncated data
+            _ if !instruction.data.is_empty() => {
+                let index = self.rng.gen_range(0..instruction.data.len());
+                if self.rng.gen_bool(0.5) {
+                    instruction.data[index] ^= 1 << self.rng.gen_range(0..8);
+                } else {
+                    instruction.data.truncate(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Sends one valid or mutated instruction, its errors are expected
+    async fn step(&mut self) {
+        let mut instruction = self.valid_instruction().await;
+        let mutated = self.rng.gen_bool(0.6);
+        if mutated {
+            self.mutate(&mut instruction);
+        }
+
+        let mut signers = vec![&self.payer, &self.attacker];
+        if !mutated {
+            signers.extend([&self.user, &self.admin]);
+        }
+        for account in instruction.accounts.iter_mut() {
+            if account.is_signer
+                && !signers
+                    .iter()
+                    .any(|signer| signer.pubkey() == account.pubkey)
+            {
+                account.is_signer = false;
+            }
+        }
+        let signers: Vec<&Keypair> = signers
+            .into_iter()
+            .filter(|signer| {
+                signer.pubkey() == self.payer.pubkey()
+                    || instruction
+                        .accounts
+                        .iter()
+                        .any(|account| account.is_signer && account.pubkey == signer.pubkey())
+            })
+            .collect();
+
+        let mut transaction =
+            Transaction::new_with_payer(&[instruction], Some(&self.payer.pubkey()));
+        let blockhash = self
+            .test_client
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+        transaction.sign(&signers, blockhash);
+
+        let _ = self
+            .test_client
+            .banks_client
+            .process_transaction(transaction)
+            .await;
+    }
+
+    /// Checks that the circulating wrapper tokens are backed by the reserve and the reserve shards,
+    /// that no lamports left the PDAs, that the admin is unchanged and that neither the user nor
+    /// the attacker gained tokens
+    async fn check_invariants(&mut self, iteration: u64, seed: u64) {
+        let context = format!("iteration {iteration} of seed {seed}");
+
+        for index in 0..self.wrappers.len() {
+            let wrapper_config = self.get_wrapper_config(index).await;
+            let wrapper = &self.wrappers[index];
+            let reserve = wrapper_config
+                .as_ref()
+                .and_then(WrapperConfig::migrated_reserve)
+                .unwrap_or(wrapper.reserve_token_2022_token_account);
+            let reserve_shards: Vec<Pubkey> = (1..=wrapper_config
+                .map_or(0, |config| config.reserve_shard_count))
+                .map(|reserve_shard| {
+                    get_reserve_shard(
+                        wrapper.token_2022_mint,
+                        wrapper.reserve_authority,
+                        reserve_shard,
+                        token2022_wrapper::id(),
+                    )
+                    .0
+                })
+                .collect();
+            let wrapper_inventory = get_fuzzed_wrapper_inventory(wrapper);
+            let (
+                wrapper_token_mint,
+                user_token_2022,
+                user_wrapper,
+                attacker_token_2022,
+                attacker_wrapper,
+            ) = (
+                wrapper.wrapper_token_mint,
+                wrapper.user_token_2022_token_account,
+                wrapper.user_wrapper_token_account,
+                wrapper.attacker_token_2022_token_account,
+                wrapper.attacker_wrapper_token_account,
+            );
+
+            let mut reserve_balance = self.get_token_2022_balance(&reserve).await;
+            for reserve_shard in &reserve_shards {
+                reserve_balance += self.get_token_2022_balance(reserve_shard).await;
+            }
+            let wrapper_supply = spl_token::state::Mint::unpack(
+                &get_account(&mut self.test_client, &wrapper_token_mint)
+                    .await
+                    .data,
+            )
+            .unwrap()
+            .supply;
+            // Wrapper tokens held by the inventory are not circulating and need no backing
+            let circulating_supply =
+                wrapper_supply - self.get_token_balance(&wrapper_inventory).await;
+            assert!(
+                circulating_supply <= reserve_balance,
+                "{context}: circulating wrapper supply {circulating_supply} exceeds reserve \
+                 balance {reserve_balance}"
+            );
+
+            let user_balance = self.get_token_2022_balance(&user_token_2022).await
+                + self.get_token_balance(&user_wrapper).await;
+            assert!(
+                user_balance <= USER_AMOUNT,
+                "{context}: user holds {user_balance} tokens, more than the {USER_AMOUNT} minted"
+            );
+
+            assert_eq!(
+                self.get_token_2022_balance(&attacker_token_2022).await,
+                0,
+                "{context}: attacker received Token 2022 tokens"
+            );
+            assert_eq!(
+                self.get_token_balance(&attacker_wrapper).await,
+                0,
+                "{context}: attacker received wrapper tokens"
+            );
+        }
+
+        let pda_lamports = self.get_pda_lamports().await;
+        for ((pda, before), (_, after)) in self.pda_lamports.iter().zip(&pda_lamports) {
+            assert!(
+                after >= before,
+                "{context}: lamports of {pda} decreased from {before} to {after}"
+            );
+        }
+        self.pda_lamports = pda_lamports;
+
+        let (program_config, _, _) = get_program_config(token2022_wrapper::id());
+        let program_config = ProgramConfig::unpack(
+            &get_account(&mut self.test_client, &program_config)
+                .await
+                .data,
+        )
+        .unwrap();
+        assert_eq!(
+            program_config.admin,
+            self.admin.pubkey(),
+            "{context}: admin changed"
+        );
+    }
+}
+
+/// Reserve set by `MigrateReserve`, the associated token account of the reserve authority
+fn get_migrated_reserve(wrapper: &FuzzedWrapper) -> Pubkey {
+    spl_associated_token_account::get_associated_token_address_with_program_id(
+        &wrapper.reserve_authority,
+        &wrapper.token_2022_mint,
+        &spl_token_2022::id(),
+    )
+}
+
+/// Wrapper inventory set by `InitializeWrapperInventory`
+fn get_fuzzed_wrapper_inventory(wrapper: &FuzzedWrapper) -> Pubkey {
+    get_wrapper_inventory(
+        wrapper.token_2022_mint,
+        wrapper.reserve_authority,
+        token2022_wrapper::id(),
+    )
+    .0
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} must be an integer"))
+    })
+}
+
+/// Test 1 - fuzzes the processor with valid, forged and mutated instructions, checking the
+/// invariants of the program after each transaction
+///
+/// The instructions are drawn from `FUZZ_SEED`, random if unset and printed to reproduce a
+/// failure, for `FUZZ_ITERATIONS` transactions.
+#[tokio::test]
+async fn test_1() {
+    let seed = env_u64("FUZZ_SEED").unwrap_or_else(rand::random);
+    let iterations = env_u64("FUZZ_ITERATIONS").unwrap_or(DEFAULT_ITERATIONS);
+    println!("Fuzzing {iterations} transactions with FUZZ_SEED={seed}");
+
+    let mut fuzzer = Fuzzer::new(seed).await;
+    fuzzer.check_invariants(0, seed).await;
+
+    for iteration in 1..=iterations {
+        fuzzer.step().await;
+        fuzzer.check_invariants(iteration, seed).await;
+    }
+}
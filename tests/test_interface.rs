@@ -0,0 +1,1595 @@
+pub mod utils;
+
+use anchor_lang::{AccountDeserialize, Discriminator, Id, InstructionData, ToAccountMetas};
+use solana_program::{
+    account_info::{AccountInfo, IntoAccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    account::Account,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use token2022_wrapper::{
+    error::TokenWrapperError,
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, QuoteArgs,
+        RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_accept_admin_instruction, create_collect_fees_instruction,
+        create_deposit_and_mint_wrapper_tokens_instruction,
+        create_deposit_and_mint_wrapper_tokens_v2_instruction,
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_deposit_from_wrapper_inventory_instruction,
+        create_initialize_program_config_instruction, create_initialize_reserve_shard_instruction,
+        create_initialize_wrapper_inventory_instruction,
+        create_initialize_wrapper_token_instruction,
+        create_initialize_wrapper_token_v2_instruction, create_migrate_reserve_instruction,
+        create_mirror_freeze_instruction, create_mirror_thaw_instruction,
+        create_quote_unwrap_instruction, create_quote_wrap_instruction,
+        create_realloc_reserve_instruction, create_rebalance_reserve_shards_instruction,
+        create_register_wrapper_group_member_instruction, create_retire_wrapper_instruction,
+        create_set_fee_instruction, create_set_global_pause_instruction,
+        create_set_pause_instruction, create_set_pending_admin_instruction,
+        create_set_wrapper_limits_instruction, create_top_up_wrapper_inventory_instruction,
+        create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction,
+        create_withdraw_to_wrapper_inventory_instruction, with_wrapper_limits,
+    },
+    state::{
+        AccountType, IssuerFreeze, ProgramConfig, WrapperConfig, WrapperGroup, WrapperGroupMember,
+        WrapperLimits,
+    },
+    utils::{
+        get_fee_vault, get_program_config, get_reserve_authority,
+        get_reserve_authority_token_account, get_reserve_shard, get_wrapper_group,
+        get_wrapper_group_member, get_wrapper_inventory, get_wrapper_limits,
+        get_wrapper_token_mint,
+    },
+};
+use token2022_wrapper_interface::{
+    accounts, anchor, cpi, instruction as interface_instruction, pda, state,
+};
+use utils::{
+    airdrop, create_associated_token_account, create_token_2022_mint, get_token_balance,
+    get_token_balance_2022, mint_token_2022_tokens, sign_send_instructions, TestClient,
+};
+
+const PROGRAM_ID: Pubkey = token2022_wrapper::id();
+
+/// Accounts holding the keys of `instruction`, to build the interface account structs from
+fn accounts_of(instruction: &Instruction) -> Vec<(Pubkey, Account)> {
+    instruction
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, Account::default()))
+        .collect()
+}
+
+fn account_infos(accounts: &mut [(Pubkey, Account)]) -> Vec<AccountInfo<'_>> {
+    accounts
+        .iter_mut()
+        .map(|(key, account)| (&*key, account).into_account_info())
+        .collect()
+}
+
+/// Builds the interface accounts struct `$name` from `$infos`, in the order of `$field`s then of
+/// the `$optional_field`s, `None` once `$infos` runs out
+macro_rules! positional_accounts {
+    (
+        $name:ident,
+        $infos:expr,
+        [$($field:ident),* $(,)?]
+        $(, optional [$($optional_field:ident),* $(,)?])?
+    ) => {{
+        let mut infos = $infos.iter();
+        accounts::$name {
+            $($field: infos.next().unwrap(),)*
+            $($($optional_field: infos.next(),)*)?
+        }
+    }};
+}
+
+/// Test 1 - the interface wrap and unwrap instructions match the program builders
+///
+///
+#[test]
+fn test_1() {
+    let user = Pubkey::new_unique();
+    let token_2022_mint = Pubkey::new_unique();
+    let user_wrapper_token_account = Pubkey::new_unique();
+    let user_token_2022_token_account = Pubkey::new_unique();
+
+    let (wrapper_token_mint, _) = pda::find_wrapper_token_mint(&token_2022_mint);
+    let (reserve_authority, _) = pda::find_reserve_authority(&token_2022_mint);
+    let (reserve_token_2022_token_account, _) =
+        pda::find_reserve_authority_token_account(&token_2022_mint, &reserve_authority);
+    let (program_config, _) = pda::find_program_config();
+
+    let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+        &user,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensArgs {
+            amount: 1_000,
+            min_out: 990,
+            flags: 0,
+            memo: Some("wrap".to_string()),
+        },
+    );
+
+    let mut keys = [
+        user,
+        reserve_authority,
+        token_2022_mint,
+        wrapper_token_mint,
+        user_wrapper_token_account,
+        user_token_2022_token_account,
+        reserve_token_2022_token_account,
+        spl_token::id(),
+        spl_token_2022::id(),
+        program_config,
+    ]
+    .map(|key| (key, Account::default()));
+    let infos = account_infos(&mut keys);
+
+    let deposit_accounts = accounts::DepositAndMintWrapperTokensV2 {
+        user_authority: &infos[0],
+        reserve_authority: &infos[1],
+        token_2022_mint: &infos[2],
+        wrapper_token_mint: &infos[3],
+        user_wrapper_token_account: &infos[4],
+        user_token_2022_token_account: &infos[5],
+        reserve_token_2022_token_account: &infos[6],
+        token_program: &infos[7],
+        token_2022_program: &infos[8],
+        program_config: &infos[9],
+    };
+
+    assert_eq!(
+        cpi::deposit_and_mint_wrapper_tokens_v2_instruction(
+            &deposit_accounts,
+            interface_instruction::DepositAndMintWrapperTokensArgs {
+                amount: 1_000,
+                min_out: 990,
+                flags: 0,
+                memo: Some("wrap".to_string()),
+            },
+        ),
+        deposit_ix,
+    );
+
+    let withdraw_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+        &user,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        WithdrawAndBurnWrapperTokensArgs {
+            amount: 1_000,
+            ..Default::default()
+        },
+    );
+
+    let withdraw_accounts = accounts::WithdrawAndBurnWrapperTokensV2 {
+        user_authority: &infos[0],
+        reserve_authority: &infos[1],
+        token_2022_mint: &infos[2],
+        wrapper_token_mint: &infos[3],
+        user_wrapper_token_account: &infos[4],
+        user_token_2022_token_account: &infos[5],
+        reserve_token_2022_token_account: &infos[6],
+        token_program: &infos[7],
+        token_2022_program: &infos[8],
+    };
+
+    assert_eq!(
+        cpi::withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &withdraw_accounts,
+            interface_instruction::WithdrawAndBurnWrapperTokensArgs {
+                amount: 1_000,
+                ..Default::default()
+            },
+        ),
+        withdraw_ix,
+    );
+}
+
+/// Test 2 - every interface instruction matches the program builder, account flags included
+///
+///
+#[test]
+fn test_2() {
+    let authority = Pubkey::new_unique();
+    let token_2022_mint = Pubkey::new_unique();
+    let user_wrapper_token_account = Pubkey::new_unique();
+    let user_token_2022_token_account = Pubkey::new_unique();
+    let fee_recipient = Pubkey::new_unique();
+    let group = Pubkey::new_unique();
+
+    let ix = create_initialize_wrapper_token_instruction(&authority, &token_2022_mint);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        InitializeWrapperToken,
+        infos,
+        [
+            payer,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            system_program,
+            rent,
+        ]
+    );
+    assert_eq!(cpi::initialize_wrapper_token_instruction(&accounts), ix);
+
+    let ix = create_deposit_and_mint_wrapper_tokens_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        7,
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        DepositAndMintWrapperTokens,
+        infos,
+        [
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            system_program,
+            associated_token_program,
+            rent,
+            program_config,
+        ]
+    );
+    assert_eq!(
+        cpi::deposit_and_mint_wrapper_tokens_instruction(
+            &accounts,
+            interface_instruction::AmountArgs { amount: 7 },
+        ),
+        ix,
+    );
+
+    let ix = create_withdraw_and_burn_wrapper_tokens_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        7,
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        WithdrawAndBurnWrapperTokens,
+        infos,
+        [
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            system_program,
+            rent,
+        ]
+    );
+    assert_eq!(
+        cpi::withdraw_and_burn_wrapper_tokens_instruction(
+            &accounts,
+            interface_instruction::AmountArgs { amount: 7 },
+        ),
+        ix,
+    );
+
+    let ix = create_initialize_wrapper_token_v2_instruction(&authority, &token_2022_mint);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        InitializeWrapperTokenV2,
+        infos,
+        [
+            payer,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::initialize_wrapper_token_v2_instruction(&accounts), ix);
+
+    let ix = create_set_fee_instruction(
+        &authority,
+        &token_2022_mint,
+        &fee_recipient,
+        SetFeeArgs {
+            wrap_fee_basis_points: 10,
+            unwrap_fee_basis_points: 20,
+            maximum_fee: 30,
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        SetFee,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            fee_recipient,
+            system_program,
+            fee_vault,
+            token_program,
+        ]
+    );
+    assert_eq!(
+        cpi::set_fee_instruction(
+            &accounts,
+            interface_instruction::SetFeeArgs {
+                wrap_fee_basis_points: 10,
+                unwrap_fee_basis_points: 20,
+                maximum_fee: 30,
+            },
+        ),
+        ix,
+    );
+
+    let ix = create_collect_fees_instruction(&authority, &token_2022_mint, &fee_recipient);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        CollectFees,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            fee_vault,
+            fee_recipient,
+            token_program,
+        ]
+    );
+    assert_eq!(cpi::collect_fees_instruction(&accounts), ix);
+
+    let ix = create_initialize_program_config_instruction(&authority);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        InitializeProgramConfig,
+        infos,
+        [
+            upgrade_authority,
+            program_data,
+            program_config,
+            system_program
+        ]
+    );
+    assert_eq!(cpi::initialize_program_config_instruction(&accounts), ix);
+
+    let ix =
+        create_set_pause_instruction(&authority, &token_2022_mint, SetPauseArgs { paused: true });
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        SetPause,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            system_program,
+        ]
+    );
+    assert_eq!(
+        cpi::set_pause_instruction(
+            &accounts,
+            interface_instruction::SetPauseArgs { paused: true }
+        ),
+        ix,
+    );
+
+    let ix = create_set_global_pause_instruction(&authority, SetPauseArgs { paused: true });
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(SetGlobalPause, infos, [admin, program_config]);
+    assert_eq!(
+        cpi::set_global_pause_instruction(
+            &accounts,
+            interface_instruction::SetPauseArgs { paused: true }
+        ),
+        ix,
+    );
+
+    let ix = create_set_pending_admin_instruction(
+        &authority,
+        SetPendingAdminArgs {
+            pending_admin: group,
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(SetPendingAdmin, infos, [admin, program_config]);
+    assert_eq!(
+        cpi::set_pending_admin_instruction(
+            &accounts,
+            interface_instruction::SetPendingAdminArgs {
+                pending_admin: group,
+            },
+        ),
+        ix,
+    );
+
+    let ix = create_accept_admin_instruction(&authority);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(AcceptAdmin, infos, [pending_admin, program_config]);
+    assert_eq!(cpi::accept_admin_instruction(&accounts), ix);
+
+    let ix = create_mirror_freeze_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &group,
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        MirrorFreeze,
+        infos,
+        [
+            authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            wrapper_token_account,
+            token_2022_token_account,
+            token_program,
+            issuer_freeze,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::mirror_freeze_instruction(&accounts), ix);
+
+    let ix = create_mirror_thaw_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &group,
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        MirrorThaw,
+        infos,
+        [
+            authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            wrapper_token_account,
+            token_2022_token_account,
+            token_program,
+            issuer_freeze,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::mirror_thaw_instruction(&accounts), ix);
+
+    let ix = create_migrate_reserve_instruction(&authority, &token_2022_mint);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        MigrateReserve,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            reserve_token_2022_token_account,
+            new_reserve_token_2022_token_account,
+            token_2022_program,
+            associated_token_program,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::migrate_reserve_instruction(&accounts), ix);
+
+    let ix = create_realloc_reserve_instruction(&authority, &token_2022_mint);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        ReallocReserve,
+        infos,
+        [
+            payer,
+            token_2022_mint,
+            reserve_authority,
+            reserve_token_2022_token_account,
+            token_2022_program,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::realloc_reserve_instruction(&accounts), ix);
+
+    let ix = create_retire_wrapper_instruction(&authority, &token_2022_mint);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        RetireWrapper,
+        infos,
+        [
+            authority,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::retire_wrapper_instruction(&accounts), ix);
+
+    let ix = create_register_wrapper_group_member_instruction(&authority, &token_2022_mint, &group);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        RegisterWrapperGroupMember,
+        infos,
+        [
+            payer,
+            token_2022_mint,
+            wrapper_token_mint,
+            wrapper_group,
+            wrapper_group_member,
+            system_program,
+        ]
+    );
+    assert_eq!(
+        cpi::register_wrapper_group_member_instruction(&accounts),
+        ix
+    );
+
+    let ix = create_quote_wrap_instruction(&token_2022_mint, QuoteArgs { amount: 7 });
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        QuoteWrap,
+        infos,
+        [
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            program_config,
+        ]
+    );
+    assert_eq!(
+        cpi::quote_wrap_instruction(&accounts, interface_instruction::QuoteArgs { amount: 7 }),
+        ix
+    );
+
+    let ix = create_quote_unwrap_instruction(&token_2022_mint, QuoteArgs { amount: 7 });
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        QuoteUnwrap,
+        infos,
+        [token_2022_mint, wrapper_token_mint, reserve_authority]
+    );
+    assert_eq!(
+        cpi::quote_unwrap_instruction(&accounts, interface_instruction::QuoteArgs { amount: 7 }),
+        ix
+    );
+
+    let ix = create_initialize_reserve_shard_instruction(&authority, &token_2022_mint, 1);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        InitializeReserveShard,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            reserve_shard,
+            token_2022_program,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::initialize_reserve_shard_instruction(&accounts), ix);
+
+    let ix = create_rebalance_reserve_shards_instruction(
+        &authority,
+        &token_2022_mint,
+        0,
+        RebalanceReserveShardsArgs {
+            source_reserve_shard: 0,
+            destination_reserve_shard: 1,
+            amount: 7,
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        RebalanceReserveShards,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            token_2022_program,
+            reserve_token_2022_token_account,
+        ]
+    );
+    assert_eq!(
+        cpi::rebalance_reserve_shards_instruction(
+            &accounts,
+            interface_instruction::RebalanceReserveShardsArgs {
+                source_reserve_shard: 0,
+                destination_reserve_shard: 1,
+                amount: 7,
+            },
+        ),
+        ix
+    );
+
+    let ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensV3Args {
+            reserve_shard: 2,
+            args: DepositAndMintWrapperTokensArgs {
+                amount: 7,
+                min_out: 6,
+                flags: 0,
+                memo: Some("memo".to_string()),
+            },
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        DepositAndMintWrapperTokensV3,
+        infos,
+        [
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            program_config,
+            fee_vault,
+            wrapper_limits,
+        ]
+    );
+    assert_eq!(
+        cpi::deposit_and_mint_wrapper_tokens_v3_instruction(
+            &accounts,
+            interface_instruction::DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 2,
+                args: interface_instruction::DepositAndMintWrapperTokensArgs {
+                    amount: 7,
+                    min_out: 6,
+                    flags: 0,
+                    memo: Some("memo".to_string()),
+                },
+            },
+        ),
+        ix
+    );
+
+    let ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        WithdrawAndBurnWrapperTokensV3Args {
+            reserve_shard: 2,
+            args: WithdrawAndBurnWrapperTokensArgs {
+                amount: 7,
+                min_out: 6,
+                ..Default::default()
+            },
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        WithdrawAndBurnWrapperTokensV3,
+        infos,
+        [
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            fee_vault,
+            wrapper_limits,
+        ]
+    );
+    assert_eq!(
+        cpi::withdraw_and_burn_wrapper_tokens_v3_instruction(
+            &accounts,
+            interface_instruction::WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 2,
+                args: interface_instruction::WithdrawAndBurnWrapperTokensArgs {
+                    amount: 7,
+                    min_out: 6,
+                    ..Default::default()
+                },
+            },
+        ),
+        ix
+    );
+
+    let ix = create_initialize_wrapper_inventory_instruction(&authority, &token_2022_mint);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        InitializeWrapperInventory,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            wrapper_inventory,
+            token_program,
+            system_program,
+        ]
+    );
+    assert_eq!(cpi::initialize_wrapper_inventory_instruction(&accounts), ix);
+
+    // With and without the wrapper limits
+    let top_up_ix = create_top_up_wrapper_inventory_instruction(&authority, &token_2022_mint, 7);
+    for ix in [
+        top_up_ix.clone(),
+        with_wrapper_limits(top_up_ix, &token_2022_mint),
+    ] {
+        let mut keys = accounts_of(&ix);
+        let infos = account_infos(&mut keys);
+        let accounts = positional_accounts!(
+            TopUpWrapperInventory,
+            infos,
+            [
+                admin,
+                program_config,
+                token_2022_mint,
+                wrapper_token_mint,
+                reserve_authority,
+                wrapper_inventory,
+                token_program,
+            ],
+            optional[wrapper_limits]
+        );
+        assert_eq!(accounts.to_account_infos().len(), ix.accounts.len());
+        assert_eq!(
+            cpi::top_up_wrapper_inventory_instruction(
+                &accounts,
+                interface_instruction::AmountArgs { amount: 7 },
+            ),
+            ix
+        );
+    }
+
+    let ix = create_deposit_from_wrapper_inventory_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensV3Args {
+            reserve_shard: 1,
+            args: DepositAndMintWrapperTokensArgs {
+                amount: 7,
+                min_out: 6,
+                ..Default::default()
+            },
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        DepositFromWrapperInventory,
+        infos,
+        [
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            program_config,
+            wrapper_inventory,
+            fee_vault,
+            wrapper_limits,
+        ]
+    );
+    assert_eq!(
+        cpi::deposit_from_wrapper_inventory_instruction(
+            &accounts,
+            interface_instruction::DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 1,
+                args: interface_instruction::DepositAndMintWrapperTokensArgs {
+                    amount: 7,
+                    min_out: 6,
+                    ..Default::default()
+                },
+            },
+        ),
+        ix
+    );
+
+    let ix = create_withdraw_to_wrapper_inventory_instruction(
+        &authority,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        WithdrawAndBurnWrapperTokensV3Args {
+            reserve_shard: 1,
+            args: WithdrawAndBurnWrapperTokensArgs {
+                amount: 7,
+                min_out: 6,
+                ..Default::default()
+            },
+        },
+    );
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        WithdrawToWrapperInventory,
+        infos,
+        [
+            user_authority,
+            reserve_authority,
+            token_2022_mint,
+            wrapper_token_mint,
+            user_wrapper_token_account,
+            user_token_2022_token_account,
+            reserve_token_2022_token_account,
+            token_program,
+            token_2022_program,
+            wrapper_inventory,
+            fee_vault,
+            wrapper_limits,
+        ]
+    );
+    assert_eq!(
+        cpi::withdraw_to_wrapper_inventory_instruction(
+            &accounts,
+            interface_instruction::WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 1,
+                args: interface_instruction::WithdrawAndBurnWrapperTokensArgs {
+                    amount: 7,
+                    min_out: 6,
+                    ..Default::default()
+                },
+            },
+        ),
+        ix
+    );
+
+    let args = SetWrapperLimitsArgs {
+        supply_cap: 1_000,
+        epoch_wrap_limit: 100,
+        epoch_unwrap_limit: 50,
+    };
+    let ix = create_set_wrapper_limits_instruction(&authority, &token_2022_mint, args);
+    let mut keys = accounts_of(&ix);
+    let infos = account_infos(&mut keys);
+    let accounts = positional_accounts!(
+        SetWrapperLimits,
+        infos,
+        [
+            admin,
+            program_config,
+            token_2022_mint,
+            wrapper_token_mint,
+            reserve_authority,
+            wrapper_limits,
+            system_program,
+        ]
+    );
+    assert_eq!(
+        cpi::set_wrapper_limits_instruction(
+            &accounts,
+            interface_instruction::SetWrapperLimitsArgs {
+                supply_cap: 1_000,
+                epoch_wrap_limit: 100,
+                epoch_unwrap_limit: 50,
+            },
+        ),
+        ix
+    );
+
+    // The interface decodes the quotes returned by the program
+    let quote = token2022_wrapper::quote::Quote {
+        amount_in: 1_000,
+        transfer_fee: 10,
+        wrapper_fee: 3,
+        amount_out: 987,
+    };
+    let decoded: interface_instruction::Quote =
+        borsh::from_slice(&borsh::to_vec(&quote).unwrap()).unwrap();
+    assert_eq!(
+        decoded,
+        interface_instruction::Quote {
+            amount_in: 1_000,
+            transfer_fee: 10,
+            wrapper_fee: 3,
+            amount_out: 987,
+        }
+    );
+}
+
+/// Test 3 - the interface PDAs match the program ones
+///
+///
+#[test]
+fn test_3() {
+    let program_id = token2022_wrapper::id();
+    let token_2022_mint = Pubkey::new_unique();
+    let group = Pubkey::new_unique();
+
+    assert_eq!(token2022_wrapper_interface::id(), program_id);
+
+    let (wrapper_token_mint, bump, _) = get_wrapper_token_mint(token_2022_mint, program_id);
+    assert_eq!(
+        pda::find_wrapper_token_mint(&token_2022_mint),
+        (wrapper_token_mint, bump)
+    );
+
+    let (reserve_authority, bump, _) = get_reserve_authority(token_2022_mint, program_id);
+    assert_eq!(
+        pda::find_reserve_authority(&token_2022_mint),
+        (reserve_authority, bump)
+    );
+
+    let (reserve_token_2022_token_account, bump, _) =
+        get_reserve_authority_token_account(token_2022_mint, reserve_authority, program_id);
+    assert_eq!(
+        pda::find_reserve_authority_token_account(&token_2022_mint, &reserve_authority),
+        (reserve_token_2022_token_account, bump)
+    );
+
+    let (program_config, bump, _) = get_program_config(program_id);
+    assert_eq!(pda::find_program_config(), (program_config, bump));
+
+    let (wrapper_group, bump, _) = get_wrapper_group(group, program_id);
+    assert_eq!(pda::find_wrapper_group(&group), (wrapper_group, bump));
+
+    let (wrapper_group_member, bump, _) =
+        get_wrapper_group_member(group, token_2022_mint, program_id);
+    assert_eq!(
+        pda::find_wrapper_group_member(&group, &token_2022_mint),
+        (wrapper_group_member, bump)
+    );
+    let (reserve_shard, bump, _) =
+        get_reserve_shard(token_2022_mint, reserve_authority, 3, program_id);
+    assert_eq!(
+        pda::find_reserve_shard(&token_2022_mint, &reserve_authority, 3),
+        (reserve_shard, bump)
+    );
+    let (wrapper_inventory, bump, _) =
+        get_wrapper_inventory(token_2022_mint, reserve_authority, program_id);
+    assert_eq!(
+        pda::find_wrapper_inventory(&token_2022_mint, &reserve_authority),
+        (wrapper_inventory, bump)
+    );
+    let (wrapper_limits, bump, _) = get_wrapper_limits(token_2022_mint, program_id);
+    assert_eq!(
+        pda::find_wrapper_limits(&token_2022_mint),
+        (wrapper_limits, bump)
+    );
+}
+
+/// Test 4 - the interface state layouts read the accounts written by the program
+///
+///
+#[test]
+fn test_4() {
+    let program_config = ProgramConfig {
+        account_type: AccountType::ProgramConfig,
+        admin: Pubkey::new_unique(),
+        pending_admin: Pubkey::new_unique(),
+        paused: true,
+        reserved: [0; 64],
+    };
+    let mut data = vec![0; ProgramConfig::LEN];
+    program_config.pack(&mut data).unwrap();
+
+    assert_eq!(state::ProgramConfig::LEN, ProgramConfig::LEN);
+    let unpacked = state::ProgramConfig::unpack(&data).unwrap();
+    assert_eq!(unpacked.admin, program_config.admin);
+    assert_eq!(unpacked.pending_admin, program_config.pending_admin);
+    assert!(unpacked.paused);
+
+    let wrapper_config = WrapperConfig {
+        account_type: AccountType::WrapperConfig,
+        token_2022_mint: Pubkey::new_unique(),
+        fee_recipient: Pubkey::new_unique(),
+        wrap_fee_basis_points: 25,
+        unwrap_fee_basis_points: 50,
+        maximum_fee: 1_000,
+        paused: true,
+        reserve_token_2022_token_account: Pubkey::new_unique(),
+        token_2022_decimals: 6,
+        token_2022_mint_fingerprint: [7; 32],
+        retired: true,
+        reserve_shard_count: 4,
+        has_wrapper_inventory: true,
+        has_wrapper_limits: true,
+        has_fee_vault: true,
+        fee_vault_bump: 254,
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperConfig::LEN];
+    wrapper_config.pack(&mut data).unwrap();
+
+    assert_eq!(state::WrapperConfig::LEN, WrapperConfig::LEN);
+    let unpacked = state::WrapperConfig::unpack(&data).unwrap();
+    assert_eq!(unpacked.token_2022_mint, wrapper_config.token_2022_mint);
+    assert_eq!(unpacked.fee_recipient, wrapper_config.fee_recipient);
+    assert_eq!(unpacked.maximum_fee, 1_000);
+    assert!(unpacked.paused);
+    assert_eq!(
+        unpacked.migrated_reserve(),
+        wrapper_config.migrated_reserve()
+    );
+    assert_eq!(unpacked.token_2022_decimals, 6);
+    assert_eq!(unpacked.token_2022_mint_fingerprint, [7; 32]);
+    assert!(unpacked.retired);
+    assert_eq!(unpacked.reserve_shard_count, 4);
+    assert!(unpacked.has_wrapper_inventory);
+    assert!(unpacked.has_wrapper_limits);
+    assert!(unpacked.has_fee_vault);
+    assert_eq!(unpacked.fee_vault_bump, 254);
+    for amount in [0, 1, 399, 400, 10_000, u64::MAX] {
+        assert_eq!(unpacked.wrap_fee(amount), wrapper_config.wrap_fee(amount));
+        assert_eq!(
+            unpacked.unwrap_fee(amount),
+            wrapper_config.unwrap_fee(amount)
+        );
+    }
+
+    // A program config is not a wrapper config
+    assert!(state::WrapperConfig::unpack(&{
+        let mut data = vec![0; WrapperConfig::LEN];
+        program_config.pack(&mut data).unwrap();
+        data
+    })
+    .is_err());
+
+    let wrapper_group = WrapperGroup {
+        account_type: AccountType::WrapperGroup,
+        group: Pubkey::new_unique(),
+        wrapper_count: 3,
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperGroup::LEN];
+    wrapper_group.pack(&mut data).unwrap();
+
+    assert_eq!(state::WrapperGroup::LEN, WrapperGroup::LEN);
+    let unpacked = state::WrapperGroup::unpack(&data).unwrap();
+    assert_eq!(unpacked.group, wrapper_group.group);
+    assert_eq!(unpacked.wrapper_count, 3);
+
+    let wrapper_group_member = WrapperGroupMember {
+        account_type: AccountType::WrapperGroupMember,
+        group: wrapper_group.group,
+        token_2022_mint: Pubkey::new_unique(),
+        wrapper_token_mint: Pubkey::new_unique(),
+        member_number: 4,
+        index: 2,
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperGroupMember::LEN];
+    wrapper_group_member.pack(&mut data).unwrap();
+
+    assert_eq!(state::WrapperGroupMember::LEN, WrapperGroupMember::LEN);
+    let unpacked = state::WrapperGroupMember::unpack(&data).unwrap();
+    assert_eq!(unpacked.group, wrapper_group_member.group);
+    assert_eq!(
+        unpacked.token_2022_mint,
+        wrapper_group_member.token_2022_mint
+    );
+    assert_eq!(
+        unpacked.wrapper_token_mint,
+        wrapper_group_member.wrapper_token_mint
+    );
+    assert_eq!(unpacked.member_number, 4);
+    assert_eq!(unpacked.index, 2);
+
+    let wrapper_limits = WrapperLimits {
+        account_type: AccountType::WrapperLimits,
+        token_2022_mint: Pubkey::new_unique(),
+        supply_cap: 1_000,
+        epoch_wrap_limit: 100,
+        epoch_unwrap_limit: 50,
+        epoch: 7,
+        epoch_wrapped: 30,
+        epoch_unwrapped: 20,
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperLimits::LEN];
+    wrapper_limits.pack(&mut data).unwrap();
+
+    assert_eq!(state::WrapperLimits::LEN, WrapperLimits::LEN);
+    let unpacked = state::WrapperLimits::unpack(&data).unwrap();
+    assert_eq!(unpacked.token_2022_mint, wrapper_limits.token_2022_mint);
+    assert_eq!(unpacked.supply_cap, 1_000);
+    assert_eq!(unpacked.epoch_wrap_limit, 100);
+    assert_eq!(unpacked.epoch_unwrap_limit, 50);
+    assert_eq!(unpacked.epoch, 7);
+    assert_eq!(unpacked.epoch_wrapped, 30);
+    assert_eq!(unpacked.epoch_unwrapped, 20);
+
+    let issuer_freeze = IssuerFreeze {
+        account_type: AccountType::IssuerFreeze,
+        wrapper_token_account: Pubkey::new_unique(),
+        frozen: true,
+        ..Default::default()
+    };
+    let mut data = vec![0; IssuerFreeze::LEN];
+    issuer_freeze.pack(&mut data).unwrap();
+
+    assert_eq!(state::IssuerFreeze::LEN, IssuerFreeze::LEN);
+    let unpacked = state::IssuerFreeze::unpack(&data).unwrap();
+    assert_eq!(
+        unpacked.wrapper_token_account,
+        issuer_freeze.wrapper_token_account
+    );
+    assert!(unpacked.frozen);
+}
+
+/// Test 5 - the Anchor declaration matches the program instructions, accounts and errors
+///
+///
+#[test]
+fn test_5() {
+    let user = Pubkey::new_unique();
+    let token_2022_mint = Pubkey::new_unique();
+    let user_wrapper_token_account = Pubkey::new_unique();
+    let user_token_2022_token_account = Pubkey::new_unique();
+
+    let (wrapper_token_mint, _) = pda::find_wrapper_token_mint(&token_2022_mint);
+    let (reserve_authority, _) = pda::find_reserve_authority(&token_2022_mint);
+    let (reserve_token_2022_token_account, _) =
+        pda::find_reserve_authority_token_account(&token_2022_mint, &reserve_authority);
+    let (program_config, _) = pda::find_program_config();
+
+    let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+        &user,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensArgs {
+            amount: 1_000,
+            min_out: 990,
+            flags: 0,
+            memo: Some("wrap".to_string()),
+        },
+    );
+
+    let anchor_accounts = anchor::client::accounts::DepositAndMintWrapperTokensV2 {
+        user_authority: user,
+        reserve_authority,
+        token_2022_mint,
+        wrapper_token_mint,
+        user_wrapper_token_account,
+        user_token_2022_token_account,
+        reserve_token_2022_token_account,
+        token_program: spl_token::id(),
+        token_2022_program: spl_token_2022::id(),
+        program_config,
+    };
+    let anchor_args = anchor::client::args::DepositAndMintWrapperTokensV2 {
+        deposit_and_mint_wrapper_tokens_args: anchor::types::DepositAndMintWrapperTokensArgs {
+            amount: 1_000,
+            min_out: 990,
+            flags: 0,
+            memo: Some("wrap".to_string()),
+        },
+    };
+
+    assert_eq!(anchor::ID, token2022_wrapper::id());
+    assert_eq!(
+        anchor::program::Token2022Wrapper::id(),
+        token2022_wrapper::id()
+    );
+    assert_eq!(
+        Instruction {
+            program_id: anchor::ID,
+            accounts: anchor_accounts.to_account_metas(None),
+            data: anchor_args.data(),
+        },
+        deposit_ix,
+    );
+
+    let set_fee_ix = create_set_fee_instruction(
+        &user,
+        &token_2022_mint,
+        &user_token_2022_token_account,
+        SetFeeArgs {
+            wrap_fee_basis_points: 25,
+            unwrap_fee_basis_points: 50,
+            maximum_fee: 1_000,
+        },
+    );
+    let anchor_args = anchor::client::args::SetFee {
+        set_fee_args: anchor::types::SetFeeArgs {
+            wrap_fee_basis_points: 25,
+            unwrap_fee_basis_points: 50,
+            maximum_fee: 1_000,
+        },
+    };
+    assert_eq!(anchor_args.data(), set_fee_ix.data);
+
+    let idl: serde_json::Value =
+        serde_json::from_str(include_str!("../interface/idls/token2022_wrapper.json")).unwrap();
+
+    // Instruction discriminators are the instruction tags
+    for (tag, instruction) in idl["instructions"].as_array().unwrap().iter().enumerate() {
+        assert_eq!(instruction["discriminator"], serde_json::json!([tag]));
+    }
+    assert_eq!(
+        anchor::client::args::RegisterWrapperGroupMember::DISCRIMINATOR,
+        &[18]
+    );
+    assert_eq!(anchor::client::args::QuoteUnwrap::DISCRIMINATOR, &[20]);
+    assert_eq!(
+        anchor::client::args::WithdrawAndBurnWrapperTokensV3::DISCRIMINATOR,
+        &[24]
+    );
+    assert_eq!(
+        anchor::client::args::WithdrawToWrapperInventory::DISCRIMINATOR,
+        &[28]
+    );
+    assert_eq!(anchor::client::args::SetWrapperLimits::DISCRIMINATOR, &[29]);
+
+    // Account discriminators are the account types
+    let wrapper_config = WrapperConfig {
+        account_type: AccountType::WrapperConfig,
+        token_2022_mint,
+        fee_recipient: Pubkey::new_unique(),
+        wrap_fee_basis_points: 25,
+        unwrap_fee_basis_points: 50,
+        maximum_fee: 1_000,
+        paused: true,
+        token_2022_decimals: 6,
+        token_2022_mint_fingerprint: [7; 32],
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperConfig::LEN];
+    wrapper_config.pack(&mut data).unwrap();
+
+    let unpacked = anchor::accounts::WrapperConfig::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(unpacked.token_2022_mint, token_2022_mint);
+    assert_eq!(unpacked.fee_recipient, wrapper_config.fee_recipient);
+    assert_eq!(unpacked.wrap_fee_basis_points, 25);
+    assert_eq!(unpacked.unwrap_fee_basis_points, 50);
+    assert_eq!(unpacked.maximum_fee, 1_000);
+    assert!(unpacked.paused);
+    assert_eq!(unpacked.token_2022_decimals, 6);
+    assert_eq!(unpacked.token_2022_mint_fingerprint, [7; 32]);
+    assert!(!unpacked.retired);
+    assert!(anchor::accounts::ProgramConfig::try_deserialize(&mut data.as_slice()).is_err());
+
+    let wrapper_group = WrapperGroup {
+        account_type: AccountType::WrapperGroup,
+        group: Pubkey::new_unique(),
+        wrapper_count: 3,
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperGroup::LEN];
+    wrapper_group.pack(&mut data).unwrap();
+
+    let unpacked = anchor::accounts::WrapperGroup::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(unpacked.group, wrapper_group.group);
+    assert_eq!(unpacked.wrapper_count, 3);
+
+    let wrapper_limits = WrapperLimits {
+        account_type: AccountType::WrapperLimits,
+        token_2022_mint,
+        supply_cap: 1_000,
+        epoch: 7,
+        epoch_wrapped: 30,
+        ..Default::default()
+    };
+    let mut data = vec![0; WrapperLimits::LEN];
+    wrapper_limits.pack(&mut data).unwrap();
+
+    let unpacked = anchor::accounts::WrapperLimits::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(unpacked.token_2022_mint, token_2022_mint);
+    assert_eq!(unpacked.supply_cap, 1_000);
+    assert_eq!(unpacked.epoch, 7);
+    assert_eq!(unpacked.epoch_wrapped, 30);
+    assert!(anchor::accounts::WrapperConfig::try_deserialize(&mut data.as_slice()).is_err());
+
+    let issuer_freeze = IssuerFreeze {
+        account_type: AccountType::IssuerFreeze,
+        wrapper_token_account: user_wrapper_token_account,
+        frozen: true,
+        ..Default::default()
+    };
+    let mut data = vec![0; IssuerFreeze::LEN];
+    issuer_freeze.pack(&mut data).unwrap();
+
+    let unpacked = anchor::accounts::IssuerFreeze::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(unpacked.wrapper_token_account, user_wrapper_token_account);
+    assert!(unpacked.frozen);
+
+    // Errors are the ones of `TokenWrapperError`, all of them
+    let errors = idl["errors"].as_array().unwrap();
+    for error in errors {
+        let code = error["code"].as_u64().unwrap() as u32;
+        let program_error = TokenWrapperError::try_from(code).unwrap();
+        assert_eq!(error["name"], format!("{:?}", program_error));
+        assert_eq!(error["msg"], program_error.to_string());
+    }
+    assert!(TokenWrapperError::try_from(errors.len() as u32).is_err());
+}
+
+/// Program wrapping and unwrapping through `cpi::wrap` and `cpi::unwrap`, with the accounts of
+/// the v3 instructions followed by the wrapper program, the first byte of the data selects the
+/// instruction and the next eight hold the amount
+fn process_wrap_caller(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+
+    if data[0] == 0 {
+        let accounts = positional_accounts!(
+            DepositAndMintWrapperTokensV3,
+            accounts,
+            [
+                user_authority,
+                reserve_authority,
+                token_2022_mint,
+                wrapper_token_mint,
+                user_wrapper_token_account,
+                user_token_2022_token_account,
+                reserve_token_2022_token_account,
+                token_program,
+                token_2022_program,
+                program_config,
+                fee_vault,
+                wrapper_limits,
+            ]
+        );
+        cpi::wrap(&accounts, amount)
+    } else {
+        let accounts = positional_accounts!(
+            WithdrawAndBurnWrapperTokensV3,
+            accounts,
+            [
+                user_authority,
+                reserve_authority,
+                token_2022_mint,
+                wrapper_token_mint,
+                user_wrapper_token_account,
+                user_token_2022_token_account,
+                reserve_token_2022_token_account,
+                token_program,
+                token_2022_program,
+                fee_vault,
+                wrapper_limits,
+            ]
+        );
+        cpi::unwrap(&accounts, amount)
+    }
+}
+
+/// Test 6 - `cpi::wrap` and `cpi::unwrap` charge the fees of a wrapper with a fee vault
+///
+///
+#[tokio::test]
+async fn test_6() {
+    let wrap_caller = Pubkey::new_unique();
+    let mut test_client = TestClient::new_with_programs(|program| {
+        program.add_program("wrap_caller", wrap_caller, processor!(process_wrap_caller));
+    })
+    .await;
+    let payer = test_client.get_payer_clone();
+    let admin = test_client.admin.insecure_clone();
+    let user = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    airdrop(&mut test_client, &admin.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    airdrop(&mut test_client, &user.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    let token_2022_mint = create_token_2022_mint(
+        &mut test_client,
+        &mint_authority.pubkey(),
+        None,
+        6,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+    let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+    let (fee_vault, _, _) = get_fee_vault(token_2022_mint, reserve_authority, PROGRAM_ID);
+
+    sign_send_instructions(
+        &mut test_client,
+        &[
+            create_initialize_program_config_instruction(&admin.pubkey()),
+            create_initialize_wrapper_token_v2_instruction(&payer.pubkey(), &token_2022_mint),
+        ],
+        vec![&payer, &admin],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let user_token_2022_token_account = create_associated_token_account(
+        &mut test_client,
+        &user.pubkey(),
+        &token_2022_mint,
+        &spl_token_2022::id(),
+    )
+    .await
+    .unwrap();
+    let user_wrapper_token_account = create_associated_token_account(
+        &mut test_client,
+        &user.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+    let fee_recipient = create_associated_token_account(
+        &mut test_client,
+        &admin.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+    mint_token_2022_tokens(
+        &mut test_client,
+        &mint_authority,
+        &token_2022_mint,
+        &user_token_2022_token_account,
+        10_000,
+        None,
+    )
+    .await
+    .unwrap();
+
+    sign_send_instructions(
+        &mut test_client,
+        &[create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &fee_recipient,
+            SetFeeArgs {
+                wrap_fee_basis_points: 100,
+                unwrap_fee_basis_points: 50,
+                maximum_fee: 0,
+            },
+        )],
+        vec![&payer, &admin],
+        None,
+    )
+    .await
+    .unwrap();
+
+    // The accounts of the v3 instruction, then the wrapper program invoked by the caller
+    let caller_ix = |instruction: Instruction, unwrap: bool, amount: u64| {
+        let mut accounts = instruction.accounts;
+        accounts.push(AccountMeta::new_readonly(PROGRAM_ID, false));
+
+        Instruction {
+            program_id: wrap_caller,
+            accounts,
+            data: [&[unwrap as u8][..], &amount.to_le_bytes()].concat(),
+        }
+    };
+
+    // 1% of 10_000 is minted to the fee vault, 9_900 wrapper tokens to the user
+    let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+        &user.pubkey(),
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensV3Args::default(),
+    );
+    sign_send_instructions(
+        &mut test_client,
+        &[caller_ix(deposit_ix, false, 10_000)],
+        vec![&payer, &user],
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+        9_900
+    );
+    assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 100);
+
+    // 0.5% of 9_900 rounded up is transferred to the fee vault, the other 9_850 are withdrawn
+    let withdraw_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+        &user.pubkey(),
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        WithdrawAndBurnWrapperTokensV3Args::default(),
+    );
+    sign_send_instructions(
+        &mut test_client,
+        &[caller_ix(withdraw_ix, true, 9_900)],
+        vec![&payer, &user],
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+        0
+    );
+    assert_eq!(
+        get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+        9_850
+    );
+    assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 150);
+}
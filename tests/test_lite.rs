@@ -0,0 +1,473 @@
+pub mod utils;
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use token2022_wrapper::{
+    instruction::{
+        DepositAndMintWrapperTokensArgs, SetFeeArgs, SetPauseArgs, SetWrapperLimitsArgs,
+        WithdrawAndBurnWrapperTokensArgs,
+    },
+    instruction_builders::{
+        create_deposit_and_mint_wrapper_tokens_instruction,
+        create_deposit_and_mint_wrapper_tokens_v2_instruction,
+        create_initialize_program_config_instruction,
+        create_initialize_wrapper_token_v2_instruction, create_set_fee_instruction,
+        create_set_global_pause_instruction, create_set_pause_instruction,
+        create_set_wrapper_limits_instruction, create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction,
+    },
+    utils::{
+        get_fee_vault, get_reserve_authority, get_reserve_authority_token_account,
+        get_wrapper_limits, get_wrapper_token_mint,
+    },
+};
+use utils::{
+    airdrop, create_associated_token_account, create_token_2022_mint, get_account,
+    mint_token_2022_tokens, sign_send_instructions, test_transfer_fee_config_with_keypairs,
+    TestClient, TransferFeeConfigWithKeypairs,
+};
+
+/// Keypairs shared by both validators, so the wrapper accounts are at the same addresses
+struct Keys {
+    token_2022_mint: Keypair,
+    mint_authority: Keypair,
+    user: Keypair,
+    fee_owner: Keypair,
+    transfer_fee_config: TransferFeeConfigWithKeypairs,
+}
+
+impl Keys {
+    fn token_2022_mint(&self) -> Pubkey {
+        self.token_2022_mint.pubkey()
+    }
+
+    fn user_token_2022_token_account(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.user.pubkey(),
+            &self.token_2022_mint(),
+            &spl_token_2022::id(),
+        )
+    }
+
+    fn user_wrapper_token_account(&self) -> Pubkey {
+        let (wrapper_token_mint, _, _) =
+            get_wrapper_token_mint(self.token_2022_mint(), token2022_wrapper::id());
+
+        get_associated_token_address_with_program_id(
+            &self.user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+    }
+
+    fn fee_recipient(&self) -> Pubkey {
+        let (wrapper_token_mint, _, _) =
+            get_wrapper_token_mint(self.token_2022_mint(), token2022_wrapper::id());
+
+        get_associated_token_address_with_program_id(
+            &self.fee_owner.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+    }
+
+    /// Accounts written by the deposit and withdraw instructions
+    fn wrapper_accounts(&self) -> Vec<Pubkey> {
+        let (wrapper_token_mint, _, _) =
+            get_wrapper_token_mint(self.token_2022_mint(), token2022_wrapper::id());
+        let (reserve_authority, _, _) =
+            get_reserve_authority(self.token_2022_mint(), token2022_wrapper::id());
+        let (reserve_token_2022_token_account, _, _) = get_reserve_authority_token_account(
+            self.token_2022_mint(),
+            reserve_authority,
+            token2022_wrapper::id(),
+        );
+        let (wrapper_limits, _, _) =
+            get_wrapper_limits(self.token_2022_mint(), token2022_wrapper::id());
+        let (fee_vault, _, _) = get_fee_vault(
+            self.token_2022_mint(),
+            reserve_authority,
+            token2022_wrapper::id(),
+        );
+
+        vec![
+            self.token_2022_mint(),
+            wrapper_token_mint,
+            reserve_authority,
+            reserve_token_2022_token_account,
+            wrapper_limits,
+            fee_vault,
+            self.user_token_2022_token_account(),
+            self.user_wrapper_token_account(),
+            self.user.pubkey(),
+        ]
+    }
+}
+
+/// Sets up a wrapper of a Token 2022 mint with a transfer fee, and funds the user
+async fn setup(test_client: &mut TestClient, keys: &Keys) {
+    let payer = test_client.get_payer_clone();
+    let admin = test_client.admin.insecure_clone();
+
+    airdrop(test_client, &admin.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    airdrop(test_client, &keys.user.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    sign_send_instructions(
+        test_client,
+        &[create_initialize_program_config_instruction(
+            &admin.pubkey(),
+        )],
+        vec![&payer, &admin],
+        None,
+    )
+    .await
+    .unwrap();
+
+    create_token_2022_mint(
+        test_client,
+        &keys.mint_authority.pubkey(),
+        None,
+        6,
+        Some(keys.token_2022_mint.insecure_clone()),
+        Some(&keys.transfer_fee_config),
+    )
+    .await
+    .unwrap();
+
+    sign_send_instructions(
+        test_client,
+        &[create_initialize_wrapper_token_v2_instruction(
+            &payer.pubkey(),
+            &keys.token_2022_mint(),
+        )],
+        vec![&payer],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let (wrapper_token_mint, _, _) =
+        get_wrapper_token_mint(keys.token_2022_mint(), token2022_wrapper::id());
+    create_associated_token_account(
+        test_client,
+        &keys.user.pubkey(),
+        &keys.token_2022_mint(),
+        &spl_token_2022::id(),
+    )
+    .await
+    .unwrap();
+    create_associated_token_account(
+        test_client,
+        &keys.user.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+    create_associated_token_account(
+        test_client,
+        &keys.fee_owner.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+
+    mint_token_2022_tokens(
+        test_client,
+        &keys.mint_authority,
+        &keys.token_2022_mint(),
+        &keys.user_token_2022_token_account(),
+        1_000_000_000,
+        None,
+    )
+    .await
+    .unwrap();
+}
+
+/// Instruction of a scenario, signed by the user, or by the admin in place of the default pubkey
+enum Step {
+    User(Instruction),
+    Admin(Instruction),
+}
+
+fn scenario(keys: &Keys) -> Vec<(&'static str, Step)> {
+    let token_2022_mint = keys.token_2022_mint();
+    let user = keys.user.pubkey();
+    let user_token_2022_token_account = keys.user_token_2022_token_account();
+    let user_wrapper_token_account = keys.user_wrapper_token_account();
+
+    let fee_recipient = keys.fee_recipient();
+
+    let deposit_v2 = |amount, min_out, flags, memo: Option<&str>| {
+        create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user,
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount,
+                min_out,
+                flags,
+                memo: memo.map(str::to_string),
+            },
+        )
+    };
+    let withdraw_v2 = |amount, min_out, memo: Option<&str>| {
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user,
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount,
+                min_out,
+                flags: 0,
+                memo: memo.map(str::to_string),
+            },
+        )
+    };
+    let deposit_v1 = |amount| {
+        create_deposit_and_mint_wrapper_tokens_instruction(
+            &user,
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            amount,
+        )
+    };
+    let withdraw_v1 = |amount| {
+        create_withdraw_and_burn_wrapper_tokens_instruction(
+            &user,
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            amount,
+        )
+    };
+    let set_fee = |wrap_fee_basis_points, unwrap_fee_basis_points| {
+        create_set_fee_instruction(
+            &Pubkey::default(),
+            &token_2022_mint,
+            &fee_recipient,
+            SetFeeArgs {
+                wrap_fee_basis_points,
+                unwrap_fee_basis_points,
+                maximum_fee: 150_000,
+            },
+        )
+    };
+
+    let mut wrong_reserve_authority = deposit_v2(5_000_000, 0, 0, None);
+    wrong_reserve_authority.accounts[1].pubkey = user;
+
+    let mut wrong_wrapper_token_mint = deposit_v2(5_000_000, 0, 0, None);
+    wrong_wrapper_token_mint.accounts[3].pubkey = token_2022_mint;
+
+    let mut truncated_args = deposit_v2(6_000_000, 0, 0, None);
+    truncated_args.data.pop();
+
+    // v1 deposits require the program config, which follows the legacy accounts
+    let mut deposit_v1_without_program_config = create_deposit_and_mint_wrapper_tokens_instruction(
+        &user,
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        4_000_000,
+    );
+    deposit_v1_without_program_config.accounts.pop();
+
+    vec![
+        (
+            "deposit v2 with memo",
+            Step::User(deposit_v2(100_000_000, 0, 0, Some("lite"))),
+        ),
+        ("deposit v1", Step::User(deposit_v1(50_000_000))),
+        (
+            "withdraw v2",
+            Step::User(withdraw_v2(10_000_000, 9_000_000, None)),
+        ),
+        ("withdraw v1", Step::User(withdraw_v1(20_000_000))),
+        (
+            "deposit v2 below min out",
+            Step::User(deposit_v2(7_000_000, 7_000_000, 0, None)),
+        ),
+        (
+            "withdraw v2 below min out",
+            Step::User(withdraw_v2(8_000_000, 8_000_000, Some("slippage"))),
+        ),
+        (
+            "deposit v2 with flags",
+            Step::User(deposit_v2(9_000_000, 0, 1, None)),
+        ),
+        (
+            "deposit v2 with wrong reserve authority",
+            Step::User(wrong_reserve_authority),
+        ),
+        (
+            "deposit v2 with wrong wrapper token mint",
+            Step::User(wrong_wrapper_token_mint),
+        ),
+        ("deposit v2 with truncated args", Step::User(truncated_args)),
+        (
+            "deposit v1 without the program config",
+            Step::User(deposit_v1_without_program_config.clone()),
+        ),
+        (
+            "pause wrapper",
+            Step::Admin(create_set_pause_instruction(
+                &Pubkey::default(),
+                &token_2022_mint,
+                SetPauseArgs { paused: true },
+            )),
+        ),
+        (
+            "deposit v2 while paused",
+            Step::User(deposit_v2(11_000_000, 0, 0, None)),
+        ),
+        (
+            "withdraw v2 while paused",
+            Step::User(withdraw_v2(12_000_000, 0, None)),
+        ),
+        (
+            "resume wrapper",
+            Step::Admin(create_set_pause_instruction(
+                &Pubkey::default(),
+                &token_2022_mint,
+                SetPauseArgs { paused: false },
+            )),
+        ),
+        (
+            "pause all wrappers",
+            Step::Admin(create_set_global_pause_instruction(
+                &Pubkey::default(),
+                SetPauseArgs { paused: true },
+            )),
+        ),
+        (
+            "deposit v1 while globally paused",
+            Step::User(deposit_v1(13_000_000)),
+        ),
+        (
+            "deposit v1 without the program config while globally paused",
+            Step::User(deposit_v1_without_program_config),
+        ),
+        (
+            "resume all wrappers",
+            Step::Admin(create_set_global_pause_instruction(
+                &Pubkey::default(),
+                SetPauseArgs { paused: false },
+            )),
+        ),
+        (
+            "withdraw v2 of the full balance",
+            Step::User(withdraw_v2(u64::MAX, 0, None)),
+        ),
+        ("deposit v2", Step::User(deposit_v2(60_000_000, 0, 0, None))),
+        // v1 and v2 take neither the fee vault nor the wrapper limits
+        ("set fees", Step::Admin(set_fee(25, 40))),
+        (
+            "deposit v2 charged a wrap fee",
+            Step::User(deposit_v2(14_000_000, 0, 0, None)),
+        ),
+        (
+            "withdraw v1 charged an unwrap fee",
+            Step::User(withdraw_v1(15_000_000)),
+        ),
+        ("remove fees", Step::Admin(set_fee(0, 0))),
+        (
+            "deposit v1 without fees",
+            Step::User(deposit_v1(16_000_000)),
+        ),
+        (
+            "set wrapper limits",
+            Step::Admin(create_set_wrapper_limits_instruction(
+                &Pubkey::default(),
+                &token_2022_mint,
+                SetWrapperLimitsArgs {
+                    supply_cap: 150_000_000,
+                    epoch_wrap_limit: 30_000_000,
+                    epoch_unwrap_limit: 20_000_000,
+                },
+            )),
+        ),
+        (
+            "deposit v2 with wrapper limits",
+            Step::User(deposit_v2(17_000_000, 0, 0, None)),
+        ),
+        (
+            "withdraw v1 with wrapper limits",
+            Step::User(withdraw_v1(18_000_000)),
+        ),
+    ]
+}
+
+/// Sends `step` with the admin of `test_client`, and returns the error of the transaction if any
+async fn send(test_client: &mut TestClient, keys: &Keys, step: &Step) -> Option<String> {
+    let payer = test_client.get_payer_clone();
+    let admin = test_client.admin.insecure_clone();
+
+    let (instruction, signer) = match step {
+        Step::User(instruction) => (instruction.clone(), &keys.user),
+        Step::Admin(instruction) => {
+            let mut instruction = instruction.clone();
+            instruction.accounts[0].pubkey = admin.pubkey();
+            (instruction, &admin)
+        }
+    };
+
+    sign_send_instructions(test_client, &[instruction], vec![&payer, signer], None)
+        .await
+        .err()
+        .map(|error| error.to_string())
+}
+
+/// Test 1 - the `lite` deposit and withdraw handlers leave the same accounts and fail with the
+/// same errors as the processor
+///
+/// The program runs natively, so the CPIs of the `lite` handlers go through their `invoke_signed`
+/// fallback and not through `sol_invoke_signed_c`, which only the SBF build exercises.
+///
+#[tokio::test]
+async fn test_1() {
+    let keys = Keys {
+        token_2022_mint: Keypair::new(),
+        mint_authority: Keypair::new(),
+        user: Keypair::new(),
+        fee_owner: Keypair::new(),
+        transfer_fee_config: test_transfer_fee_config_with_keypairs(),
+    };
+
+    let mut reference_client = TestClient::new().await;
+    let mut lite_client = TestClient::new_with_lite_processor().await;
+
+    setup(&mut reference_client, &keys).await;
+    setup(&mut lite_client, &keys).await;
+
+    for (name, step) in scenario(&keys) {
+        let reference_error = send(&mut reference_client, &keys, &step).await;
+        let lite_error = send(&mut lite_client, &keys, &step).await;
+
+        assert_eq!(reference_error, lite_error, "{name}: results differ");
+
+        for address in keys.wrapper_accounts() {
+            let reference_account = get_account(&mut reference_client, &address).await;
+            let lite_account = get_account(&mut lite_client, &address).await;
+
+            assert_eq!(
+                (
+                    reference_account.lamports,
+                    reference_account.owner,
+                    reference_account.data
+                ),
+                (lite_account.lamports, lite_account.owner, lite_account.data),
+                "{name}: account {address} differs"
+            );
+        }
+    }
+}
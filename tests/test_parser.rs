@@ -0,0 +1,632 @@
+use serde_json::json;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::message::Message;
+use token2022_wrapper::{
+    instruction::{
+        DepositAndMintWrapperTokensArgs, DepositAndMintWrapperTokensV3Args, QuoteArgs,
+        RebalanceReserveShardsArgs, SetFeeArgs, SetPauseArgs, SetPendingAdminArgs,
+        SetWrapperLimitsArgs, WithdrawAndBurnWrapperTokensArgs, WithdrawAndBurnWrapperTokensV3Args,
+    },
+    instruction_builders::{
+        create_accept_admin_instruction, create_collect_fees_instruction,
+        create_deposit_and_mint_wrapper_tokens_instruction,
+        create_deposit_and_mint_wrapper_tokens_v2_instruction,
+        create_deposit_and_mint_wrapper_tokens_v3_instruction,
+        create_deposit_from_wrapper_inventory_instruction,
+        create_initialize_program_config_instruction, create_initialize_reserve_shard_instruction,
+        create_initialize_wrapper_inventory_instruction,
+        create_initialize_wrapper_token_instruction,
+        create_initialize_wrapper_token_v2_instruction, create_migrate_reserve_instruction,
+        create_mirror_freeze_instruction, create_mirror_thaw_instruction,
+        create_quote_unwrap_instruction, create_quote_wrap_instruction,
+        create_realloc_reserve_instruction, create_rebalance_reserve_shards_instruction,
+        create_register_wrapper_group_member_instruction, create_retire_wrapper_instruction,
+        create_set_fee_instruction, create_set_global_pause_instruction,
+        create_set_pause_instruction, create_set_pending_admin_instruction,
+        create_set_wrapper_limits_instruction, create_top_up_wrapper_inventory_instruction,
+        create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction,
+        create_withdraw_to_wrapper_inventory_instruction, with_reserve_shards,
+        with_wrapper_inventory, with_wrapper_limits,
+    },
+    parser::{
+        parse_account_data, parse_instruction, parse_wrapper_instruction, ParseError,
+        ParsedWrapperInstruction, UiProgramConfig, UiWrapperConfig, WrapperAccountType,
+    },
+    state::{
+        AccountType, IssuerFreeze, ProgramConfig, WrapperConfig, WrapperGroup, WrapperGroupMember,
+        WrapperLimits,
+    },
+};
+
+/// One instruction of each type, built by the program builders
+fn instructions(authority: &Pubkey, token_2022_mint: &Pubkey) -> Vec<Instruction> {
+    let token_account = Pubkey::new_unique();
+    let other_token_account = Pubkey::new_unique();
+
+    vec![
+        create_initialize_wrapper_token_instruction(authority, token_2022_mint),
+        create_deposit_and_mint_wrapper_tokens_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            1_000,
+        ),
+        create_withdraw_and_burn_wrapper_tokens_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            1_000,
+        ),
+        create_initialize_wrapper_token_v2_instruction(authority, token_2022_mint),
+        create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: u64::MAX,
+                min_out: 990,
+                flags: 0,
+                memo: Some("wrap".to_string()),
+            },
+        ),
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: 1_000,
+                min_out: 990,
+                ..Default::default()
+            },
+        ),
+        create_set_fee_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            SetFeeArgs {
+                wrap_fee_basis_points: 25,
+                unwrap_fee_basis_points: 50,
+                maximum_fee: 1_000,
+            },
+        ),
+        create_collect_fees_instruction(authority, token_2022_mint, &token_account),
+        create_initialize_program_config_instruction(authority),
+        create_set_pause_instruction(authority, token_2022_mint, SetPauseArgs { paused: true }),
+        create_set_global_pause_instruction(authority, SetPauseArgs { paused: false }),
+        create_set_pending_admin_instruction(
+            authority,
+            SetPendingAdminArgs {
+                pending_admin: other_token_account,
+            },
+        ),
+        create_accept_admin_instruction(authority),
+        create_mirror_freeze_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+        ),
+        create_mirror_thaw_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+        ),
+        create_migrate_reserve_instruction(authority, token_2022_mint),
+        create_realloc_reserve_instruction(authority, token_2022_mint),
+        create_retire_wrapper_instruction(authority, token_2022_mint),
+        create_register_wrapper_group_member_instruction(
+            authority,
+            token_2022_mint,
+            &Pubkey::new_unique(),
+        ),
+        create_quote_wrap_instruction(token_2022_mint, QuoteArgs { amount: 7 }),
+        create_quote_unwrap_instruction(token_2022_mint, QuoteArgs { amount: 7 }),
+        create_initialize_reserve_shard_instruction(authority, token_2022_mint, 1),
+        create_rebalance_reserve_shards_instruction(
+            authority,
+            token_2022_mint,
+            0,
+            RebalanceReserveShardsArgs {
+                source_reserve_shard: 0,
+                destination_reserve_shard: 2,
+                amount: u64::MAX,
+            },
+        ),
+        create_deposit_and_mint_wrapper_tokens_v3_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 2,
+                args: DepositAndMintWrapperTokensArgs {
+                    amount: 1_000,
+                    ..Default::default()
+                },
+            },
+        ),
+        create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 2,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: 1_000,
+                    ..Default::default()
+                },
+            },
+        ),
+        create_initialize_wrapper_inventory_instruction(authority, token_2022_mint),
+        create_top_up_wrapper_inventory_instruction(authority, token_2022_mint, 5_000),
+        create_deposit_from_wrapper_inventory_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 1,
+                args: DepositAndMintWrapperTokensArgs {
+                    amount: 1_000,
+                    ..Default::default()
+                },
+            },
+        ),
+        create_withdraw_to_wrapper_inventory_instruction(
+            authority,
+            token_2022_mint,
+            &token_account,
+            &other_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 1,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: 1_000,
+                    ..Default::default()
+                },
+            },
+        ),
+        create_set_wrapper_limits_instruction(
+            authority,
+            token_2022_mint,
+            SetWrapperLimitsArgs {
+                supply_cap: 1_000_000,
+                epoch_wrap_limit: 10_000,
+                epoch_unwrap_limit: 0,
+            },
+        ),
+    ]
+}
+
+/// camelCase of a PascalCase IDL name
+fn camel_case(name: &str) -> String {
+    name[..1].to_lowercase() + &name[1..]
+}
+
+/// Test 1 - every instruction of a transaction is parsed with the accounts of the IDL
+///
+///
+#[test]
+fn test_1() {
+    let idl: serde_json::Value =
+        serde_json::from_str(include_str!("../sdk/src/idl/token2022_wrapper.json")).unwrap();
+    let idl_instructions = idl["instructions"].as_array().unwrap();
+
+    let authority = Pubkey::new_unique();
+    let token_2022_mint = Pubkey::new_unique();
+    let instructions = instructions(&authority, &token_2022_mint);
+    assert_eq!(instructions.len(), idl_instructions.len());
+
+    for (instruction, idl_instruction) in instructions.iter().zip(idl_instructions) {
+        let message = Message::new(std::slice::from_ref(instruction), Some(&authority));
+        let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+
+        assert_eq!(
+            parsed.instruction_type,
+            camel_case(idl_instruction["name"].as_str().unwrap())
+        );
+        let idl_accounts = idl_instruction["accounts"].as_array().unwrap();
+        assert_eq!(idl_accounts.len(), instruction.accounts.len());
+        for (idl_account, account_meta) in idl_accounts.iter().zip(&instruction.accounts) {
+            assert_eq!(
+                parsed.info[idl_account["name"].as_str().unwrap()],
+                account_meta.pubkey.to_string()
+            );
+        }
+    }
+
+    // Arguments are decoded, `u64` amounts as strings
+    let parsed = parse_wrapper_instruction(
+        &instructions[4]
+            .accounts
+            .iter()
+            .map(|account_meta| account_meta.pubkey)
+            .collect::<Vec<_>>(),
+        &instructions[4].data,
+    )
+    .unwrap();
+    assert_eq!(parsed.info["amount"], u64::MAX.to_string());
+    assert_eq!(parsed.info["minOut"], "990");
+    assert_eq!(parsed.info["flags"], 0);
+    assert_eq!(parsed.info["memo"], "wrap");
+
+    let message = Message::new(&instructions[6..7], Some(&authority));
+    let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+    assert_eq!(parsed.info["wrapFeeBasisPoints"], 25);
+    assert_eq!(parsed.info["unwrapFeeBasisPoints"], 50);
+    assert_eq!(parsed.info["maximumFee"], "1000");
+
+    let message = Message::new(&instructions[22..23], Some(&authority));
+    let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+    assert_eq!(parsed.info["sourceReserveShard"], 0);
+    assert_eq!(parsed.info["destinationReserveShard"], 2);
+    assert_eq!(parsed.info["amount"], u64::MAX.to_string());
+
+    let message = Message::new(&instructions[23..24], Some(&authority));
+    let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+    assert_eq!(parsed.info["reserveShard"], 2);
+    assert_eq!(parsed.info["amount"], "1000");
+
+    let message = Message::new(&instructions[26..27], Some(&authority));
+    let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+    assert_eq!(parsed.info["amount"], "5000");
+
+    let message = Message::new(&instructions[29..30], Some(&authority));
+    let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+    assert_eq!(parsed.instruction_type, "setWrapperLimits");
+    assert_eq!(parsed.info["supplyCap"], "1000000");
+    assert_eq!(parsed.info["epochWrapLimit"], "10000");
+    assert_eq!(parsed.info["epochUnwrapLimit"], "0");
+
+    // Serialized as by `solana-transaction-status`
+    let message = Message::new(&instructions[10..11], Some(&authority));
+    let parsed = parse_instruction(&message.instructions[0], &message.account_keys).unwrap();
+    let program_config = message.account_keys[message.instructions[0].accounts[1] as usize];
+    assert_eq!(
+        serde_json::to_value(&parsed).unwrap(),
+        json!({
+            "type": "setGlobalPause",
+            "info": {
+                "admin": authority.to_string(),
+                "programConfig": program_config.to_string(),
+                "paused": false,
+            },
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<ParsedWrapperInstruction>(serde_json::to_value(&parsed).unwrap())
+            .unwrap(),
+        parsed
+    );
+}
+
+/// Test 2 - instructions of other programs, with invalid data or missing accounts are rejected
+///
+///
+#[test]
+fn test_2() {
+    let authority = Pubkey::new_unique();
+    let instruction =
+        create_set_global_pause_instruction(&authority, SetPauseArgs { paused: true });
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|account_meta| account_meta.pubkey)
+        .collect::<Vec<_>>();
+
+    let mut message = Message::new(std::slice::from_ref(&instruction), Some(&authority));
+    message.account_keys[message.instructions[0].program_id_index as usize] = spl_token::id();
+    assert_eq!(
+        parse_instruction(&message.instructions[0], &message.account_keys),
+        Err(ParseError::UnexpectedProgramId(spl_token::id()))
+    );
+
+    let mut message = Message::new(std::slice::from_ref(&instruction), Some(&authority));
+    message.instructions[0].accounts[1] = 42;
+    assert_eq!(
+        parse_instruction(&message.instructions[0], &message.account_keys),
+        Err(ParseError::AccountIndexOutOfBounds(42))
+    );
+
+    assert_eq!(
+        parse_wrapper_instruction(&accounts, &[10]),
+        Err(ParseError::InvalidInstructionData)
+    );
+    assert_eq!(
+        parse_wrapper_instruction(&accounts, &[200, 0]),
+        Err(ParseError::InvalidInstructionData)
+    );
+    assert_eq!(
+        parse_wrapper_instruction(&accounts[..1], &instruction.data),
+        Err(ParseError::NotEnoughAccounts {
+            expected: 2,
+            actual: 1
+        })
+    );
+
+    // Extra accounts are listed as remaining accounts
+    let extra_account = Pubkey::new_unique();
+    let parsed = parse_wrapper_instruction(
+        &[&accounts[..], &[extra_account]].concat(),
+        &instruction.data,
+    )
+    .unwrap();
+    assert_eq!(parsed.info.as_object().unwrap().len(), 4);
+    assert_eq!(
+        parsed.info["remainingAccounts"],
+        json!([extra_account.to_string()])
+    );
+}
+
+/// Test 3 - the accounts of the program are decoded by their account type
+///
+///
+#[test]
+fn test_3() {
+    let token_2022_mint = Pubkey::new_unique();
+    let fee_recipient = Pubkey::new_unique();
+    let migrated_reserve = Pubkey::new_unique();
+
+    let mut data = vec![0; WrapperConfig::LEN];
+    WrapperConfig {
+        account_type: AccountType::WrapperConfig,
+        token_2022_mint,
+        fee_recipient,
+        wrap_fee_basis_points: 25,
+        unwrap_fee_basis_points: 50,
+        maximum_fee: 1_000,
+        paused: true,
+        reserve_token_2022_token_account: migrated_reserve,
+        token_2022_decimals: 6,
+        token_2022_mint_fingerprint: [0xab; 32],
+        reserve_shard_count: 3,
+        has_wrapper_inventory: true,
+        has_wrapper_limits: true,
+        has_fee_vault: true,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+    assert_eq!(
+        parse_account_data(&data).unwrap(),
+        WrapperAccountType::WrapperConfig(UiWrapperConfig {
+            token_2022_mint: token_2022_mint.to_string(),
+            fee_recipient: fee_recipient.to_string(),
+            wrap_fee_basis_points: 25,
+            unwrap_fee_basis_points: 50,
+            maximum_fee: "1000".to_string(),
+            paused: true,
+            reserve_token_2022_token_account: Some(migrated_reserve.to_string()),
+            token_2022_decimals: 6,
+            token_2022_mint_fingerprint: Some("ab".repeat(32)),
+            retired: false,
+            reserve_shard_count: 3,
+            has_wrapper_inventory: true,
+            has_wrapper_limits: true,
+            has_fee_vault: true,
+        })
+    );
+
+    let admin = Pubkey::new_unique();
+    let mut data = vec![0; ProgramConfig::LEN];
+    ProgramConfig {
+        account_type: AccountType::ProgramConfig,
+        admin,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+    let parsed = parse_account_data(&data).unwrap();
+    assert_eq!(
+        parsed,
+        WrapperAccountType::ProgramConfig(UiProgramConfig {
+            admin: admin.to_string(),
+            pending_admin: None,
+            paused: false,
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(&parsed).unwrap(),
+        json!({
+            "type": "programConfig",
+            "info": {
+                "admin": admin.to_string(),
+                "pendingAdmin": null,
+                "paused": false,
+            },
+        })
+    );
+
+    let group = Pubkey::new_unique();
+    let mut data = vec![0; WrapperGroup::LEN];
+    WrapperGroup {
+        account_type: AccountType::WrapperGroup,
+        group,
+        wrapper_count: 3,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(parse_account_data(&data).unwrap()).unwrap(),
+        json!({
+            "type": "wrapperGroup",
+            "info": {
+                "group": group.to_string(),
+                "wrapperCount": "3",
+            },
+        })
+    );
+
+    let wrapper_token_mint = Pubkey::new_unique();
+    let mut data = vec![0; WrapperGroupMember::LEN];
+    WrapperGroupMember {
+        account_type: AccountType::WrapperGroupMember,
+        group,
+        token_2022_mint,
+        wrapper_token_mint,
+        member_number: 2,
+        index: 1,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(parse_account_data(&data).unwrap()).unwrap(),
+        json!({
+            "type": "wrapperGroupMember",
+            "info": {
+                "group": group.to_string(),
+                "token2022Mint": token_2022_mint.to_string(),
+                "wrapperTokenMint": wrapper_token_mint.to_string(),
+                "memberNumber": 2,
+                "index": "1",
+            },
+        })
+    );
+
+    let mut data = vec![0; WrapperLimits::LEN];
+    WrapperLimits {
+        account_type: AccountType::WrapperLimits,
+        token_2022_mint,
+        supply_cap: 1_000_000,
+        epoch_wrap_limit: 10_000,
+        epoch: 12,
+        epoch_wrapped: 4_000,
+        epoch_unwrapped: 500,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(parse_account_data(&data).unwrap()).unwrap(),
+        json!({
+            "type": "wrapperLimits",
+            "info": {
+                "token2022Mint": token_2022_mint.to_string(),
+                "supplyCap": "1000000",
+                "epochWrapLimit": "10000",
+                "epochUnwrapLimit": "0",
+                "epoch": "12",
+                "epochWrapped": "4000",
+                "epochUnwrapped": "500",
+            },
+        })
+    );
+
+    let wrapper_token_account = Pubkey::new_unique();
+    let mut data = vec![0; IssuerFreeze::LEN];
+    IssuerFreeze {
+        account_type: AccountType::IssuerFreeze,
+        wrapper_token_account,
+        frozen: true,
+        ..Default::default()
+    }
+    .pack(&mut data)
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(parse_account_data(&data).unwrap()).unwrap(),
+        json!({
+            "type": "issuerFreeze",
+            "info": {
+                "wrapperTokenAccount": wrapper_token_account.to_string(),
+                "frozen": true,
+            },
+        })
+    );
+
+    // Uninitialized, empty and truncated accounts
+    assert_eq!(
+        parse_account_data(&[0; WrapperConfig::LEN]),
+        Err(ParseError::InvalidAccountData)
+    );
+    assert_eq!(parse_account_data(&[]), Err(ParseError::InvalidAccountData));
+    assert_eq!(
+        parse_account_data(&[AccountType::WrapperConfig as u8; 10]),
+        Err(ParseError::InvalidAccountData)
+    );
+    assert_eq!(
+        parse_account_data(&[9]),
+        Err(ParseError::InvalidAccountData)
+    );
+}
+
+/// Test 4 - trailing optional accounts are named when passed, v1 deposits sent without the
+/// program config are parsed, and the reserve shards are listed as remaining accounts
+///
+///
+#[test]
+fn test_4() {
+    let authority = Pubkey::new_unique();
+    let token_2022_mint = Pubkey::new_unique();
+    let accounts_of = |instruction: &Instruction| {
+        instruction
+            .accounts
+            .iter()
+            .map(|account_meta| account_meta.pubkey)
+            .collect::<Vec<_>>()
+    };
+
+    // The 12 accounts of the v1 deposit built by earlier versions of the SDK
+    let instruction = create_deposit_and_mint_wrapper_tokens_instruction(
+        &authority,
+        &token_2022_mint,
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        1_000,
+    );
+    let accounts = accounts_of(&instruction);
+    let parsed = parse_wrapper_instruction(&accounts[..12], &instruction.data).unwrap();
+    assert_eq!(parsed.instruction_type, "depositAndMintWrapperTokens");
+    assert_eq!(parsed.info["rent"], accounts[11].to_string());
+    assert_eq!(parsed.info["amount"], "1000");
+    assert!(parsed.info.get("programConfig").is_none());
+    assert!(parsed.info.get("remainingAccounts").is_none());
+
+    let parsed = parse_wrapper_instruction(&accounts, &instruction.data).unwrap();
+    assert_eq!(parsed.info["programConfig"], accounts[12].to_string());
+
+    assert_eq!(
+        parse_wrapper_instruction(&accounts[..11], &instruction.data),
+        Err(ParseError::NotEnoughAccounts {
+            expected: 12,
+            actual: 11
+        })
+    );
+
+    // The wrapper limits of top ups once the wrapper has them
+    let instruction = with_wrapper_limits(
+        create_top_up_wrapper_inventory_instruction(&authority, &token_2022_mint, 7),
+        &token_2022_mint,
+    );
+    let accounts = accounts_of(&instruction);
+    let parsed = parse_wrapper_instruction(&accounts, &instruction.data).unwrap();
+    assert_eq!(parsed.info["wrapperLimits"], accounts[7].to_string());
+    assert!(parsed.info.get("remainingAccounts").is_none());
+
+    // The reserve shards and the wrapper inventory taken after the fixed accounts
+    let instruction = with_wrapper_inventory(
+        with_reserve_shards(
+            create_migrate_reserve_instruction(&authority, &token_2022_mint),
+            &token_2022_mint,
+            2,
+        ),
+        &token_2022_mint,
+    );
+    let accounts = accounts_of(&instruction);
+    let parsed = parse_wrapper_instruction(&accounts, &instruction.data).unwrap();
+    assert_eq!(
+        parsed.info["remainingAccounts"],
+        json!(accounts[10..]
+            .iter()
+            .map(Pubkey::to_string)
+            .collect::<Vec<_>>())
+    );
+}
@@ -0,0 +1,235 @@
+use solana_client::rpc_filter::RpcFilterType;
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{
+        metadata_pointer::MetadataPointer, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensionsMut,
+    },
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+use token2022_wrapper::{
+    client::wrapper_config_filters,
+    registry::{Registry, RegistryEntry, RegistryError, MAINNET_REGISTRY_JSON},
+    state::{AccountType, ProgramConfig, WrapperConfig},
+    utils::get_wrapper_token_mint,
+};
+
+const PYUSD: &str = "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo";
+
+/// Token 2022 mint account data with `decimals`, and token metadata with `symbol` if any
+fn token_2022_mint_data(mint: Pubkey, decimals: u8, symbol: Option<&str>) -> Vec<u8> {
+    let metadata = symbol.map(|symbol| TokenMetadata {
+        mint,
+        name: "Token".to_string(),
+        symbol: symbol.to_string(),
+        ..Default::default()
+    });
+    let (extension_types, metadata_len) = match &metadata {
+        Some(metadata) => (
+            vec![ExtensionType::MetadataPointer],
+            metadata.tlv_size_of().unwrap(),
+        ),
+        None => (vec![], 0),
+    };
+    let mut data = vec![
+        0;
+        ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap()
+            + metadata_len
+    ];
+
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    if let Some(metadata) = &metadata {
+        state
+            .init_extension::<MetadataPointer>(true)
+            .unwrap()
+            .metadata_address = Some(mint).try_into().unwrap();
+        state.init_variable_len_extension(metadata, false).unwrap();
+    }
+    state.base = Mint {
+        mint_authority: COption::Some(Pubkey::new_unique()),
+        decimals,
+        is_initialized: true,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    data
+}
+
+fn entry(token_2022_mint: Pubkey) -> RegistryEntry {
+    RegistryEntry {
+        token_2022_mint,
+        token_name: "TEST".to_string(),
+        decimals: 6,
+        wrapper_token_mint: get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id()).0,
+    }
+}
+
+/// Test 1 - the embedded mainnet registry is valid and looked up by either mint
+///
+///
+#[test]
+fn test_1() {
+    let registry = Registry::mainnet();
+    assert!(!registry.entries().is_empty());
+
+    let pyusd: Pubkey = PYUSD.parse().unwrap();
+    let entry = registry.find_by_token_2022_mint(&pyusd).unwrap();
+    assert_eq!(entry.token_name, "PYUSD");
+    assert_eq!(entry.decimals, 6);
+    assert_eq!(
+        entry.wrapper_token_mint,
+        get_wrapper_token_mint(pyusd, token2022_wrapper::id()).0
+    );
+    assert_eq!(
+        registry.find_by_wrapper_token_mint(&entry.wrapper_token_mint),
+        Some(entry)
+    );
+    assert_eq!(
+        registry.find_by_token_2022_mint(&entry.wrapper_token_mint),
+        None
+    );
+    assert_eq!(registry.find_by_wrapper_token_mint(&pyusd), None);
+
+    // Serializing the registry gives back the file
+    assert_eq!(registry.to_json(), MAINNET_REGISTRY_JSON);
+    assert_eq!(Registry::from_json(&registry.to_json()).unwrap(), *registry);
+}
+
+/// Test 2 - registries with an underived wrapper token mint, a duplicate mint or invalid JSON
+/// are rejected
+///
+///
+#[test]
+fn test_2() {
+    let token_2022_mint = Pubkey::new_unique();
+
+    let mut underived = entry(token_2022_mint);
+    underived.wrapper_token_mint = Pubkey::new_unique();
+    assert!(matches!(
+        Registry::new(vec![entry(Pubkey::new_unique()), underived.clone()]),
+        Err(RegistryError::UnexpectedWrapperTokenMint { token_2022_mint: mint, listed, .. })
+            if mint == token_2022_mint && listed == underived.wrapper_token_mint
+    ));
+
+    assert!(matches!(
+        Registry::new(vec![entry(token_2022_mint), entry(token_2022_mint)]),
+        Err(RegistryError::DuplicateToken2022Mint(mint)) if mint == token_2022_mint
+    ));
+
+    let json = Registry::new(vec![entry(token_2022_mint)])
+        .unwrap()
+        .to_json();
+    assert!(matches!(
+        Registry::from_json(&json.replace(&token_2022_mint.to_string(), "not-a-pubkey")),
+        Err(RegistryError::Json(_))
+    ));
+    assert!(matches!(
+        Registry::from_json(&json.replace("\"decimals\"", "\"decimal\"")),
+        Err(RegistryError::Json(_))
+    ));
+    assert!(matches!(
+        Registry::from_json(&underived_json(&json, &underived)),
+        Err(RegistryError::UnexpectedWrapperTokenMint { .. })
+    ));
+}
+
+fn underived_json(json: &str, underived: &RegistryEntry) -> String {
+    let (derived, _, _) =
+        get_wrapper_token_mint(underived.token_2022_mint, token2022_wrapper::id());
+    json.replace(
+        &derived.to_string(),
+        &underived.wrapper_token_mint.to_string(),
+    )
+}
+
+/// Test 3 - entries built from a Token 2022 mint use its metadata symbol and decimals, and are
+/// validated against the decimals of the mint
+///
+///
+#[test]
+fn test_3() {
+    let token_2022_mint = Pubkey::new_unique();
+    let (wrapper_token_mint, _, _) =
+        get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id());
+
+    let with_metadata = token_2022_mint_data(token_2022_mint, 9, Some("WRAP"));
+    let entry =
+        RegistryEntry::from_token_2022_mint(token_2022_mint, &with_metadata, Some("OLD")).unwrap();
+    assert_eq!(
+        entry,
+        RegistryEntry {
+            token_2022_mint,
+            token_name: "WRAP".to_string(),
+            decimals: 9,
+            wrapper_token_mint,
+        }
+    );
+    entry.validate().unwrap();
+    entry.validate_mint(&with_metadata).unwrap();
+
+    // Without metadata, or with an empty symbol, the known name then the mint address are used
+    let without_metadata = token_2022_mint_data(token_2022_mint, 6, None);
+    let entry =
+        RegistryEntry::from_token_2022_mint(token_2022_mint, &without_metadata, Some("OLD"))
+            .unwrap();
+    assert_eq!(entry.token_name, "OLD");
+    assert_eq!(entry.decimals, 6);
+
+    let empty_symbol = token_2022_mint_data(token_2022_mint, 6, Some(""));
+    let entry = RegistryEntry::from_token_2022_mint(token_2022_mint, &empty_symbol, None).unwrap();
+    assert_eq!(entry.token_name, token_2022_mint.to_string());
+
+    assert!(matches!(
+        entry.validate_mint(&with_metadata),
+        Err(RegistryError::UnexpectedDecimals {
+            listed: 6,
+            actual: 9,
+            ..
+        })
+    ));
+    assert!(matches!(
+        entry.validate_mint(&[0; 10]),
+        Err(RegistryError::Program(_))
+    ));
+}
+
+/// Test 4 - the generator filters of `getProgramAccounts` select the wrapper configs only
+///
+///
+#[test]
+fn test_4() {
+    let allowed = |data: &[u8]| {
+        wrapper_config_filters().iter().all(|filter| match filter {
+            RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+            RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(data),
+            RpcFilterType::TokenAccountState => unreachable!(),
+        })
+    };
+
+    let mut wrapper_config = vec![0; WrapperConfig::LEN];
+    WrapperConfig {
+        account_type: AccountType::WrapperConfig,
+        token_2022_mint: Pubkey::new_unique(),
+        ..Default::default()
+    }
+    .pack(&mut wrapper_config)
+    .unwrap();
+    assert!(allowed(&wrapper_config));
+
+    let mut program_config = vec![0; ProgramConfig::LEN];
+    ProgramConfig {
+        account_type: AccountType::ProgramConfig,
+        ..Default::default()
+    }
+    .pack(&mut program_config)
+    .unwrap();
+    assert!(!allowed(&program_config));
+
+    wrapper_config[0] = AccountType::Uninitialized as u8;
+    assert!(!allowed(&wrapper_config));
+
+    assert!(!allowed(&[AccountType::WrapperConfig as u8; Mint::LEN]));
+}
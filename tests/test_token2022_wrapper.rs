@@ -3,22 +3,41 @@ pub mod utils;
 use std::time::Duration;
 
 use crate::utils::TestClient;
-use solana_program::pubkey::Pubkey;
+use solana_program::{pubkey::Pubkey, system_instruction};
 use solana_program_test::*;
-use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey, signature::Keypair, signer::Signer};
-use spl_associated_token_account::get_associated_token_address;
+use solana_sdk::{
+    account::Account, native_token::LAMPORTS_PER_SOL, pubkey, signature::Keypair, signer::Signer,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use spl_token_2022::{
+    extension::{
+        immutable_owner::ImmutableOwner, BaseStateWithExtensions, BaseStateWithExtensionsMut,
+        ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+    },
+    state::{Account as Token2022Account, AccountState, Mint as Token2022Mint},
+};
 use token2022_wrapper::{
+    instruction::{
+        AmountArgs, DepositAndMintWrapperTokensArgs, TokenWrapperInstruction,
+        WithdrawAndBurnWrapperTokensArgs,
+    },
     instruction_builders::{
-        create_deposit_and_mint_wrapper_tokens_instruction,
-        create_initialize_wrapper_token_instruction,
-        create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_collect_fees_instruction, create_deposit_and_mint_wrapper_tokens_instruction,
+        create_deposit_and_mint_wrapper_tokens_v2_instruction,
+        create_initialize_program_config_instruction, create_initialize_wrapper_token_instruction,
+        create_initialize_wrapper_token_v2_instruction, create_migrate_reserve_instruction,
+        create_set_fee_instruction, create_withdraw_and_burn_wrapper_tokens_instruction,
+        create_withdraw_and_burn_wrapper_tokens_v2_instruction, with_migrated_reserve,
     },
     utils::get_wrapper_token_mint,
 };
 use utils::{
     airdrop, assert_with_msg, create_associated_token_account, create_mint, create_token_2022_mint,
-    create_token_account_token_2022, extract_error_code, get_token_balance, get_token_balance_2022,
-    get_token_mint, mint_token_2022_tokens, mint_tokens, sign_send_instructions,
+    create_token_account_token_2022, extract_error_code, get_account, get_token_account,
+    get_token_account_2022, get_token_balance, get_token_balance_2022, get_token_mint,
+    mint_token_2022_tokens, mint_tokens, rent_exempt, sign_send_instructions, simulate_return_data,
     test_transfer_fee_config_with_keypairs, TransferFeeConfigWithKeypairs,
 };
 
@@ -142,11 +161,270 @@ pub async fn create_and_mint_frozen_tokens_token_2022(
     (token_mint, token_account, status)
 }
 
+pub async fn create_wrapped_token_2022_balance(
+    client: &mut TestClient,
+    owner: &Keypair,
+    amount: u64,
+    decimals: u8,
+) -> (Pubkey, Pubkey, Pubkey) {
+    let payer_keypair = client.get_payer_clone();
+
+    let token_2022_mint = create_token_2022_mint(
+        client,
+        &payer_keypair.pubkey(),
+        Some(&payer_keypair.pubkey()),
+        decimals,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let user_token_2022_token_account = create_associated_token_account(
+        client,
+        &owner.pubkey(),
+        &token_2022_mint,
+        &spl_token_2022::id(),
+    )
+    .await
+    .unwrap();
+
+    mint_token_2022_tokens(
+        client,
+        &payer_keypair,
+        &token_2022_mint,
+        &user_token_2022_token_account,
+        amount,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let initialize_ix =
+        create_initialize_wrapper_token_v2_instruction(&payer_keypair.pubkey(), &token_2022_mint);
+
+    sign_send_instructions(client, &[initialize_ix], vec![&payer_keypair], None)
+        .await
+        .unwrap();
+
+    let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+
+    let user_wrapper_token_account = create_associated_token_account(
+        client,
+        &owner.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+
+    let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+        &owner.pubkey(),
+        &token_2022_mint,
+        &user_wrapper_token_account,
+        &user_token_2022_token_account,
+        DepositAndMintWrapperTokensArgs {
+            amount,
+            ..Default::default()
+        },
+    );
+
+    sign_send_instructions(client, &[deposit_ix], vec![owner, &payer_keypair], None)
+        .await
+        .unwrap();
+
+    (
+        token_2022_mint,
+        user_token_2022_token_account,
+        user_wrapper_token_account,
+    )
+}
+
+pub async fn initialize_program_config(client: &mut TestClient) {
+    let payer_keypair = client.get_payer_clone();
+    let admin = client.admin.insecure_clone();
+
+    let initialize_program_config_ix =
+        create_initialize_program_config_instruction(&admin.pubkey());
+
+    sign_send_instructions(
+        client,
+        &[initialize_program_config_ix],
+        vec![&admin, &payer_keypair],
+        None,
+    )
+    .await
+    .unwrap();
+}
+
+/// Creates a Token 2022 mint holding a token group, with the payer as mint and update authority
+pub async fn create_token_group_mint(client: &mut TestClient) -> Pubkey {
+    let payer_keypair = client.get_payer_clone();
+    let group_mint_keypair = Keypair::new();
+    let group_mint = group_mint_keypair.pubkey();
+
+    let space =
+        ExtensionType::try_calculate_account_len::<Token2022Mint>(&[ExtensionType::GroupPointer])
+            .unwrap();
+    // The group extension is allocated by `initialize_group`, which expects the lamports upfront
+    let lamports = rent_exempt(
+        ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::GroupPointer,
+            ExtensionType::TokenGroup,
+        ])
+        .unwrap(),
+    );
+
+    sign_send_instructions(
+        client,
+        &[
+            system_instruction::create_account(
+                &payer_keypair.pubkey(),
+                &group_mint,
+                lamports,
+                space as u64,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::extension::group_pointer::instruction::initialize(
+                &spl_token_2022::id(),
+                &group_mint,
+                Some(payer_keypair.pubkey()),
+                Some(group_mint),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::id(),
+                &group_mint,
+                &payer_keypair.pubkey(),
+                None,
+                0,
+            )
+            .unwrap(),
+            spl_token_group_interface::instruction::initialize_group(
+                &spl_token_2022::id(),
+                &group_mint,
+                &group_mint,
+                &payer_keypair.pubkey(),
+                Some(payer_keypair.pubkey()),
+                10,
+            ),
+        ],
+        vec![&payer_keypair, &group_mint_keypair],
+        None,
+    )
+    .await
+    .unwrap();
+
+    group_mint
+}
+
+/// Creates a Token 2022 mint, member of the token group of `group_mint`
+pub async fn create_token_group_member_mint(
+    client: &mut TestClient,
+    group_mint: &Pubkey,
+) -> Pubkey {
+    let payer_keypair = client.get_payer_clone();
+    let member_mint_keypair = Keypair::new();
+    let member_mint = member_mint_keypair.pubkey();
+
+    let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+        ExtensionType::GroupMemberPointer,
+    ])
+    .unwrap();
+    let lamports = rent_exempt(
+        ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::GroupMemberPointer,
+            ExtensionType::TokenGroupMember,
+        ])
+        .unwrap(),
+    );
+
+    sign_send_instructions(
+        client,
+        &[
+            system_instruction::create_account(
+                &payer_keypair.pubkey(),
+                &member_mint,
+                lamports,
+                space as u64,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::extension::group_member_pointer::instruction::initialize(
+                &spl_token_2022::id(),
+                &member_mint,
+                Some(payer_keypair.pubkey()),
+                Some(member_mint),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::id(),
+                &member_mint,
+                &payer_keypair.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+            spl_token_group_interface::instruction::initialize_member(
+                &spl_token_2022::id(),
+                &member_mint,
+                &member_mint,
+                &payer_keypair.pubkey(),
+                group_mint,
+                &payer_keypair.pubkey(),
+            ),
+        ],
+        vec![&payer_keypair, &member_mint_keypair],
+        None,
+    )
+    .await
+    .unwrap();
+
+    member_mint
+}
+
 mod tests {
 
     use std::u64;
 
-    use token2022_wrapper::{error::TokenWrapperError, utils::get_reserve_authority};
+    use solana_sdk::{
+        account_info::IntoAccountInfo, clock::Clock, instruction::InstructionError,
+        transaction::TransactionError,
+    };
+    use token2022_wrapper::{
+        error::TokenWrapperError,
+        instruction::{
+            DepositAndMintWrapperTokensV3Args, QuoteArgs, RebalanceReserveShardsArgs, SetFeeArgs,
+            SetPauseArgs, SetPendingAdminArgs, SetWrapperLimitsArgs,
+            WithdrawAndBurnWrapperTokensV3Args,
+        },
+        instruction_builders::{
+            create_accept_admin_instruction, create_deposit_and_mint_wrapper_tokens_v3_instruction,
+            create_deposit_from_wrapper_inventory_instruction,
+            create_initialize_reserve_shard_instruction,
+            create_initialize_wrapper_inventory_instruction, create_mirror_freeze_instruction,
+            create_mirror_thaw_instruction, create_quote_unwrap_instruction,
+            create_quote_wrap_instruction, create_realloc_reserve_instruction,
+            create_rebalance_reserve_shards_instruction,
+            create_register_wrapper_group_member_instruction, create_retire_wrapper_instruction,
+            create_set_global_pause_instruction, create_set_pause_instruction,
+            create_set_pending_admin_instruction, create_set_wrapper_limits_instruction,
+            create_top_up_wrapper_inventory_instruction,
+            create_withdraw_and_burn_wrapper_tokens_v3_instruction,
+            create_withdraw_to_wrapper_inventory_instruction, with_reserve_shards,
+            with_wrapper_inventory, with_wrapper_limits,
+        },
+        quote::{quote_unwrap, quote_wrap, Quote},
+        state::{
+            IssuerFreeze, WrapperConfig, WrapperGroup, WrapperGroupMember, WrapperLimits,
+            MAX_RESERVE_SHARDS,
+        },
+        utils::{
+            get_fee_vault, get_issuer_freeze, get_program_config, get_reserve_authority,
+            get_reserve_authority_token_account, get_reserve_shard, get_wrapper_group,
+            get_wrapper_group_member, get_wrapper_inventory, get_wrapper_limits,
+            is_wrapper_group_member,
+        },
+    };
 
     use super::*;
 
@@ -2606,4 +2884,5673 @@ mod tests {
             }
         };
     }
+
+    /// Test 20 - v2 instructions with minimal account lists initialize, mint and burn
+    ///
+    ///
+    #[tokio::test]
+    async fn test_20() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 10_000_u64 * 10_u64.pow(decimal_2022 as u32);
+        let amount_wrapper = amount_2022 / 4;
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            None,
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+        assert_eq!(initialize_ix.accounts.len(), 8);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let wrapper_token_ac = get_token_mint(&mut test_client, &wrapper_token_mint)
+            .await
+            .unwrap();
+        assert_eq!(wrapper_token_ac.decimals, decimal_2022);
+        assert_eq!(wrapper_token_ac.supply, 0);
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: amount_wrapper,
+                min_out: amount_wrapper,
+                flags: 0,
+                memo: Some("wrap".to_string()),
+            },
+        );
+        assert_eq!(deposit_ix.accounts.len(), 10);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022 - amount_wrapper,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            amount_wrapper,
+        );
+
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: amount_wrapper,
+                min_out: amount_wrapper,
+                flags: 0,
+                memo: None,
+            },
+        );
+        assert_eq!(burn_ix.accounts.len(), 9);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            0,
+        );
+    }
+
+    /// Test 21 - v1 and v2 instructions operate on the same wrapper
+    ///
+    ///
+    #[tokio::test]
+    async fn test_21() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let decimal_2022 = 9_u8;
+        let amount_2022 = 1_000_u64 * 10_u64.pow(decimal_2022 as u32);
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            None,
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix =
+            create_initialize_wrapper_token_instruction(&payer_keypair.pubkey(), &token_2022_mint);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Initializing again through v2 must fail the same way as v1
+        let duplicate_initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[duplicate_initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedWrapperToken as u32),
+        );
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: amount_2022,
+                ..Default::default()
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            u64::MAX,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            0,
+        );
+    }
+
+    /// Test 22 - instruction data round trips through pack/unpack and trailing bytes are rejected
+    ///
+    ///
+    #[test]
+    fn test_22() {
+        let instructions = vec![
+            TokenWrapperInstruction::InitializeWrapperToken,
+            TokenWrapperInstruction::DepositAndMintWrapperTokens(AmountArgs { amount: 42 }),
+            TokenWrapperInstruction::WithdrawAndBurnWrapperTokens(AmountArgs { amount: u64::MAX }),
+            TokenWrapperInstruction::InitializeWrapperTokenV2,
+            TokenWrapperInstruction::DepositAndMintWrapperTokensV2(
+                DepositAndMintWrapperTokensArgs {
+                    amount: 1_000,
+                    min_out: 990,
+                    flags: 0,
+                    memo: Some("memo".to_string()),
+                },
+            ),
+            TokenWrapperInstruction::WithdrawAndBurnWrapperTokensV2(
+                WithdrawAndBurnWrapperTokensArgs {
+                    amount: 1_000,
+                    min_out: 990,
+                    flags: 0,
+                    memo: None,
+                },
+            ),
+        ];
+
+        for (tag, instruction) in instructions.into_iter().enumerate() {
+            let data = instruction.pack();
+            assert_eq!(data[0], tag as u8);
+            assert_eq!(TokenWrapperInstruction::unpack(&data).unwrap(), instruction);
+
+            let mut trailing_data = data.clone();
+            trailing_data.push(0);
+            assert!(TokenWrapperInstruction::unpack(&trailing_data).is_err());
+        }
+
+        // The v1 layout stays a tag followed by a little-endian u64 amount
+        let mut legacy_data = vec![1];
+        legacy_data.extend_from_slice(&7_u64.to_le_bytes());
+        assert_eq!(
+            TokenWrapperInstruction::unpack(&legacy_data).unwrap(),
+            TokenWrapperInstruction::DepositAndMintWrapperTokens(AmountArgs { amount: 7 }),
+        );
+
+        assert!(TokenWrapperInstruction::unpack(&[]).is_err());
+        assert!(TokenWrapperInstruction::unpack(&[6]).is_err());
+        assert!(TokenWrapperInstruction::unpack(&[1, 0, 0]).is_err());
+    }
+
+    /// Test 23 - v2 deposit and withdraw enforce min_out and reject reserved flags
+    ///
+    ///
+    #[tokio::test]
+    async fn test_23() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 10_000_u64;
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            Some(&transfer_fee_config),
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        // 1% transfer fee, depositing 1000 mints 990 wrapper tokens
+        let deposit_args = DepositAndMintWrapperTokensArgs {
+            amount: 1_000,
+            min_out: 991,
+            flags: 0,
+            memo: None,
+        };
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            deposit_args.clone(),
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SlippageExceeded as u32),
+        );
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                min_out: 990,
+                flags: 1,
+                ..deposit_args.clone()
+            },
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnsupportedInstructionFlags as u32),
+        );
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                min_out: 990,
+                ..deposit_args
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            990,
+        );
+
+        // Withdrawing 990 delivers 980 Token 2022 tokens after the 1% transfer fee
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: 990,
+                min_out: 981,
+                flags: 0,
+                memo: None,
+            },
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SlippageExceeded as u32),
+        );
+
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: 990,
+                min_out: 980,
+                flags: 0,
+                memo: None,
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022 - 1_000 + 980,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            0,
+        );
+    }
+
+    /// Test 24 - wrap and unwrap fees are collected in the fee vault and moved to the fee recipient
+    /// by the admin, a maximum fee of zero does not cap them
+    ///
+    ///
+    #[tokio::test]
+    async fn test_24() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 1_000_000_u64;
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            None,
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let (fee_vault, _, _) = get_fee_vault(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+        let fee_recipient = create_associated_token_account(
+            &mut test_client,
+            &admin.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let set_fee_ix = create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &fee_recipient,
+            SetFeeArgs {
+                wrap_fee_basis_points: 100,
+                unwrap_fee_basis_points: 50,
+                maximum_fee: 0,
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The v2 deposit takes no fee vault, so it cannot charge the wrap fee
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: 10_000,
+                ..Default::default()
+            },
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnsupportedInstructionVersion as u32),
+        );
+
+        // 1% of 10_000 is minted to the fee vault, 9_900 wrapper tokens to the user
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: DepositAndMintWrapperTokensArgs {
+                    amount: 10_000,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            9_900,
+        );
+        assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 100);
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            10_000,
+        );
+
+        // 0.5% of 9_900 rounded up is transferred to the fee vault, the other 9_850 wrapper
+        // tokens are burned and withdrawn
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: u64::MAX,
+                    min_out: 9_850,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            0,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022 - 150,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            150,
+        );
+        assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 150);
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            150,
+        );
+
+        let collect_fees_ix =
+            create_collect_fees_instruction(&admin.pubkey(), &token_2022_mint, &fee_recipient);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[collect_fees_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &fee_recipient).await,
+            150
+        );
+        assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 0);
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            150,
+        );
+    }
+
+    /// Test 25 - only the admin can set fees, fees are bounded, paid to a wrapper token account and
+    /// apply to v1 instructions
+    ///
+    ///
+    #[tokio::test]
+    async fn test_25() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 1_000_000_u64;
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            None,
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix =
+            create_initialize_wrapper_token_instruction(&payer_keypair.pubkey(), &token_2022_mint);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let set_fee_args = SetFeeArgs {
+            wrap_fee_basis_points: 1_000,
+            unwrap_fee_basis_points: 1_000,
+            maximum_fee: 10,
+        };
+
+        let set_fee_ix = create_set_fee_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            set_fee_args,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        let set_fee_ix = create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            SetFeeArgs {
+                wrap_fee_basis_points: 1_001,
+                ..set_fee_args
+            },
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::FeeTooHigh as u32),
+        );
+
+        // Fees are collected in wrapper tokens
+        let set_fee_ix = create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            set_fee_args,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidTokenAccount as u32),
+        );
+
+        let set_fee_ix = create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            set_fee_args,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // 10% of 10_000 is capped to the maximum fee of 10
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: DepositAndMintWrapperTokensArgs {
+                    amount: 10_000,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            9_990,
+        );
+
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: 9_990,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022 - 20,
+        );
+    }
+
+    /// Test 26 - paused wrappers reject deposits and still allow withdrawals
+    ///
+    ///
+    #[tokio::test]
+    async fn test_26() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 1_000_000_u64;
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            None,
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let deposit_v1_ix = create_deposit_and_mint_wrapper_tokens_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            10_000,
+        );
+        let deposit_v2_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: 10_000,
+                ..Default::default()
+            },
+        );
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: 5_000,
+                ..Default::default()
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&deposit_v2_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // A different program config account is rejected
+        let mut spoofed_deposit_ix = deposit_v2_ix.clone();
+        spoofed_deposit_ix.accounts[9].pubkey = Pubkey::new_unique();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[spoofed_deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidProgramConfig as u32),
+        );
+
+        // Pausing the wrapper
+        let set_pause_ix = create_set_pause_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            SetPauseArgs { paused: true },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_pause_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        for deposit_ix in [&deposit_v1_ix, &deposit_v2_ix] {
+            let e = sign_send_instructions(
+                &mut test_client,
+                std::slice::from_ref(deposit_ix),
+                vec![&user, &payer_keypair],
+                None,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(
+                extract_error_code(e.to_string().as_str()),
+                Some(TokenWrapperError::DepositsPaused as u32),
+            );
+        }
+
+        sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&burn_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            5_000,
+        );
+
+        let set_pause_ix = create_set_pause_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            SetPauseArgs { paused: false },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_pause_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&deposit_v1_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Pausing all wrappers, only the admin can
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_set_global_pause_instruction(
+                &user.pubkey(),
+                SetPauseArgs { paused: true },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_set_global_pause_instruction(
+                &admin.pubkey(),
+                SetPauseArgs { paused: true },
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&deposit_v2_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::DepositsPaused as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            10_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            amount_2022 - 10_000,
+        );
+    }
+
+    /// Test 27 - rotating the admin in two steps
+    ///
+    ///
+    #[tokio::test]
+    async fn test_27() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+        let new_admin = Keypair::new();
+        let user = Keypair::new();
+
+        // Only the upgrade authority can initialize the program config
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_program_config_instruction(
+                &payer_keypair.pubkey(),
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_set_pending_admin_instruction(
+                &admin.pubkey(),
+                SetPendingAdminArgs {
+                    pending_admin: new_admin.pubkey(),
+                },
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The pending admin has no rights until it accepts
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_set_global_pause_instruction(
+                &new_admin.pubkey(),
+                SetPauseArgs { paused: true },
+            )],
+            vec![&new_admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_accept_admin_instruction(&user.pubkey())],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_accept_admin_instruction(&new_admin.pubkey())],
+            vec![&new_admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_set_global_pause_instruction(
+                &admin.pubkey(),
+                SetPauseArgs { paused: true },
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_set_global_pause_instruction(
+                &new_admin.pubkey(),
+                SetPauseArgs { paused: true },
+            )],
+            vec![&new_admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The pending admin is cleared once accepted
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_accept_admin_instruction(&new_admin.pubkey())],
+            vec![&new_admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+    }
+
+    /// Test 28 - wrapper token accounts mirror the freeze state of the owner's Token 2022 account
+    ///
+    ///
+    #[tokio::test]
+    async fn test_28() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let watcher = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &watcher.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        let mirror_freeze_ix = create_mirror_freeze_instruction(
+            &watcher.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user.pubkey(),
+        );
+        let mirror_thaw_ix = create_mirror_thaw_instruction(
+            &watcher.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user.pubkey(),
+        );
+
+        // The Token 2022 token account is not frozen
+        let e = sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&mirror_freeze_ix),
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::FreezeStateMismatch as u32),
+        );
+
+        let freeze_ix = spl_token_2022::instruction::freeze_account(
+            &spl_token_2022::id(),
+            &user_token_2022_token_account,
+            &token_2022_mint,
+            &payer_keypair.pubkey(),
+            &[&payer_keypair.pubkey()],
+        )
+        .unwrap();
+
+        sign_send_instructions(&mut test_client, &[freeze_ix], vec![&payer_keypair], None)
+            .await
+            .unwrap();
+
+        // The failed transaction is cached with its blockhash, the retry waits for a new one
+        let blockhash = test_client
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+        test_client
+            .banks_client
+            .get_new_latest_blockhash(&blockhash)
+            .await
+            .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&mirror_freeze_ix),
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            get_token_account(&mut test_client, &user_wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen()
+        );
+
+        // The Token 2022 token account is still frozen
+        let e = sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&mirror_thaw_ix),
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::FreezeStateMismatch as u32),
+        );
+
+        let thaw_ix = spl_token_2022::instruction::thaw_account(
+            &spl_token_2022::id(),
+            &user_token_2022_token_account,
+            &token_2022_mint,
+            &payer_keypair.pubkey(),
+            &[&payer_keypair.pubkey()],
+        )
+        .unwrap();
+
+        sign_send_instructions(&mut test_client, &[thaw_ix], vec![&payer_keypair], None)
+            .await
+            .unwrap();
+
+        // The failed transaction is cached with its blockhash, the retry waits for a new one
+        let blockhash = test_client
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+        test_client
+            .banks_client
+            .get_new_latest_blockhash(&blockhash)
+            .await
+            .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&mirror_thaw_ix),
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            !get_token_account(&mut test_client, &user_wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen()
+        );
+    }
+
+    /// Test 29 - the Token 2022 freeze authority freezes and thaws any wrapper token account
+    ///
+    ///
+    #[tokio::test]
+    async fn test_29() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let watcher = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &watcher.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let (token_2022_mint, _, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        // The recipient holds wrapper tokens only, without a Token 2022 token account
+        let recipient = Keypair::new();
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let recipient_wrapper_token_account = create_associated_token_account(
+            &mut test_client,
+            &recipient.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let transfer_ix = spl_token::instruction::transfer(
+            &spl_token::id(),
+            &user_wrapper_token_account,
+            &recipient_wrapper_token_account,
+            &user.pubkey(),
+            &[&user.pubkey()],
+            4_000,
+        )
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[transfer_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        for (wrapper_token_account, owner) in [
+            (user_wrapper_token_account, user.pubkey()),
+            (recipient_wrapper_token_account, recipient.pubkey()),
+        ] {
+            // Only the freeze authority can freeze without a frozen Token 2022 token account
+            assert!(sign_send_instructions(
+                &mut test_client,
+                &[create_mirror_freeze_instruction(
+                    &watcher.pubkey(),
+                    &token_2022_mint,
+                    &wrapper_token_account,
+                    &owner,
+                )],
+                vec![&watcher, &payer_keypair],
+                None,
+            )
+            .await
+            .is_err());
+
+            sign_send_instructions(
+                &mut test_client,
+                &[create_mirror_freeze_instruction(
+                    &payer_keypair.pubkey(),
+                    &token_2022_mint,
+                    &wrapper_token_account,
+                    &owner,
+                )],
+                vec![&payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+
+            assert!(get_token_account(&mut test_client, &wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen());
+
+            sign_send_instructions(
+                &mut test_client,
+                &[create_mirror_thaw_instruction(
+                    &payer_keypair.pubkey(),
+                    &token_2022_mint,
+                    &wrapper_token_account,
+                    &owner,
+                )],
+                vec![&payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+
+            assert!(!get_token_account(&mut test_client, &wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen());
+        }
+    }
+
+    /// Test 30 - migrating the reserve to the reserve authority associated token account
+    ///
+    ///
+    #[tokio::test]
+    async fn test_30() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let new_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+            &reserve_authority,
+            &token_2022_mint,
+            &spl_token_2022::id(),
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_migrate_reserve_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_migrate_reserve_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            0,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            10_000,
+        );
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            10_000,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_migrate_reserve_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::ReserveAlreadyMigrated as u32),
+        );
+
+        // The original reserve is no longer accepted
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            4_000,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&burn_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedReserveTokenAccount as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_migrated_reserve(burn_ix, &token_2022_mint)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: 1_000,
+                ..Default::default()
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_migrated_reserve(deposit_ix, &token_2022_mint)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            7_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            7_000,
+        );
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            7_000,
+        );
+    }
+
+    /// Test 31 - migrating a reserve with transfer fees requires a surplus covering the fee
+    ///
+    ///
+    #[tokio::test]
+    async fn test_31() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 10_000_u64;
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            Some(&transfer_fee_config),
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let new_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+            &reserve_authority,
+            &token_2022_mint,
+            &spl_token_2022::id(),
+        );
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_wrapper_token_v2_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        // 1% transfer fee, the reserve receives 9_900 backing 9_900 wrapper tokens
+        sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount: amount_2022,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Moving 9_900 would leave 9_801 in the new reserve
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_migrate_reserve_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::ReserveUndercollateralized as u32),
+        );
+
+        // A surplus of 200 covers the 101 transfer fee on 10_100
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &reserve_token_2022_token_account,
+            200,
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_migrate_reserve_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            9_999,
+        );
+
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: u64::MAX,
+                ..Default::default()
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_migrated_reserve(burn_ix, &token_2022_mint)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            0,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            9_801,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            99,
+        );
+    }
+
+    /// Test 32 - the reserve is sized for the account extensions required by the mint
+    ///
+    ///
+    #[tokio::test]
+    async fn test_32() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        let (token_2022_mint, _) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &payer_keypair.pubkey(),
+            10_000,
+            6,
+            Some(&transfer_fee_config),
+        )
+        .await;
+
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_wrapper_token_v2_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let expected_len = ExtensionType::try_calculate_account_len::<Token2022Account>(&[
+            ExtensionType::TransferFeeAmount,
+            ExtensionType::ImmutableOwner,
+        ])
+        .unwrap();
+
+        let reserve_data = get_account(&mut test_client, &reserve_token_2022_token_account)
+            .await
+            .data;
+        assert_eq!(reserve_data.len(), expected_len);
+
+        let reserve_extensions = StateWithExtensions::<Token2022Account>::unpack(&reserve_data)
+            .unwrap()
+            .get_extension_types()
+            .unwrap();
+        assert!(reserve_extensions.contains(&ExtensionType::TransferFeeAmount));
+        assert!(reserve_extensions.contains(&ExtensionType::ImmutableOwner));
+
+        // Nothing to grow
+        sign_send_instructions(
+            &mut test_client,
+            &[create_realloc_reserve_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_account(&mut test_client, &reserve_token_2022_token_account)
+                .await
+                .data
+                .len(),
+            expected_len,
+        );
+    }
+
+    /// Test 33 - growing a reserve missing an account extension required by the mint
+    ///
+    ///
+    #[tokio::test]
+    async fn test_33() {
+        let token_2022_mint_keypair = Keypair::new();
+        let token_2022_mint = token_2022_mint_keypair.pubkey();
+
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+
+        // A reserve sized for `ImmutableOwner` only, as if the mint had gained an extension
+        // requiring account space after the wrapper was initialized
+        let undersized_len = ExtensionType::try_calculate_account_len::<Token2022Account>(&[
+            ExtensionType::ImmutableOwner,
+        ])
+        .unwrap();
+        let mut reserve_data = vec![0; undersized_len];
+        let mut reserve =
+            StateWithExtensionsMut::<Token2022Account>::unpack_uninitialized(&mut reserve_data)
+                .unwrap();
+        reserve.init_extension::<ImmutableOwner>(true).unwrap();
+        reserve.base = Token2022Account {
+            mint: token_2022_mint,
+            owner: reserve_authority,
+            state: AccountState::Initialized,
+            ..Default::default()
+        };
+        reserve.pack_base();
+        reserve.init_account_type().unwrap();
+
+        let mut test_client = TestClient::new_with_accounts(vec![(
+            reserve_token_2022_token_account,
+            Account {
+                lamports: rent_exempt(undersized_len),
+                data: reserve_data,
+                owner: spl_token_2022::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )])
+        .await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        create_token_2022_mint(
+            &mut test_client,
+            &payer_keypair.pubkey(),
+            Some(&payer_keypair.pubkey()),
+            6,
+            Some(token_2022_mint_keypair),
+            Some(&transfer_fee_config),
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_realloc_reserve_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let reserve_account =
+            get_account(&mut test_client, &reserve_token_2022_token_account).await;
+        let expected_len = ExtensionType::try_calculate_account_len::<Token2022Account>(&[
+            ExtensionType::ImmutableOwner,
+            ExtensionType::TransferFeeAmount,
+        ])
+        .unwrap();
+
+        assert_eq!(reserve_account.data.len(), expected_len);
+        assert!(reserve_account.lamports >= rent_exempt(expected_len));
+    }
+
+    /// Test 34 - a Token 2022 mint closed and re-created with other extensions is rejected, and
+    /// the wrapper can then be retired by anyone
+    ///
+    ///
+    #[tokio::test]
+    async fn test_34() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let token_2022_mint_keypair = Keypair::new();
+        let token_2022_mint = token_2022_mint_keypair.pubkey();
+
+        let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::MintCloseAuthority,
+        ])
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                system_instruction::create_account(
+                    &payer_keypair.pubkey(),
+                    &token_2022_mint,
+                    rent_exempt(space),
+                    space as u64,
+                    &spl_token_2022::id(),
+                ),
+                spl_token_2022::instruction::initialize_mint_close_authority(
+                    &spl_token_2022::id(),
+                    &token_2022_mint,
+                    Some(&payer_keypair.pubkey()),
+                )
+                .unwrap(),
+                spl_token_2022::instruction::initialize_mint2(
+                    &spl_token_2022::id(),
+                    &token_2022_mint,
+                    &payer_keypair.pubkey(),
+                    None,
+                    6,
+                )
+                .unwrap(),
+            ],
+            vec![&payer_keypair, &token_2022_mint_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_wrapper_token_v2_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+
+        let user_token_2022_token_account = create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &token_2022_mint,
+            &spl_token_2022::id(),
+        )
+        .await
+        .unwrap();
+        let user_wrapper_token_account = create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            1_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensArgs {
+                amount: 1_000,
+                ..Default::default()
+            },
+        );
+        let withdraw_ix = create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensArgs {
+                amount: 1_000,
+                ..Default::default()
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix.clone(), withdraw_ix.clone()],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The mint is unchanged, only the admin can retire the wrapper
+        let retire_ix = create_retire_wrapper_instruction(&user.pubkey(), &token_2022_mint);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&retire_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        // Everything unwrapped, the supply is burned and the mint closed
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                spl_token_2022::instruction::burn(
+                    &spl_token_2022::id(),
+                    &user_token_2022_token_account,
+                    &token_2022_mint,
+                    &user.pubkey(),
+                    &[],
+                    1_000,
+                )
+                .unwrap(),
+                spl_token_2022::instruction::close_account(
+                    &spl_token_2022::id(),
+                    &token_2022_mint,
+                    &payer_keypair.pubkey(),
+                    &payer_keypair.pubkey(),
+                    &[],
+                )
+                .unwrap(),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Re-created at the same address with the same decimals, without the close authority
+        create_token_2022_mint(
+            &mut test_client,
+            &payer_keypair.pubkey(),
+            None,
+            6,
+            Some(token_2022_mint_keypair),
+            None,
+        )
+        .await
+        .unwrap();
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            1_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            std::slice::from_ref(&deposit_ix),
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SourceMintChanged as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[retire_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let wrapper_config =
+            WrapperConfig::unpack(&get_account(&mut test_client, &reserve_authority).await.data)
+                .unwrap();
+        assert!(wrapper_config.retired);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::WrapperRetired as u32),
+        );
+    }
+
+    /// Test 35 - a retired wrapper rejects deposits and still allows withdrawals
+    ///
+    ///
+    #[tokio::test]
+    async fn test_35() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 1_000, 6).await;
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_retire_wrapper_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount: 1,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::WrapperRetired as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensArgs {
+                    amount: u64::MAX,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            0,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            1_000,
+        );
+    }
+
+    /// Test 36 - wrapping and unwrapping Token 2022 native SOL, with SOL sent directly to the
+    /// reserve kept as surplus
+    ///
+    ///
+    #[tokio::test]
+    async fn test_36() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let native_mint = spl_token_2022::native_mint::id();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                spl_token_2022::instruction::create_native_mint(
+                    &spl_token_2022::id(),
+                    &payer_keypair.pubkey(),
+                )
+                .unwrap(),
+                create_initialize_wrapper_token_v2_instruction(
+                    &payer_keypair.pubkey(),
+                    &native_mint,
+                ),
+            ],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(native_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(native_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(native_mint, reserve_authority, PROGRAM_ID);
+
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .decimals,
+            spl_token_2022::native_mint::DECIMALS,
+        );
+
+        let user_token_2022_token_account = create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &native_mint,
+            &spl_token_2022::id(),
+        )
+        .await
+        .unwrap();
+        let user_wrapper_token_account = create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                system_instruction::transfer(
+                    &user.pubkey(),
+                    &user_token_2022_token_account,
+                    2 * LAMPORTS_PER_SOL,
+                ),
+                spl_token_2022::instruction::sync_native(
+                    &spl_token_2022::id(),
+                    &user_token_2022_token_account,
+                )
+                .unwrap(),
+                create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                    &user.pubkey(),
+                    &native_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    DepositAndMintWrapperTokensArgs {
+                        amount: LAMPORTS_PER_SOL,
+                        ..Default::default()
+                    },
+                ),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let reserve_lamports = get_account(&mut test_client, &reserve_token_2022_token_account)
+            .await
+            .lamports;
+
+        // SOL sent straight to the reserve is synced as surplus, not credited to the next deposit
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                system_instruction::transfer(
+                    &user.pubkey(),
+                    &reserve_token_2022_token_account,
+                    LAMPORTS_PER_SOL / 2,
+                ),
+                create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                    &user.pubkey(),
+                    &native_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    DepositAndMintWrapperTokensArgs {
+                        amount: LAMPORTS_PER_SOL / 2,
+                        ..Default::default()
+                    },
+                ),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            3 * LAMPORTS_PER_SOL / 2,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            2 * LAMPORTS_PER_SOL,
+        );
+        assert_eq!(
+            get_account(&mut test_client, &reserve_token_2022_token_account)
+                .await
+                .lamports,
+            reserve_lamports + LAMPORTS_PER_SOL,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &native_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensArgs {
+                    amount: u64::MAX,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            2 * LAMPORTS_PER_SOL,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            LAMPORTS_PER_SOL / 2,
+        );
+        assert_eq!(
+            get_account(&mut test_client, &reserve_token_2022_token_account)
+                .await
+                .lamports,
+            reserve_lamports - LAMPORTS_PER_SOL / 2,
+        );
+    }
+
+    /// Test 37 - registering the wrappers of token group members in their wrapper group
+    ///
+    ///
+    #[tokio::test]
+    async fn test_37() {
+        let mut test_client = TestClient::new_with_token_2022_from_crate().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let group_mint = create_token_group_mint(&mut test_client).await;
+        let member_mints = [
+            create_token_group_member_mint(&mut test_client, &group_mint).await,
+            create_token_group_member_mint(&mut test_client, &group_mint).await,
+        ];
+        let (other_mint, _) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &payer_keypair.pubkey(),
+            0,
+            6,
+            None,
+        )
+        .await;
+
+        for token_2022_mint in member_mints.iter().chain([&other_mint]) {
+            sign_send_instructions(
+                &mut test_client,
+                &[create_initialize_wrapper_token_v2_instruction(
+                    &payer_keypair.pubkey(),
+                    token_2022_mint,
+                )],
+                vec![&payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        for token_2022_mint in &member_mints {
+            sign_send_instructions(
+                &mut test_client,
+                &[create_register_wrapper_group_member_instruction(
+                    &payer_keypair.pubkey(),
+                    token_2022_mint,
+                    &group_mint,
+                )],
+                vec![&payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        // Registering again is a no-op, the transfer keeps the transaction distinct from the first
+        // registration
+        let register_ix = create_register_wrapper_group_member_instruction(
+            &payer_keypair.pubkey(),
+            &member_mints[0],
+            &group_mint,
+        );
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                system_instruction::transfer(&payer_keypair.pubkey(), &group_mint, 1),
+                register_ix,
+            ],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_group, _, _) = get_wrapper_group(group_mint, PROGRAM_ID);
+        let wrapper_group_data =
+            WrapperGroup::unpack(&get_account(&mut test_client, &wrapper_group).await.data)
+                .unwrap();
+        assert_eq!(wrapper_group_data.group, group_mint);
+        assert_eq!(wrapper_group_data.wrapper_count, 2);
+
+        for (index, token_2022_mint) in member_mints.iter().enumerate() {
+            let (wrapper_group_member, _, _) =
+                get_wrapper_group_member(group_mint, *token_2022_mint, PROGRAM_ID);
+            let mut wrapper_group_member_account =
+                get_account(&mut test_client, &wrapper_group_member).await;
+
+            let member = WrapperGroupMember::unpack(&wrapper_group_member_account.data).unwrap();
+            let (wrapper_token_mint, _, _) = get_wrapper_token_mint(*token_2022_mint, PROGRAM_ID);
+            assert_eq!(member.token_2022_mint, *token_2022_mint);
+            assert_eq!(member.wrapper_token_mint, wrapper_token_mint);
+            assert_eq!(member.member_number, index as u32 + 1);
+            assert_eq!(member.index, index as u64);
+
+            let wrapper_group_member_info =
+                (&wrapper_group_member, &mut wrapper_group_member_account).into_account_info();
+            assert!(is_wrapper_group_member(
+                &PROGRAM_ID,
+                &group_mint,
+                token_2022_mint,
+                &wrapper_group_member_info,
+            ));
+            assert!(!is_wrapper_group_member(
+                &PROGRAM_ID,
+                &other_mint,
+                token_2022_mint,
+                &wrapper_group_member_info,
+            ));
+        }
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_register_wrapper_group_member_instruction(
+                &payer_keypair.pubkey(),
+                &other_mint,
+                &group_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::NotAGroupMember as u32),
+        );
+    }
+
+    /// Test 38 - wrap and unwrap quotes match the amounts received on-chain, transfer fee and
+    /// wrapper fees included
+    ///
+    ///
+    #[tokio::test]
+    async fn test_38() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 1_000_000_u64;
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            Some(&transfer_fee_config),
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+        let fee_recipient = create_associated_token_account(
+            &mut test_client,
+            &admin.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let set_fee_ix = create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &fee_recipient,
+            SetFeeArgs {
+                wrap_fee_basis_points: 30,
+                unwrap_fee_basis_points: 45,
+                maximum_fee: 40,
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let token_2022_mint_data = get_account(&mut test_client, &token_2022_mint).await.data;
+        let wrapper_config =
+            WrapperConfig::unpack(&get_account(&mut test_client, &reserve_authority).await.data)
+                .unwrap();
+        let epoch = test_client
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .epoch;
+
+        for amount in [1, 99, 101, 999, 12_345, 100_001] {
+            let quote =
+                quote_wrap(&token_2022_mint_data, Some(&wrapper_config), epoch, amount).unwrap();
+            assert_eq!(quote.amount_in, amount);
+
+            let pre_balance =
+                get_token_balance(&mut test_client, &user_wrapper_token_account).await;
+
+            let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        min_out: quote.amount_out,
+                        ..Default::default()
+                    },
+                },
+            );
+
+            sign_send_instructions(
+                &mut test_client,
+                &[deposit_ix],
+                vec![&user, &payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(
+                get_token_balance(&mut test_client, &user_wrapper_token_account).await
+                    - pre_balance,
+                quote.amount_out,
+            );
+            assert_eq!(
+                quote.amount_in - quote.transfer_fee - quote.wrapper_fee,
+                quote.amount_out
+            );
+        }
+
+        for amount in [1, 98, 1_000, 9_999, 50_000] {
+            let quote =
+                quote_unwrap(&token_2022_mint_data, Some(&wrapper_config), epoch, amount).unwrap();
+
+            let pre_balance =
+                get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await;
+
+            let burn_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount,
+                        min_out: quote.amount_out,
+                        ..Default::default()
+                    },
+                },
+            );
+
+            sign_send_instructions(
+                &mut test_client,
+                &[burn_ix],
+                vec![&user, &payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(
+                get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await
+                    - pre_balance,
+                quote.amount_out,
+            );
+            assert_eq!(
+                quote.amount_in - quote.wrapper_fee - quote.transfer_fee,
+                quote.amount_out
+            );
+        }
+
+        // Without a transfer fee or a wrapper config nothing is retained
+        let (plain_mint, _) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            None,
+        )
+        .await;
+        let plain_mint_data = get_account(&mut test_client, &plain_mint).await.data;
+
+        assert_eq!(
+            quote_wrap(&plain_mint_data, None, epoch, 1_000).unwrap(),
+            Quote {
+                amount_in: 1_000,
+                transfer_fee: 0,
+                wrapper_fee: 0,
+                amount_out: 1_000,
+            },
+        );
+        assert_eq!(
+            quote_unwrap(&plain_mint_data, None, epoch, 1_000)
+                .unwrap()
+                .amount_out,
+            1_000,
+        );
+    }
+
+    /// Test 39 - QuoteWrap and QuoteUnwrap return the quotes of the wraps and unwraps, and fail
+    /// when the wrap or unwrap would
+    ///
+    ///
+    #[tokio::test]
+    async fn test_39() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let decimal_2022 = 6_u8;
+        let amount_2022 = 1_000_000_u64;
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            decimal_2022,
+            Some(&transfer_fee_config),
+        )
+        .await;
+
+        let quote_wrap_ix =
+            create_quote_wrap_instruction(&token_2022_mint, QuoteArgs { amount: 12_345 });
+
+        // Uninitialized wrapper
+        let e = simulate_return_data(
+            &mut test_client,
+            std::slice::from_ref(&quote_wrap_ix),
+            vec![&payer_keypair],
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedWrapperToken as u32),
+        );
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        let initialize_ix = create_initialize_wrapper_token_v2_instruction(
+            &payer_keypair.pubkey(),
+            &token_2022_mint,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+        let fee_recipient = create_associated_token_account(
+            &mut test_client,
+            &admin.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let set_fee_ix = create_set_fee_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            &fee_recipient,
+            SetFeeArgs {
+                wrap_fee_basis_points: 30,
+                unwrap_fee_basis_points: 45,
+                maximum_fee: 40,
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_fee_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let token_2022_mint_data = get_account(&mut test_client, &token_2022_mint).await.data;
+        let wrapper_config =
+            WrapperConfig::unpack(&get_account(&mut test_client, &reserve_authority).await.data)
+                .unwrap();
+        let epoch = test_client
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .epoch;
+
+        // The returned quotes are the ones computed off-chain, and the amounts received
+        let return_data = simulate_return_data(
+            &mut test_client,
+            std::slice::from_ref(&quote_wrap_ix),
+            vec![&payer_keypair],
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let wrap_quote: Quote = borsh::from_slice(&return_data).unwrap();
+        assert_eq!(
+            wrap_quote,
+            quote_wrap(&token_2022_mint_data, Some(&wrapper_config), epoch, 12_345).unwrap(),
+        );
+        assert!(wrap_quote.transfer_fee > 0);
+        assert!(wrap_quote.wrapper_fee > 0);
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            DepositAndMintWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: DepositAndMintWrapperTokensArgs {
+                    amount: 12_345,
+                    min_out: wrap_quote.amount_out,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            wrap_quote.amount_out,
+        );
+
+        let quote_unwrap_ix = create_quote_unwrap_instruction(
+            &token_2022_mint,
+            QuoteArgs {
+                amount: wrap_quote.amount_out,
+            },
+        );
+        let return_data = simulate_return_data(
+            &mut test_client,
+            std::slice::from_ref(&quote_unwrap_ix),
+            vec![&payer_keypair],
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let unwrap_quote: Quote = borsh::from_slice(&return_data).unwrap();
+        assert_eq!(
+            unwrap_quote,
+            quote_unwrap(
+                &token_2022_mint_data,
+                Some(&wrapper_config),
+                epoch,
+                wrap_quote.amount_out
+            )
+            .unwrap(),
+        );
+
+        let pre_balance =
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await;
+
+        let burn_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: wrap_quote.amount_out,
+                    min_out: unwrap_quote.amount_out,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[burn_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await
+                - pre_balance,
+            unwrap_quote.amount_out,
+        );
+
+        // Quoting a wrap of a paused wrapper fails as the wrap does, unwraps are still quoted
+        let set_pause_ix = create_set_pause_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            SetPauseArgs { paused: true },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[set_pause_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = simulate_return_data(&mut test_client, &[quote_wrap_ix], vec![&payer_keypair])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::DepositsPaused as u32),
+        );
+
+        assert!(
+            simulate_return_data(&mut test_client, &[quote_unwrap_ix], vec![&payer_keypair])
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    /// Test 40 - reserve shards are created by the admin in order, up to the maximum, and
+    /// deposits and withdrawals go through the reserve shard of their choice
+    ///
+    ///
+    #[tokio::test]
+    async fn test_40() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let (reserve_shard_1, _, _) =
+            get_reserve_shard(token_2022_mint, reserve_authority, 1, PROGRAM_ID);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_reserve_shard_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                1,
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        // Shards are created in order
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_reserve_shard_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                2,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidReserveShard as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_reserve_shard_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                1,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let reserve_shard_account = get_token_account_2022(&mut test_client, &reserve_shard_1)
+            .await
+            .unwrap();
+        assert_eq!(reserve_shard_account.base.owner, reserve_authority);
+        assert_eq!(reserve_shard_account.base.mint, token_2022_mint);
+        assert!(reserve_shard_account
+            .get_extension::<ImmutableOwner>()
+            .is_ok());
+
+        let deposit_ix = |reserve_shard, amount| {
+            create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+        let withdraw_ix = |reserve_shard, amount| {
+            create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+
+        // Unwrap everything, then wrap again into the shard and partly into the reserve
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                withdraw_ix(0, u64::MAX),
+                deposit_ix(1, 6_000),
+                deposit_ix(0, 4_000),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            6_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            4_000,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            10_000,
+        );
+
+        // A shard that has not been created, or another shard than the one requested
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(2, 1_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidReserveShard as u32),
+        );
+
+        let mut mismatched_ix = withdraw_ix(1, 1_000);
+        mismatched_ix.accounts[6].pubkey = reserve_token_2022_token_account;
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[mismatched_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidReserveShard as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(1, 5_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            1_000,
+        );
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            5_000,
+        );
+
+        for reserve_shard in 2..=MAX_RESERVE_SHARDS {
+            sign_send_instructions(
+                &mut test_client,
+                &[create_initialize_reserve_shard_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    reserve_shard,
+                )],
+                vec![&admin, &payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let wrapper_config =
+            WrapperConfig::unpack(&get_account(&mut test_client, &reserve_authority).await.data)
+                .unwrap();
+        assert_eq!(wrapper_config.reserve_shard_count, MAX_RESERVE_SHARDS);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_reserve_shard_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                MAX_RESERVE_SHARDS + 1,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::TooManyReserveShards as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(MAX_RESERVE_SHARDS, 1_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Test 41 - rebalancing reserve shards of a mint with a transfer fee keeps the wrapper
+    /// token supply backed
+    ///
+    ///
+    #[tokio::test]
+    async fn test_41() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let transfer_fee_config = test_transfer_fee_config_with_keypairs();
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            10_000,
+            6,
+            Some(&transfer_fee_config),
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let (reserve_shard_1, _, _) =
+            get_reserve_shard(token_2022_mint, reserve_authority, 1, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_initialize_wrapper_token_v2_instruction(
+                    &payer_keypair.pubkey(),
+                    &token_2022_mint,
+                ),
+                create_initialize_reserve_shard_instruction(&admin.pubkey(), &token_2022_mint, 1),
+            ],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        // 1% transfer fee, the reserve receives 9_900 backing 9_900 wrapper tokens
+        sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount: 10_000,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let rebalance_ix = |source_reserve_shard, destination_reserve_shard, amount| {
+            create_rebalance_reserve_shards_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                1,
+                RebalanceReserveShardsArgs {
+                    source_reserve_shard,
+                    destination_reserve_shard,
+                    amount,
+                },
+            )
+        };
+
+        // Moving 5_000 withholds 50, leaving 9_850 for 9_900 wrapper tokens
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[rebalance_ix(0, 1, 5_000)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::ReserveUndercollateralized as u32),
+        );
+
+        let mut user_rebalance_ix = rebalance_ix(0, 1, 5_000);
+        user_rebalance_ix.accounts[0].pubkey = user.pubkey();
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[user_rebalance_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        for (source_reserve_shard, destination_reserve_shard) in [(1, 1), (0, 2)] {
+            let e = sign_send_instructions(
+                &mut test_client,
+                &[rebalance_ix(
+                    source_reserve_shard,
+                    destination_reserve_shard,
+                    1_000,
+                )],
+                vec![&admin, &payer_keypair],
+                None,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(
+                extract_error_code(e.to_string().as_str()),
+                Some(TokenWrapperError::InvalidReserveShard as u32),
+            );
+        }
+
+        // Every reserve shard must be passed
+        let mut missing_shard_ix = rebalance_ix(0, 1, 1_000);
+        missing_shard_ix.accounts.pop();
+        assert!(sign_send_instructions(
+            &mut test_client,
+            &[missing_shard_ix],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .is_err());
+
+        // A surplus of 200 covers the transfer fee
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &reserve_token_2022_token_account,
+            200,
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[rebalance_ix(0, 1, 5_000)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            5_100,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            4_950,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 1,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount: 2_000,
+                        ..Default::default()
+                    },
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            2_950,
+        );
+
+        // Moving the full balance of the shard back withholds 30 of 2_950
+        sign_send_instructions(
+            &mut test_client,
+            &[rebalance_ix(1, 0, u64::MAX)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            0,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            8_020,
+        );
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            7_900,
+        );
+    }
+
+    /// Test 42 - fees of deposits through the reserve shards are collected in the fee vault, and
+    /// the reserve of a wrapper with reserve shards is migrated
+    ///
+    ///
+    #[tokio::test]
+    async fn test_42() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            20_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (fee_vault, _, _) = get_fee_vault(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let fee_recipient = create_associated_token_account(
+            &mut test_client,
+            &admin.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let new_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+            &reserve_authority,
+            &token_2022_mint,
+            &spl_token_2022::id(),
+        );
+        let (reserve_shard_1, _, _) =
+            get_reserve_shard(token_2022_mint, reserve_authority, 1, PROGRAM_ID);
+        let (reserve_shard_2, _, _) =
+            get_reserve_shard(token_2022_mint, reserve_authority, 2, PROGRAM_ID);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_initialize_reserve_shard_instruction(&admin.pubkey(), &token_2022_mint, 1),
+                create_initialize_reserve_shard_instruction(&admin.pubkey(), &token_2022_mint, 2),
+                create_set_fee_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    &fee_recipient,
+                    SetFeeArgs {
+                        wrap_fee_basis_points: 100,
+                        unwrap_fee_basis_points: 0,
+                        maximum_fee: u64::MAX,
+                    },
+                ),
+            ],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // 1% of each deposit is minted to the fee vault, the whole deposit is kept in its shard
+        let deposit_ixs: Vec<_> = [1, 2]
+            .into_iter()
+            .map(|reserve_shard| {
+                create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    DepositAndMintWrapperTokensV3Args {
+                        reserve_shard,
+                        args: DepositAndMintWrapperTokensArgs {
+                            amount: 10_000,
+                            ..Default::default()
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        sign_send_instructions(
+            &mut test_client,
+            &deposit_ixs,
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            30_000,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            29_800,
+        );
+        assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 200);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_collect_fees_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                &fee_recipient,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &fee_recipient).await,
+            200
+        );
+        assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 0);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount: 9_900,
+                        ..Default::default()
+                    },
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            100,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            10_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_2).await,
+            10_000,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_reserve_shards(
+                create_migrate_reserve_instruction(&admin.pubkey(), &token_2022_mint),
+                &token_2022_mint,
+                2,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The migrated reserve is shard zero, the other shards are unchanged
+        let rebalance_ix = create_rebalance_reserve_shards_instruction(
+            &admin.pubkey(),
+            &token_2022_mint,
+            2,
+            RebalanceReserveShardsArgs {
+                source_reserve_shard: 2,
+                destination_reserve_shard: 0,
+                amount: 3_000,
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_migrated_reserve(rebalance_ix, &token_2022_mint)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            3_100,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_2).await,
+            7_000,
+        );
+
+        let withdraw_ix = create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: 3_000,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_migrated_reserve(withdraw_ix, &token_2022_mint)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            17_100,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            100,
+        );
+    }
+
+    /// Test 43 - the wrapper inventory is created and topped up by the admin, and deposits and
+    /// withdrawals move wrapper tokens out of and into it without writing to the wrapper mint
+    ///
+    ///
+    #[tokio::test]
+    async fn test_43() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            10_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (reserve_token_2022_token_account, _, _) =
+            get_reserve_authority_token_account(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let (wrapper_inventory, _, _) =
+            get_wrapper_inventory(token_2022_mint, reserve_authority, PROGRAM_ID);
+
+        let deposit_ix = |amount| {
+            create_deposit_from_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(1_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidWrapperInventory as u32),
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        let initialize_ix =
+            create_initialize_wrapper_inventory_instruction(&admin.pubkey(), &token_2022_mint);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[initialize_ix.clone(), initialize_ix.clone()],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidWrapperInventory as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                initialize_ix,
+                create_top_up_wrapper_inventory_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    5_000,
+                ),
+            ],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let wrapper_config =
+            WrapperConfig::unpack(&get_account(&mut test_client, &reserve_authority).await.data)
+                .unwrap();
+        assert!(wrapper_config.has_wrapper_inventory);
+
+        let wrapper_inventory_account = get_token_account(&mut test_client, &wrapper_inventory)
+            .await
+            .unwrap();
+        assert_eq!(wrapper_inventory_account.owner, reserve_authority);
+        assert_eq!(wrapper_inventory_account.mint, wrapper_token_mint);
+        assert_eq!(wrapper_inventory_account.amount, 5_000);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_top_up_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                5_000,
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        // The wrapper token mint is not write locked by deposits from the inventory
+        let ix = deposit_ix(2_000);
+        assert!(ix
+            .accounts
+            .iter()
+            .filter(|account| account.pubkey == wrapper_token_mint)
+            .all(|account| !account.is_writable));
+
+        sign_send_instructions(&mut test_client, &[ix], vec![&user, &payer_keypair], None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            12_000,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &wrapper_inventory).await,
+            3_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            12_000,
+        );
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            15_000,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(4_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InsufficientWrapperInventory as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_to_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount: 1_000,
+                        ..Default::default()
+                    },
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            11_000,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &wrapper_inventory).await,
+            4_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await,
+            11_000,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            9_000,
+        );
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            15_000,
+        );
+    }
+
+    /// Test 44 - fees of deposits from the wrapper inventory are collected in the fee vault, and
+    /// the reserve of a wrapper with an inventory is rebalanced and migrated
+    ///
+    ///
+    #[tokio::test]
+    async fn test_44() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            10_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (fee_vault, _, _) = get_fee_vault(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let fee_recipient = create_associated_token_account(
+            &mut test_client,
+            &admin.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+        let (wrapper_inventory, _, _) =
+            get_wrapper_inventory(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let new_reserve_token_2022_token_account = get_associated_token_address_with_program_id(
+            &reserve_authority,
+            &token_2022_mint,
+            &spl_token_2022::id(),
+        );
+        let (reserve_shard_1, _, _) =
+            get_reserve_shard(token_2022_mint, reserve_authority, 1, PROGRAM_ID);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_initialize_wrapper_inventory_instruction(&admin.pubkey(), &token_2022_mint),
+                create_top_up_wrapper_inventory_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    10_000,
+                ),
+                create_initialize_reserve_shard_instruction(&admin.pubkey(), &token_2022_mint, 1),
+                create_set_fee_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    &fee_recipient,
+                    SetFeeArgs {
+                        wrap_fee_basis_points: 100,
+                        unwrap_fee_basis_points: 0,
+                        maximum_fee: u64::MAX,
+                    },
+                ),
+            ],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // 9_900 wrapper tokens leave the inventory for the user and 100 for the fee vault
+        sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_from_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount: 10_000,
+                        ..Default::default()
+                    },
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &wrapper_inventory).await,
+            0
+        );
+        assert_eq!(get_token_balance(&mut test_client, &fee_vault).await, 100);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_collect_fees_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                &fee_recipient,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &fee_recipient).await,
+            100
+        );
+
+        let rebalance_ix = |amount| {
+            create_rebalance_reserve_shards_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                1,
+                RebalanceReserveShardsArgs {
+                    source_reserve_shard: 0,
+                    destination_reserve_shard: 1,
+                    amount,
+                },
+            )
+        };
+
+        assert!(sign_send_instructions(
+            &mut test_client,
+            &[rebalance_ix(5_000)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .is_err());
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_wrapper_inventory(
+                rebalance_ix(5_000),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &reserve_shard_1).await,
+            5_000,
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_wrapper_inventory(
+                with_reserve_shards(
+                    create_migrate_reserve_instruction(&admin.pubkey(), &token_2022_mint),
+                    &token_2022_mint,
+                    1,
+                ),
+                &token_2022_mint,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            15_000,
+        );
+
+        let withdraw_ix = create_withdraw_to_wrapper_inventory_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            WithdrawAndBurnWrapperTokensV3Args {
+                reserve_shard: 0,
+                args: WithdrawAndBurnWrapperTokensArgs {
+                    amount: 900,
+                    ..Default::default()
+                },
+            },
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_migrated_reserve(withdraw_ix, &token_2022_mint)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &wrapper_inventory).await,
+            900,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &new_reserve_token_2022_token_account).await,
+            14_100,
+        );
+    }
+
+    /// Test 45 - the admin sets a supply cap and per-epoch limits on the net wrapped and
+    /// unwrapped amounts, which reset when the epoch changes
+    ///
+    ///
+    #[tokio::test]
+    async fn test_45() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            10_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (wrapper_limits, _, _) = get_wrapper_limits(token_2022_mint, PROGRAM_ID);
+
+        let deposit_ix = |amount| {
+            create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+        let withdraw_ix = |amount| {
+            create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+        let limits = SetWrapperLimitsArgs {
+            supply_cap: 15_000,
+            epoch_wrap_limit: 3_000,
+            epoch_unwrap_limit: 2_000,
+        };
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_set_wrapper_limits_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                limits,
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnexpectedAdmin as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_set_wrapper_limits_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                limits,
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let wrapper_config =
+            WrapperConfig::unpack(&get_account(&mut test_client, &reserve_authority).await.data)
+                .unwrap();
+        assert!(wrapper_config.has_wrapper_limits);
+
+        let epoch = test_client
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .epoch;
+        let wrapper_limits_account = get_account(&mut test_client, &wrapper_limits).await;
+        assert_eq!(wrapper_limits_account.owner, PROGRAM_ID);
+        let state = WrapperLimits::unpack(&wrapper_limits_account.data).unwrap();
+        assert_eq!(state.token_2022_mint, token_2022_mint);
+        assert_eq!(state.supply_cap, 15_000);
+        assert_eq!(state.epoch_wrap_limit, 3_000);
+        assert_eq!(state.epoch_unwrap_limit, 2_000);
+        assert_eq!(state.epoch, epoch);
+
+        // The v2 deposit takes no wrapper limits, so it cannot check them
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount: 1_000,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnsupportedInstructionVersion as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(2_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(1_500)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochWrapLimitExceeded as u32),
+        );
+
+        // Unwraps free wrap capacity in the same epoch
+        sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(1_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(1_500)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let state =
+            WrapperLimits::unpack(&get_account(&mut test_client, &wrapper_limits).await.data)
+                .unwrap();
+        assert_eq!(state.epoch, epoch);
+        assert_eq!(state.epoch_wrapped, 3_500);
+        assert_eq!(state.epoch_unwrapped, 1_000);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(5_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochUnwrapLimitExceeded as u32),
+        );
+
+        // The volumes reset in the next epoch, the supply cap does not
+        test_client.warp_to_epoch(epoch + 1);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(3_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SupplyCapExceeded as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(2_500)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            15_000,
+        );
+
+        let state =
+            WrapperLimits::unpack(&get_account(&mut test_client, &wrapper_limits).await.data)
+                .unwrap();
+        assert_eq!(state.epoch, epoch + 1);
+        assert_eq!(state.epoch_wrapped, 2_500);
+        assert_eq!(state.epoch_unwrapped, 0);
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(1)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SupplyCapExceeded as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(4_500)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(1)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochUnwrapLimitExceeded as u32),
+        );
+
+        test_client.warp_to_epoch(epoch + 2);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(2_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(2)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochUnwrapLimitExceeded as u32),
+        );
+
+        // Zero disables a limit, the volumes of the epoch are kept
+        sign_send_instructions(
+            &mut test_client,
+            &[create_set_wrapper_limits_instruction(
+                &admin.pubkey(),
+                &token_2022_mint,
+                SetWrapperLimitsArgs {
+                    epoch_unwrap_limit: 0,
+                    ..limits
+                },
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let state =
+            WrapperLimits::unpack(&get_account(&mut test_client, &wrapper_limits).await.data)
+                .unwrap();
+        assert_eq!(state.epoch_unwrap_limit, 0);
+        assert_eq!(state.epoch, epoch + 2);
+        assert_eq!(state.epoch_unwrapped, 2_000);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[withdraw_ix(3_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            5_500,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            14_500,
+        );
+    }
+
+    /// Test 46 - the wrapper limits apply to the v3 instructions and to the wrapper inventory,
+    /// whose deposits are not bound by the supply cap but whose top ups are, and the v1
+    /// instructions fail as they take no wrapper limits
+    ///
+    ///
+    #[tokio::test]
+    async fn test_46() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            10_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+        let (wrapper_inventory, _, _) =
+            get_wrapper_inventory(token_2022_mint, reserve_authority, PROGRAM_ID);
+        let (wrapper_limits, _, _) = get_wrapper_limits(token_2022_mint, PROGRAM_ID);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_initialize_wrapper_inventory_instruction(&admin.pubkey(), &token_2022_mint),
+                create_top_up_wrapper_inventory_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    2_000,
+                ),
+                create_set_wrapper_limits_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    SetWrapperLimitsArgs {
+                        supply_cap: 13_000,
+                        epoch_wrap_limit: 2_500,
+                        epoch_unwrap_limit: 0,
+                    },
+                ),
+            ],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Top ups mint wrapper tokens and are bound by the supply cap
+        let top_up_ix = |amount| {
+            create_top_up_wrapper_inventory_instruction(&admin.pubkey(), &token_2022_mint, amount)
+        };
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[top_up_ix(500)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::InvalidWrapperLimits as u32),
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[with_wrapper_limits(top_up_ix(1_500), &token_2022_mint)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SupplyCapExceeded as u32),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[with_wrapper_limits(top_up_ix(1_000), &token_2022_mint)],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            13_000,
+        );
+
+        // Deposits from the inventory leave the supply unchanged, the epoch wrap limit applies
+        let inventory_deposit_ix = |amount| {
+            create_deposit_from_wrapper_inventory_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+
+        sign_send_instructions(
+            &mut test_client,
+            &[inventory_deposit_ix(2_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[inventory_deposit_ix(1_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochWrapLimitExceeded as u32),
+        );
+
+        // Minting deposits are bound by the supply cap
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount: 100,
+                        ..Default::default()
+                    },
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::SupplyCapExceeded as u32),
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                1_000,
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::UnsupportedInstructionVersion as u32),
+        );
+
+        // Unwraps through the v3 and inventory instructions count toward the same volume
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    WithdrawAndBurnWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: WithdrawAndBurnWrapperTokensArgs {
+                            amount: 1_000,
+                            ..Default::default()
+                        },
+                    },
+                ),
+                create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    WithdrawAndBurnWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: WithdrawAndBurnWrapperTokensArgs {
+                            amount: 500,
+                            ..Default::default()
+                        },
+                    },
+                ),
+                create_withdraw_to_wrapper_inventory_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    WithdrawAndBurnWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: WithdrawAndBurnWrapperTokensArgs {
+                            amount: 500,
+                            ..Default::default()
+                        },
+                    },
+                ),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let state =
+            WrapperLimits::unpack(&get_account(&mut test_client, &wrapper_limits).await.data)
+                .unwrap();
+        assert_eq!(state.epoch_wrapped, 2_000);
+        assert_eq!(state.epoch_unwrapped, 2_000);
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            11_500,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &wrapper_inventory).await,
+            1_500,
+        );
+
+        // The v3 deposits mint up to the supply cap again
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    DepositAndMintWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: DepositAndMintWrapperTokensArgs {
+                            amount: 1_000,
+                            ..Default::default()
+                        },
+                    },
+                ),
+                create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    DepositAndMintWrapperTokensV3Args {
+                        reserve_shard: 0,
+                        args: DepositAndMintWrapperTokensArgs {
+                            amount: 500,
+                            ..Default::default()
+                        },
+                    },
+                ),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            13_000,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[inventory_deposit_ix(1_100)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochWrapLimitExceeded as u32),
+        );
+
+        // The epoch wrap limit resets in the next epoch
+        let epoch = test_client
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .epoch;
+        test_client.warp_to_epoch(epoch + 1);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[inventory_deposit_ix(1_100)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let state =
+            WrapperLimits::unpack(&get_account(&mut test_client, &wrapper_limits).await.data)
+                .unwrap();
+        assert_eq!(state.epoch, epoch + 1);
+        assert_eq!(state.epoch_wrapped, 1_100);
+        assert_eq!(state.epoch_unwrapped, 0);
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            12_600,
+        );
+        assert_eq!(
+            get_token_balance(&mut test_client, &wrapper_inventory).await,
+            400,
+        );
+    }
+
+    /// Test 47 - v1 deposits require the program config, so leaving it out does not get around
+    /// the global pause
+    ///
+    ///
+    #[tokio::test]
+    async fn test_47() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let amount_2022 = 1_000_000_u64;
+
+        let (token_2022_mint, user_token_2022_token_account) = create_and_mint_tokens_token_2022(
+            &mut test_client,
+            &user.pubkey(),
+            amount_2022,
+            6,
+            None,
+        )
+        .await;
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let user_wrapper_token_account =
+            get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_wrapper_token_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        let deposit_ix = create_deposit_and_mint_wrapper_tokens_instruction(
+            &user.pubkey(),
+            &token_2022_mint,
+            &user_wrapper_token_account,
+            &user_token_2022_token_account,
+            10_000,
+        );
+        let mut legacy_deposit_ix = deposit_ix.clone();
+        let program_config = legacy_deposit_ix.accounts.pop().unwrap();
+        assert_eq!(program_config.pubkey, get_program_config(PROGRAM_ID).0);
+        assert_eq!(legacy_deposit_ix.accounts.len(), 12);
+
+        sign_send_instructions(
+            &mut test_client,
+            &[legacy_deposit_ix.clone()],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        // Leaving out the program config does not get around the global pause
+        sign_send_instructions(
+            &mut test_client,
+            &[create_set_global_pause_instruction(
+                &admin.pubkey(),
+                SetPauseArgs { paused: true },
+            )],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[legacy_deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::DepositsPaused as u32),
+        );
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            0,
+        );
+    }
+
+    /// Test 48 - a wrapper token account frozen by the freeze authority is only thawed by it
+    ///
+    ///
+    #[tokio::test]
+    async fn test_48() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let user = Keypair::new();
+        let watcher = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &watcher.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+        let (issuer_freeze, _, _) = get_issuer_freeze(user_wrapper_token_account, PROGRAM_ID);
+
+        // The freeze authority freezes the wrapper token account, the Token 2022 token account
+        // stays thawed
+        sign_send_instructions(
+            &mut test_client,
+            &[create_mirror_freeze_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            !get_token_account(&mut test_client, &user_token_2022_token_account)
+                .await
+                .unwrap()
+                .is_frozen()
+        );
+        let state = IssuerFreeze::unpack(&get_account(&mut test_client, &issuer_freeze).await.data)
+            .unwrap();
+        assert_eq!(state.wrapper_token_account, user_wrapper_token_account);
+        assert!(state.frozen);
+
+        // Mirroring the thawed Token 2022 token account does not lift the freeze
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[create_mirror_thaw_instruction(
+                &watcher.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            )],
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::FreezeStateMismatch as u32),
+        );
+        assert!(
+            get_token_account(&mut test_client, &user_wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen()
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_mirror_thaw_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            !get_token_account(&mut test_client, &user_wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen()
+        );
+        let state = IssuerFreeze::unpack(&get_account(&mut test_client, &issuer_freeze).await.data)
+            .unwrap();
+        assert!(!state.frozen);
+
+        // Once thawed by the freeze authority, the account mirrors the Token 2022 token account
+        let freeze_ix = spl_token_2022::instruction::freeze_account(
+            &spl_token_2022::id(),
+            &user_token_2022_token_account,
+            &token_2022_mint,
+            &payer_keypair.pubkey(),
+            &[&payer_keypair.pubkey()],
+        )
+        .unwrap();
+        let thaw_ix = spl_token_2022::instruction::thaw_account(
+            &spl_token_2022::id(),
+            &user_token_2022_token_account,
+            &token_2022_mint,
+            &payer_keypair.pubkey(),
+            &[&payer_keypair.pubkey()],
+        )
+        .unwrap();
+
+        sign_send_instructions(&mut test_client, &[freeze_ix], vec![&payer_keypair], None)
+            .await
+            .unwrap();
+        sign_send_instructions(
+            &mut test_client,
+            &[create_mirror_freeze_instruction(
+                &watcher.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            )],
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+        sign_send_instructions(&mut test_client, &[thaw_ix], vec![&payer_keypair], None)
+            .await
+            .unwrap();
+        sign_send_instructions(
+            &mut test_client,
+            &[create_mirror_thaw_instruction(
+                &watcher.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user.pubkey(),
+            )],
+            vec![&watcher, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            !get_token_account(&mut test_client, &user_wrapper_token_account)
+                .await
+                .unwrap()
+                .is_frozen()
+        );
+    }
+
+    /// Test 49 - a token group member initialized on the Token 2022 mint after wrapping keeps
+    /// the recorded fingerprint, withdrawals and deposits go through
+    ///
+    ///
+    #[tokio::test]
+    async fn test_49() {
+        let mut test_client = TestClient::new_with_token_2022_from_crate().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let group_mint = create_token_group_mint(&mut test_client).await;
+
+        // The group member pointer only, the member is initialized once wrapped
+        let token_2022_mint_keypair = Keypair::new();
+        let token_2022_mint = token_2022_mint_keypair.pubkey();
+
+        let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::GroupMemberPointer,
+        ])
+        .unwrap();
+        let lamports = rent_exempt(
+            ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+                ExtensionType::GroupMemberPointer,
+                ExtensionType::TokenGroupMember,
+            ])
+            .unwrap(),
+        );
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                system_instruction::create_account(
+                    &payer_keypair.pubkey(),
+                    &token_2022_mint,
+                    lamports,
+                    space as u64,
+                    &spl_token_2022::id(),
+                ),
+                spl_token_2022::extension::group_member_pointer::instruction::initialize(
+                    &spl_token_2022::id(),
+                    &token_2022_mint,
+                    Some(payer_keypair.pubkey()),
+                    Some(token_2022_mint),
+                )
+                .unwrap(),
+                spl_token_2022::instruction::initialize_mint2(
+                    &spl_token_2022::id(),
+                    &token_2022_mint,
+                    &payer_keypair.pubkey(),
+                    None,
+                    6,
+                )
+                .unwrap(),
+            ],
+            vec![&payer_keypair, &token_2022_mint_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_initialize_wrapper_token_v2_instruction(
+                &payer_keypair.pubkey(),
+                &token_2022_mint,
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+
+        let user_token_2022_token_account = create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &token_2022_mint,
+            &spl_token_2022::id(),
+        )
+        .await
+        .unwrap();
+        let user_wrapper_token_account = create_associated_token_account(
+            &mut test_client,
+            &user.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            1_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensArgs {
+                    amount: 1_000,
+                    ..Default::default()
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[spl_token_group_interface::instruction::initialize_member(
+                &spl_token_2022::id(),
+                &token_2022_mint,
+                &token_2022_mint,
+                &payer_keypair.pubkey(),
+                &group_mint,
+                &payer_keypair.pubkey(),
+            )],
+            vec![&payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_withdraw_and_burn_wrapper_tokens_v2_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    WithdrawAndBurnWrapperTokensArgs {
+                        amount: 600,
+                        ..Default::default()
+                    },
+                ),
+                create_deposit_and_mint_wrapper_tokens_v2_instruction(
+                    &user.pubkey(),
+                    &token_2022_mint,
+                    &user_wrapper_token_account,
+                    &user_token_2022_token_account,
+                    DepositAndMintWrapperTokensArgs {
+                        amount: 100,
+                        ..Default::default()
+                    },
+                ),
+            ],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            500,
+        );
+        assert_eq!(
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await,
+            500,
+        );
+    }
+
+    /// Test 50 - the wrapper limits count wraps with their wrap fee and unwraps without their
+    /// unwrap fee, as they change the wrapper token supply
+    ///
+    ///
+    #[tokio::test]
+    async fn test_50() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let (token_2022_mint, user_token_2022_token_account, user_wrapper_token_account) =
+            create_wrapped_token_2022_balance(&mut test_client, &user, 10_000, 6).await;
+
+        mint_token_2022_tokens(
+            &mut test_client,
+            &payer_keypair,
+            &token_2022_mint,
+            &user_token_2022_token_account,
+            20_000,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (wrapper_token_mint, _, _) = get_wrapper_token_mint(token_2022_mint, PROGRAM_ID);
+        let (wrapper_limits, _, _) = get_wrapper_limits(token_2022_mint, PROGRAM_ID);
+        let fee_recipient = create_associated_token_account(
+            &mut test_client,
+            &admin.pubkey(),
+            &wrapper_token_mint,
+            &spl_token::id(),
+        )
+        .await
+        .unwrap();
+
+        sign_send_instructions(
+            &mut test_client,
+            &[
+                create_set_fee_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    &fee_recipient,
+                    SetFeeArgs {
+                        wrap_fee_basis_points: 100,
+                        unwrap_fee_basis_points: 50,
+                        maximum_fee: 0,
+                    },
+                ),
+                create_set_wrapper_limits_instruction(
+                    &admin.pubkey(),
+                    &token_2022_mint,
+                    SetWrapperLimitsArgs {
+                        supply_cap: 30_000,
+                        epoch_wrap_limit: 10_000,
+                        epoch_unwrap_limit: 0,
+                    },
+                ),
+            ],
+            vec![&admin, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let deposit_ix = |amount| {
+            create_deposit_and_mint_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                DepositAndMintWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: DepositAndMintWrapperTokensArgs {
+                        amount,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+
+        // The 100 wrapper tokens of the wrap fee are minted too, using up the epoch wrap limit
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(10_000)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            20_000,
+        );
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(1)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochWrapLimitExceeded as u32),
+        );
+
+        // The 5 wrapper tokens of the unwrap fee go to the fee vault, only 995 are burned
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_v3_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                WithdrawAndBurnWrapperTokensV3Args {
+                    reserve_shard: 0,
+                    args: WithdrawAndBurnWrapperTokensArgs {
+                        amount: 1_000,
+                        ..Default::default()
+                    },
+                },
+            )],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_token_mint(&mut test_client, &wrapper_token_mint)
+                .await
+                .unwrap()
+                .supply,
+            19_005,
+        );
+
+        // The net recorded volume is the supply change since the limits were set
+        let state =
+            WrapperLimits::unpack(&get_account(&mut test_client, &wrapper_limits).await.data)
+                .unwrap();
+        assert_eq!(state.epoch_wrapped, 10_000);
+        assert_eq!(state.epoch_unwrapped, 995);
+        assert_eq!(state.epoch_wrapped - state.epoch_unwrapped, 19_005 - 10_000);
+
+        // The unwrap frees exactly the capacity it burned
+        sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(995)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let e = sign_send_instructions(
+            &mut test_client,
+            &[deposit_ix(1)],
+            vec![&user, &payer_keypair],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            extract_error_code(e.to_string().as_str()),
+            Some(TokenWrapperError::EpochWrapLimitExceeded as u32),
+        );
+    }
+
+    /// Test 51 - a wrapper can be retired by anyone once its Token 2022 mint is closed, but not
+    /// while the mint address holds an account that fails to load for another reason
+    ///
+    ///
+    #[tokio::test]
+    async fn test_51() {
+        let mut test_client = TestClient::new().await;
+        let payer_keypair = test_client.get_payer_clone();
+
+        let admin = test_client.admin.insecure_clone();
+
+        let user = Keypair::new();
+        let _ = airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+        let _ = airdrop(&mut test_client, &admin.pubkey(), 5 * LAMPORTS_PER_SOL).await;
+
+        initialize_program_config(&mut test_client).await;
+
+        let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::MintCloseAuthority,
+        ])
+        .unwrap();
+
+        for replaced_by_uninitialized_account in [false, true] {
+            let token_2022_mint_keypair = Keypair::new();
+            let token_2022_mint = token_2022_mint_keypair.pubkey();
+
+            sign_send_instructions(
+                &mut test_client,
+                &[
+                    system_instruction::create_account(
+                        &payer_keypair.pubkey(),
+                        &token_2022_mint,
+                        rent_exempt(space),
+                        space as u64,
+                        &spl_token_2022::id(),
+                    ),
+                    spl_token_2022::instruction::initialize_mint_close_authority(
+                        &spl_token_2022::id(),
+                        &token_2022_mint,
+                        Some(&payer_keypair.pubkey()),
+                    )
+                    .unwrap(),
+                    spl_token_2022::instruction::initialize_mint2(
+                        &spl_token_2022::id(),
+                        &token_2022_mint,
+                        &payer_keypair.pubkey(),
+                        None,
+                        6,
+                    )
+                    .unwrap(),
+                    create_initialize_wrapper_token_v2_instruction(
+                        &payer_keypair.pubkey(),
+                        &token_2022_mint,
+                    ),
+                ],
+                vec![&payer_keypair, &token_2022_mint_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+
+            sign_send_instructions(
+                &mut test_client,
+                &[spl_token_2022::instruction::close_account(
+                    &spl_token_2022::id(),
+                    &token_2022_mint,
+                    &payer_keypair.pubkey(),
+                    &payer_keypair.pubkey(),
+                    &[],
+                )
+                .unwrap()],
+                vec![&payer_keypair],
+                None,
+            )
+            .await
+            .unwrap();
+
+            let retire_ix = create_retire_wrapper_instruction(&user.pubkey(), &token_2022_mint);
+
+            if replaced_by_uninitialized_account {
+                // Owned by Token 2022 but not a mint, which is not a replaced source mint
+                sign_send_instructions(
+                    &mut test_client,
+                    &[system_instruction::create_account(
+                        &payer_keypair.pubkey(),
+                        &token_2022_mint,
+                        rent_exempt(space),
+                        space as u64,
+                        &spl_token_2022::id(),
+                    )],
+                    vec![&payer_keypair, &token_2022_mint_keypair],
+                    None,
+                )
+                .await
+                .unwrap();
+
+                let e = sign_send_instructions(
+                    &mut test_client,
+                    &[retire_ix],
+                    vec![&user, &payer_keypair],
+                    None,
+                )
+                .await
+                .unwrap_err();
+                assert_eq!(
+                    e.unwrap(),
+                    TransactionError::InstructionError(0, InstructionError::UninitializedAccount),
+                );
+            } else {
+                sign_send_instructions(
+                    &mut test_client,
+                    &[retire_ix],
+                    vec![&user, &payer_keypair],
+                    None,
+                )
+                .await
+                .unwrap();
+
+                let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, PROGRAM_ID);
+                let wrapper_config = WrapperConfig::unpack(
+                    &get_account(&mut test_client, &reserve_authority).await.data,
+                )
+                .unwrap();
+                assert!(wrapper_config.retired);
+            }
+        }
+    }
 }
@@ -0,0 +1,343 @@
+pub mod utils;
+
+use proptest::prelude::*;
+use solana_program::clock::Clock;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token_2022::extension::transfer_fee::{
+    instruction::set_transfer_fee, TransferFee, MAX_FEE_BASIS_POINTS,
+};
+use token2022_wrapper::{
+    instruction_builders::{
+        create_deposit_and_mint_wrapper_tokens_instruction,
+        create_initialize_wrapper_token_instruction,
+        create_withdraw_and_burn_wrapper_tokens_instruction,
+    },
+    quote::{quote_unwrap, quote_wrap},
+    utils::{get_reserve_authority, get_reserve_authority_token_account, get_wrapper_token_mint},
+};
+use utils::{
+    airdrop, create_associated_token_account, create_token_2022_mint,
+    create_token_account_token_2022, get_account, get_token_balance, get_token_balance_2022,
+    get_token_mint, mint_token_2022_tokens, sign_send_instructions,
+    transfer_fee_config_with_keypairs, TestClient,
+};
+
+/// Cases run by default, `PROPTEST_CASES` overrides it for longer runs
+const DEFAULT_CASES: u32 = 16;
+
+/// Whole tokens minted to the user
+const USER_TOKENS: u64 = 1_000_000;
+
+/// Epochs after which a transfer fee set on the mint applies
+const TRANSFER_FEE_DELAY_EPOCHS: u64 = 2;
+
+/// Deposit followed by a partial withdrawal, after moving the clock
+#[derive(Clone, Debug)]
+struct Step {
+    /// Epochs the clock moves forward by before the deposit
+    epochs: u64,
+    /// Deposit in parts per million of the user balance
+    deposit_ppm: u64,
+    /// Withdrawal in parts per million of the user wrapper balance
+    withdraw_ppm: u64,
+}
+
+/// Transfer fee applied at the creation of the mint, transfer fee set right after and applying
+/// from `TRANSFER_FEE_DELAY_EPOCHS`, and the deposits and withdrawals made across the epochs
+#[derive(Clone, Debug)]
+struct Scenario {
+    decimals: u8,
+    older_fee: (u16, u64),
+    newer_fee: (u16, u64),
+    steps: Vec<Step>,
+}
+
+fn transfer_fee() -> impl Strategy<Value = (u16, u64)> {
+    (
+        prop_oneof![
+            Just(0),
+            Just(MAX_FEE_BASIS_POINTS),
+            0..=MAX_FEE_BASIS_POINTS
+        ],
+        prop_oneof![Just(0), 1..1_000_u64, Just(u64::MAX), any::<u64>()],
+    )
+}
+
+fn ppm() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0), Just(1_000_000), 0..=1_000_000_u64]
+}
+
+fn scenario() -> impl Strategy<Value = Scenario> {
+    (
+        0..=9_u8,
+        transfer_fee(),
+        transfer_fee(),
+        prop::collection::vec(
+            (0..=TRANSFER_FEE_DELAY_EPOCHS, ppm(), ppm()).prop_map(
+                |(epochs, deposit_ppm, withdraw_ppm)| Step {
+                    epochs,
+                    deposit_ppm,
+                    withdraw_ppm,
+                },
+            ),
+            1..=4,
+        ),
+    )
+        .prop_map(|(decimals, older_fee, newer_fee, steps)| Scenario {
+            decimals,
+            older_fee,
+            newer_fee,
+            steps,
+        })
+}
+
+fn to_transfer_fee((transfer_fee_basis_points, maximum_fee): (u16, u64)) -> TransferFee {
+    TransferFee {
+        epoch: 0.into(),
+        transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        maximum_fee: maximum_fee.into(),
+    }
+}
+
+fn part(amount: u64, ppm: u64) -> u64 {
+    (amount as u128 * ppm as u128 / 1_000_000) as u64
+}
+
+async fn get_epoch(test_client: &mut TestClient) -> u64 {
+    test_client
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .epoch
+}
+
+/// Wraps and unwraps through `scenario` and checks that the wrapper tokens minted are the Token
+/// 2022 tokens received by the reserve, that the fee of the epoch is withheld and that the user
+/// never ends up with more tokens than minted
+async fn run_scenario(scenario: Scenario) {
+    let mut test_client = TestClient::new().await;
+    let payer = test_client.get_payer_clone();
+    let user = Keypair::new();
+    airdrop(&mut test_client, &user.pubkey(), 5 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    let older_fee = to_transfer_fee(scenario.older_fee);
+    let newer_fee = to_transfer_fee(scenario.newer_fee);
+    let transfer_fee_config = transfer_fee_config_with_keypairs(older_fee);
+    let token_2022_mint = create_token_2022_mint(
+        &mut test_client,
+        &payer.pubkey(),
+        None,
+        scenario.decimals,
+        None,
+        Some(&transfer_fee_config),
+    )
+    .await
+    .unwrap();
+
+    let initial_epoch = get_epoch(&mut test_client).await;
+    sign_send_instructions(
+        &mut test_client,
+        &[set_transfer_fee(
+            &spl_token_2022::id(),
+            &token_2022_mint,
+            &transfer_fee_config.transfer_fee_config_authority.pubkey(),
+            &[],
+            scenario.newer_fee.0,
+            scenario.newer_fee.1,
+        )
+        .unwrap()],
+        vec![&payer, &transfer_fee_config.transfer_fee_config_authority],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let user_amount = USER_TOKENS * 10_u64.pow(scenario.decimals as u32);
+    let user_token_2022_token_account =
+        create_token_account_token_2022(&mut test_client, &user.pubkey(), &token_2022_mint)
+            .await
+            .unwrap();
+    mint_token_2022_tokens(
+        &mut test_client,
+        &payer,
+        &token_2022_mint,
+        &user_token_2022_token_account,
+        user_amount,
+        None,
+    )
+    .await
+    .unwrap();
+
+    sign_send_instructions(
+        &mut test_client,
+        &[create_initialize_wrapper_token_instruction(
+            &payer.pubkey(),
+            &token_2022_mint,
+        )],
+        vec![&payer],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let (wrapper_token_mint, _, _) =
+        get_wrapper_token_mint(token_2022_mint, token2022_wrapper::id());
+    let (reserve_authority, _, _) = get_reserve_authority(token_2022_mint, token2022_wrapper::id());
+    let (reserve_token_2022_token_account, _, _) = get_reserve_authority_token_account(
+        token_2022_mint,
+        reserve_authority,
+        token2022_wrapper::id(),
+    );
+    create_associated_token_account(
+        &mut test_client,
+        &user.pubkey(),
+        &wrapper_token_mint,
+        &spl_token::id(),
+    )
+    .await
+    .unwrap();
+    let user_wrapper_token_account =
+        get_associated_token_address(&user.pubkey(), &wrapper_token_mint);
+
+    let mut epoch = initial_epoch;
+    for step in scenario.steps {
+        if step.epochs > 0 {
+            epoch += step.epochs;
+            test_client.warp_to_epoch(epoch);
+        }
+        assert_eq!(get_epoch(&mut test_client).await, epoch);
+
+        let transfer_fee = if epoch >= initial_epoch + TRANSFER_FEE_DELAY_EPOCHS {
+            newer_fee
+        } else {
+            older_fee
+        };
+        let token_2022_mint_data = get_account(&mut test_client, &token_2022_mint).await.data;
+
+        // Deposit
+        let user_balance =
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await;
+        let deposit_amount = part(user_balance, step.deposit_ppm);
+        let reserve_before =
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await;
+        let wrapper_before = get_token_balance(&mut test_client, &user_wrapper_token_account).await;
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_deposit_and_mint_wrapper_tokens_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                deposit_amount,
+            )],
+            vec![&payer, &user],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let reserve_delta =
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await
+                - reserve_before;
+        let minted =
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await - wrapper_before;
+        let expected_fee = transfer_fee.calculate_fee(deposit_amount).unwrap();
+        assert_eq!(
+            minted, reserve_delta,
+            "minted differs from the reserve delta"
+        );
+        assert_eq!(
+            minted,
+            deposit_amount - expected_fee,
+            "fee of epoch {epoch}"
+        );
+        assert_eq!(
+            quote_wrap(&token_2022_mint_data, None, epoch, deposit_amount)
+                .unwrap()
+                .amount_out,
+            minted
+        );
+
+        // Withdrawal
+        let burn_amount = part(
+            get_token_balance(&mut test_client, &user_wrapper_token_account).await,
+            step.withdraw_ppm,
+        );
+        let reserve_before =
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await;
+        let user_before =
+            get_token_balance_2022(&mut test_client, &user_token_2022_token_account).await;
+
+        sign_send_instructions(
+            &mut test_client,
+            &[create_withdraw_and_burn_wrapper_tokens_instruction(
+                &user.pubkey(),
+                &token_2022_mint,
+                &user_wrapper_token_account,
+                &user_token_2022_token_account,
+                burn_amount,
+            )],
+            vec![&payer, &user],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let reserve_delta = reserve_before
+            - get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await;
+        let received = get_token_balance_2022(&mut test_client, &user_token_2022_token_account)
+            .await
+            - user_before;
+        let expected_fee = transfer_fee.calculate_fee(burn_amount).unwrap();
+        assert_eq!(
+            reserve_delta, burn_amount,
+            "reserve delta differs from burned"
+        );
+        assert_eq!(received, burn_amount - expected_fee, "fee of epoch {epoch}");
+        assert_eq!(
+            quote_unwrap(&token_2022_mint_data, None, epoch, burn_amount)
+                .unwrap()
+                .amount_out,
+            received
+        );
+
+        // Round trips never create value and every wrapper token stays backed
+        let wrapper_supply = get_token_mint(&mut test_client, &wrapper_token_mint)
+            .await
+            .unwrap()
+            .supply;
+        let reserve_balance =
+            get_token_balance_2022(&mut test_client, &reserve_token_2022_token_account).await;
+        let user_total = get_token_balance_2022(&mut test_client, &user_token_2022_token_account)
+            .await
+            + get_token_balance(&mut test_client, &user_wrapper_token_account).await;
+        assert_eq!(wrapper_supply, reserve_balance);
+        assert!(user_total <= user_amount, "round trips created value");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(
+        std::env::var("PROPTEST_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(DEFAULT_CASES)
+    ))]
+
+    /// Test 1 - wraps and unwraps with random transfer fees, decimals and amounts across the
+    /// epoch the newer transfer fee applies from
+    ///
+    /// The wrapper tokens minted match the reserve delta and the fee of the epoch, and round
+    /// trips never create value.
+    #[test]
+    fn test_1(scenario in scenario()) {
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(run_scenario(scenario));
+    }
+}
@@ -36,6 +36,32 @@ pub async fn sign_send_instructions(
     res
 }
 
+/// Simulates `ixs` and returns the return data of the transaction, if any
+pub async fn simulate_return_data(
+    client: &mut TestClient,
+    ixs: &[Instruction],
+    signers: Vec<&Keypair>,
+) -> TransactionResult<Option<Vec<u8>>> {
+    let mut transaction = Transaction::new_with_payer(ixs, Some(&client.payer.pubkey()));
+    transaction.sign(
+        &signers,
+        client.banks_client.get_latest_blockhash().await.unwrap(),
+    );
+
+    let simulation = client
+        .banks_client
+        .simulate_transaction(transaction)
+        .await?;
+    if let Some(Err(e)) = simulation.result {
+        return Err(e.into());
+    }
+
+    Ok(simulation
+        .simulation_details
+        .and_then(|details| details.return_data)
+        .map(|return_data| return_data.data))
+}
+
 pub async fn get_account(client: &mut TestClient, pubkey: &Pubkey) -> Account {
     let acc = match client.banks_client.get_account(*pubkey).await {
         Ok(ac) => ac,
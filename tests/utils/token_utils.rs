@@ -21,7 +21,11 @@ pub async fn create_associated_token_account(
         ),
     ];
 
-    let ata_addr = spl_associated_token_account::get_associated_token_address(wallet, token_mint);
+    let ata_addr = spl_associated_token_account::get_associated_token_address_with_program_id(
+        wallet,
+        token_mint,
+        token_program,
+    );
 
     let res = match sign_send_instructions(
         client,
@@ -19,7 +19,13 @@ pub struct TransferFeeConfigWithKeypairs {
 }
 
 pub fn test_transfer_fee_config_with_keypairs() -> TransferFeeConfigWithKeypairs {
-    let transfer_fee = test_transfer_fee();
+    transfer_fee_config_with_keypairs(test_transfer_fee())
+}
+
+/// Transfer fee config applying `transfer_fee` from the creation of the mint
+pub fn transfer_fee_config_with_keypairs(
+    transfer_fee: TransferFee,
+) -> TransferFeeConfigWithKeypairs {
     let transfer_fee_config_authority = Keypair::new();
     let withdraw_withheld_authority = Keypair::new();
 
@@ -1,7 +1,15 @@
-use solana_program::{program_error::ProgramError, pubkey::Pubkey};
-use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_program::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::{
+    processor, BanksClient, BanksClientError, ProgramTest, ProgramTestContext,
+};
 use solana_sdk::{
-    signature::{Keypair, Signature},
+    account::Account,
+    signature::{Keypair, Signature, Signer},
     transport::TransportError,
 };
 use thiserror::Error;
@@ -36,24 +44,129 @@ pub enum TransactionError {
 pub struct TestClient {
     pub banks_client: BanksClient,
     pub payer: Keypair,
+    pub admin: Keypair,
+    /// Context of the test validator, to move its clock
+    pub context: ProgramTestContext,
 }
 
 impl TestClient {
     pub async fn new() -> TestClient {
+        Self::new_with_accounts(vec![]).await
+    }
+
+    /// Starts the test validator with `accounts` added at genesis
+    pub async fn new_with_accounts(accounts: Vec<(Pubkey, Account)>) -> TestClient {
+        Self::start(accounts, false).await
+    }
+
+    /// Starts the test validator with Token 2022 run from the `spl-token-2022` crate instead of
+    /// the older release bundled with `solana-program-test`, for extensions it does not support
+    /// such as token groups
+    pub async fn new_with_token_2022_from_crate() -> TestClient {
+        Self::start(vec![], true).await
+    }
+
+    /// Starts the test validator running the program built with the `lite` feature, whose
+    /// deposit and withdraw instructions are handled by `lite::process_instruction`
+    #[cfg(feature = "lite")]
+    pub async fn new_with_lite_processor() -> TestClient {
+        let program = ProgramTest::new(
+            "token2022_wrapper",
+            token2022_wrapper::id(),
+            processor!(token2022_wrapper::lite::process_instruction),
+        );
+
+        Self::start_program(program, vec![], false).await
+    }
+
+    /// Starts the test validator with the programs added by `add_programs` next to the program,
+    /// e.g. a program calling it through CPI
+    pub async fn new_with_programs(add_programs: impl FnOnce(&mut ProgramTest)) -> TestClient {
+        let mut program = ProgramTest::new(
+            "token2022_wrapper",
+            token2022_wrapper::id(),
+            processor!(token2022_wrapper::processor::process_instruction),
+        );
+        add_programs(&mut program);
+
+        Self::start_program(program, vec![], false).await
+    }
+
+    /// Starts the test validator running the SBF build of the program, `token2022_wrapper.so`
+    /// found in `BPF_OUT_DIR` or `SBF_OUT_DIR` as set by `cargo test-sbf`, so that its compute
+    /// units are metered
+    pub async fn new_with_program_from_so() -> TestClient {
+        let mut program = ProgramTest::default();
+        program.prefer_bpf(true);
+        program.add_program("token2022_wrapper", token2022_wrapper::id(), None);
+
+        Self::start_program(program, vec![], false).await
+    }
+
+    async fn start(accounts: Vec<(Pubkey, Account)>, token_2022_from_crate: bool) -> TestClient {
         let program = ProgramTest::new(
             "token2022_wrapper",
             token2022_wrapper::id(),
             processor!(token2022_wrapper::processor::process_instruction),
         );
 
-        let (client, payer, _) = program.start().await;
+        Self::start_program(program, accounts, token_2022_from_crate).await
+    }
+
+    async fn start_program(
+        mut program: ProgramTest,
+        accounts: Vec<(Pubkey, Account)>,
+        token_2022_from_crate: bool,
+    ) -> TestClient {
+        if token_2022_from_crate {
+            // Token 2022 has no shared object next to the program, it runs natively
+            program.prefer_bpf(false);
+            program.add_program(
+                "spl_token_2022",
+                spl_token_2022::id(),
+                processor!(spl_token_2022::processor::Processor::process),
+            );
+        }
+
+        // The program is loaded as a builtin or by the non-upgradeable loader, so its program data
+        // account holding the upgrade authority is added by hand
+        let admin = Keypair::new();
+        let program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(admin.pubkey()),
+        })
+        .unwrap();
+
+        program.add_account(
+            bpf_loader_upgradeable::get_program_data_address(&token2022_wrapper::id()),
+            Account {
+                lamports: Rent::default().minimum_balance(program_data.len()),
+                data: program_data,
+                owner: bpf_loader_upgradeable::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        for (address, account) in accounts {
+            program.add_account(address, account);
+        }
+
+        let context = program.start_with_context().await;
 
         TestClient {
-            banks_client: client,
-            payer,
+            banks_client: context.banks_client.clone(),
+            payer: context.payer.insecure_clone(),
+            admin,
+            context,
         }
     }
 
+    /// Warps the test validator to the first slot of `epoch`
+    pub fn warp_to_epoch(&mut self, epoch: u64) {
+        self.context.warp_to_epoch(epoch).unwrap();
+    }
+
     /// UNSAFE, only for tests
     pub fn get_payer_clone(&self) -> Keypair {
         self.payer.insecure_clone()